@@ -0,0 +1,852 @@
+//! Файловая система поверх архива (zip, tar, сжатый tar, 7z), только для чтения
+//!
+//! Панель «входит» в архив через `panel_fs = "archive:<id>"`; пути отсчитываются от корня
+//! архива. Оглавление строится один раз при открытии, а запись читается отдельно, без
+//! распаковки всего архива: в zip — через центральный каталог, в tar — по смещению данных,
+//! в сжатом tar и 7z — последовательным проходом до нужной записи.
+
+use crate::api::remote::{normalize_remote_path, pipe, remote_name, remote_parent, RangeReader};
+use crate::archives::{open_7z, tar_decoder, zip_timestamp, ArchiveFormat};
+use crate::core::{
+    guess_mime_type, DirectoryPage, ErrorKind, FileReader, FileSystem, FileSystemEntry,
+    FileSystemError, FileSystemResult, FileWriter, ListOptions, TrashEntry,
+};
+use base64::{engine::general_purpose, Engine as _};
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::sync::{Arc, Mutex};
+
+/// Жесткие ссылки tar могут указывать друг на друга; глубже не разворачиваем
+const MAX_LINK_DEPTH: usize = 8;
+
+/// Сколько кусков распакованной записи ждут читателя
+const READ_QUEUE_CHUNKS: usize = 8;
+
+/// Повторное открытие файла архива (для чтения отдельных записей)
+pub(crate) type ArchiveSource = Box<dyn Fn() -> FileSystemResult<Box<dyn FileReader>> + Send + Sync>;
+
+/// Где лежат данные записи
+#[derive(Debug, Clone, PartialEq)]
+enum EntryData {
+    Dir,
+    /// Номер записи в центральном каталоге zip
+    Zip(usize),
+    /// Смещение данных в несжатом tar
+    TarAt(u64),
+    /// Порядковый номер записи в сжатом tar
+    TarEntry(usize),
+    /// Имя записи в 7z
+    SevenZ(String),
+    /// Жесткая ссылка tar на другую запись архива
+    HardLink(String),
+    /// Символическая ссылка: содержимого нет
+    Symlink,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    entry: FileSystemEntry,
+    data: EntryData,
+    /// Полные пути дочерних записей
+    children: BTreeSet<String>,
+}
+
+impl Node {
+    fn dir(path: &str) -> Self {
+        let mut entry = FileSystemEntry {
+            is_dir: true,
+            ..Default::default()
+        };
+        fill_entry(&mut entry, path);
+        Self {
+            entry,
+            data: EntryData::Dir,
+            children: BTreeSet::new(),
+        }
+    }
+}
+
+fn fill_entry(entry: &mut FileSystemEntry, path: &str) {
+    let name = remote_name(path).to_string();
+    entry.path = path.to_string();
+    entry.is_hidden = name.starts_with('.');
+    entry.mime_type = guess_mime_type(&name, entry.is_dir);
+    entry.name = name;
+}
+
+/// Оглавление архива: путь → запись
+///
+/// Родительские директории, которых нет в архиве явно, достраиваются.
+#[derive(Debug)]
+struct ArchiveIndex {
+    nodes: HashMap<String, Node>,
+}
+
+impl ArchiveIndex {
+    fn new() -> Self {
+        let mut nodes = HashMap::new();
+        nodes.insert("/".to_string(), Node::dir("/"));
+        Self { nodes }
+    }
+
+    fn get(&self, path: &str) -> FileSystemResult<&Node> {
+        self.nodes
+            .get(path)
+            .ok_or_else(|| FileSystemError::not_found(format!("Path does not exist in archive: {}", path)))
+    }
+
+    fn insert(&mut self, raw_path: &str, mut entry: FileSystemEntry, data: EntryData) {
+        // `./a`, `../a` и `/a` из архива попадают в `/a`: выйти за корень архива нельзя
+        let path = normalize_remote_path("/", raw_path);
+        if path == "/" {
+            return;
+        }
+        let parent = remote_parent(&path).to_string();
+        self.ensure_dir(&parent);
+        fill_entry(&mut entry, &path);
+
+        if let Some(existing) = self.nodes.get_mut(&path) {
+            if existing.data == EntryData::Dir {
+                if data == EntryData::Dir {
+                    // Явная запись директории после неявной: содержимое сохраняется
+                    existing.entry = entry;
+                    return;
+                }
+                // Одноименный файл не затирает директорию с содержимым
+                if !existing.children.is_empty() {
+                    return;
+                }
+            }
+        }
+
+        self.nodes.insert(
+            path.clone(),
+            Node {
+                entry,
+                data,
+                children: BTreeSet::new(),
+            },
+        );
+        if let Some(parent) = self.nodes.get_mut(&parent) {
+            parent.children.insert(path);
+        }
+    }
+
+    fn ensure_dir(&mut self, path: &str) {
+        if self.nodes.get(path).is_some_and(|node| node.data == EntryData::Dir) {
+            return;
+        }
+        let parent = remote_parent(path).to_string();
+        self.ensure_dir(&parent);
+        self.nodes.insert(path.to_string(), Node::dir(path));
+        if let Some(parent) = self.nodes.get_mut(&parent) {
+            parent.children.insert(path.to_string());
+        }
+    }
+
+    fn children(&self, path: &str) -> FileSystemResult<Vec<FileSystemEntry>> {
+        let node = self.get(path)?;
+        if node.data != EntryData::Dir {
+            return Err(FileSystemError::not_a_directory(format!("Path is not a directory: {}", path)));
+        }
+        Ok(node
+            .children
+            .iter()
+            .filter_map(|child| self.nodes.get(child))
+            .map(|child| child.entry.clone())
+            .collect())
+    }
+}
+
+fn zip_error(err: zip::result::ZipError) -> FileSystemError {
+    use zip::result::ZipError;
+
+    match err {
+        ZipError::Io(err) => FileSystemError::from_io(&err, format!("Failed to read zip archive: {}", err)),
+        ZipError::FileNotFound => FileSystemError::not_found("Entry does not exist in archive"),
+        ZipError::InvalidPassword => FileSystemError::permission_denied("Wrong archive password"),
+        err => FileSystemError::with_kind(ErrorKind::InvalidInput, format!("Unsupported zip archive: {}", err)),
+    }
+}
+
+fn tar_error(err: &io::Error) -> FileSystemError {
+    FileSystemError::from_io(err, format!("Failed to read tar archive: {}", err))
+}
+
+fn index_zip(archive: &mut zip::ZipArchive<Box<dyn FileReader>>) -> FileSystemResult<ArchiveIndex> {
+    let mut index = ArchiveIndex::new();
+    for number in 0..archive.len() {
+        // Без распаковки и расшифровки: нужны только метаданные
+        let file = archive.by_index_raw(number).map_err(zip_error)?;
+        let is_dir = file.is_dir();
+        let data = if is_dir {
+            EntryData::Dir
+        } else if file.is_symlink() {
+            EntryData::Symlink
+        } else {
+            EntryData::Zip(number)
+        };
+        let entry = FileSystemEntry {
+            is_dir,
+            is_file: !is_dir,
+            size: (!is_dir).then(|| file.size()),
+            modified: file.last_modified().and_then(zip_timestamp),
+            mode: file.unix_mode(),
+            is_symlink: data == EntryData::Symlink,
+            ..Default::default()
+        };
+        let name = file.name().replace('\\', "/");
+        index.insert(&name, entry, data);
+    }
+    Ok(index)
+}
+
+fn index_7z(archive: &sevenz_rust::Archive) -> ArchiveIndex {
+    let mut index = ArchiveIndex::new();
+    for file in archive.files.iter().filter(|file| !file.is_anti_item()) {
+        let is_dir = file.is_directory();
+        let data = if is_dir {
+            EntryData::Dir
+        } else {
+            EntryData::SevenZ(file.name().to_string())
+        };
+        let entry = FileSystemEntry {
+            is_dir,
+            is_file: !is_dir,
+            size: (!is_dir).then(|| file.size()),
+            modified: file
+                .has_last_modified_date
+                .then(|| file.last_modified_date().to_unix_time())
+                .and_then(|secs| u64::try_from(secs).ok()),
+            ..Default::default()
+        };
+        index.insert(&file.name().replace('\\', "/"), entry, data);
+    }
+    index
+}
+
+/// `seekable` — несжатый tar, прочитанный через `entries_with_seek`: данные записей
+/// потом читаются по смещению
+fn index_tar<R: Read>(entries: tar::Entries<'_, R>, seekable: bool) -> FileSystemResult<ArchiveIndex> {
+    let mut index = ArchiveIndex::new();
+    for (ordinal, item) in entries.enumerate() {
+        let item = item.map_err(|e| tar_error(&e))?;
+        let kind = item.header().entry_type();
+        let link_name = || {
+            item.link_name()
+                .ok()
+                .flatten()
+                .map(|target| target.to_string_lossy().into_owned())
+        };
+
+        let data = if kind.is_dir() {
+            EntryData::Dir
+        } else if kind.is_symlink() {
+            EntryData::Symlink
+        } else if kind.is_hard_link() {
+            match link_name() {
+                Some(target) => EntryData::HardLink(normalize_remote_path("/", &target)),
+                None => continue,
+            }
+        } else if seekable && (kind.is_file() || kind == tar::EntryType::Continuous) {
+            EntryData::TarAt(item.raw_file_position())
+        } else if kind.is_file() || kind == tar::EntryType::Continuous || kind.is_gnu_sparse() {
+            EntryData::TarEntry(ordinal)
+        } else {
+            // Устройства, FIFO и т.п. в панели не показываются
+            continue;
+        };
+
+        let is_dir = data == EntryData::Dir;
+        let size = match &data {
+            EntryData::Dir => None,
+            // У жесткой ссылки в заголовке размер 0, данные — у цели
+            EntryData::HardLink(target) => index.nodes.get(target).and_then(|node| node.entry.size),
+            _ => Some(item.size()),
+        };
+        let entry = FileSystemEntry {
+            is_dir,
+            is_file: !is_dir,
+            size,
+            modified: item.header().mtime().ok(),
+            mode: item.header().mode().ok(),
+            is_symlink: data == EntryData::Symlink,
+            symlink_target: if data == EntryData::Symlink { link_name() } else { None },
+            ..Default::default()
+        };
+        let name = item.path().map_err(|e| tar_error(&e))?.to_string_lossy().into_owned();
+        index.insert(&name, entry, data);
+    }
+    Ok(index)
+}
+
+/// Окно `[start, start + len)` внутри несжатого tar
+struct EntryReader {
+    inner: Box<dyn FileReader>,
+    start: u64,
+    len: u64,
+    pos: u64,
+}
+
+impl Read for EntryReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.len.saturating_sub(self.pos);
+        if remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+        let max = buf.len().min(usize::try_from(remaining).unwrap_or(usize::MAX));
+        let read = self.inner.read(&mut buf[..max])?;
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for EntryReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+        }
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid seek position"))?;
+        self.inner.seek(SeekFrom::Start(self.start + target))?;
+        self.pos = target;
+        Ok(target)
+    }
+}
+
+struct ArchiveInner {
+    /// Имя файла архива (корень панели)
+    name: String,
+    format: ArchiveFormat,
+    source: ArchiveSource,
+    index: ArchiveIndex,
+    /// Открытый zip: центральный каталог читается один раз
+    zip: Option<Mutex<zip::ZipArchive<Box<dyn FileReader>>>>,
+}
+
+impl ArchiveInner {
+    fn node(&self, path: &str) -> FileSystemResult<&Node> {
+        self.index.get(&normalize_remote_path("/", path))
+    }
+
+    /// Запись с данными: жесткие ссылки разворачиваются
+    fn resolve(&self, path: &str) -> FileSystemResult<&Node> {
+        let mut node = self.node(path)?;
+        for _ in 0..MAX_LINK_DEPTH {
+            match &node.data {
+                EntryData::HardLink(target) => node = self.index.get(target)?,
+                _ => return Ok(node),
+            }
+        }
+        Err(FileSystemError::not_found(format!("Too many levels of links: {}", path)))
+    }
+
+    fn copy_entry(&self, path: &str, out: &mut dyn Write) -> FileSystemResult<u64> {
+        let node = self.resolve(path)?;
+        let size = node.entry.size.unwrap_or(0);
+        let copied = match &node.data {
+            EntryData::Dir => {
+                return Err(FileSystemError::is_a_directory(format!("Path is not a file: {}", path)));
+            }
+            EntryData::Symlink | EntryData::HardLink(_) => {
+                return Err(FileSystemError::with_kind(
+                    ErrorKind::InvalidInput,
+                    format!("Links inside an archive have no content: {}", path),
+                ));
+            }
+            EntryData::Zip(number) => {
+                let zip = self
+                    .zip
+                    .as_ref()
+                    .ok_or_else(|| FileSystemError::new("Archive is not a zip archive"))?;
+                let mut archive = zip.lock().unwrap();
+                let mut file = archive.by_index(*number).map_err(zip_error)?;
+                io::copy(&mut file, out)
+            }
+            EntryData::TarAt(offset) => {
+                let mut reader = (self.source)()?;
+                reader
+                    .seek(SeekFrom::Start(*offset))
+                    .and_then(|_| io::copy(&mut (&mut reader).take(size), out))
+            }
+            EntryData::TarEntry(ordinal) => {
+                tar_decoder(self.format, (self.source)()?).and_then(|stream| {
+                    let mut archive = tar::Archive::new(stream);
+                    let mut entries = archive.entries()?;
+                    match entries.nth(*ordinal) {
+                        Some(Ok(mut item)) => io::copy(&mut item, out),
+                        Some(Err(err)) => Err(err),
+                        None => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Archive entry is missing")),
+                    }
+                })
+            }
+            EntryData::SevenZ(name) => {
+                let mut archive = open_7z((self.source)()?).map_err(FileSystemError::new)?;
+                let mut copied = None;
+                archive
+                    .for_each_entries(|entry, data| {
+                        // Следующий блок 7z тоже вызывает обработчик: после находки сразу стоп
+                        if copied.is_some() {
+                            return Ok(false);
+                        }
+                        if entry.name() != name.as_str() {
+                            // Блок распаковывается подряд: чужие данные пропускаются чтением
+                            io::copy(data, &mut io::sink()).map_err(sevenz_rust::Error::io)?;
+                            return Ok(true);
+                        }
+                        copied = Some(io::copy(data, out).map_err(sevenz_rust::Error::io)?);
+                        Ok(false)
+                    })
+                    .map_err(|e| io::Error::other(e.to_string()))
+                    .and_then(|()| {
+                        copied.ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "Archive entry is missing"))
+                    })
+            }
+        };
+        copied.map_err(|e| FileSystemError::from_io(&e, format!("Failed to read {} from archive: {}", path, e)))
+    }
+
+    /// Как `copy_entry`, но zip читается через свое подключение: писатель может долго
+    /// ждать читателя, и общий замок оглавления не должен держаться все это время
+    fn stream_entry(&self, path: &str, out: &mut dyn Write) -> FileSystemResult<u64> {
+        let EntryData::Zip(number) = self.resolve(path)?.data else {
+            return self.copy_entry(path, out);
+        };
+        let mut archive = zip::ZipArchive::new((self.source)()?).map_err(zip_error)?;
+        let mut file = archive.by_index(number).map_err(zip_error)?;
+        io::copy(&mut file, out)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read {} from archive: {}", path, e)))
+    }
+}
+
+/// Архив, открытый как файловая система (только чтение)
+#[derive(Clone)]
+pub struct ArchiveFileSystem {
+    label: String,
+    inner: Option<Arc<ArchiveInner>>,
+}
+
+impl ArchiveFileSystem {
+    /// Открытие архива: читает оглавление целиком
+    ///
+    /// `source` вызывается заново для чтения записей tar и 7z, поэтому архив должен оставаться
+    /// доступным, пока открыта сессия.
+    pub(crate) fn open(
+        label: impl Into<String>,
+        name: impl Into<String>,
+        format: ArchiveFormat,
+        source: ArchiveSource,
+    ) -> FileSystemResult<Self> {
+        let reader = source()?;
+        let (index, zip) = match format {
+            ArchiveFormat::Zip => {
+                let mut archive = zip::ZipArchive::new(reader).map_err(zip_error)?;
+                (index_zip(&mut archive)?, Some(Mutex::new(archive)))
+            }
+            ArchiveFormat::Tar => {
+                let mut archive = tar::Archive::new(reader);
+                let entries = archive.entries_with_seek().map_err(|e| tar_error(&e))?;
+                (index_tar(entries, true)?, None)
+            }
+            ArchiveFormat::SevenZ => {
+                let archive = open_7z(reader).map_err(FileSystemError::new)?;
+                (index_7z(archive.archive()), None)
+            }
+            ArchiveFormat::TarGz | ArchiveFormat::TarXz | ArchiveFormat::TarZst | ArchiveFormat::TarBz2 => {
+                let mut archive = tar::Archive::new(tar_decoder(format, reader).map_err(|e| tar_error(&e))?);
+                let entries = archive.entries().map_err(|e| tar_error(&e))?;
+                (index_tar(entries, false)?, None)
+            }
+        };
+
+        Ok(Self {
+            label: label.into(),
+            inner: Some(Arc::new(ArchiveInner {
+                name: name.into(),
+                format,
+                source,
+                index,
+                zip,
+            })),
+        })
+    }
+
+    /// Закрытая сессия: все операции возвращают ошибку
+    pub fn closed(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            inner: None,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn inner(&self) -> FileSystemResult<&ArchiveInner> {
+        self.inner
+            .as_deref()
+            .ok_or_else(|| FileSystemError::not_found(format!("Archive session is closed: {}", self.label)))
+    }
+}
+
+fn read_only() -> FileSystemError {
+    FileSystemError::permission_denied("Archive is opened read-only")
+}
+
+impl FileSystem for ArchiveFileSystem {
+    fn read_directory_with_options(
+        &self,
+        path: &str,
+        options: &ListOptions,
+    ) -> FileSystemResult<DirectoryPage> {
+        let dir = normalize_remote_path("/", path);
+        let name_filter = options.name_filter()?;
+
+        let mut result: Vec<FileSystemEntry> = self
+            .inner()?
+            .index
+            .children(&dir)?
+            .into_iter()
+            .filter(|entry| name_filter.matches(&entry.name))
+            .filter(|entry| !entry.is_hidden || options.include_hidden())
+            .collect();
+
+        result.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        });
+
+        options.apply(result)
+    }
+
+    fn get_file_info(&self, path: &str) -> FileSystemResult<FileSystemEntry> {
+        Ok(self.inner()?.node(path)?.entry.clone())
+    }
+
+    fn delete_item(&self, _path: &str) -> FileSystemResult<()> {
+        Err(read_only())
+    }
+
+    fn trash_item(&self, _path: &str) -> FileSystemResult<TrashEntry> {
+        Err(read_only())
+    }
+
+    fn list_trash(&self) -> FileSystemResult<Vec<TrashEntry>> {
+        Ok(Vec::new())
+    }
+
+    fn restore_from_trash(&self, id: &str) -> FileSystemResult<TrashEntry> {
+        Err(FileSystemError::not_found(format!("Trash item not found: {}", id)))
+    }
+
+    fn empty_trash(&self) -> FileSystemResult<usize> {
+        Ok(0)
+    }
+
+    fn purge_trash_older_than(&self, _cutoff: u64) -> FileSystemResult<usize> {
+        Ok(0)
+    }
+
+    fn rename_item(&self, _old_path: &str, _new_name: &str) -> FileSystemResult<()> {
+        Err(read_only())
+    }
+
+    fn create_folder(&self, _path: &str, _name: &str) -> FileSystemResult<()> {
+        Err(read_only())
+    }
+
+    fn create_file(&self, _path: &str, _name: &str, _content: Option<&str>) -> FileSystemResult<()> {
+        Err(read_only())
+    }
+
+    fn create_files_batch(
+        &self,
+        _path: &str,
+        _files: &[(String, Option<String>)],
+    ) -> FileSystemResult<Vec<FileSystemResult<()>>> {
+        Err(read_only())
+    }
+
+    fn copy_items(&self, _sources: &[String], _destination: &str) -> FileSystemResult<()> {
+        Err(read_only())
+    }
+
+    fn copy_with_custom_name(
+        &self,
+        _source: &str,
+        _destination_dir: &str,
+        _new_name: &str,
+    ) -> FileSystemResult<()> {
+        Err(read_only())
+    }
+
+    fn move_items(&self, _sources: &[String], _destination: &str) -> FileSystemResult<()> {
+        Err(read_only())
+    }
+
+    fn get_home_directory(&self) -> FileSystemResult<String> {
+        self.inner()?;
+        Ok("/".to_string())
+    }
+
+    fn get_system_folders(&self) -> FileSystemResult<Vec<FileSystemEntry>> {
+        let inner = self.inner()?;
+        let mut root = inner.node("/")?.entry.clone();
+        root.name = inner.name.clone();
+        Ok(vec![root])
+    }
+
+    fn read_file_content(&self, path: &str, max_size: Option<u64>) -> FileSystemResult<String> {
+        let entry = self.inner()?.resolve(path)?.entry.clone();
+        if entry.is_dir {
+            return Err(FileSystemError::is_a_directory(format!("Path is not a file: {}", path)));
+        }
+
+        let limit = max_size.unwrap_or(10_000_000);
+        let size = entry.size.unwrap_or(0);
+        if size > limit {
+            return Err(FileSystemError::quota_exceeded(format!(
+                "File too large: {} bytes (limit: {} bytes)",
+                size, limit
+            )));
+        }
+
+        let content = self.read_file_bytes(path)?;
+        match String::from_utf8(content) {
+            Ok(text) => Ok(text),
+            Err(err) => Ok(general_purpose::STANDARD.encode(err.as_bytes())),
+        }
+    }
+
+    fn read_file_bytes(&self, path: &str) -> FileSystemResult<Vec<u8>> {
+        let mut content = Vec::new();
+        self.inner()?.copy_entry(path, &mut content)?;
+        Ok(content)
+    }
+
+    fn write_file_content(&self, _path: &str, _content: &str) -> FileSystemResult<()> {
+        Err(read_only())
+    }
+
+    fn write_file_bytes(&self, _path: &str, _content: &[u8]) -> FileSystemResult<()> {
+        Err(read_only())
+    }
+
+    fn open_read(&self, path: &str) -> FileSystemResult<Box<dyn FileReader>> {
+        let inner = self.inner()?;
+        let node = inner.resolve(path)?;
+        if let EntryData::TarAt(offset) = node.data {
+            // Несжатый tar: окно поверх самого архива, без копирования
+            let mut reader = (inner.source)()?;
+            reader
+                .seek(SeekFrom::Start(offset))
+                .map_err(|e| tar_error(&e))?;
+            return Ok(Box::new(EntryReader {
+                inner: reader,
+                start: offset,
+                len: node.entry.size.unwrap_or(0),
+                pos: 0,
+            }));
+        }
+
+        // Сжатые записи не поддерживают перемотку: распаковка идет в фоновом потоке прямо
+        // в читатель, без временного файла (архив может быть из хранилища). Перемотка назад
+        // начинает распаковку заново.
+        match node.data {
+            EntryData::Dir => return Err(FileSystemError::is_a_directory(format!("Path is not a file: {}", path))),
+            EntryData::Symlink => {
+                return Err(FileSystemError::with_kind(
+                    ErrorKind::InvalidInput,
+                    format!("Links inside an archive have no content: {}", path),
+                ));
+            }
+            _ => {}
+        }
+        let inner = Arc::clone(self.inner.as_ref().expect("checked by inner()"));
+        let path = normalize_remote_path("/", path);
+        Ok(Box::new(RangeReader::new(node.entry.size, move |offset| {
+            let (mut writer, reader) = pipe(READ_QUEUE_CHUNKS);
+            let inner = Arc::clone(&inner);
+            let path = path.clone();
+            std::thread::spawn(move || match inner.stream_entry(&path, &mut writer) {
+                Ok(_) => writer.close(),
+                Err(err) => writer.fail(err.message),
+            });
+            RangeReader::skip_to(Box::new(reader), offset, false)
+        })))
+    }
+
+    fn open_write(&self, _path: &str) -> FileSystemResult<Box<dyn FileWriter>> {
+        Err(read_only())
+    }
+
+    fn open_file(&self, _path: &str) -> FileSystemResult<()> {
+        Err(FileSystemError::with_kind(ErrorKind::InvalidInput, "Opening files inside an archive is not supported"))
+    }
+
+    fn reveal_in_finder(&self, _path: &str) -> FileSystemResult<()> {
+        Err(FileSystemError::with_kind(ErrorKind::InvalidInput, "Revealing files inside an archive is not supported"))
+    }
+
+    fn normalize_path(&self, path: &str) -> FileSystemResult<String> {
+        let normalized = normalize_remote_path("/", path);
+        self.inner()?.index.get(&normalized)?;
+        Ok(normalized)
+    }
+
+    fn get_path_suggestions(&self, partial_path: &str) -> FileSystemResult<Vec<String>> {
+        let (dir, prefix) = if partial_path.is_empty() || partial_path.ends_with('/') {
+            (normalize_remote_path("/", partial_path), String::new())
+        } else {
+            let path = normalize_remote_path("/", partial_path);
+            (remote_parent(&path).to_string(), remote_name(&path).to_lowercase())
+        };
+
+        let Ok(entries) = self.inner()?.index.children(&dir) else {
+            return Ok(Vec::new());
+        };
+
+        Ok(entries
+            .into_iter()
+            .filter(|entry| entry.is_dir && !entry.is_hidden && entry.name.to_lowercase().starts_with(&prefix))
+            .map(|entry| entry.path)
+            .take(10)
+            .collect())
+    }
+
+    fn open_terminal(&self, _path: &str) -> FileSystemResult<()> {
+        Err(FileSystemError::with_kind(ErrorKind::InvalidInput, "Opening a terminal inside an archive is not supported"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::path::{Path, PathBuf};
+
+    fn file_source(path: &Path) -> ArchiveSource {
+        let path = path.to_path_buf();
+        Box::new(move || {
+            File::open(&path)
+                .map(|file| Box::new(file) as Box<dyn FileReader>)
+                .map_err(|e| FileSystemError::from_io(&e, e.to_string()))
+        })
+    }
+
+    fn build_zip(dir: &Path) -> PathBuf {
+        let path = dir.join("sample.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        zip.add_directory("docs/", options).unwrap();
+        zip.start_file("docs/readme.txt", options).unwrap();
+        zip.write_all(b"hello from zip").unwrap();
+        // Родительская директория `src/nested` не записана явно
+        zip.start_file("./src/nested/main.rs", options).unwrap();
+        zip.write_all(b"fn main() {}").unwrap();
+        zip.start_file(".hidden", options).unwrap();
+        zip.finish().unwrap();
+        path
+    }
+
+    fn build_tar(path: &Path, gzip: bool) {
+        let file = File::create(path).unwrap();
+        let writer: Box<dyn Write> = if gzip {
+            Box::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()))
+        } else {
+            Box::new(file)
+        };
+        let mut builder = tar::Builder::new(writer);
+        for (name, content) in [("data/a.txt", &b"first"[..]), ("data/deep/b.bin", &[7u8; 2000][..])] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(1_700_000_000);
+            header.set_cksum();
+            builder.append_data(&mut header, name, content).unwrap();
+        }
+        let mut link = tar::Header::new_gnu();
+        link.set_entry_type(tar::EntryType::Link);
+        link.set_size(0);
+        link.set_cksum();
+        builder.append_link(&mut link, "data/copy.txt", "data/a.txt").unwrap();
+        builder.into_inner().unwrap().flush().unwrap();
+    }
+
+    #[test]
+    fn browses_zip_with_implicit_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = build_zip(dir.path());
+        let fs = ArchiveFileSystem::open("archive:test", "sample.zip", ArchiveFormat::Zip, file_source(&path)).unwrap();
+
+        let root: Vec<String> = fs.read_directory("/").unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(root, vec!["docs", "src"]);
+
+        let nested = fs.read_directory("/src/nested").unwrap();
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].path, "/src/nested/main.rs");
+
+        let info = fs.get_file_info("docs/readme.txt").unwrap();
+        assert!(info.is_file);
+        assert_eq!(info.size, Some(14));
+        assert!(info.modified.is_some());
+        assert_eq!(fs.read_file_bytes("/docs/readme.txt").unwrap(), b"hello from zip");
+        assert_eq!(fs.read_file_content("/src/nested/main.rs", None).unwrap(), "fn main() {}");
+
+        let mut reader = fs.open_read("/docs/readme.txt").unwrap();
+        reader.seek(SeekFrom::Start(6)).unwrap();
+        let mut tail = String::new();
+        reader.read_to_string(&mut tail).unwrap();
+        assert_eq!(tail, "from zip");
+    }
+
+    #[test]
+    fn reads_single_tar_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, format) in [("plain.tar", ArchiveFormat::Tar), ("packed.tar.gz", ArchiveFormat::TarGz)] {
+            let path = dir.path().join(name);
+            build_tar(&path, format == ArchiveFormat::TarGz);
+            let fs = ArchiveFileSystem::open("archive:test", name, format, file_source(&path)).unwrap();
+
+            let data: Vec<String> = fs.read_directory("/data").unwrap().into_iter().map(|e| e.name).collect();
+            assert_eq!(data, vec!["deep", "a.txt", "copy.txt"]);
+            assert_eq!(fs.get_file_info("/data/a.txt").unwrap().modified, Some(1_700_000_000));
+            assert_eq!(fs.read_file_bytes("/data/deep/b.bin").unwrap(), vec![7u8; 2000]);
+            assert_eq!(fs.read_file_bytes("/data/copy.txt").unwrap(), b"first");
+            assert_eq!(fs.get_file_info("/data/copy.txt").unwrap().size, Some(5));
+
+            let mut reader = fs.open_read("/data/deep/b.bin").unwrap();
+            assert_eq!(reader.seek(SeekFrom::End(-10)).unwrap(), 1990);
+            let mut tail = Vec::new();
+            reader.read_to_end(&mut tail).unwrap();
+            assert_eq!(tail, vec![7u8; 10]);
+        }
+    }
+
+    #[test]
+    fn archive_is_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = build_zip(dir.path());
+        let fs = ArchiveFileSystem::open("archive:test", "sample.zip", ArchiveFormat::Zip, file_source(&path)).unwrap();
+
+        assert_eq!(fs.delete_item("/docs").unwrap_err().kind, ErrorKind::PermissionDenied);
+        assert_eq!(fs.write_file_bytes("/new.txt", b"x").unwrap_err().kind, ErrorKind::PermissionDenied);
+        assert_eq!(fs.read_file_bytes("/docs").unwrap_err().kind, ErrorKind::IsADirectory);
+        assert_eq!(fs.get_file_info("/missing").unwrap_err().kind, ErrorKind::NotFound);
+
+        let closed = ArchiveFileSystem::closed("archive:gone");
+        assert_eq!(closed.read_directory("/").unwrap_err().kind, ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn entries_cannot_escape_archive_root() {
+        let mut index = ArchiveIndex::new();
+        index.insert("../../etc/passwd", FileSystemEntry::default(), EntryData::TarEntry(0));
+        assert!(index.nodes.contains_key("/etc/passwd"));
+        assert_eq!(index.children("/").unwrap()[0].path, "/etc");
+    }
+}
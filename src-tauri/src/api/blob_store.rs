@@ -1,8 +1,24 @@
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use tempfile::NamedTempFile;
 use uuid::Uuid;
-use crate::api::security::{VaultSession, encrypt_blob, decrypt_blob, atomic_write};
+use crate::api::security::{
+    VaultSession, encrypt_blob, decrypt_blob, atomic_write,
+    encrypt_stream_chunk, decrypt_stream_chunk, generate_stream_nonce_prefix,
+    STREAM_NONCE_PREFIX_LENGTH,
+};
 use crate::api::vault_error::{VaultResult, VaultError};
+use crate::core::FileReader;
+
+/// Сигнатура потокового (блочного) формата зашифрованного блоба
+/// Формат: [MAGIC (8)] + [Nonce prefix (8)] + N * [Ciphertext chunk + AuthTag]
+const STREAM_MAGIC: &[u8; 8] = b"VFDBLOB2";
+const STREAM_HEADER_LENGTH: u64 = (STREAM_MAGIC.len() + STREAM_NONCE_PREFIX_LENGTH) as u64;
+/// Размер открытого текста в одном блоке
+const STREAM_CHUNK_SIZE: usize = 1024 * 1024;
+const AUTH_TAG_LENGTH: usize = 16;
+const STREAM_CIPHER_CHUNK_SIZE: u64 = (STREAM_CHUNK_SIZE + AUTH_TAG_LENGTH) as u64;
 
 /// Менеджер зашифрованного хранилища контента
 #[derive(Clone, Debug)]
@@ -60,12 +76,61 @@ impl BlobStore {
             .map_err(|e| VaultError::Io(e))?;
 
         if let Some(sess) = session {
-             decrypt_blob(&file_data, sess)
+            if file_data.starts_with(STREAM_MAGIC) {
+                let mut reader = ChunkedBlobReader::new(Cursor::new(file_data), sess.clone())?;
+                let mut content = Vec::with_capacity(reader.plain_len as usize);
+                reader.read_to_end(&mut content)?;
+                return Ok(content);
+            }
+            decrypt_blob(&file_data, sess)
         } else {
              Ok(file_data)
         }
     }
 
+    /// Открывает блоб для потокового чтения
+    /// Блобы в блочном формате расшифровываются по одному блоку;
+    /// старые (одноблочные) блобы расшифровываются целиком.
+    pub fn open_read(&self, file_id: &str, session: Option<&VaultSession>) -> VaultResult<Box<dyn FileReader>> {
+        let path = self.get_path(file_id);
+
+        if !path.exists() {
+            return Err(VaultError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Blob {} not found", file_id)
+            )));
+        }
+
+        let mut file = fs::File::open(&path)?;
+
+        let Some(sess) = session else {
+            return Ok(Box::new(file));
+        };
+
+        let mut magic = [0u8; 8];
+        let is_stream = match file.read_exact(&mut magic) {
+            Ok(()) => &magic == STREAM_MAGIC,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => false,
+            Err(e) => return Err(VaultError::Io(e)),
+        };
+        file.seek(SeekFrom::Start(0))?;
+
+        if is_stream {
+            Ok(Box::new(ChunkedBlobReader::new(file, sess.clone())?))
+        } else {
+            let mut file_data = Vec::new();
+            file.read_to_end(&mut file_data)?;
+            Ok(Box::new(Cursor::new(decrypt_blob(&file_data, sess)?)))
+        }
+    }
+
+    /// Открывает блоб для потоковой записи
+    /// Контент становится доступен под ID только после `BlobWriter::commit`
+    pub fn open_write(&self, session: Option<&VaultSession>, file_id: Option<&str>) -> VaultResult<BlobWriter> {
+        let id = file_id.map(String::from).unwrap_or_else(|| Uuid::new_v4().to_string());
+        BlobWriter::new(&self.base_path, self.get_path(&id), id, session.cloned())
+    }
+
     /// Удаляет файл контента
     pub fn delete(&self, file_id: &str) -> VaultResult<()> {
         let path = self.get_path(file_id);
@@ -75,3 +140,260 @@ impl BlobStore {
         Ok(())
     }
 }
+
+/// Потоковая запись блоба во временный файл с атомарной заменой при commit
+pub struct BlobWriter {
+    temp_file: NamedTempFile,
+    path: PathBuf,
+    id: String,
+    session: Option<VaultSession>,
+    nonce_prefix: [u8; STREAM_NONCE_PREFIX_LENGTH],
+    chunk_index: u32,
+    buffer: Vec<u8>,
+    written: u64,
+}
+
+impl BlobWriter {
+    fn new(dir: &Path, path: PathBuf, id: String, session: Option<VaultSession>) -> VaultResult<Self> {
+        // Temp file in the SAME directory (required for atomic rename)
+        let mut temp_file = NamedTempFile::new_in(dir)?;
+        let nonce_prefix = generate_stream_nonce_prefix();
+
+        if session.is_some() {
+            temp_file.write_all(STREAM_MAGIC)?;
+            temp_file.write_all(&nonce_prefix)?;
+        }
+
+        Ok(Self {
+            temp_file,
+            path,
+            id,
+            session,
+            nonce_prefix,
+            chunk_index: 0,
+            buffer: Vec::new(),
+            written: 0,
+        })
+    }
+
+    /// Шифрует и записывает блок открытого текста
+    fn write_chunk(&mut self, chunk: &[u8], last: bool) -> VaultResult<()> {
+        let session = self.session.as_ref().ok_or(VaultError::EncryptionFailed)?;
+        let encrypted = encrypt_stream_chunk(chunk, &self.nonce_prefix, self.chunk_index, last, session)?;
+        self.temp_file.write_all(&encrypted)?;
+        self.chunk_index = self.chunk_index.checked_add(1)
+            .ok_or_else(|| VaultError::CryptoError("Blob is too large".into()))?;
+        Ok(())
+    }
+
+    /// Завершает запись и атомарно сохраняет блоб
+    /// Returns: (ID блоба, размер открытого текста)
+    pub fn commit(mut self) -> VaultResult<(String, u64)> {
+        if self.session.is_some() {
+            // Последний блок пишется всегда (может быть пустым), чтобы обнаруживать обрезку
+            let rest = std::mem::take(&mut self.buffer);
+            self.write_chunk(&rest, true)?;
+        }
+
+        self.temp_file.flush()?;
+        self.temp_file.persist(&self.path)
+            .map_err(|e| VaultError::Io(e.error))?;
+
+        Ok((self.id, self.written))
+    }
+}
+
+impl Write for BlobWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.session.is_none() {
+            let n = self.temp_file.write(buf)?;
+            self.written += n as u64;
+            return Ok(n);
+        }
+
+        self.buffer.extend_from_slice(buf);
+        self.written += buf.len() as u64;
+
+        // Полный блок шифруется только когда известно, что за ним есть еще данные
+        while self.buffer.len() > STREAM_CHUNK_SIZE {
+            let chunk: Vec<u8> = self.buffer.drain(..STREAM_CHUNK_SIZE).collect();
+            self.write_chunk(&chunk, false)
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.temp_file.flush()
+    }
+}
+
+/// Чтение блоба в блочном формате с произвольным доступом
+struct ChunkedBlobReader<R: Read + Seek> {
+    inner: R,
+    session: VaultSession,
+    nonce_prefix: [u8; STREAM_NONCE_PREFIX_LENGTH],
+    data_len: u64,
+    chunk_count: u64,
+    plain_len: u64,
+    position: u64,
+    current: Option<(u64, Vec<u8>)>,
+}
+
+impl<R: Read + Seek> ChunkedBlobReader<R> {
+    fn new(mut inner: R, session: VaultSession) -> VaultResult<Self> {
+        let total_len = inner.seek(SeekFrom::End(0))?;
+        inner.seek(SeekFrom::Start(0))?;
+
+        let mut header = [0u8; STREAM_HEADER_LENGTH as usize];
+        inner.read_exact(&mut header).map_err(|_| VaultError::InvalidData)?;
+        if &header[..STREAM_MAGIC.len()] != STREAM_MAGIC {
+            return Err(VaultError::InvalidData);
+        }
+        let mut nonce_prefix = [0u8; STREAM_NONCE_PREFIX_LENGTH];
+        nonce_prefix.copy_from_slice(&header[STREAM_MAGIC.len()..]);
+
+        let data_len = total_len - STREAM_HEADER_LENGTH;
+        let chunk_count = data_len.div_ceil(STREAM_CIPHER_CHUNK_SIZE);
+        let last_len = data_len - chunk_count.saturating_sub(1) * STREAM_CIPHER_CHUNK_SIZE;
+        if chunk_count == 0 || last_len < AUTH_TAG_LENGTH as u64 {
+            return Err(VaultError::InvalidData);
+        }
+
+        Ok(Self {
+            inner,
+            session,
+            nonce_prefix,
+            data_len,
+            chunk_count,
+            plain_len: data_len - chunk_count * AUTH_TAG_LENGTH as u64,
+            position: 0,
+            current: None,
+        })
+    }
+
+    fn load_chunk(&mut self, index: u64) -> VaultResult<()> {
+        if matches!(self.current, Some((i, _)) if i == index) {
+            return Ok(());
+        }
+
+        let offset = index * STREAM_CIPHER_CHUNK_SIZE;
+        let len = STREAM_CIPHER_CHUNK_SIZE.min(self.data_len - offset);
+        let mut ciphertext = vec![0u8; len as usize];
+        self.inner.seek(SeekFrom::Start(STREAM_HEADER_LENGTH + offset))?;
+        self.inner.read_exact(&mut ciphertext)?;
+
+        let last = index + 1 == self.chunk_count;
+        let plain = decrypt_stream_chunk(&ciphertext, &self.nonce_prefix, index as u32, last, &self.session)?;
+        self.current = Some((index, plain));
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Read for ChunkedBlobReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.plain_len {
+            return Ok(0);
+        }
+
+        let index = self.position / STREAM_CHUNK_SIZE as u64;
+        self.load_chunk(index)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+        let chunk = match &self.current {
+            Some((_, data)) => data,
+            None => return Ok(0),
+        };
+        let offset = (self.position % STREAM_CHUNK_SIZE as u64) as usize;
+        let n = buf.len().min(chunk.len().saturating_sub(offset));
+        buf[..n].copy_from_slice(&chunk[offset..offset + n]);
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for ChunkedBlobReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(p) => Some(p),
+            SeekFrom::End(d) => self.plain_len.checked_add_signed(d),
+            SeekFrom::Current(d) => self.position.checked_add_signed(d),
+        };
+
+        match target {
+            Some(p) => {
+                self.position = p;
+                Ok(p)
+            }
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid seek to a negative position")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_write_and_seek_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BlobStore::new(dir.path().to_path_buf());
+        let session = VaultSession::new([42u8; 32]);
+
+        // Больше двух блоков, чтобы проверить границы
+        let data: Vec<u8> = (0..STREAM_CHUNK_SIZE * 2 + 123).map(|i| (i % 251) as u8).collect();
+
+        let mut writer = store.open_write(Some(&session), None).unwrap();
+        for part in data.chunks(64 * 1024) {
+            writer.write_all(part).unwrap();
+        }
+        let (id, size) = writer.commit().unwrap();
+        assert_eq!(size, data.len() as u64);
+
+        // Полное чтение через старый API
+        assert_eq!(store.read(&id, Some(&session)).unwrap(), data);
+
+        // Произвольный доступ через границу блока
+        let mut reader = store.open_read(&id, Some(&session)).unwrap();
+        let start = STREAM_CHUNK_SIZE as u64 - 10;
+        reader.seek(SeekFrom::Start(start)).unwrap();
+        let mut buf = [0u8; 20];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf[..], &data[start as usize..start as usize + 20]);
+    }
+
+    #[test]
+    fn test_stream_empty_and_legacy_blobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BlobStore::new(dir.path().to_path_buf());
+        let session = VaultSession::new([1u8; 32]);
+
+        let (empty_id, size) = store.open_write(Some(&session), None).unwrap().commit().unwrap();
+        assert_eq!(size, 0);
+        assert!(store.read(&empty_id, Some(&session)).unwrap().is_empty());
+
+        let legacy_id = store.write(b"legacy content", Some(&session), None).unwrap();
+        let mut content = Vec::new();
+        store.open_read(&legacy_id, Some(&session)).unwrap().read_to_end(&mut content).unwrap();
+        assert_eq!(content, b"legacy content");
+    }
+
+    #[test]
+    fn test_stream_truncation_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BlobStore::new(dir.path().to_path_buf());
+        let session = VaultSession::new([9u8; 32]);
+
+        let mut writer = store.open_write(Some(&session), None).unwrap();
+        writer.write_all(&vec![5u8; STREAM_CHUNK_SIZE + 10]).unwrap();
+        let (id, _) = writer.commit().unwrap();
+
+        // Отрезаем последний блок целиком
+        let path = dir.path().join(&id);
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..STREAM_HEADER_LENGTH as usize + STREAM_CIPHER_CHUNK_SIZE as usize]).unwrap();
+
+        assert!(store.read(&id, Some(&session)).is_err());
+    }
+}
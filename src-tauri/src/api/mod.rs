@@ -10,5 +10,12 @@ pub mod share;
 pub use real_fs::RealFileSystem;
 pub mod steganography;
 pub mod temporary_fs;
+pub mod remote;
+pub mod sftp_fs;
+pub mod webdav_fs;
+pub mod s3_fs;
+pub mod archive_fs;
 pub mod torrent;
 pub mod monitor;
+pub mod watcher;
+pub mod trash;
@@ -1,4 +1,8 @@
-use crate::core::{FileSystem, FileSystemEntry, FileSystemError, FileSystemResult};
+use crate::api::trash::TrashDir;
+use crate::core::{
+    guess_mime_type, FileReader, FileSystem, FileSystemEntry, FileSystemError, FileSystemResult,
+    DirectoryPage, FileWriter, ListOptions, TrashEntry,
+};
 use base64::{Engine as _, engine::general_purpose};
 use std::fs;
 use std::io::Read;
@@ -6,6 +10,114 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::SystemTime;
 
+/// Запись с метаданными ОС
+///
+/// `display_path` — путь, под которым запись видна клиенту (для временной ФС — путь внутри сессии).
+/// Для символических ссылок метаданные берутся у цели, у битой ссылки — у самой ссылки.
+pub(crate) fn os_entry(path: &Path, display_path: String) -> FileSystemResult<FileSystemEntry> {
+    let link_metadata = fs::symlink_metadata(path)
+        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read metadata: {}", e)))?;
+    let is_symlink = link_metadata.file_type().is_symlink();
+    let symlink_target = if is_symlink {
+        fs::read_link(path).ok().map(|target| target.to_string_lossy().to_string())
+    } else {
+        None
+    };
+    let metadata = if is_symlink {
+        fs::metadata(path).unwrap_or(link_metadata)
+    } else {
+        link_metadata
+    };
+
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    let to_secs = |time: std::io::Result<SystemTime>| {
+        time.ok()
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+    };
+
+    let mut entry = FileSystemEntry {
+        path: display_path,
+        is_dir: metadata.is_dir(),
+        is_file: metadata.is_file(),
+        size: if metadata.is_file() {
+            Some(metadata.len())
+        } else {
+            None
+        },
+        modified: to_secs(metadata.modified()),
+        created: to_secs(metadata.created()),
+        accessed: to_secs(metadata.accessed()),
+        is_symlink,
+        symlink_target,
+        is_hidden: is_hidden(&name, &metadata),
+        mime_type: guess_mime_type(&name, metadata.is_dir()),
+        name,
+        ..Default::default()
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        entry.mode = Some(metadata.mode());
+        entry.uid = Some(metadata.uid());
+        entry.gid = Some(metadata.gid());
+        entry.owner = owner_name(metadata.uid());
+        entry.nlink = Some(metadata.nlink());
+        entry.inode = Some(metadata.ino());
+        entry.dev = Some(metadata.dev());
+    }
+
+    Ok(entry)
+}
+
+/// Dot-файлы скрыты на всех платформах, в Windows также учитывается атрибут hidden
+fn is_hidden(name: &str, metadata: &fs::Metadata) -> bool {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+
+        if metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0 {
+            return true;
+        }
+    }
+    #[cfg(not(windows))]
+    let _ = metadata;
+
+    name.starts_with('.')
+}
+
+/// Имя пользователя по uid (из /etc/passwd, с кэшем)
+#[cfg(unix)]
+fn owner_name(uid: u32) -> Option<String> {
+    use once_cell::sync::Lazy;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    static OWNERS: Lazy<Mutex<HashMap<u32, Option<String>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+    let mut owners = OWNERS.lock().unwrap();
+    owners
+        .entry(uid)
+        .or_insert_with(|| {
+            let passwd = fs::read_to_string("/etc/passwd").ok()?;
+            passwd.lines().find_map(|line| {
+                let mut fields = line.split(':');
+                let name = fields.next()?;
+                let id: u32 = fields.nth(1)?.parse().ok()?;
+                (id == uid).then(|| name.to_string())
+            })
+        })
+        .clone()
+}
+
 /// Реализация файловой системы через реальную ОС
 pub struct RealFileSystem;
 
@@ -16,65 +128,23 @@ impl RealFileSystem {
 
     /// Конвертирует путь в FileSystemEntry
     fn path_to_entry(path: &Path) -> FileSystemResult<FileSystemEntry> {
-        let metadata = fs::metadata(path)
-            .map_err(|e| FileSystemError::new(format!("Failed to read metadata: {}", e)))?;
-
-        let name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
-
-        let path_str = path.to_str().unwrap_or("").to_string();
-
-        let modified = metadata
-            .modified()
-            .ok()
-            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs());
-
-        let created = metadata
-            .created()
-            .ok()
-            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs());
-
-        let accessed = metadata
-            .accessed()
-            .ok()
-            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs());
-
-        Ok(FileSystemEntry {
-            path: path_str,
-            name,
-            is_dir: metadata.is_dir(),
-            is_file: metadata.is_file(),
-            size: if metadata.is_file() {
-                Some(metadata.len())
-            } else {
-                None
-            },
-            modified,
-            created,
-            accessed,
-        })
+        os_entry(path, path.to_str().unwrap_or("").to_string())
     }
 
     /// Рекурсивное копирование директории
     fn copy_dir_recursive(src: &Path, dest: &Path) -> FileSystemResult<()> {
         if !src.is_dir() {
-            return Err(FileSystemError::new("Source is not a directory"));
+            return Err(FileSystemError::not_a_directory("Source is not a directory"));
         }
 
         fs::create_dir_all(dest)
-            .map_err(|e| FileSystemError::new(format!("Failed to create directory: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create directory: {}", e)))?;
 
         for entry in fs::read_dir(src)
-            .map_err(|e| FileSystemError::new(format!("Failed to read directory: {}", e)))?
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read directory: {}", e)))?
         {
             let entry = entry
-                .map_err(|e| FileSystemError::new(format!("Failed to read entry: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read entry: {}", e)))?;
             let path = entry.path();
             let file_name = path
                 .file_name()
@@ -85,7 +155,7 @@ impl RealFileSystem {
                 Self::copy_dir_recursive(&path, &dest_path)?;
             } else {
                 fs::copy(&path, &dest_path)
-                    .map_err(|e| FileSystemError::new(format!("Failed to copy file: {}", e)))?;
+                    .map_err(|e| FileSystemError::from_io(&e, format!("Failed to copy file: {}", e)))?;
             }
         }
 
@@ -94,7 +164,11 @@ impl RealFileSystem {
 }
 
 impl FileSystem for RealFileSystem {
-    fn read_directory(&self, path: &str) -> FileSystemResult<Vec<FileSystemEntry>> {
+    fn read_directory_with_options(
+        &self,
+        path: &str,
+        options: &ListOptions,
+    ) -> FileSystemResult<DirectoryPage> {
         let dir_path = if path.is_empty() || path == "My Computer" || path == "/" {
             dirs::home_dir()
                 .ok_or_else(|| FileSystemError::new("Could not find home directory"))?
@@ -103,39 +177,35 @@ impl FileSystem for RealFileSystem {
         };
 
         if !dir_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "Directory does not exist: {}",
                 path
             )));
         }
 
         if !dir_path.is_dir() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_a_directory(format!(
                 "Path is not a directory: {}",
                 path
             )));
         }
 
         let entries = fs::read_dir(&dir_path)
-            .map_err(|e| FileSystemError::new(format!("Failed to read directory: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read directory: {}", e)))?;
 
+        let name_filter = options.name_filter()?;
         let mut result = Vec::new();
 
         for entry in entries {
             let entry = entry
-                .map_err(|e| FileSystemError::new(format!("Failed to read entry: {}", e)))?;
-            let path = entry.path();
-
-            // Skip hidden files on Unix-like systems
-            if let Some(name) = path.file_name() {
-                if let Some(name_str) = name.to_str() {
-                    if name_str.starts_with('.') {
-                        continue;
-                    }
-                }
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read entry: {}", e)))?;
+            if !name_filter.matches(&entry.file_name().to_string_lossy()) {
+                continue;
             }
+            let path = entry.path();
 
             match Self::path_to_entry(&path) {
+                Ok(fs_entry) if fs_entry.is_hidden && !options.include_hidden() => continue,
                 Ok(fs_entry) => result.push(fs_entry),
                 Err(_) => continue, // Skip files we can't read
             }
@@ -148,14 +218,14 @@ impl FileSystem for RealFileSystem {
             _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
         });
 
-        Ok(result)
+        options.apply(result)
     }
 
     fn get_file_info(&self, path: &str) -> FileSystemResult<FileSystemEntry> {
         let file_path = PathBuf::from(path);
 
         if !file_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "Path does not exist: {}",
                 path
             )));
@@ -168,7 +238,7 @@ impl FileSystem for RealFileSystem {
         let file_path = PathBuf::from(path);
 
         if !file_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "Path does not exist: {}",
                 path
             )));
@@ -176,20 +246,47 @@ impl FileSystem for RealFileSystem {
 
         if file_path.is_dir() {
             fs::remove_dir_all(&file_path)
-                .map_err(|e| FileSystemError::new(format!("Failed to delete directory: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to delete directory: {}", e)))?;
         } else {
             fs::remove_file(&file_path)
-                .map_err(|e| FileSystemError::new(format!("Failed to delete file: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to delete file: {}", e)))?;
         }
 
         Ok(())
     }
 
+    fn trash_item(&self, path: &str) -> FileSystemResult<TrashEntry> {
+        let file_path = PathBuf::from(path);
+        // Без резолва симлинков: в корзину попадает сама ссылка
+        let original_path = std::path::absolute(&file_path)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Invalid path: {}", e)))?;
+
+        TrashDir::home()?.trash(&file_path, &original_path.to_string_lossy())
+    }
+
+    fn list_trash(&self) -> FileSystemResult<Vec<TrashEntry>> {
+        TrashDir::home()?.list()
+    }
+
+    fn restore_from_trash(&self, id: &str) -> FileSystemResult<TrashEntry> {
+        let trash = TrashDir::home()?;
+        let entry = trash.entry(id)?;
+        trash.restore(id, Path::new(&entry.original_path))
+    }
+
+    fn empty_trash(&self) -> FileSystemResult<usize> {
+        TrashDir::home()?.empty()
+    }
+
+    fn purge_trash_older_than(&self, cutoff: u64) -> FileSystemResult<usize> {
+        TrashDir::home()?.purge_older_than(cutoff)
+    }
+
     fn rename_item(&self, old_path: &str, new_name: &str) -> FileSystemResult<()> {
         let old_file_path = PathBuf::from(old_path);
 
         if !old_file_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "Path does not exist: {}",
                 old_path
             )));
@@ -202,14 +299,14 @@ impl FileSystem for RealFileSystem {
         let new_file_path = parent.join(new_name);
 
         if new_file_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::already_exists(format!(
                 "File already exists: {}",
                 new_name
             )));
         }
 
         fs::rename(&old_file_path, &new_file_path)
-            .map_err(|e| FileSystemError::new(format!("Failed to rename: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to rename: {}", e)))?;
 
         Ok(())
     }
@@ -218,7 +315,7 @@ impl FileSystem for RealFileSystem {
         let dir_path = PathBuf::from(path);
 
         if !dir_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "Parent directory does not exist: {}",
                 path
             )));
@@ -227,14 +324,14 @@ impl FileSystem for RealFileSystem {
         let new_folder_path = dir_path.join(name);
 
         if new_folder_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::already_exists(format!(
                 "Folder already exists: {}",
                 name
             )));
         }
 
         fs::create_dir(&new_folder_path)
-            .map_err(|e| FileSystemError::new(format!("Failed to create folder: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create folder: {}", e)))?;
 
         Ok(())
     }
@@ -245,7 +342,7 @@ impl FileSystem for RealFileSystem {
         let dir_path = PathBuf::from(path);
 
         if !dir_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "Parent directory does not exist: {}",
                 path
             )));
@@ -254,18 +351,18 @@ impl FileSystem for RealFileSystem {
         let new_file_path = dir_path.join(name);
 
         if new_file_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::already_exists(format!(
                 "File already exists: {}",
                 name
             )));
         }
 
         let mut file = fs::File::create(&new_file_path)
-            .map_err(|e| FileSystemError::new(format!("Failed to create file: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create file: {}", e)))?;
 
         if let Some(content_str) = content {
             file.write_all(content_str.as_bytes())
-                .map_err(|e| FileSystemError::new(format!("Failed to write file content: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write file content: {}", e)))?;
         }
 
         Ok(())
@@ -279,7 +376,7 @@ impl FileSystem for RealFileSystem {
         let dir_path = PathBuf::from(path);
 
         if !dir_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "Parent directory does not exist: {}",
                 path
             )));
@@ -299,7 +396,7 @@ impl FileSystem for RealFileSystem {
         let dest_path = PathBuf::from(destination);
 
         if !dest_path.exists() || !dest_path.is_dir() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_a_directory(format!(
                 "Destination is not a valid directory: {}",
                 destination
             )));
@@ -309,7 +406,7 @@ impl FileSystem for RealFileSystem {
             let source_path = PathBuf::from(source);
 
             if !source_path.exists() {
-                return Err(FileSystemError::new(format!(
+                return Err(FileSystemError::not_found(format!(
                     "Source does not exist: {}",
                     source
                 )));
@@ -325,7 +422,7 @@ impl FileSystem for RealFileSystem {
                 Self::copy_dir_recursive(&source_path, &dest_file_path)?;
             } else {
                 fs::copy(&source_path, &dest_file_path)
-                    .map_err(|e| FileSystemError::new(format!("Failed to copy file: {}", e)))?;
+                    .map_err(|e| FileSystemError::from_io(&e, format!("Failed to copy file: {}", e)))?;
             }
         }
 
@@ -344,7 +441,7 @@ impl FileSystem for RealFileSystem {
 
         // Check if source exists
         if !source_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "Source does not exist: {}",
                 source
             )));
@@ -352,7 +449,7 @@ impl FileSystem for RealFileSystem {
 
         // Check if destination directory exists
         if !dest_dir.exists() || !dest_dir.is_dir() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_a_directory(format!(
                 "Destination is not a valid directory: {}",
                 destination_dir
             )));
@@ -361,7 +458,7 @@ impl FileSystem for RealFileSystem {
         // Copy file or directory
         if source_path.is_file() {
             fs::copy(&source_path, &dest_path)
-                .map_err(|e| FileSystemError::new(format!("Failed to copy file: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to copy file: {}", e)))?;
 
             // Copy permissions
             if let Ok(metadata) = fs::metadata(&source_path) {
@@ -382,7 +479,7 @@ impl FileSystem for RealFileSystem {
         let dest_path = PathBuf::from(destination);
 
         if !dest_path.exists() || !dest_path.is_dir() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_a_directory(format!(
                 "Destination is not a valid directory: {}",
                 destination
             )));
@@ -392,7 +489,7 @@ impl FileSystem for RealFileSystem {
             let source_path = PathBuf::from(source);
 
             if !source_path.exists() {
-                return Err(FileSystemError::new(format!(
+                return Err(FileSystemError::not_found(format!(
                     "Source does not exist: {}",
                     source
                 )));
@@ -405,7 +502,7 @@ impl FileSystem for RealFileSystem {
             let dest_file_path = dest_path.join(file_name);
 
             fs::rename(&source_path, &dest_file_path)
-                .map_err(|e| FileSystemError::new(format!("Failed to move: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to move: {}", e)))?;
         }
 
         Ok(())
@@ -476,27 +573,27 @@ impl FileSystem for RealFileSystem {
         let file_path = PathBuf::from(path);
 
         if !file_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "File does not exist: {}",
                 path
             )));
         }
 
         if !file_path.is_file() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::is_a_directory(format!(
                 "Path is not a file: {}",
                 path
             )));
         }
 
         let metadata = fs::metadata(&file_path)
-            .map_err(|e| FileSystemError::new(format!("Failed to read metadata: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read metadata: {}", e)))?;
 
         let file_size = metadata.len();
         let limit = max_size.unwrap_or(10_000_000); // Default 10MB limit
 
         if file_size > limit {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::quota_exceeded(format!(
                 "File too large: {} bytes (limit: {} bytes)",
                 file_size, limit
             )));
@@ -514,17 +611,17 @@ impl FileSystem for RealFileSystem {
         if image_extensions.contains(&extension.as_str()) {
             // For images, return base64 encoded content
             let mut file = fs::File::open(&file_path)
-                .map_err(|e| FileSystemError::new(format!("Failed to open file: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to open file: {}", e)))?;
 
             let mut buffer = Vec::new();
             file.read_to_end(&mut buffer)
-                .map_err(|e| FileSystemError::new(format!("Failed to read file: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read file: {}", e)))?;
 
             Ok(general_purpose::STANDARD.encode(&buffer))
         } else {
             // For text files, return content as string
             fs::read_to_string(&file_path)
-                .map_err(|e| FileSystemError::new(format!("Failed to read file as text: {}", e)))
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read file as text: {}", e)))
         }
     }
 
@@ -532,21 +629,21 @@ impl FileSystem for RealFileSystem {
         let file_path = PathBuf::from(path);
 
         if !file_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "File does not exist: {}",
                 path
             )));
         }
 
         if !file_path.is_file() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::is_a_directory(format!(
                 "Path is not a file: {}",
                 path
             )));
         }
 
         fs::read(&file_path)
-            .map_err(|e| FileSystemError::new(format!("Failed to read file: {}", e)))
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read file: {}", e)))
     }
 
     fn write_file_content(&self, path: &str, content: &str) -> FileSystemResult<()> {
@@ -555,7 +652,7 @@ impl FileSystem for RealFileSystem {
         // Verify parent directory exists
         if let Some(parent) = file_path.parent() {
             if !parent.exists() {
-                return Err(FileSystemError::new(format!(
+                return Err(FileSystemError::not_found(format!(
                     "Parent directory does not exist: {}",
                     parent.display()
                 )));
@@ -564,7 +661,7 @@ impl FileSystem for RealFileSystem {
 
         // Write content to file (creates or overwrites)
         fs::write(&file_path, content)
-            .map_err(|e| FileSystemError::new(format!("Failed to write file: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write file: {}", e)))?;
 
         Ok(())
     }
@@ -575,7 +672,7 @@ impl FileSystem for RealFileSystem {
         // Verify parent directory exists
         if let Some(parent) = file_path.parent() {
             if !parent.exists() {
-                return Err(FileSystemError::new(format!(
+                return Err(FileSystemError::not_found(format!(
                     "Parent directory does not exist: {}",
                     parent.display()
                 )));
@@ -583,14 +680,47 @@ impl FileSystem for RealFileSystem {
         }
 
         fs::write(&file_path, content)
-            .map_err(|e| FileSystemError::new(format!("Failed to write file: {}", e)))
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write file: {}", e)))
+    }
+
+    fn open_read(&self, path: &str) -> FileSystemResult<Box<dyn FileReader>> {
+        let file_path = PathBuf::from(path);
+
+        if !file_path.is_file() {
+            return Err(FileSystemError::is_a_directory(format!(
+                "Path is not a file: {}",
+                path
+            )));
+        }
+
+        let file = fs::File::open(&file_path)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to open file: {}", e)))?;
+        Ok(Box::new(file))
+    }
+
+    fn open_write(&self, path: &str) -> FileSystemResult<Box<dyn FileWriter>> {
+        let file_path = PathBuf::from(path);
+
+        // Verify parent directory exists
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                return Err(FileSystemError::not_found(format!(
+                    "Parent directory does not exist: {}",
+                    parent.display()
+                )));
+            }
+        }
+
+        let file = fs::File::create(&file_path)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create file: {}", e)))?;
+        Ok(Box::new(file))
     }
 
     fn open_file(&self, path: &str) -> FileSystemResult<()> {
         let file_path = PathBuf::from(path);
 
         if !file_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "File does not exist: {}",
                 path
             )));
@@ -601,7 +731,7 @@ impl FileSystem for RealFileSystem {
             Command::new("open")
                 .arg(&file_path)
                 .spawn()
-                .map_err(|e| FileSystemError::new(format!("Failed to open file: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to open file: {}", e)))?;
         }
 
         #[cfg(target_os = "windows")]
@@ -609,7 +739,7 @@ impl FileSystem for RealFileSystem {
             Command::new("cmd")
                 .args(&["/C", "start", "", path])
                 .spawn()
-                .map_err(|e| FileSystemError::new(format!("Failed to open file: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to open file: {}", e)))?;
         }
 
         #[cfg(target_os = "linux")]
@@ -617,7 +747,7 @@ impl FileSystem for RealFileSystem {
             Command::new("xdg-open")
                 .arg(&file_path)
                 .spawn()
-                .map_err(|e| FileSystemError::new(format!("Failed to open file: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to open file: {}", e)))?;
         }
 
         Ok(())
@@ -627,7 +757,7 @@ impl FileSystem for RealFileSystem {
         let file_path = PathBuf::from(path);
 
         if !file_path.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "Path does not exist: {}",
                 path
             )));
@@ -638,7 +768,7 @@ impl FileSystem for RealFileSystem {
             Command::new("open")
                 .args(&["-R", file_path.to_str().unwrap()])
                 .spawn()
-                .map_err(|e| FileSystemError::new(format!("Failed to reveal in Finder: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to reveal in Finder: {}", e)))?;
         }
 
         #[cfg(target_os = "windows")]
@@ -646,7 +776,7 @@ impl FileSystem for RealFileSystem {
             Command::new("explorer")
                 .args(&["/select,", path])
                 .spawn()
-                .map_err(|e| FileSystemError::new(format!("Failed to reveal in Explorer: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to reveal in Explorer: {}", e)))?;
         }
 
         #[cfg(target_os = "linux")]
@@ -656,7 +786,7 @@ impl FileSystem for RealFileSystem {
                 Command::new("xdg-open")
                     .arg(parent)
                     .spawn()
-                    .map_err(|e| FileSystemError::new(format!("Failed to open directory: {}", e)))?;
+                    .map_err(|e| FileSystemError::from_io(&e, format!("Failed to open directory: {}", e)))?;
             }
         }
 
@@ -680,7 +810,7 @@ impl FileSystem for RealFileSystem {
 
         // Проверяем существование пути
         if !expanded.exists() {
-            return Err(FileSystemError::new(format!(
+            return Err(FileSystemError::not_found(format!(
                 "Path does not exist: {}",
                 path
             )));
@@ -689,7 +819,7 @@ impl FileSystem for RealFileSystem {
         // Возвращаем абсолютный путь
         expanded
             .canonicalize()
-            .map_err(|e| FileSystemError::new(format!("Failed to normalize path: {}", e)))?
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to normalize path: {}", e)))?
             .to_str()
             .map(|s| s.to_string())
             .ok_or_else(|| FileSystemError::new("Invalid path encoding"))
@@ -739,7 +869,7 @@ impl FileSystem for RealFileSystem {
 
         // Читаем содержимое директории
         let entries = fs::read_dir(&parent_dir)
-            .map_err(|e| FileSystemError::new(format!("Failed to read directory: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read directory: {}", e)))?;
 
         for entry in entries {
             if let Ok(entry) = entry {
@@ -808,7 +938,7 @@ impl FileSystem for RealFileSystem {
         } else if path.is_dir() {
             path
         } else {
-            return Err(FileSystemError::new(
+            return Err(FileSystemError::not_found(
                 format!("Path does not exist: {}", path.display())
             ));
         };
@@ -821,7 +951,7 @@ impl FileSystem for RealFileSystem {
                 .arg("Terminal")
                 .arg(dir)
                 .spawn()
-                .map_err(|e| FileSystemError::new(
+                .map_err(|e| FileSystemError::from_io(&e,
                     format!("Failed to open terminal: {}", e)
                 ))?;
         }
@@ -868,7 +998,7 @@ impl FileSystem for RealFileSystem {
                 .arg("/D")
                 .arg(dir)
                 .spawn()
-                .map_err(|e| FileSystemError::new(
+                .map_err(|e| FileSystemError::from_io(&e,
                     format!("Failed to open terminal: {}", e)
                 ))?;
         }
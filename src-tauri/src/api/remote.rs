@@ -0,0 +1,533 @@
+//! Общие части удаленных файловых систем (SFTP, WebDAV, S3)
+//!
+//! Пути на удаленной стороне — абсолютные пути через `/`. Корзина хранится на самом
+//! сервере в формате freedesktop.org (`files/` + `info/*.trashinfo`), backend дает
+//! только примитивы через `TrashStore`.
+
+use crate::api::trash::{self, TrashInfo, TRASH_INFO_EXTENSION};
+use crate::core::{FileSystemError, FileSystemResult, TrashEntry};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Mutex;
+
+/// Абсолютный путь: `~` и относительные пути — от `home`,
+/// `.` и `..` сворачиваются (выше `/` подняться нельзя)
+pub(crate) fn normalize_remote_path(home: &str, path: &str) -> String {
+    let path = path.trim();
+    let expanded = if path.is_empty() || path == "~" {
+        home.to_string()
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        format!("{}/{}", home, rest)
+    } else if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("{}/{}", home, path)
+    };
+
+    // Панели на Windows собирают пути через `Path::join`, поэтому `\` тоже разделитель
+    let mut parts: Vec<&str> = Vec::new();
+    for part in expanded.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    format!("/{}", parts.join("/"))
+}
+
+pub(crate) fn remote_join(dir: &str, name: &str) -> String {
+    if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+pub(crate) fn remote_parent(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(index) => &path[..index],
+    }
+}
+
+pub(crate) fn remote_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or("")
+}
+
+/// Поток тела ответа
+pub(crate) type Body = Box<dyn Read + Send + Sync>;
+
+/// Чтение HTTP-ресурса: поток открывается лениво, `seek` переоткрывает его с нового смещения
+pub(crate) struct RangeReader {
+    /// Открыть поток с заданного смещения (обычно GET с `Range`)
+    open: Box<dyn Fn(u64) -> io::Result<Body> + Send>,
+    size: Option<u64>,
+    position: u64,
+    body: Option<Body>,
+}
+
+impl RangeReader {
+    pub(crate) fn new(size: Option<u64>, open: impl Fn(u64) -> io::Result<Body> + Send + 'static) -> Self {
+        Self {
+            open: Box::new(open),
+            size,
+            position: 0,
+            body: None,
+        }
+    }
+
+    /// Пропустить начало потока, если сервер отдал ресурс целиком, не поддержав `Range`
+    pub(crate) fn skip_to(mut body: Body, offset: u64, partial: bool) -> io::Result<Body> {
+        if offset > 0 && !partial {
+            io::copy(&mut (&mut body).take(offset), &mut io::sink())?;
+        }
+        Ok(body)
+    }
+}
+
+impl Read for RangeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.size.is_some_and(|size| self.position >= size) || buf.is_empty() {
+            return Ok(0);
+        }
+        if self.body.is_none() {
+            self.body = Some((self.open)(self.position)?);
+        }
+        let read = self.body.as_mut().unwrap().read(buf)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for RangeReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => offset as i128,
+            SeekFrom::Current(offset) => self.position as i128 + offset as i128,
+            SeekFrom::End(offset) => {
+                let size = self
+                    .size
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "File size is unknown"))?;
+                size as i128 + offset as i128
+            }
+        };
+        if target < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Seek before start of file"));
+        }
+        if target as u64 != self.position {
+            self.position = target as u64;
+            self.body = None;
+        }
+        Ok(self.position)
+    }
+}
+
+/// Блок данных канала между потоками
+enum Chunk {
+    Data(Vec<u8>),
+    End,
+    Failed(String),
+}
+
+/// Канал между потоками без промежуточного файла: данные (возможно, из хранилища) не попадают
+/// на диск. Писатель блокируется, когда в канале `capacity` непрочитанных блоков.
+pub(crate) fn pipe(capacity: usize) -> (PipeWriter, PipeReader) {
+    let (sender, receiver) = mpsc::sync_channel(capacity);
+    (
+        PipeWriter { sender },
+        PipeReader {
+            receiver: Mutex::new(receiver),
+            chunk: Vec::new(),
+            position: 0,
+            done: false,
+        },
+    )
+}
+
+/// Пишущий конец канала; без `close` читатель получит ошибку, а не конец данных
+pub(crate) struct PipeWriter {
+    sender: SyncSender<Chunk>,
+}
+
+impl PipeWriter {
+    /// Конец данных
+    pub(crate) fn close(self) {
+        let _ = self.sender.send(Chunk::End);
+    }
+
+    /// Передать читателю ошибку источника
+    pub(crate) fn fail(self, message: impl Into<String>) {
+        let _ = self.sender.send(Chunk::Failed(message.into()));
+    }
+}
+
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.sender
+            .send(Chunk::Data(buf.to_vec()))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Reader is gone"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Читающий конец канала
+pub(crate) struct PipeReader {
+    /// `Mutex` делает читатель `Sync`, как тело HTTP-ответа
+    receiver: Mutex<Receiver<Chunk>>,
+    chunk: Vec<u8>,
+    position: usize,
+    done: bool,
+}
+
+impl Read for PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.position == self.chunk.len() {
+            if self.done {
+                return Ok(0);
+            }
+            match self.receiver.get_mut().unwrap().recv() {
+                Ok(Chunk::Data(chunk)) => {
+                    self.chunk = chunk;
+                    self.position = 0;
+                }
+                Ok(Chunk::End) => self.done = true,
+                Ok(Chunk::Failed(message)) => return Err(io::Error::other(message)),
+                Err(_) => return Err(io::Error::new(io::ErrorKind::ConnectionAborted, "Writer is gone")),
+            }
+        }
+
+        let read = (self.chunk.len() - self.position).min(buf.len());
+        buf[..read].copy_from_slice(&self.chunk[self.position..self.position + read]);
+        self.position += read;
+        Ok(read)
+    }
+}
+
+/// Примитивы хранилища, нужные корзине
+pub(crate) trait TrashStore {
+    fn exists(&self, path: &str) -> FileSystemResult<bool>;
+    fn create_dir_all(&self, path: &str) -> FileSystemResult<()>;
+    /// Создать файл, только если его нет; `false`, если уже существует
+    fn create_new(&self, path: &str, content: &[u8]) -> FileSystemResult<bool>;
+    fn read(&self, path: &str) -> FileSystemResult<Vec<u8>>;
+    /// `(is_dir, size)` без перехода по ссылкам
+    fn metadata(&self, path: &str) -> FileSystemResult<(bool, Option<u64>)>;
+    /// Переименование без перезаписи
+    fn rename(&self, from: &str, to: &str) -> FileSystemResult<()>;
+    fn remove_all(&self, path: &str) -> FileSystemResult<()>;
+    /// Имена элементов директории
+    fn list_names(&self, dir: &str) -> FileSystemResult<Vec<String>>;
+}
+
+/// Корзина freedesktop.org в каталоге `root` удаленного хранилища
+pub(crate) struct RemoteTrash<'a, S: TrashStore + ?Sized> {
+    store: &'a S,
+    root: String,
+}
+
+impl<'a, S: TrashStore + ?Sized> RemoteTrash<'a, S> {
+    pub(crate) fn new(store: &'a S, root: impl Into<String>) -> Self {
+        Self {
+            store,
+            root: root.into(),
+        }
+    }
+
+    fn files_dir(&self) -> String {
+        remote_join(&self.root, "files")
+    }
+
+    fn info_dir(&self) -> String {
+        remote_join(&self.root, "info")
+    }
+
+    fn info_path(&self, id: &str) -> String {
+        remote_join(&self.info_dir(), &format!("{}.{}", id, TRASH_INFO_EXTENSION))
+    }
+
+    /// Находится ли путь внутри корзины
+    pub(crate) fn contains(&self, path: &str) -> bool {
+        path == self.root || path.starts_with(&format!("{}/", self.root))
+    }
+
+    /// Переместить `path` в корзину
+    pub(crate) fn trash(&self, path: &str) -> FileSystemResult<TrashEntry> {
+        if !self.store.exists(path)? {
+            return Err(FileSystemError::not_found(format!("Path does not exist: {}", path)));
+        }
+        if self.contains(path) {
+            return Err(FileSystemError::permission_denied("Cannot move the trash into itself"));
+        }
+
+        self.store.create_dir_all(&self.files_dir())?;
+        self.store.create_dir_all(&self.info_dir())?;
+
+        let id = self.reserve_id(remote_name(path), &TrashInfo::new(path))?;
+        if let Err(err) = self.store.rename(path, &remote_join(&self.files_dir(), &id)) {
+            let _ = self.store.remove_all(&self.info_path(&id));
+            return Err(err);
+        }
+
+        self.entry(&id)
+    }
+
+    /// Создать `.trashinfo` с уникальным именем
+    fn reserve_id(&self, name: &str, info: &TrashInfo) -> FileSystemResult<String> {
+        let content = info.to_trashinfo();
+
+        let mut attempt = 0u32;
+        loop {
+            attempt += 1;
+            let id = if attempt == 1 {
+                name.to_string()
+            } else {
+                format!("{}.{}", name, attempt)
+            };
+
+            if self.store.exists(&remote_join(&self.files_dir(), &id))? {
+                continue;
+            }
+            if self.store.create_new(&self.info_path(&id), content.as_bytes())? {
+                return Ok(id);
+            }
+        }
+    }
+
+    pub(crate) fn entry(&self, id: &str) -> FileSystemResult<TrashEntry> {
+        trash::validate_id(id)?;
+
+        let content = self
+            .store
+            .read(&self.info_path(id))
+            .map_err(|_| FileSystemError::not_found(format!("Trash item not found: {}", id)))?;
+        let info = TrashInfo::parse(&String::from_utf8_lossy(&content))
+            .ok_or_else(|| FileSystemError::new(format!("Invalid trash info: {}", id)))?;
+        let (is_dir, size) = self.store.metadata(&remote_join(&self.files_dir(), id))?;
+
+        Ok(trash::to_entry(id, info, is_dir, size))
+    }
+
+    /// Содержимое корзины, новые элементы первыми
+    pub(crate) fn list(&self) -> FileSystemResult<Vec<TrashEntry>> {
+        if !self.store.exists(&self.info_dir())? {
+            return Ok(Vec::new());
+        }
+
+        let suffix = format!(".{}", TRASH_INFO_EXTENSION);
+        let mut entries: Vec<TrashEntry> = self
+            .store
+            .list_names(&self.info_dir())?
+            .iter()
+            .filter_map(|name| name.strip_suffix(&suffix))
+            .filter_map(|id| self.entry(id).ok())
+            .collect();
+
+        entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(entries)
+    }
+
+    /// Восстановить элемент по исходному пути; возвращает запись и этот путь
+    pub(crate) fn restore(&self, id: &str) -> FileSystemResult<(TrashEntry, String)> {
+        let entry = self.entry(id)?;
+        let target = normalize_remote_path("/", &entry.original_path);
+
+        if self.store.exists(&target)? {
+            return Err(FileSystemError::already_exists(format!(
+                "Cannot restore, path already exists: {}",
+                entry.original_path
+            )));
+        }
+        self.store.create_dir_all(remote_parent(&target))?;
+        self.store.rename(&remote_join(&self.files_dir(), id), &target)?;
+        let _ = self.store.remove_all(&self.info_path(id));
+
+        Ok((entry, target))
+    }
+
+    /// Окончательно удалить элемент корзины
+    pub(crate) fn remove(&self, id: &str) -> FileSystemResult<()> {
+        trash::validate_id(id)?;
+
+        for path in [remote_join(&self.files_dir(), id), self.info_path(id)] {
+            if self.store.exists(&path)? {
+                self.store.remove_all(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Удалить элементы, для которых `predicate` истинен
+    pub(crate) fn purge_where(&self, predicate: impl Fn(&TrashEntry) -> bool) -> FileSystemResult<usize> {
+        let mut removed = 0;
+        for entry in self.list()?.into_iter().filter(|entry| predicate(entry)) {
+            self.remove(&entry.id)?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// Хранилище в памяти: путь → `None` для папки, `Some(данные)` для файла
+    #[derive(Default)]
+    struct MemoryStore {
+        nodes: Mutex<BTreeMap<String, Option<Vec<u8>>>>,
+    }
+
+    impl TrashStore for MemoryStore {
+        fn exists(&self, path: &str) -> FileSystemResult<bool> {
+            Ok(path == "/" || self.nodes.lock().unwrap().contains_key(path))
+        }
+
+        fn create_dir_all(&self, path: &str) -> FileSystemResult<()> {
+            let mut current = path;
+            let mut nodes = self.nodes.lock().unwrap();
+            while current != "/" {
+                nodes.entry(current.to_string()).or_insert(None);
+                current = remote_parent(current);
+            }
+            Ok(())
+        }
+
+        fn create_new(&self, path: &str, content: &[u8]) -> FileSystemResult<bool> {
+            let mut nodes = self.nodes.lock().unwrap();
+            if nodes.contains_key(path) {
+                return Ok(false);
+            }
+            nodes.insert(path.to_string(), Some(content.to_vec()));
+            Ok(true)
+        }
+
+        fn read(&self, path: &str) -> FileSystemResult<Vec<u8>> {
+            self.nodes
+                .lock()
+                .unwrap()
+                .get(path)
+                .cloned()
+                .flatten()
+                .ok_or_else(|| FileSystemError::not_found(path))
+        }
+
+        fn metadata(&self, path: &str) -> FileSystemResult<(bool, Option<u64>)> {
+            match self.nodes.lock().unwrap().get(path) {
+                Some(None) => Ok((true, None)),
+                Some(Some(data)) => Ok((false, Some(data.len() as u64))),
+                None => Err(FileSystemError::not_found(path)),
+            }
+        }
+
+        fn rename(&self, from: &str, to: &str) -> FileSystemResult<()> {
+            let mut nodes = self.nodes.lock().unwrap();
+            let moved: Vec<String> = nodes
+                .keys()
+                .filter(|key| *key == from || key.starts_with(&format!("{}/", from)))
+                .cloned()
+                .collect();
+            for key in moved {
+                let value = nodes.remove(&key).unwrap();
+                nodes.insert(format!("{}{}", to, &key[from.len()..]), value);
+            }
+            Ok(())
+        }
+
+        fn remove_all(&self, path: &str) -> FileSystemResult<()> {
+            self.nodes
+                .lock()
+                .unwrap()
+                .retain(|key, _| key != path && !key.starts_with(&format!("{}/", path)));
+            Ok(())
+        }
+
+        fn list_names(&self, dir: &str) -> FileSystemResult<Vec<String>> {
+            Ok(self
+                .nodes
+                .lock()
+                .unwrap()
+                .keys()
+                .filter(|key| remote_parent(key) == dir)
+                .map(|key| remote_name(key).to_string())
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_normalize_remote_path() {
+        assert_eq!(normalize_remote_path("/home/dev", ""), "/home/dev");
+        assert_eq!(normalize_remote_path("/home/dev", "~"), "/home/dev");
+        assert_eq!(normalize_remote_path("/home/dev", "~/src/app"), "/home/dev/src/app");
+        assert_eq!(normalize_remote_path("/home/dev", "logs"), "/home/dev/logs");
+        assert_eq!(normalize_remote_path("/home/dev", "/var/./log/../www/"), "/var/www");
+        assert_eq!(normalize_remote_path("/home/dev", "/../../etc"), "/etc");
+        assert_eq!(normalize_remote_path("/home/dev", "/srv\\data\\a.txt"), "/srv/data/a.txt");
+        assert_eq!(remote_parent("/srv/data"), "/srv");
+        assert_eq!(remote_parent("/srv"), "/");
+        assert_eq!(remote_join("/", "srv"), "/srv");
+    }
+
+    #[test]
+    fn test_pipe_needs_close() {
+        let (mut writer, mut reader) = pipe(4);
+        writer.write_all(b"hello ").unwrap();
+        writer.write_all(b"world").unwrap();
+        writer.close();
+        let mut content = Vec::new();
+        reader.read_to_end(&mut content).unwrap();
+        assert_eq!(content, b"hello world");
+
+        // Писатель брошен без `close`
+        let (mut writer, mut reader) = pipe(4);
+        writer.write_all(b"partial").unwrap();
+        drop(writer);
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionAborted);
+
+        let (writer, mut reader) = pipe(4);
+        writer.fail("broken entry");
+        assert_eq!(reader.read(&mut [0u8; 4]).unwrap_err().to_string(), "broken entry");
+    }
+
+    #[test]
+    fn test_remote_trash_roundtrip() {
+        let store = MemoryStore::default();
+        store.create_dir_all("/docs").unwrap();
+        store.create_new("/docs/a.txt", b"first").unwrap();
+        let trash = RemoteTrash::new(&store, "/.trash");
+
+        let first = trash.trash("/docs/a.txt").unwrap();
+        assert_eq!(first.id, "a.txt");
+        assert_eq!(first.size, Some(5));
+        assert!(!store.exists("/docs/a.txt").unwrap());
+
+        store.create_new("/docs/a.txt", b"second!").unwrap();
+        let second = trash.trash("/docs/a.txt").unwrap();
+        assert_eq!(second.id, "a.txt.2");
+        assert_eq!(trash.list().unwrap().len(), 2);
+        assert!(trash.trash("/.trash/files/a.txt").is_err());
+
+        let (restored, target) = trash.restore("a.txt").unwrap();
+        assert_eq!(restored.original_path, "/docs/a.txt");
+        assert_eq!(target, "/docs/a.txt");
+        assert_eq!(store.read("/docs/a.txt").unwrap(), b"first");
+        assert!(trash.restore("a.txt.2").is_err());
+
+        assert_eq!(trash.purge_where(|_| true).unwrap(), 1);
+        assert!(trash.list().unwrap().is_empty());
+    }
+}
@@ -4,7 +4,7 @@ use argon2::{Argon2, Algorithm, Version, Params};
 use sha2::{Sha256, Digest};
 use subtle::ConstantTimeEq;
 use aes_gcm::{
-    aead::{Aead, KeyInit},
+    aead::{Aead, KeyInit, Payload},
     Aes256Gcm, Nonce,
 };
 use rand::{Rng, rngs::OsRng};
@@ -21,6 +21,7 @@ const ARGON_T_COST: u32 = 2;     // 2 iterations
 const ARGON_P_COST: u32 = 1;     // 1 thread (parallelism)
 const SALT_LENGTH: usize = 16;   // 16 bytes for salt
 const NONCE_LENGTH: usize = 12;  // 12 bytes for AES-GCM nonce
+pub const STREAM_NONCE_PREFIX_LENGTH: usize = 8; // random part of per-chunk nonce
 
 /// Configuration saved in vault.meta
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -181,6 +182,59 @@ pub fn decrypt_blob(blob: &[u8], session: &VaultSession) -> VaultResult<Vec<u8>>
         .map_err(|_| VaultError::DecryptionFailed)
 }
 
+// ==================== STREAM (CHUNKED) ENCRYPTION ====================
+
+/// Generate a random nonce prefix for a chunked stream
+pub fn generate_stream_nonce_prefix() -> [u8; STREAM_NONCE_PREFIX_LENGTH] {
+    let mut prefix = [0u8; STREAM_NONCE_PREFIX_LENGTH];
+    OsRng.fill(&mut prefix);
+    prefix
+}
+
+/// Build nonce for chunk: [Prefix (8 bytes)] + [Chunk index (u32 BE)]
+fn stream_chunk_nonce(prefix: &[u8; STREAM_NONCE_PREFIX_LENGTH], index: u32) -> [u8; NONCE_LENGTH] {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    nonce[..STREAM_NONCE_PREFIX_LENGTH].copy_from_slice(prefix);
+    nonce[STREAM_NONCE_PREFIX_LENGTH..].copy_from_slice(&index.to_be_bytes());
+    nonce
+}
+
+/// Encrypt one chunk of a stream using AES-256-GCM
+/// The `last` flag is authenticated as AAD, so truncated streams are detected.
+/// Returns: [Ciphertext + AuthTag]
+pub fn encrypt_stream_chunk(
+    chunk: &[u8],
+    prefix: &[u8; STREAM_NONCE_PREFIX_LENGTH],
+    index: u32,
+    last: bool,
+    session: &VaultSession,
+) -> VaultResult<Vec<u8>> {
+    let cipher = Aes256Gcm::new(&session.master_key.into());
+    let nonce_bytes = stream_chunk_nonce(prefix, index);
+    let aad = [last as u8];
+
+    cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), Payload { msg: chunk, aad: &aad })
+        .map_err(|_| VaultError::EncryptionFailed)
+}
+
+/// Decrypt one chunk of a stream using AES-256-GCM
+pub fn decrypt_stream_chunk(
+    ciphertext: &[u8],
+    prefix: &[u8; STREAM_NONCE_PREFIX_LENGTH],
+    index: u32,
+    last: bool,
+    session: &VaultSession,
+) -> VaultResult<Vec<u8>> {
+    let cipher = Aes256Gcm::new(&session.master_key.into());
+    let nonce_bytes = stream_chunk_nonce(prefix, index);
+    let aad = [last as u8];
+
+    cipher
+        .decrypt(Nonce::from_slice(&nonce_bytes), Payload { msg: ciphertext, aad: &aad })
+        .map_err(|_| VaultError::DecryptionFailed)
+}
+
 // ==================== ATOMIC FILE OPERATIONS ====================
 
 /// Atomic write using temp file + rename
@@ -272,4 +326,20 @@ mod tests {
         // Trying to decrypt with wrong key should fail
         assert!(decrypt_blob(&encrypted, &session2).is_err());
     }
+
+    #[test]
+    fn test_stream_chunk_last_flag_is_authenticated() {
+        let session = VaultSession::new([7u8; 32]);
+        let prefix = generate_stream_nonce_prefix();
+
+        let encrypted = encrypt_stream_chunk(b"chunk data", &prefix, 3, true, &session).unwrap();
+        assert_eq!(
+            decrypt_stream_chunk(&encrypted, &prefix, 3, true, &session).unwrap(),
+            b"chunk data"
+        );
+
+        // Wrong index or a non-final chunk presented as final must fail
+        assert!(decrypt_stream_chunk(&encrypted, &prefix, 2, true, &session).is_err());
+        assert!(decrypt_stream_chunk(&encrypted, &prefix, 3, false, &session).is_err());
+    }
 }
@@ -0,0 +1,907 @@
+//! Файловая система на удаленном сервере по SFTP
+//!
+//! Панель выбирает подключение через `panel_fs = "sftp:<id профиля>"`. Пути — абсолютные
+//! пути на сервере, `~` раскрывается в домашнюю папку пользователя.
+//!
+//! Корзина — `~/.local/share/Trash` на сервере в формате freedesktop.org, как и у
+//! `RealFileSystem`, поэтому удаленные элементы видны и в корзине рабочего стола сервера.
+
+use crate::api::remote::{
+    normalize_remote_path, remote_join, remote_name, remote_parent, RemoteTrash, TrashStore,
+};
+use crate::api::watcher::{ChangeType, WatchBackend, WATCHER};
+use crate::config::{SftpAuth, SftpProfile};
+use crate::core::{
+    guess_mime_type, DirectoryPage, ErrorKind, FileReader, FileSystem, FileSystemEntry,
+    FileSystemError, FileSystemResult, FileWriter, ListOptions, TrashEntry,
+};
+use base64::{engine::general_purpose, Engine as _};
+use ssh2::{
+    CheckResult, ErrorCode, FileStat, HashType, KnownHostFileKind, OpenFlags, OpenType, Session, Sftp,
+};
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// Таймаут одной операции libssh2 (мс)
+const SESSION_TIMEOUT_MS: u32 = 60_000;
+const DIR_MODE: i32 = 0o755;
+const FILE_MODE: i32 = 0o644;
+
+// Коды статуса SFTP (draft-ietf-secsh-filexfer)
+const FX_NO_SUCH_FILE: i32 = 2;
+const FX_PERMISSION_DENIED: i32 = 3;
+const FX_NO_SUCH_PATH: i32 = 10;
+const FX_FILE_ALREADY_EXISTS: i32 = 11;
+const FX_WRITE_PROTECT: i32 = 12;
+const FX_NO_SPACE_ON_FILESYSTEM: i32 = 14;
+const FX_QUOTA_EXCEEDED: i32 = 15;
+const FX_LOCK_CONFLICT: i32 = 17;
+const FX_NOT_A_DIRECTORY: i32 = 19;
+const FX_FILE_IS_A_DIRECTORY: i32 = 24;
+
+/// Категория ошибки по коду libssh2 / SFTP
+pub(crate) fn error_kind(code: ErrorCode) -> ErrorKind {
+    match code {
+        ErrorCode::SFTP(FX_NO_SUCH_FILE | FX_NO_SUCH_PATH) => ErrorKind::NotFound,
+        ErrorCode::SFTP(FX_PERMISSION_DENIED | FX_WRITE_PROTECT) => ErrorKind::PermissionDenied,
+        ErrorCode::SFTP(FX_FILE_ALREADY_EXISTS) => ErrorKind::AlreadyExists,
+        ErrorCode::SFTP(FX_NO_SPACE_ON_FILESYSTEM | FX_QUOTA_EXCEEDED) => ErrorKind::QuotaExceeded,
+        ErrorCode::SFTP(FX_LOCK_CONFLICT) => ErrorKind::Locked,
+        ErrorCode::SFTP(FX_NOT_A_DIRECTORY) => ErrorKind::NotADirectory,
+        ErrorCode::SFTP(FX_FILE_IS_A_DIRECTORY) => ErrorKind::IsADirectory,
+        _ => ErrorKind::Io,
+    }
+}
+
+fn sftp_error(err: ssh2::Error, context: impl std::fmt::Display) -> FileSystemError {
+    FileSystemError::with_kind(error_kind(err.code()), format!("{}: {}", context, err.message()))
+}
+
+/// Запись по атрибутам SFTP; для ссылки `target` — атрибуты объекта, на который она указывает
+pub(crate) fn stat_to_entry(
+    path: &str,
+    stat: &FileStat,
+    target: Option<&FileStat>,
+    symlink_target: Option<String>,
+) -> FileSystemEntry {
+    let is_symlink = stat.file_type().is_symlink();
+    let resolved = target.unwrap_or(stat);
+    let name = if path == "/" { "/".to_string() } else { remote_name(path).to_string() };
+
+    FileSystemEntry {
+        path: path.to_string(),
+        is_dir: resolved.is_dir(),
+        is_file: resolved.is_file(),
+        size: if resolved.is_file() { resolved.size } else { None },
+        modified: resolved.mtime,
+        accessed: resolved.atime,
+        mode: resolved.perm,
+        uid: resolved.uid,
+        gid: resolved.gid,
+        is_symlink,
+        symlink_target,
+        is_hidden: name.starts_with('.'),
+        mime_type: guess_mime_type(&name, resolved.is_dir()),
+        name,
+        ..Default::default()
+    }
+}
+
+fn expand_local_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Ошибка подключения по SFTP
+#[derive(Debug)]
+pub enum SftpConnectError {
+    /// Ключа сервера нет ни в профиле, ни в `known_hosts`: подключение возможно,
+    /// когда пользователь подтвердит отпечаток
+    UnknownHostKey { fingerprint: String },
+    Failed(FileSystemError),
+}
+
+impl From<FileSystemError> for SftpConnectError {
+    fn from(err: FileSystemError) -> Self {
+        SftpConnectError::Failed(err)
+    }
+}
+
+/// Отпечаток ключа сервера в формате OpenSSH (`SHA256:<base64>`)
+fn host_key_fingerprint(session: &Session) -> Option<String> {
+    session
+        .host_key_hash(HashType::Sha256)
+        .map(|hash| format!("SHA256:{}", general_purpose::STANDARD_NO_PAD.encode(hash)))
+}
+
+/// Проверка ключа сервера; возвращает его отпечаток
+///
+/// Закрепленный в профиле ключ (`pinned`) должен совпасть. Без него ключ сверяется
+/// с `~/.ssh/known_hosts`: несовпадение — отказ, неизвестный ключ принимается, только
+/// если пользователь подтвердил его отпечаток (`accepted`).
+fn verify_host_key(
+    session: &Session,
+    host: &str,
+    port: u16,
+    pinned: Option<&str>,
+    accepted: Option<&str>,
+) -> Result<String, SftpConnectError> {
+    let (key, _) = session
+        .host_key()
+        .ok_or_else(|| FileSystemError::new(format!("Server {} did not send a host key", host)))?;
+    let fingerprint = host_key_fingerprint(session)
+        .ok_or_else(|| FileSystemError::new(format!("Failed to hash the host key of {}", host)))?;
+
+    if let Some(pinned) = pinned {
+        if pinned != fingerprint {
+            return Err(FileSystemError::permission_denied(format!(
+                "Host key of {}:{} has changed: expected {}, got {}",
+                host, port, pinned, fingerprint
+            ))
+            .into());
+        }
+        return Ok(fingerprint);
+    }
+
+    let mut known_hosts = session
+        .known_hosts()
+        .map_err(|e| sftp_error(e, "Failed to init known hosts"))?;
+    if let Some(path) = dirs::home_dir().map(|home| home.join(".ssh").join("known_hosts")) {
+        if path.exists() {
+            if let Err(err) = known_hosts.read_file(&path, KnownHostFileKind::OpenSSH) {
+                tracing::warn!("Failed to read {}: {}", path.display(), err);
+            }
+        }
+    }
+
+    match known_hosts.check_port(host, port, key) {
+        CheckResult::Match => Ok(fingerprint),
+        CheckResult::Mismatch => Err(FileSystemError::permission_denied(format!(
+            "Host key of {}:{} does not match known_hosts",
+            host, port
+        ))
+        .into()),
+        CheckResult::NotFound | CheckResult::Failure if accepted == Some(fingerprint.as_str()) => {
+            tracing::info!("Host key {} of {}:{} accepted by the user", fingerprint, host, port);
+            Ok(fingerprint)
+        }
+        CheckResult::NotFound | CheckResult::Failure => Err(SftpConnectError::UnknownHostKey { fingerprint }),
+    }
+}
+
+struct SftpConnection {
+    session: Session,
+    sftp: Sftp,
+    home: String,
+    host_key: String,
+}
+
+/// SFTP-подключение по сохраненному профилю
+///
+/// Клоны разделяют одну SSH-сессию. Экземпляр без подключения (`disconnected`)
+/// отвечает на все вызовы ошибкой `Locked`: панель отключенного сервера не должна
+/// молча переключаться на локальную ФС.
+#[derive(Clone)]
+pub struct SftpFileSystem {
+    label: String,
+    connection: Option<Arc<SftpConnection>>,
+}
+
+impl SftpFileSystem {
+    /// Подключиться к серверу профиля
+    ///
+    /// `secret` заменяет сохраненный пароль или парольную фразу ключа. `accepted_host_key` —
+    /// подтвержденный пользователем отпечаток сервера, которого нет в `known_hosts`.
+    pub fn connect(
+        label: &str,
+        profile: &SftpProfile,
+        secret: Option<&str>,
+        accepted_host_key: Option<&str>,
+    ) -> Result<Self, SftpConnectError> {
+        tracing::info!("Connecting to sftp://{}@{}:{}", profile.username, profile.host, profile.port);
+
+        let addresses = (profile.host.as_str(), profile.port)
+            .to_socket_addrs()
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to resolve {}: {}", profile.host, e)))?;
+        let mut last_error = None;
+        let mut stream = None;
+        for address in addresses {
+            match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
+                Ok(tcp) => {
+                    stream = Some(tcp);
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let stream = stream.ok_or_else(|| match last_error {
+            Some(e) => FileSystemError::from_io(&e, format!("Failed to connect to {}: {}", profile.host, e)),
+            None => FileSystemError::not_found(format!("No address found for {}", profile.host)),
+        })?;
+
+        let mut session = Session::new().map_err(|e| sftp_error(e, "Failed to create SSH session"))?;
+        session.set_tcp_stream(stream);
+        session.set_timeout(SESSION_TIMEOUT_MS);
+        session.handshake().map_err(|e| sftp_error(e, "SSH handshake failed"))?;
+        let host_key = verify_host_key(
+            &session,
+            &profile.host,
+            profile.port,
+            profile.host_key.as_deref(),
+            accepted_host_key,
+        )?;
+
+        let auth_result = match &profile.auth {
+            SftpAuth::Password { password } => {
+                let password = secret.or(password.as_deref()).ok_or_else(|| {
+                    FileSystemError::permission_denied(format!("Password required for {}", profile.name))
+                })?;
+                session.userauth_password(&profile.username, password)
+            }
+            SftpAuth::Key { private_key_path, passphrase } => session.userauth_pubkey_file(
+                &profile.username,
+                None,
+                &expand_local_home(private_key_path),
+                secret.or(passphrase.as_deref()),
+            ),
+        };
+        auth_result.map_err(|e| {
+            FileSystemError::permission_denied(format!("Authentication failed for {}: {}", profile.username, e.message()))
+        })?;
+        if !session.authenticated() {
+            return Err(FileSystemError::permission_denied(format!(
+                "Authentication failed for {}",
+                profile.username
+            ))
+            .into());
+        }
+
+        let sftp = session.sftp().map_err(|e| sftp_error(e, "Failed to start SFTP subsystem"))?;
+        let home = sftp
+            .realpath(Path::new("."))
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|_| "/".to_string());
+
+        Ok(Self {
+            label: label.to_string(),
+            connection: Some(Arc::new(SftpConnection { session, sftp, home, host_key })),
+        })
+    }
+
+    /// Заглушка для панели, чей сервер не подключен
+    pub fn disconnected(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            connection: None,
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    /// Домашняя папка на сервере (`/` без подключения)
+    pub fn home(&self) -> &str {
+        self.connection.as_ref().map(|c| c.home.as_str()).unwrap_or("/")
+    }
+
+    /// Отпечаток ключа подключенного сервера
+    pub fn host_key(&self) -> Option<&str> {
+        self.connection.as_ref().map(|c| c.host_key.as_str())
+    }
+
+    /// Закрыть SSH-сессию (у всех клонов)
+    pub fn disconnect(&self) {
+        if let Some(connection) = &self.connection {
+            let _ = connection.session.disconnect(None, "Disconnected by user", None);
+        }
+    }
+
+    fn connection(&self) -> FileSystemResult<&SftpConnection> {
+        self.connection
+            .as_deref()
+            .ok_or_else(|| FileSystemError::locked(format!("SFTP session '{}' is not connected", self.label)))
+    }
+
+    fn sftp(&self) -> FileSystemResult<&Sftp> {
+        self.connection().map(|c| &c.sftp)
+    }
+
+    fn resolve(&self, path: &str) -> FileSystemResult<String> {
+        Ok(normalize_remote_path(&self.connection()?.home, path))
+    }
+
+    /// Метка сессии (`panel_fs`)
+    pub(crate) fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn notify_change(&self, path: &str, change_type: ChangeType) {
+        WATCHER.notify_change(WatchBackend::Remote, Some(&self.label), path, change_type);
+    }
+
+    fn lstat(&self, path: &str) -> FileSystemResult<FileStat> {
+        self.sftp()?
+            .lstat(Path::new(path))
+            .map_err(|e| sftp_error(e, format!("Failed to stat {}", path)))
+    }
+
+    fn exists(&self, path: &str) -> FileSystemResult<bool> {
+        match self.sftp()?.lstat(Path::new(path)) {
+            Ok(_) => Ok(true),
+            Err(e) if error_kind(e.code()) == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(sftp_error(e, format!("Failed to stat {}", path))),
+        }
+    }
+
+    /// Запись с раскрытием символической ссылки
+    fn entry(&self, path: &str, stat: &FileStat) -> FileSystemResult<FileSystemEntry> {
+        if !stat.file_type().is_symlink() {
+            return Ok(stat_to_entry(path, stat, None, None));
+        }
+        let sftp = self.sftp()?;
+        let target = sftp.stat(Path::new(path)).ok();
+        let link = sftp
+            .readlink(Path::new(path))
+            .ok()
+            .map(|target| target.to_string_lossy().to_string());
+        Ok(stat_to_entry(path, stat, target.as_ref(), link))
+    }
+
+    fn read_dir_raw(&self, path: &str) -> FileSystemResult<Vec<(String, FileStat)>> {
+        let entries = self
+            .sftp()?
+            .readdir(Path::new(path))
+            .map_err(|e| sftp_error(e, format!("Failed to read directory {}", path)))?;
+        Ok(entries
+            .into_iter()
+            .filter_map(|(child, stat)| {
+                let name = child.file_name()?.to_string_lossy().to_string();
+                Some((remote_join(path, &name), stat))
+            })
+            .collect())
+    }
+
+    fn mkdir(&self, path: &str) -> FileSystemResult<()> {
+        self.sftp()?
+            .mkdir(Path::new(path), DIR_MODE)
+            .map_err(|e| sftp_error(e, format!("Failed to create folder {}", path)))
+    }
+
+    /// `mkdir -p`
+    fn create_dir_all(&self, path: &str) -> FileSystemResult<()> {
+        if path == "/" || self.exists(path)? {
+            return Ok(());
+        }
+        self.create_dir_all(remote_parent(path))?;
+        self.mkdir(path)
+    }
+
+    fn rename_raw(&self, from: &str, to: &str) -> FileSystemResult<()> {
+        self.sftp()?
+            .rename(Path::new(from), Path::new(to), None)
+            .map_err(|e| sftp_error(e, format!("Failed to move {} to {}", from, to)))
+    }
+
+    /// Рекурсивное удаление; ссылки удаляются, а не обходятся
+    fn remove_recursive(&self, path: &str) -> FileSystemResult<()> {
+        let stat = self.lstat(path)?;
+        let sftp = self.sftp()?;
+        if stat.is_dir() {
+            for (child, _) in self.read_dir_raw(path)? {
+                self.remove_recursive(&child)?;
+            }
+            sftp.rmdir(Path::new(path))
+                .map_err(|e| sftp_error(e, format!("Failed to delete directory {}", path)))
+        } else {
+            sftp.unlink(Path::new(path))
+                .map_err(|e| sftp_error(e, format!("Failed to delete file {}", path)))
+        }
+    }
+
+    /// Копирование на сервере: в SFTP нет серверного копирования, данные проходят через клиента.
+    /// Ссылки копируются как ссылки, а не обходятся (иначе цикл ссылок не завершится)
+    fn copy_recursive(&self, from: &str, to: &str) -> FileSystemResult<()> {
+        let sftp = self.sftp()?;
+        let stat = self.lstat(from)?;
+        if stat.file_type().is_symlink() {
+            let target = sftp
+                .readlink(Path::new(from))
+                .map_err(|e| sftp_error(e, format!("Failed to read link {}", from)))?;
+            sftp.symlink(&target, Path::new(to))
+                .map_err(|e| sftp_error(e, format!("Failed to create link {}", to)))?;
+        } else if stat.is_dir() {
+            self.mkdir(to)?;
+            for (child, _) in self.read_dir_raw(from)? {
+                self.copy_recursive(&child, &remote_join(to, remote_name(&child)))?;
+            }
+        } else {
+            let mut source = sftp
+                .open(Path::new(from))
+                .map_err(|e| sftp_error(e, format!("Failed to open {}", from)))?;
+            let mut destination = sftp
+                .create(Path::new(to))
+                .map_err(|e| sftp_error(e, format!("Failed to create {}", to)))?;
+            io::copy(&mut source, &mut destination)
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to copy {}: {}", from, e)))?;
+        }
+        Ok(())
+    }
+
+    fn read_all(&self, path: &str) -> FileSystemResult<Vec<u8>> {
+        let mut file = self
+            .sftp()?
+            .open(Path::new(path))
+            .map_err(|e| sftp_error(e, format!("Failed to open {}", path)))?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read {}: {}", path, e)))?;
+        Ok(content)
+    }
+
+    fn write_all(&self, path: &str, content: &[u8], change_type: ChangeType) -> FileSystemResult<()> {
+        if !self.exists(remote_parent(path))? {
+            return Err(FileSystemError::not_found("Parent directory does not exist"));
+        }
+        let mut file = self
+            .sftp()?
+            .create(Path::new(path))
+            .map_err(|e| sftp_error(e, format!("Failed to create {}", path)))?;
+        file.write_all(content)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write {}: {}", path, e)))?;
+        self.notify_change(path, change_type);
+        Ok(())
+    }
+
+    fn trash(&self) -> FileSystemResult<RemoteTrash<'_, Self>> {
+        Ok(RemoteTrash::new(self, remote_join(&self.connection()?.home, ".local/share/Trash")))
+    }
+}
+
+impl FileWriter for ssh2::File {
+    fn finish(mut self: Box<Self>) -> FileSystemResult<()> {
+        self.flush()
+            .map_err(|e| FileSystemError::new(format!("Failed to flush file: {}", e)))
+    }
+}
+
+impl TrashStore for SftpFileSystem {
+    fn exists(&self, path: &str) -> FileSystemResult<bool> {
+        SftpFileSystem::exists(self, path)
+    }
+
+    fn create_dir_all(&self, path: &str) -> FileSystemResult<()> {
+        SftpFileSystem::create_dir_all(self, path)
+    }
+
+    fn create_new(&self, path: &str, content: &[u8]) -> FileSystemResult<bool> {
+        let flags = OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::EXCLUSIVE;
+        match self.sftp()?.open_mode(Path::new(path), flags, FILE_MODE, OpenType::File) {
+            Ok(mut file) => {
+                file.write_all(content)
+                    .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write {}: {}", path, e)))?;
+                Ok(true)
+            }
+            // Многие серверы отвечают на EXCLUSIVE общим FAILURE, поэтому проверяем сами
+            Err(_) if SftpFileSystem::exists(self, path)? => Ok(false),
+            Err(e) => Err(sftp_error(e, format!("Failed to create {}", path))),
+        }
+    }
+
+    fn read(&self, path: &str) -> FileSystemResult<Vec<u8>> {
+        self.read_all(path)
+    }
+
+    fn metadata(&self, path: &str) -> FileSystemResult<(bool, Option<u64>)> {
+        let stat = self.lstat(path)?;
+        Ok((stat.is_dir(), if stat.is_file() { stat.size } else { None }))
+    }
+
+    fn rename(&self, from: &str, to: &str) -> FileSystemResult<()> {
+        self.rename_raw(from, to)
+    }
+
+    fn remove_all(&self, path: &str) -> FileSystemResult<()> {
+        self.remove_recursive(path)
+    }
+
+    fn list_names(&self, dir: &str) -> FileSystemResult<Vec<String>> {
+        Ok(self
+            .read_dir_raw(dir)?
+            .iter()
+            .map(|(path, _)| remote_name(path).to_string())
+            .collect())
+    }
+}
+
+impl FileSystem for SftpFileSystem {
+    fn read_directory_with_options(
+        &self,
+        path: &str,
+        options: &ListOptions,
+    ) -> FileSystemResult<DirectoryPage> {
+        let dir = self.resolve(path)?;
+        let name_filter = options.name_filter()?;
+
+        let mut result = Vec::new();
+        for (child, stat) in self.read_dir_raw(&dir)? {
+            if !name_filter.matches(remote_name(&child)) {
+                continue;
+            }
+            match self.entry(&child, &stat) {
+                Ok(entry) if entry.is_hidden && !options.include_hidden() => continue,
+                Ok(entry) => result.push(entry),
+                Err(_) => continue,
+            }
+        }
+
+        result.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        });
+
+        options.apply(result)
+    }
+
+    fn get_file_info(&self, path: &str) -> FileSystemResult<FileSystemEntry> {
+        let path = self.resolve(path)?;
+        let stat = self.lstat(&path)?;
+        self.entry(&path, &stat)
+    }
+
+    fn delete_item(&self, path: &str) -> FileSystemResult<()> {
+        let path = self.resolve(path)?;
+        if path == "/" {
+            return Err(FileSystemError::permission_denied("Cannot delete the root directory"));
+        }
+        self.remove_recursive(&path)?;
+        self.notify_change(&path, ChangeType::Deleted);
+        Ok(())
+    }
+
+    fn trash_item(&self, path: &str) -> FileSystemResult<TrashEntry> {
+        let path = self.resolve(path)?;
+        if path == "/" || path == self.home() {
+            return Err(FileSystemError::permission_denied("Cannot move this folder to trash"));
+        }
+        let entry = self.trash()?.trash(&path)?;
+        self.notify_change(&path, ChangeType::Deleted);
+        Ok(entry)
+    }
+
+    fn list_trash(&self) -> FileSystemResult<Vec<TrashEntry>> {
+        self.trash()?.list()
+    }
+
+    fn restore_from_trash(&self, id: &str) -> FileSystemResult<TrashEntry> {
+        let (entry, target) = self.trash()?.restore(id)?;
+        self.notify_change(&target, ChangeType::Created);
+        Ok(entry)
+    }
+
+    fn empty_trash(&self) -> FileSystemResult<usize> {
+        self.trash()?.purge_where(|_| true)
+    }
+
+    fn purge_trash_older_than(&self, cutoff: u64) -> FileSystemResult<usize> {
+        self.trash()?.purge_where(|entry| entry.deleted_at < cutoff)
+    }
+
+    fn rename_item(&self, old_path: &str, new_name: &str) -> FileSystemResult<()> {
+        let old_path = self.resolve(old_path)?;
+        if new_name.is_empty() || new_name.contains('/') {
+            return Err(FileSystemError::with_kind(ErrorKind::InvalidInput, format!("Invalid name: {}", new_name)));
+        }
+        self.lstat(&old_path)?;
+
+        let new_path = remote_join(remote_parent(&old_path), new_name);
+        if self.exists(&new_path)? {
+            return Err(FileSystemError::already_exists(format!("File already exists: {}", new_name)));
+        }
+        self.rename_raw(&old_path, &new_path)?;
+
+        WATCHER.notify_rename(WatchBackend::Remote, Some(&self.label), &old_path, &new_path);
+        Ok(())
+    }
+
+    fn create_folder(&self, path: &str, name: &str) -> FileSystemResult<()> {
+        let parent = self.resolve(path)?;
+        if !self.exists(&parent)? {
+            return Err(FileSystemError::not_found(format!("Parent directory does not exist: {}", path)));
+        }
+
+        let new_path = remote_join(&parent, name);
+        if self.exists(&new_path)? {
+            return Err(FileSystemError::already_exists(format!("Folder already exists: {}", name)));
+        }
+        self.mkdir(&new_path)?;
+
+        self.notify_change(&new_path, ChangeType::Created);
+        Ok(())
+    }
+
+    fn create_file(&self, path: &str, name: &str, content: Option<&str>) -> FileSystemResult<()> {
+        let parent = self.resolve(path)?;
+        let new_path = remote_join(&parent, name);
+        if self.exists(&new_path)? {
+            return Err(FileSystemError::already_exists(format!("File already exists: {}", name)));
+        }
+
+        self.write_all(&new_path, content.unwrap_or_default().as_bytes(), ChangeType::Created)
+    }
+
+    fn create_files_batch(&self, path: &str, files: &[(String, Option<String>)]) -> FileSystemResult<Vec<FileSystemResult<()>>> {
+        Ok(files
+            .iter()
+            .map(|(name, content)| self.create_file(path, name, content.as_deref()))
+            .collect())
+    }
+
+    fn copy_items(&self, sources: &[String], destination: &str) -> FileSystemResult<()> {
+        let destination = self.resolve(destination)?;
+        if !self.sftp()?.stat(Path::new(&destination)).map(|s| s.is_dir()).unwrap_or(false) {
+            return Err(FileSystemError::not_a_directory("Destination is not a directory"));
+        }
+
+        for source in sources {
+            let source = self.resolve(source)?;
+            let target = remote_join(&destination, remote_name(&source));
+            if self.exists(&target)? {
+                return Err(FileSystemError::already_exists(format!("File already exists: {}", target)));
+            }
+            self.copy_recursive(&source, &target)?;
+            self.notify_change(&target, ChangeType::Created);
+        }
+        Ok(())
+    }
+
+    fn copy_with_custom_name(&self, source: &str, destination_dir: &str, new_name: &str) -> FileSystemResult<()> {
+        let source = self.resolve(source)?;
+        let target = remote_join(&self.resolve(destination_dir)?, new_name);
+        if self.exists(&target)? {
+            return Err(FileSystemError::already_exists(format!("File already exists: {}", new_name)));
+        }
+        self.copy_recursive(&source, &target)?;
+        self.notify_change(&target, ChangeType::Created);
+        Ok(())
+    }
+
+    fn move_items(&self, sources: &[String], destination: &str) -> FileSystemResult<()> {
+        let destination = self.resolve(destination)?;
+
+        for source in sources {
+            let source = self.resolve(source)?;
+            let target = remote_join(&destination, remote_name(&source));
+            if self.exists(&target)? {
+                return Err(FileSystemError::already_exists(format!("File already exists: {}", target)));
+            }
+            self.rename_raw(&source, &target)?;
+            WATCHER.notify_rename(WatchBackend::Remote, Some(&self.label), &source, &target);
+        }
+        Ok(())
+    }
+
+    fn get_home_directory(&self) -> FileSystemResult<String> {
+        Ok(self.connection()?.home.clone())
+    }
+
+    fn get_system_folders(&self) -> FileSystemResult<Vec<FileSystemEntry>> {
+        let mut home = self.get_file_info("~")?;
+        home.name = "Home".to_string();
+        let root = self.get_file_info("/")?;
+        Ok(vec![home, root])
+    }
+
+    fn read_file_content(&self, path: &str, max_size: Option<u64>) -> FileSystemResult<String> {
+        let entry = self.get_file_info(path)?;
+        if !entry.is_file {
+            return Err(FileSystemError::is_a_directory(format!("Path is not a file: {}", path)));
+        }
+
+        let limit = max_size.unwrap_or(10_000_000);
+        let size = entry.size.unwrap_or(0);
+        if size > limit {
+            return Err(FileSystemError::quota_exceeded(format!(
+                "File too large: {} bytes (limit: {} bytes)",
+                size, limit
+            )));
+        }
+
+        let content = self.read_all(&entry.path)?;
+        match String::from_utf8(content) {
+            Ok(text) => Ok(text),
+            Err(err) => Ok(general_purpose::STANDARD.encode(err.as_bytes())),
+        }
+    }
+
+    fn read_file_bytes(&self, path: &str) -> FileSystemResult<Vec<u8>> {
+        self.read_all(&self.resolve(path)?)
+    }
+
+    fn write_file_content(&self, path: &str, content: &str) -> FileSystemResult<()> {
+        self.write_all(&self.resolve(path)?, content.as_bytes(), ChangeType::Modified)
+    }
+
+    fn write_file_bytes(&self, path: &str, content: &[u8]) -> FileSystemResult<()> {
+        self.write_all(&self.resolve(path)?, content, ChangeType::Modified)
+    }
+
+    fn open_read(&self, path: &str) -> FileSystemResult<Box<dyn FileReader>> {
+        let path = self.resolve(path)?;
+        if self.sftp()?.stat(Path::new(&path)).map(|s| s.is_dir()).unwrap_or(false) {
+            return Err(FileSystemError::is_a_directory("Path is not a file"));
+        }
+        let file = self
+            .sftp()?
+            .open(Path::new(&path))
+            .map_err(|e| sftp_error(e, format!("Failed to open {}", path)))?;
+        Ok(Box::new(file))
+    }
+
+    fn open_write(&self, path: &str) -> FileSystemResult<Box<dyn FileWriter>> {
+        let path = self.resolve(path)?;
+        if !self.exists(remote_parent(&path))? {
+            return Err(FileSystemError::not_found("Parent directory does not exist"));
+        }
+        let file = self
+            .sftp()?
+            .create(Path::new(&path))
+            .map_err(|e| sftp_error(e, format!("Failed to create {}", path)))?;
+        self.notify_change(&path, ChangeType::Modified);
+        Ok(Box::new(file))
+    }
+
+    fn open_file(&self, _path: &str) -> FileSystemResult<()> {
+        Err(FileSystemError::with_kind(ErrorKind::InvalidInput, "Opening remote files is not supported"))
+    }
+
+    fn reveal_in_finder(&self, _path: &str) -> FileSystemResult<()> {
+        Err(FileSystemError::with_kind(ErrorKind::InvalidInput, "Revealing remote files is not supported"))
+    }
+
+    fn normalize_path(&self, path: &str) -> FileSystemResult<String> {
+        let normalized = self.resolve(path)?;
+        self.lstat(&normalized)?;
+        Ok(normalized)
+    }
+
+    fn get_path_suggestions(&self, partial_path: &str) -> FileSystemResult<Vec<String>> {
+        let home = &self.connection()?.home;
+        let (dir, prefix) = if partial_path.is_empty() || partial_path.ends_with('/') {
+            (normalize_remote_path(home, partial_path), String::new())
+        } else {
+            let path = normalize_remote_path(home, partial_path);
+            (remote_parent(&path).to_string(), remote_name(&path).to_lowercase())
+        };
+
+        let Ok(entries) = self.read_dir_raw(&dir) else {
+            return Ok(Vec::new());
+        };
+
+        let mut suggestions: Vec<String> = entries
+            .into_iter()
+            .filter(|(child, _)| {
+                let name = remote_name(child);
+                !name.starts_with('.') && name.to_lowercase().starts_with(&prefix)
+            })
+            .filter(|(child, stat)| {
+                stat.is_dir() || (stat.file_type().is_symlink() && self.entry(child, stat).map(|e| e.is_dir).unwrap_or(false))
+            })
+            .map(|(child, _)| child)
+            .collect();
+        suggestions.sort();
+        Ok(suggestions)
+    }
+
+    fn open_terminal(&self, _path: &str) -> FileSystemResult<()> {
+        Err(FileSystemError::with_kind(ErrorKind::InvalidInput, "Opening a terminal on a remote server is not supported"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(perm: u32, size: u64) -> FileStat {
+        FileStat {
+            size: Some(size),
+            uid: Some(1000),
+            gid: Some(1000),
+            perm: Some(perm),
+            atime: Some(10),
+            mtime: Some(20),
+        }
+    }
+
+    #[test]
+    fn test_stat_to_entry_and_error_kind() {
+        let file = stat_to_entry("/srv/.env", &stat(0o100600, 42), None, None);
+        assert!(file.is_file && !file.is_dir && file.is_hidden);
+        assert_eq!(file.name, ".env");
+        assert_eq!(file.size, Some(42));
+        assert_eq!(file.modified, Some(20));
+        assert_eq!(file.mode, Some(0o100600));
+
+        let link = stat_to_entry(
+            "/srv/current",
+            &stat(0o120777, 7),
+            Some(&stat(0o040755, 4096)),
+            Some("releases/42".to_string()),
+        );
+        assert!(link.is_symlink && link.is_dir && !link.is_file);
+        assert_eq!(link.size, None);
+        assert_eq!(link.symlink_target.as_deref(), Some("releases/42"));
+
+        assert_eq!(error_kind(ErrorCode::SFTP(FX_NO_SUCH_FILE)), ErrorKind::NotFound);
+        assert_eq!(error_kind(ErrorCode::SFTP(FX_PERMISSION_DENIED)), ErrorKind::PermissionDenied);
+        assert_eq!(error_kind(ErrorCode::Session(-9)), ErrorKind::Io);
+    }
+
+    #[test]
+    fn test_disconnected_is_locked() {
+        let fs = SftpFileSystem::disconnected("sftp:missing");
+        let err = fs.read_directory("/").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Locked);
+        assert!(fs.delete_item("/etc").is_err());
+    }
+
+    /// Against a real server:
+    /// `VFDIR_SFTP_TEST=user:password@host:port cargo test sftp -- --ignored`
+    #[test]
+    #[ignore]
+    fn test_roundtrip_against_server() {
+        let spec = std::env::var("VFDIR_SFTP_TEST").expect("VFDIR_SFTP_TEST not set");
+        let (credentials, address) = spec.rsplit_once('@').unwrap();
+        let (username, password) = credentials.split_once(':').unwrap();
+        let (host, port) = address.rsplit_once(':').unwrap_or((address, "22"));
+        let profile = SftpProfile {
+            id: "test".to_string(),
+            name: "test".to_string(),
+            host: host.to_string(),
+            port: port.parse().unwrap(),
+            username: username.to_string(),
+            auth: SftpAuth::Password { password: None },
+            secret_saved: false,
+            host_key: None,
+        };
+        let fs = match SftpFileSystem::connect("sftp:test", &profile, Some(password), None) {
+            Ok(fs) => fs,
+            Err(SftpConnectError::UnknownHostKey { fingerprint }) => {
+                SftpFileSystem::connect("sftp:test", &profile, Some(password), Some(&fingerprint)).unwrap()
+            }
+            Err(err) => panic!("{:?}", err),
+        };
+
+        let dir = format!("vfdir-test-{}", uuid::Uuid::new_v4());
+        fs.create_folder("~", &dir).unwrap();
+        let dir = format!("~/{}", dir);
+        fs.create_file(&dir, "a.txt", Some("hello")).unwrap();
+
+        let mut writer = fs.open_write(&format!("{}/b.bin", dir)).unwrap();
+        writer.write_all(&[0u8, 159, 146, 150]).unwrap();
+        writer.finish().unwrap();
+
+        let names: Vec<String> = fs.read_directory(&dir).unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["a.txt", "b.bin"]);
+        assert_eq!(fs.read_file_bytes(&format!("{}/b.bin", dir)).unwrap(), vec![0u8, 159, 146, 150]);
+
+        fs.rename_item(&format!("{}/a.txt", dir), "c.txt").unwrap();
+        assert_eq!(fs.read_file_content(&format!("{}/c.txt", dir), None).unwrap(), "hello");
+
+        let trashed = fs.trash_item(&format!("{}/c.txt", dir)).unwrap();
+        assert!(fs.get_file_info(&format!("{}/c.txt", dir)).is_err());
+        fs.restore_from_trash(&trashed.id).unwrap();
+        assert!(fs.get_file_info(&format!("{}/c.txt", dir)).is_ok());
+
+        fs.delete_item(&dir).unwrap();
+        assert!(fs.get_file_info(&dir).is_err());
+        fs.disconnect();
+    }
+}
@@ -1,4 +1,5 @@
 use axum::{
+    body::{Body, Bytes},
     extract::{Path, State},
     response::IntoResponse,
     routing::get,
@@ -7,6 +8,7 @@ use axum::{
 use local_ip_address::local_ip;
 use qrcode::QrCode;
 use qrcode::render::svg;
+use std::io::Read;
 use std::sync::Mutex;
 use tokio::sync::oneshot;
 use once_cell::sync::Lazy;
@@ -23,26 +25,60 @@ pub struct ShareInfo {
     pub filename: String,
 }
 
+/// Size of chunks sent to the client while streaming a shared file
+const SHARE_CHUNK_SIZE: usize = 64 * 1024;
+
 /// Handler to serve file content from any filesystem (Real or Virtual)
 async fn download_handler(
     State((file_path, fs_backend)): State<(String, Option<String>)>,
 ) -> impl IntoResponse {
-    // Read file content using global API (handles Real & Virtual FS)
-    // Note: This reads entire file into memory. OK for Magic Share use case.
-    match API.files.read_file_bytes(&file_path, fs_backend.as_deref()) {
-        Ok(bytes) => {
-            let mime = mime_guess::from_path(&file_path).first_or_octet_stream();
-            
-            (
-                [(axum::http::header::CONTENT_TYPE, mime.as_ref())], 
-                bytes
-            ).into_response()
-        },
+    // Open a streaming reader using global API (handles Real & Virtual FS)
+    let mut reader = match API.files.open_read(&file_path, fs_backend.as_deref()) {
+        Ok(reader) => reader,
         Err(e) => {
             error!("Failed to serve file '{}': {}", file_path, e);
-            axum::http::StatusCode::NOT_FOUND.into_response()
+            return axum::http::StatusCode::NOT_FOUND.into_response();
+        }
+    };
+
+    // Read chunks on a blocking thread and forward them to the response body
+    let (tx, rx) = tokio::sync::mpsc::channel::<std::io::Result<Bytes>>(4);
+    let path_for_log = file_path.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut buffer = vec![0u8; SHARE_CHUNK_SIZE];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => {
+                    // Client disconnected
+                    if tx.blocking_send(Ok(Bytes::copy_from_slice(&buffer[..n]))).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to read shared file '{}': {}", path_for_log, e);
+                    let _ = tx.blocking_send(Err(e));
+                    break;
+                }
+            }
         }
+    });
+
+    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    });
+
+    let mime = mime_guess::from_path(&file_path).first_or_octet_stream();
+    let mut response = (
+        [(axum::http::header::CONTENT_TYPE, mime.as_ref())],
+        Body::from_stream(stream),
+    ).into_response();
+
+    if let Some(size) = API.files.get_file_info(&file_path, fs_backend.as_deref()).ok().and_then(|info| info.size) {
+        response.headers_mut().insert(axum::http::header::CONTENT_LENGTH, size.into());
     }
+
+    response
 }
 
 /// Starts a temporary HTTP server to share a specific file
@@ -1,15 +1,22 @@
-use crate::core::{FileSystem, FileSystemEntry, FileSystemError, FileSystemResult};
+use crate::api::real_fs::os_entry;
+use crate::api::trash::TrashDir;
+use crate::api::watcher::{ChangeType, WatchBackend, WATCHER};
+use crate::core::{
+    DirectoryPage, FileReader, FileSystem, FileSystemEntry, FileSystemError, FileSystemResult,
+    FileWriter, ListOptions, TrashEntry,
+};
 use base64::{Engine as _, engine::general_purpose};
 use std::fs;
 use std::io::Read;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
 
 /// Временная файловая система, ограниченная определенной директорией (chroot)
 #[derive(Clone)]
 pub struct TemporaryFileSystem {
     root_path: PathBuf,
+    /// Метка сессии (`panel_fs`): изменения видят только панели этой сессии
+    label: Option<String>,
 }
 
 impl TemporaryFileSystem {
@@ -18,7 +25,16 @@ impl TemporaryFileSystem {
         if !root_path.exists() {
             let _ = fs::create_dir_all(&root_path);
         }
-        Self { root_path }
+        Self { root_path, label: None }
+    }
+
+    /// Временная ФС, зарегистрированная как сессия с меткой `label`
+    pub fn with_label(root_path: PathBuf, label: &str) -> Self {
+        Self { label: Some(label.to_string()), ..Self::new(root_path) }
+    }
+
+    pub(crate) fn label(&self) -> Option<&str> {
+        self.label.as_deref()
     }
 
     /// Resolve a virtual path (e.g. "/folder/file.txt") to a real path inside the root
@@ -53,12 +69,22 @@ impl TemporaryFileSystem {
         
         // Simple check for ".." components
         if path.contains("..") {
-             return Err(FileSystemError::new("Directory traversal detected"));
+             return Err(FileSystemError::permission_denied("Directory traversal detected"));
         }
 
         Ok(full_path)
     }
 
+    /// Hidden trash inside the session root (never listed by `read_directory`)
+    fn trash_dir(&self) -> TrashDir {
+        TrashDir::new(self.root_path.join(".trash"))
+    }
+
+    /// Internal mutation hook: report a change to directory watchers
+    fn notify_change(&self, real_path: &Path, change_type: ChangeType) {
+        WATCHER.notify_change(WatchBackend::Temporary, self.label.as_deref(), &self.to_virtual_path(real_path), change_type);
+    }
+
     /// Convert real path back to virtual path (relative to root)
     fn to_virtual_path(&self, real_path: &Path) -> String {
         if let Ok(relative) = real_path.strip_prefix(&self.root_path) {
@@ -75,84 +101,47 @@ impl TemporaryFileSystem {
     }
 
     fn path_to_entry(&self, path: &Path) -> FileSystemResult<FileSystemEntry> {
-        let metadata = fs::metadata(path)
-            .map_err(|e| FileSystemError::new(format!("Failed to read metadata: {}", e)))?;
-
-        let name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
-
-        let virtual_path = self.to_virtual_path(path);
-
-        let modified = metadata
-            .modified()
-            .ok()
-            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs());
-
-        let created = metadata
-            .created()
-            .ok()
-            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs());
-
-        let accessed = metadata
-            .accessed()
-            .ok()
-            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs());
-
-        Ok(FileSystemEntry {
-            path: virtual_path,
-            name,
-            is_dir: metadata.is_dir(),
-            is_file: metadata.is_file(),
-            size: if metadata.is_file() {
-                Some(metadata.len())
-            } else {
-                None
-            },
-            modified,
-            created,
-            accessed,
-        })
+        os_entry(path, self.to_virtual_path(path))
     }
 }
 
 impl FileSystem for TemporaryFileSystem {
-    fn read_directory(&self, path: &str) -> FileSystemResult<Vec<FileSystemEntry>> {
+    fn read_directory_with_options(
+        &self,
+        path: &str,
+        options: &ListOptions,
+    ) -> FileSystemResult<DirectoryPage> {
         let real_path = self.resolve_path(path)?;
 
         if !real_path.exists() {
-            return Err(FileSystemError::new(format!("Directory does not exist: {}", path)));
+            return Err(FileSystemError::not_found(format!("Directory does not exist: {}", path)));
         }
 
         if !real_path.is_dir() {
-            return Err(FileSystemError::new(format!("Path is not a directory: {}", path)));
+            return Err(FileSystemError::not_a_directory(format!("Path is not a directory: {}", path)));
         }
 
         let entries = fs::read_dir(&real_path)
-            .map_err(|e| FileSystemError::new(format!("Failed to read directory: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read directory: {}", e)))?;
 
+        let name_filter = options.name_filter()?;
         let mut result = Vec::new();
 
         for entry in entries {
             let entry = entry
-                .map_err(|e| FileSystemError::new(format!("Failed to read entry: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read entry: {}", e)))?;
+            if !name_filter.matches(&entry.file_name().to_string_lossy()) {
+                continue;
+            }
             let path = entry.path();
 
-            // Skip hidden files
-            if let Some(name) = path.file_name() {
-                if let Some(name_str) = name.to_str() {
-                    if name_str.starts_with('.') {
-                        continue;
-                    }
-                }
+            // The session trash is never listed
+            if path == self.trash_dir().root() {
+                continue;
             }
 
             match self.path_to_entry(&path) {
+                Ok(fs_entry) if fs_entry.is_hidden && !options.include_hidden() => continue,
                 Ok(fs_entry) => result.push(fs_entry),
                 Err(_) => continue,
             }
@@ -165,14 +154,14 @@ impl FileSystem for TemporaryFileSystem {
             _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
         });
 
-        Ok(result)
+        options.apply(result)
     }
 
     fn get_file_info(&self, path: &str) -> FileSystemResult<FileSystemEntry> {
         let real_path = self.resolve_path(path)?;
 
         if !real_path.exists() {
-            return Err(FileSystemError::new(format!("Path does not exist: {}", path)));
+            return Err(FileSystemError::not_found(format!("Path does not exist: {}", path)));
         }
 
         self.path_to_entry(&real_path)
@@ -182,25 +171,59 @@ impl FileSystem for TemporaryFileSystem {
         let real_path = self.resolve_path(path)?;
 
         if !real_path.exists() {
-            return Err(FileSystemError::new(format!("Path does not exist: {}", path)));
+            return Err(FileSystemError::not_found(format!("Path does not exist: {}", path)));
         }
 
         if real_path.is_dir() {
             fs::remove_dir_all(&real_path)
-                .map_err(|e| FileSystemError::new(format!("Failed to delete directory: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to delete directory: {}", e)))?;
         } else {
             fs::remove_file(&real_path)
-                .map_err(|e| FileSystemError::new(format!("Failed to delete file: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to delete file: {}", e)))?;
         }
 
+        self.notify_change(&real_path, ChangeType::Deleted);
         Ok(())
     }
 
+    fn trash_item(&self, path: &str) -> FileSystemResult<TrashEntry> {
+        let real_path = self.resolve_path(path)?;
+
+        if real_path == self.root_path {
+            return Err(FileSystemError::permission_denied("Cannot move the root to trash"));
+        }
+
+        let entry = self.trash_dir().trash(&real_path, &self.to_virtual_path(&real_path))?;
+        self.notify_change(&real_path, ChangeType::Deleted);
+        Ok(entry)
+    }
+
+    fn list_trash(&self) -> FileSystemResult<Vec<TrashEntry>> {
+        self.trash_dir().list()
+    }
+
+    fn restore_from_trash(&self, id: &str) -> FileSystemResult<TrashEntry> {
+        let trash = self.trash_dir();
+        let target = self.resolve_path(&trash.entry(id)?.original_path)?;
+
+        let entry = trash.restore(id, &target)?;
+        self.notify_change(&target, ChangeType::Created);
+        Ok(entry)
+    }
+
+    fn empty_trash(&self) -> FileSystemResult<usize> {
+        self.trash_dir().empty()
+    }
+
+    fn purge_trash_older_than(&self, cutoff: u64) -> FileSystemResult<usize> {
+        self.trash_dir().purge_older_than(cutoff)
+    }
+
     fn rename_item(&self, old_path: &str, new_name: &str) -> FileSystemResult<()> {
         let real_old_path = self.resolve_path(old_path)?;
 
         if !real_old_path.exists() {
-            return Err(FileSystemError::new(format!("Path does not exist: {}", old_path)));
+            return Err(FileSystemError::not_found(format!("Path does not exist: {}", old_path)));
         }
 
         let parent = real_old_path
@@ -210,12 +233,18 @@ impl FileSystem for TemporaryFileSystem {
         let real_new_path = parent.join(new_name);
 
         if real_new_path.exists() {
-            return Err(FileSystemError::new(format!("File already exists: {}", new_name)));
+            return Err(FileSystemError::already_exists(format!("File already exists: {}", new_name)));
         }
 
         fs::rename(&real_old_path, &real_new_path)
-            .map_err(|e| FileSystemError::new(format!("Failed to rename: {}", e)))?;
-
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to rename: {}", e)))?;
+
+        WATCHER.notify_rename(
+            WatchBackend::Temporary,
+            self.label.as_deref(),
+            &self.to_virtual_path(&real_old_path),
+            &self.to_virtual_path(&real_new_path),
+        );
         Ok(())
     }
 
@@ -223,18 +252,19 @@ impl FileSystem for TemporaryFileSystem {
         let real_parent = self.resolve_path(path)?;
 
         if !real_parent.exists() {
-            return Err(FileSystemError::new(format!("Parent directory does not exist: {}", path)));
+            return Err(FileSystemError::not_found(format!("Parent directory does not exist: {}", path)));
         }
 
         let real_new_path = real_parent.join(name);
 
         if real_new_path.exists() {
-            return Err(FileSystemError::new(format!("Folder already exists: {}", name)));
+            return Err(FileSystemError::already_exists(format!("Folder already exists: {}", name)));
         }
 
         fs::create_dir(&real_new_path)
-            .map_err(|e| FileSystemError::new(format!("Failed to create folder: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create folder: {}", e)))?;
 
+        self.notify_change(&real_new_path, ChangeType::Created);
         Ok(())
     }
 
@@ -242,23 +272,24 @@ impl FileSystem for TemporaryFileSystem {
         let real_parent = self.resolve_path(path)?;
 
         if !real_parent.exists() {
-            return Err(FileSystemError::new(format!("Parent directory does not exist: {}", path)));
+            return Err(FileSystemError::not_found(format!("Parent directory does not exist: {}", path)));
         }
 
         let real_new_path = real_parent.join(name);
 
         if real_new_path.exists() {
-            return Err(FileSystemError::new(format!("File already exists: {}", name)));
+            return Err(FileSystemError::already_exists(format!("File already exists: {}", name)));
         }
 
         let mut file = fs::File::create(&real_new_path)
-            .map_err(|e| FileSystemError::new(format!("Failed to create file: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create file: {}", e)))?;
 
         if let Some(content_str) = content {
             file.write_all(content_str.as_bytes())
-                .map_err(|e| FileSystemError::new(format!("Failed to write file content: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write file content: {}", e)))?;
         }
 
+        self.notify_change(&real_new_path, ChangeType::Created);
         Ok(())
     }
 
@@ -276,7 +307,7 @@ impl FileSystem for TemporaryFileSystem {
         
         let dest_real_path = self.resolve_path(destination)?;
         if !dest_real_path.exists() || !dest_real_path.is_dir() {
-             return Err(FileSystemError::new("Destination is not a directory"));
+             return Err(FileSystemError::not_a_directory("Destination is not a directory"));
         }
 
         for source in sources {
@@ -295,7 +326,8 @@ impl FileSystem for TemporaryFileSystem {
                 return Err(FileSystemError::new("Directory copy not fully supported in TempFS yet"));
             } else {
                 fs::copy(&src_real_path, &dest_file_path)
-                    .map_err(|e| FileSystemError::new(format!("Failed to copy: {}", e)))?;
+                    .map_err(|e| FileSystemError::from_io(&e, format!("Failed to copy: {}", e)))?;
+                self.notify_change(&dest_file_path, ChangeType::Created);
             }
         }
         Ok(())
@@ -308,10 +340,11 @@ impl FileSystem for TemporaryFileSystem {
         
         if src_real.is_file() {
             fs::copy(&src_real, &dest_file_real)
-                .map_err(|e| FileSystemError::new(format!("Failed to copy: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to copy: {}", e)))?;
+            self.notify_change(&dest_file_real, ChangeType::Created);
             Ok(())
         } else {
-            Err(FileSystemError::new("Source must be a file"))
+            Err(FileSystemError::is_a_directory("Source must be a file"))
         }
     }
 
@@ -325,7 +358,13 @@ impl FileSystem for TemporaryFileSystem {
             let dest_file = dest_real_path.join(file_name);
             
             fs::rename(&src_real_path, &dest_file)
-                .map_err(|e| FileSystemError::new(format!("Failed to move: {}", e)))?;
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to move: {}", e)))?;
+            WATCHER.notify_rename(
+                WatchBackend::Temporary,
+                self.label.as_deref(),
+                &self.to_virtual_path(&src_real_path),
+                &self.to_virtual_path(&dest_file),
+            );
         }
         Ok(())
     }
@@ -348,19 +387,19 @@ impl FileSystem for TemporaryFileSystem {
         let real_path = self.resolve_path(path)?;
         
         if !real_path.exists() || !real_path.is_file() {
-            return Err(FileSystemError::new("File does not exist"));
+            return Err(FileSystemError::not_found("File does not exist"));
         }
         
-        let metadata = fs::metadata(&real_path).map_err(|e| FileSystemError::new(e.to_string()))?;
+        let metadata = fs::metadata(&real_path).map_err(|e| FileSystemError::from_io(&e, e.to_string()))?;
         if let Some(max) = max_size {
             if metadata.len() > max {
-                return Err(FileSystemError::new("File too large"));
+                return Err(FileSystemError::quota_exceeded("File too large"));
             }
         }
         
         // Check binary vs text similar to RealFS
         // Simple check: read into string, if fails return base64
-        let content = fs::read(&real_path).map_err(|e| FileSystemError::new(e.to_string()))?;
+        let content = fs::read(&real_path).map_err(|e| FileSystemError::from_io(&e, e.to_string()))?;
         
         match String::from_utf8(content.clone()) {
             Ok(s) => Ok(s),
@@ -370,27 +409,52 @@ impl FileSystem for TemporaryFileSystem {
 
     fn read_file_bytes(&self, path: &str) -> FileSystemResult<Vec<u8>> {
         let real_path = self.resolve_path(path)?;
-        fs::read(&real_path).map_err(|e| FileSystemError::new(e.to_string()))
+        fs::read(&real_path).map_err(|e| FileSystemError::from_io(&e, e.to_string()))
     }
 
     fn write_file_content(&self, path: &str, content: &str) -> FileSystemResult<()> {
         let real_path = self.resolve_path(path)?;
         if let Some(parent) = real_path.parent() {
             if !parent.exists() {
-                return Err(FileSystemError::new("Parent directory does not exist"));
+                return Err(FileSystemError::not_found("Parent directory does not exist"));
             }
         }
-        fs::write(&real_path, content).map_err(|e| FileSystemError::new(e.to_string()))
+        fs::write(&real_path, content).map_err(|e| FileSystemError::from_io(&e, e.to_string()))?;
+        self.notify_change(&real_path, ChangeType::Modified);
+        Ok(())
     }
 
     fn write_file_bytes(&self, path: &str, content: &[u8]) -> FileSystemResult<()> {
         let real_path = self.resolve_path(path)?;
         if let Some(parent) = real_path.parent() {
             if !parent.exists() {
-                return Err(FileSystemError::new("Parent directory does not exist"));
+                return Err(FileSystemError::not_found("Parent directory does not exist"));
+            }
+        }
+        fs::write(&real_path, content).map_err(|e| FileSystemError::from_io(&e, e.to_string()))?;
+        self.notify_change(&real_path, ChangeType::Modified);
+        Ok(())
+    }
+
+    fn open_read(&self, path: &str) -> FileSystemResult<Box<dyn FileReader>> {
+        let real_path = self.resolve_path(path)?;
+        if !real_path.is_file() {
+            return Err(FileSystemError::is_a_directory("Path is not a file"));
+        }
+        let file = fs::File::open(&real_path).map_err(|e| FileSystemError::from_io(&e, e.to_string()))?;
+        Ok(Box::new(file))
+    }
+
+    fn open_write(&self, path: &str) -> FileSystemResult<Box<dyn FileWriter>> {
+        let real_path = self.resolve_path(path)?;
+        if let Some(parent) = real_path.parent() {
+            if !parent.exists() {
+                return Err(FileSystemError::not_found("Parent directory does not exist"));
             }
         }
-        fs::write(&real_path, content).map_err(|e| FileSystemError::new(e.to_string()))
+        let file = fs::File::create(&real_path).map_err(|e| FileSystemError::from_io(&e, e.to_string()))?;
+        self.notify_change(&real_path, ChangeType::Modified);
+        Ok(Box::new(file))
     }
 
     fn open_file(&self, _path: &str) -> FileSystemResult<()> {
@@ -428,8 +492,8 @@ impl FileSystem for TemporaryFileSystem {
         }
         
         let mut suggestions = Vec::new();
-        for entry in fs::read_dir(parent_dir).map_err(|e| FileSystemError::new(e.to_string()))? {
-            let entry = entry.map_err(|e| FileSystemError::new(e.to_string()))?;
+        for entry in fs::read_dir(parent_dir).map_err(|e| FileSystemError::from_io(&e, e.to_string()))? {
+            let entry = entry.map_err(|e| FileSystemError::from_io(&e, e.to_string()))?;
             let name = entry.file_name().to_string_lossy().to_string();
             if name.starts_with(&prefix) {
                 // Return virtual path
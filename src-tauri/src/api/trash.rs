@@ -0,0 +1,484 @@
+//! Корзина в формате freedesktop.org Trash specification
+//!
+//! Структура каталога корзины:
+//! - `files/<id>` — удаленный файл или директория
+//! - `info/<id>.trashinfo` — исходный путь и время удаления
+//!
+//! Используется `RealFileSystem` (домашняя корзина пользователя) и `TemporaryFileSystem`
+//! (скрытая `.trash` внутри корня сессии). Хранилище vault использует тот же формат
+//! `.trashinfo`, но хранит его в зашифрованных блобах.
+
+use crate::core::{FileSystemError, FileSystemResult, TrashEntry};
+use chrono::{Local, NaiveDateTime, TimeZone};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Расширение файлов метаданных
+pub const TRASH_INFO_EXTENSION: &str = "trashinfo";
+
+/// Формат DeletionDate по спецификации (локальное время, без часового пояса)
+const DELETION_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Метаданные удаленного элемента (содержимое `.trashinfo`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashInfo {
+    pub original_path: String,
+    /// Время удаления (секунды UNIX)
+    pub deleted_at: u64,
+}
+
+impl TrashInfo {
+    /// Метаданные для элемента, удаляемого сейчас
+    pub fn new(original_path: impl Into<String>) -> Self {
+        Self {
+            original_path: original_path.into(),
+            deleted_at: now_secs(),
+        }
+    }
+
+    /// Сериализация в формат `.trashinfo`
+    pub fn to_trashinfo(&self) -> String {
+        let deletion_date = Local
+            .timestamp_opt(self.deleted_at as i64, 0)
+            .single()
+            .map(|date| date.format(DELETION_DATE_FORMAT).to_string())
+            .unwrap_or_default();
+
+        format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            encode_path(&self.original_path),
+            deletion_date
+        )
+    }
+
+    /// Разбор `.trashinfo`; `None`, если нет секции `[Trash Info]` или ключа `Path`
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut in_section = false;
+        let mut original_path = None;
+        let mut deleted_at = None;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_section = line == "[Trash Info]";
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+
+            if let Some(value) = line.strip_prefix("Path=") {
+                original_path = urlencoding::decode(value).ok().map(|p| p.into_owned());
+            } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+                deleted_at = NaiveDateTime::parse_from_str(value, DELETION_DATE_FORMAT)
+                    .ok()
+                    .and_then(|date| Local.from_local_datetime(&date).earliest())
+                    .map(|date| date.timestamp().max(0) as u64);
+            }
+        }
+
+        Some(Self {
+            original_path: original_path?,
+            deleted_at: deleted_at.unwrap_or(0),
+        })
+    }
+}
+
+/// Каталог корзины на диске
+#[derive(Debug, Clone)]
+pub struct TrashDir {
+    root: PathBuf,
+}
+
+impl TrashDir {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Домашняя корзина пользователя
+    ///
+    /// На Linux — `$XDG_DATA_HOME/Trash` (по умолчанию `~/.local/share/Trash`).
+    /// На остальных платформах используется каталог данных приложения.
+    pub fn home() -> FileSystemResult<Self> {
+        #[cfg(target_os = "linux")]
+        let root = dirs::data_dir().map(|dir| dir.join("Trash"));
+
+        #[cfg(not(target_os = "linux"))]
+        let root = dirs::data_local_dir().map(|dir| dir.join("vfdir").join("Trash"));
+
+        root.map(Self::new)
+            .ok_or_else(|| FileSystemError::new("Could not determine trash directory"))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Находится ли путь внутри корзины
+    pub fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.root)
+    }
+
+    fn files_dir(&self) -> PathBuf {
+        self.root.join("files")
+    }
+
+    fn info_dir(&self) -> PathBuf {
+        self.root.join("info")
+    }
+
+    fn info_path(&self, id: &str) -> PathBuf {
+        self.info_dir().join(format!("{}.{}", id, TRASH_INFO_EXTENSION))
+    }
+
+    fn ensure_dirs(&self) -> FileSystemResult<()> {
+        for dir in [self.files_dir(), self.info_dir()] {
+            fs::create_dir_all(&dir)
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create trash directory: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Переместить элемент в корзину
+    ///
+    /// `original_path` записывается в `.trashinfo` как есть: для временной ФС это
+    /// путь внутри сессии, а не реальный путь на диске.
+    pub fn trash(&self, real_path: &Path, original_path: &str) -> FileSystemResult<TrashEntry> {
+        if fs::symlink_metadata(real_path).is_err() {
+            return Err(FileSystemError::not_found(format!(
+                "Path does not exist: {}",
+                original_path
+            )));
+        }
+        if self.contains(real_path) {
+            return Err(FileSystemError::permission_denied("Cannot move the trash into itself"));
+        }
+
+        self.ensure_dirs()?;
+
+        let name = real_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| FileSystemError::new("Could not get file name"))?;
+        let info = TrashInfo::new(original_path);
+        let id = self.reserve_id(name, &info)?;
+
+        if let Err(err) = move_path(real_path, &self.files_dir().join(&id)) {
+            let _ = fs::remove_file(self.info_path(&id));
+            return Err(err);
+        }
+
+        self.entry(&id)
+    }
+
+    /// Атомарно создать `.trashinfo` с уникальным именем (как требует спецификация)
+    fn reserve_id(&self, name: &str, info: &TrashInfo) -> FileSystemResult<String> {
+        let content = info.to_trashinfo();
+
+        let mut attempt = 0u32;
+        loop {
+            attempt += 1;
+            let id = if attempt == 1 {
+                name.to_string()
+            } else {
+                format!("{}.{}", name, attempt)
+            };
+
+            if fs::symlink_metadata(self.files_dir().join(&id)).is_ok() {
+                continue;
+            }
+
+            match fs::OpenOptions::new().write(true).create_new(true).open(self.info_path(&id)) {
+                Ok(mut file) => {
+                    file.write_all(content.as_bytes())
+                        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write trash info: {}", e)))?;
+                    return Ok(id);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => {
+                    return Err(FileSystemError::from_io(&e, format!("Failed to create trash info: {}", e)));
+                }
+            }
+        }
+    }
+
+    /// Информация об элементе корзины
+    pub fn entry(&self, id: &str) -> FileSystemResult<TrashEntry> {
+        validate_id(id)?;
+
+        let content = fs::read_to_string(self.info_path(id))
+            .map_err(|e| FileSystemError::from_io(&e, format!("Trash item not found: {}", id)))?;
+        let info = TrashInfo::parse(&content)
+            .ok_or_else(|| FileSystemError::new(format!("Invalid trash info: {}", id)))?;
+        let metadata = fs::symlink_metadata(self.files_dir().join(id))
+            .map_err(|e| FileSystemError::from_io(&e, format!("Trash item not found: {}", id)))?;
+
+        Ok(to_entry(id, info, metadata.is_dir(), metadata.is_file().then_some(metadata.len())))
+    }
+
+    /// Содержимое корзины, новые элементы первыми
+    pub fn list(&self) -> FileSystemResult<Vec<TrashEntry>> {
+        let info_dir = self.info_dir();
+        if !info_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for item in fs::read_dir(&info_dir)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read trash: {}", e)))?
+        {
+            let Ok(item) = item else { continue };
+            let path = item.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(TRASH_INFO_EXTENSION) {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+
+            // Элементы без файла или с поврежденным .trashinfo пропускаем
+            if let Ok(entry) = self.entry(id) {
+                entries.push(entry);
+            }
+        }
+
+        entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(entries)
+    }
+
+    /// Восстановить элемент в `target` (реальный путь, соответствующий `original_path`)
+    pub fn restore(&self, id: &str, target: &Path) -> FileSystemResult<TrashEntry> {
+        let entry = self.entry(id)?;
+
+        if fs::symlink_metadata(target).is_ok() {
+            return Err(FileSystemError::already_exists(format!(
+                "Cannot restore, path already exists: {}",
+                entry.original_path
+            )));
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create parent directory: {}", e)))?;
+        }
+
+        move_path(&self.files_dir().join(id), target)?;
+        let _ = fs::remove_file(self.info_path(id));
+
+        Ok(entry)
+    }
+
+    /// Окончательно удалить элемент корзины
+    pub fn remove(&self, id: &str) -> FileSystemResult<()> {
+        validate_id(id)?;
+
+        let path = self.files_dir().join(id);
+        match fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(&path)
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to delete directory: {}", e)))?,
+            Ok(_) => fs::remove_file(&path)
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to delete file: {}", e)))?,
+            Err(_) => {}
+        }
+
+        match fs::remove_file(self.info_path(id)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(FileSystemError::from_io(&e, format!("Failed to delete trash info: {}", e))),
+        }
+    }
+
+    /// Очистить корзину
+    pub fn empty(&self) -> FileSystemResult<usize> {
+        self.purge_where(|_| true)
+    }
+
+    /// Удалить элементы, помещенные в корзину раньше `cutoff` (секунды UNIX)
+    pub fn purge_older_than(&self, cutoff: u64) -> FileSystemResult<usize> {
+        self.purge_where(|entry| entry.deleted_at < cutoff)
+    }
+
+    fn purge_where(&self, predicate: impl Fn(&TrashEntry) -> bool) -> FileSystemResult<usize> {
+        let mut removed = 0;
+        for entry in self.list()?.into_iter().filter(|entry| predicate(entry)) {
+            self.remove(&entry.id)?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+}
+
+/// Собрать `TrashEntry` из метаданных
+pub fn to_entry(id: &str, info: TrashInfo, is_dir: bool, size: Option<u64>) -> TrashEntry {
+    let name = info
+        .original_path
+        .rsplit(['/', '\\'])
+        .find(|part| !part.is_empty())
+        .unwrap_or(id)
+        .to_string();
+
+    TrashEntry {
+        id: id.to_string(),
+        name,
+        original_path: info.original_path,
+        deleted_at: info.deleted_at,
+        is_dir,
+        size,
+    }
+}
+
+/// Идентификатор — одно имя без разделителей пути
+pub fn validate_id(id: &str) -> FileSystemResult<()> {
+    if id.is_empty() || id == "." || id == ".." || id.contains(['/', '\\']) {
+        return Err(FileSystemError::not_found(format!("Trash item not found: {}", id)));
+    }
+    Ok(())
+}
+
+/// Текущее время в секундах UNIX
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Path в `.trashinfo` кодируется как URL, но разделители остаются как есть
+fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(|part| urlencoding::encode(part).into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Перемещение с откатом на копирование, если корзина на другом разделе
+fn move_path(from: &Path, to: &Path) -> FileSystemResult<()> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            let is_dir = copy_recursive(from, to)?;
+            if is_dir {
+                fs::remove_dir_all(from)
+            } else {
+                fs::remove_file(from)
+            }
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to remove source after copy: {}", e)))
+        }
+        Err(e) => Err(FileSystemError::from_io(&e, format!("Failed to move to trash: {}", e))),
+    }
+}
+
+/// Копирование без перехода по ссылкам: ссылка копируется ссылкой, обходятся только
+/// настоящие каталоги. Возвращает, был ли `from` каталогом.
+fn copy_recursive(from: &Path, to: &Path) -> FileSystemResult<bool> {
+    let file_type = fs::symlink_metadata(from)
+        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read metadata: {}", e)))?
+        .file_type();
+
+    if file_type.is_symlink() {
+        fs::read_link(from)
+            .and_then(|target| create_symlink(&target, to))
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to copy link: {}", e)))?;
+        Ok(false)
+    } else if file_type.is_dir() {
+        fs::create_dir_all(to)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create directory: {}", e)))?;
+        for entry in fs::read_dir(from)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read directory: {}", e)))?
+        {
+            let entry = entry
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read entry: {}", e)))?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(true)
+    } else {
+        fs::copy(from, to)
+            .map(|_| false)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to copy file: {}", e)))
+    }
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &Path, _link: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "Symlinks are not supported"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ErrorKind;
+
+    #[test]
+    fn test_trashinfo_roundtrip() {
+        let info = TrashInfo {
+            original_path: "/home/user/My Documents/отчет 50%.txt".to_string(),
+            deleted_at: 1_700_000_000,
+        };
+
+        let content = info.to_trashinfo();
+        assert!(content.starts_with("[Trash Info]\n"));
+        assert!(content.contains("Path=/home/user/My%20Documents/"));
+
+        assert_eq!(TrashInfo::parse(&content), Some(info));
+        assert_eq!(TrashInfo::parse("[Other]\nPath=/tmp/x\n"), None);
+    }
+
+    #[test]
+    fn test_trash_restore_and_purge() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash = TrashDir::new(dir.path().join("Trash"));
+        let data = dir.path().join("data");
+        fs::create_dir_all(data.join("folder")).unwrap();
+        fs::write(data.join("folder/inner.txt"), b"inner").unwrap();
+        fs::write(data.join("a.txt"), b"hello").unwrap();
+
+        let file = trash.trash(&data.join("a.txt"), "/data/a.txt").unwrap();
+        assert_eq!(file.name, "a.txt");
+        assert_eq!(file.size, Some(5));
+        assert!(!data.join("a.txt").exists());
+
+        // Same name again gets a distinct id
+        fs::write(data.join("a.txt"), b"second").unwrap();
+        let second = trash.trash(&data.join("a.txt"), "/data/a.txt").unwrap();
+        assert_ne!(file.id, second.id);
+
+        let folder = trash.trash(&data.join("folder"), "/data/folder").unwrap();
+        assert!(folder.is_dir);
+        assert_eq!(trash.list().unwrap().len(), 3);
+
+        trash.restore(&file.id, &data.join("a.txt")).unwrap();
+        assert_eq!(fs::read(data.join("a.txt")).unwrap(), b"hello");
+
+        let err = trash.restore(&second.id, &data.join("a.txt")).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AlreadyExists);
+
+        assert_eq!(trash.purge_older_than(0).unwrap(), 0);
+        assert_eq!(trash.empty().unwrap(), 2);
+        assert!(trash.list().unwrap().is_empty());
+        assert_eq!(trash.entry(&folder.id).unwrap_err().kind, ErrorKind::NotFound);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_keeps_links_as_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        fs::create_dir_all(source.join("target")).unwrap();
+        fs::write(source.join("target").join("big.bin"), b"data").unwrap();
+        std::os::unix::fs::symlink(source.join("target"), source.join("link")).unwrap();
+        // Петля: при переходе по ссылкам обход не закончился бы
+        std::os::unix::fs::symlink(&source, source.join("target").join("loop")).unwrap();
+
+        let copy = dir.path().join("copy");
+        assert!(copy_recursive(&source, &copy).unwrap());
+        let link = copy.join("link");
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), source.join("target"));
+        assert!(fs::symlink_metadata(copy.join("target").join("loop")).unwrap().file_type().is_symlink());
+        assert!(!copy_recursive(&link, &dir.path().join("link-copy")).unwrap());
+    }
+}
@@ -71,4 +71,15 @@ impl From<VaultError> for VaultErrorResponse {
     }
 }
 
+// Category used when a vault error surfaces through the FileSystem trait
+impl From<&VaultError> for crate::core::ErrorKind {
+    fn from(error: &VaultError) -> Self {
+        match error {
+            VaultError::Locked | VaultError::NotInitialized => crate::core::ErrorKind::Locked,
+            VaultError::Io(e) => e.kind().into(),
+            _ => crate::core::ErrorKind::Io,
+        }
+    }
+}
+
 pub type VaultResult<T> = Result<T, VaultError>;
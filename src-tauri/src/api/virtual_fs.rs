@@ -6,10 +6,18 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use std::cell::RefCell;
 use ::base64::Engine;
 // Импортируем типы из вашего модуля
-use crate::core::{FileSystem, FileSystemEntry, FileSystemError, FileSystemResult};
+use crate::core::{
+    guess_mime_type, DirectoryPage, FileReader, FileSystem, FileSystemEntry, FileSystemError,
+    FileSystemResult, FileWriter, ListOptions, TrashEntry,
+};
 use crate::config::VaultPaths;
 use crate::state::APP_CONFIG;
-use crate::api::blob_store::BlobStore;
+use crate::api::blob_store::{BlobStore, BlobWriter};
+use crate::api::trash::{self, TrashInfo, TRASH_INFO_EXTENSION};
+use crate::api::watcher::{ChangeType, WatchBackend, WATCHER};
+
+/// Скрытая корзина в корне хранилища: `/.trash/files` и `/.trash/info`
+const TRASH_DIR: &str = ".trash";
 
 // Thread-local storage for active recovery session
 thread_local! {
@@ -261,7 +269,7 @@ impl VirtualFileSystem {
     /// Загрузить состояние из файла
     fn load_state(path: &Path) -> FileSystemResult<VfsState> {
         let data = std::fs::read(path)
-            .map_err(|e| FileSystemError::new(format!("Не удалось прочитать файл состояния: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Не удалось прочитать файл состояния: {}", e)))?;
 
         // Проверяем, что файл не пустой
         if data.is_empty() {
@@ -291,12 +299,12 @@ impl VirtualFileSystem {
                 // Encrypt
                 use crate::api::security::encrypt_blob;
                 let encrypted_blob = encrypt_blob(&serialized, session)
-                    .map_err(|e| FileSystemError::new(format!("Encryption failed: {:?}", e)))?;
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Encryption failed: {:?}", e)))?;
 
                 // Write to vault.bin
                 use crate::api::security::atomic_write;
                 atomic_write(&self.data_path, &encrypted_blob)
-                    .map_err(|e| FileSystemError::new(format!("Failed to write vault data: {:?}", e)))?;
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to write vault data: {:?}", e)))?;
             },
             status => {
                 // Fallback to JSON if vault is not active (legacy mode)
@@ -310,12 +318,12 @@ impl VirtualFileSystem {
                     if !parent.exists() {
                         tracing::info!("Creating parent directory: {:?}", parent);
                         std::fs::create_dir_all(parent)
-                            .map_err(|e| FileSystemError::new(format!("Не удалось создать директорию: {}", e)))?;
+                            .map_err(|e| FileSystemError::from_io(&e, format!("Не удалось создать директорию: {}", e)))?;
                     }
                 }
 
                 std::fs::write(&self.persistence_path, data)
-                    .map_err(|e| FileSystemError::new(format!("Не удалось записать файл состояния: {}", e)))?;
+                    .map_err(|e| FileSystemError::from_io(&e, format!("Не удалось записать файл состояния: {}", e)))?;
             }
         }
 
@@ -357,10 +365,10 @@ impl VirtualFileSystem {
             match current {
                 VfsNode::Directory { children, .. } => {
                     current = children.get(part)
-                        .ok_or_else(|| FileSystemError::new(format!("Путь не найден: {}", path)))?;
+                        .ok_or_else(|| FileSystemError::not_found(format!("Путь не найден: {}", path)))?;
                 }
                 VfsNode::File { .. } => {
-                    return Err(FileSystemError::new(format!("'{}' не является директорией", path)));
+                    return Err(FileSystemError::not_a_directory(format!("'{}' не является директорией", path)));
                 }
             }
         }
@@ -383,7 +391,14 @@ impl VirtualFileSystem {
         Ok((parent_parts, name))
     }
 
-    /// Мутабельный доступ к узлу по пути
+    /// Сообщить подписчикам об изменении узла (вызывается после сохранения состояния)
+    fn notify_change(&self, path: &str, change_type: ChangeType) {
+        WATCHER.notify_change(WatchBackend::Virtual, None, &self.normalize_path_internal(path), change_type);
+    }
+
+    /// Мутабельный доступ к узлу по пути с сохранением состояния
+    ///
+    /// Наблюдателей уведомляет вызывающий: только он знает, какой узел создан или изменен.
     fn with_node_mut<F, R>(&self, path: &str, f: F) -> FileSystemResult<R>
     where
         F: FnOnce(&mut VfsNode) -> FileSystemResult<R>,
@@ -406,10 +421,10 @@ impl VirtualFileSystem {
             match current {
                 VfsNode::Directory { children, .. } => {
                     current = children.get_mut(part)
-                        .ok_or_else(|| FileSystemError::new(format!("Путь не найден: {}", path)))?;
+                        .ok_or_else(|| FileSystemError::not_found(format!("Путь не найден: {}", path)))?;
                 }
                 VfsNode::File { .. } => {
-                    return Err(FileSystemError::new(format!("'{}' не является директорией", path)));
+                    return Err(FileSystemError::not_a_directory(format!("'{}' не является директорией", path)));
                 }
             }
         }
@@ -786,14 +801,18 @@ impl VirtualFileSystem {
     fn deep_copy_node(&self, node: &VfsNode, session: Option<&crate::api::security::VaultSession>) -> FileSystemResult<VfsNode> {
         match node {
             VfsNode::File { file_id, size, .. } => {
-                // Read original blob
-                let content = self.blob_store.read(file_id, session)
-                    .map_err(|e| FileSystemError::new(format!("Failed to read source blob during copy: {:?}", e)))?;
-                
-                // Write new blob
-                let new_id = self.blob_store.write(&content, session, None)
-                    .map_err(|e| FileSystemError::new(format!("Failed to write new blob during copy: {:?}", e)))?;
-                
+                // Копируем блок за блоком, не загружая весь файл в память
+                let mut reader = self.blob_store.open_read(file_id, session)
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to read source blob during copy: {:?}", e)))?;
+                let mut writer = self.blob_store.open_write(session, None)
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to write new blob during copy: {:?}", e)))?;
+
+                std::io::copy(&mut reader, &mut writer)
+                    .map_err(|e| FileSystemError::from_io(&e, format!("Failed to copy blob: {}", e)))?;
+
+                let (new_id, _) = writer.commit()
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to write new blob during copy: {:?}", e)))?;
+
                 Ok(VfsNode::File {
                     file_id: new_id,
                     size: *size,
@@ -816,40 +835,140 @@ impl VirtualFileSystem {
             }
         }
     }
+
+    // ==================== TRASH ====================
+
+    /// Текущая сессия хранилища (None в legacy-режиме)
+    fn current_session(&self) -> Option<crate::api::security::VaultSession> {
+        let guard = self.vault_status.read().unwrap();
+        match *guard {
+            crate::api::security::VfsStatus::Unlocked { ref session, .. } => Some(session.clone()),
+            _ => None,
+        }
+    }
+
+    /// Записать служебный файл рядом с vault.bin, зашифрованный ключом текущей сессии
+    pub fn write_sealed_file(&self, name: &str, data: &[u8]) -> FileSystemResult<()> {
+        let session = self.current_session()
+            .ok_or_else(|| FileSystemError::locked("Vault is locked"))?;
+
+        let sealed = crate::api::security::encrypt_blob(data, &session)
+            .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to encrypt {}: {}", name, e)))?;
+        crate::api::security::atomic_write(&self.data_path.with_file_name(name), &sealed)
+            .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to write {}: {}", name, e)))
+    }
+
+    /// Прочитать служебный файл, записанный `write_sealed_file`; `None`, если его нет
+    pub fn read_sealed_file(&self, name: &str) -> FileSystemResult<Option<Vec<u8>>> {
+        let session = self.current_session()
+            .ok_or_else(|| FileSystemError::locked("Vault is locked"))?;
+
+        let path = self.data_path.with_file_name(name);
+        let sealed = match std::fs::read(&path) {
+            Ok(sealed) => sealed,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(FileSystemError::from_io(&e, format!("Failed to read {}: {}", name, e))),
+        };
+
+        crate::api::security::decrypt_blob(&sealed, &session)
+            .map(Some)
+            .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to decrypt {}: {}", name, e)))
+    }
+
+    /// Прочитать `.trashinfo` элемента корзины
+    fn read_trash_info(&self, id: &str, session: Option<&crate::api::security::VaultSession>) -> FileSystemResult<TrashInfo> {
+        trash::validate_id(id)?;
+
+        let info_path = format!("/{}/info/{}.{}", TRASH_DIR, id, TRASH_INFO_EXTENSION);
+        let file_id = match self.find_node(&info_path) {
+            Ok(VfsNode::File { file_id, .. }) => file_id,
+            _ => return Err(FileSystemError::not_found(format!("Trash item not found: {}", id))),
+        };
+
+        let content = self.blob_store.read(&file_id, session)
+            .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to read trash info: {:?}", e)))?;
+        TrashInfo::parse(&String::from_utf8_lossy(&content))
+            .ok_or_else(|| FileSystemError::new(format!("Invalid trash info: {}", id)))
+    }
+
+    /// Окончательно удалить элементы корзины, удовлетворяющие условию
+    fn purge_trash_where(&self, predicate: impl Fn(&TrashEntry) -> bool) -> FileSystemResult<usize> {
+        let ids: Vec<String> = self.list_trash()?
+            .into_iter()
+            .filter(|entry| predicate(entry))
+            .map(|entry| entry.id)
+            .collect();
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut blobs_to_delete = Vec::new();
+        {
+            let mut state = self.state.write()
+                .map_err(|_| FileSystemError::new("Не удалось получить блокировку"))?;
+
+            for id in &ids {
+                if let Some(node) = trash_children_mut(&mut state.root, "files")?.remove(id) {
+                    collect_file_ids(&node, &mut blobs_to_delete);
+                }
+                let info_name = format!("{}.{}", id, TRASH_INFO_EXTENSION);
+                if let Some(node) = trash_children_mut(&mut state.root, "info")?.remove(&info_name) {
+                    collect_file_ids(&node, &mut blobs_to_delete);
+                }
+            }
+        }
+
+        self.save_state()?;
+
+        // Блобы удаляем только после того, как дерево без них сохранено
+        for file_id in blobs_to_delete {
+            let _ = self.blob_store.delete(&file_id);
+        }
+
+        Ok(ids.len())
+    }
 }
 
 impl FileSystem for VirtualFileSystem {
-    fn read_directory(&self, path: &str) -> FileSystemResult<Vec<FileSystemEntry>> {
+    fn read_directory_with_options(
+        &self,
+        path: &str,
+        options: &ListOptions,
+    ) -> FileSystemResult<DirectoryPage> {
         let normalized = self.normalize_path_internal(path);
         let node = self.find_node(&normalized)?;
 
         match node {
             VfsNode::Directory { children, .. } => {
+                let name_filter = options.name_filter()?;
                 let mut entries = Vec::new();
 
                 for (name, child) in children.iter() {
+                    if normalized == "/" && name == TRASH_DIR {
+                        continue;
+                    }
+                    if !name_filter.matches(name) {
+                        continue;
+                    }
+
                     let entry_path = if normalized == "/" {
                         format!("/{}", name)
                     } else {
                         format!("{}/{}", normalized, name)
                     };
 
-                    entries.push(FileSystemEntry {
-                        path: entry_path,
-                        name: name.clone(),
-                        is_dir: child.is_dir(),
-                        is_file: !child.is_dir(),
-                        size: Some(child.size()),
-                        modified: Some(child.modified()),
-                        created: Some(child.created()),
-                        accessed: Some(child.modified()),
-                    });
+                    let entry = node_entry(entry_path, name.clone(), child);
+                    if entry.is_hidden && !options.include_hidden() {
+                        continue;
+                    }
+                    entries.push(entry);
                 }
 
-                Ok(entries)
+                options.apply(entries)
             }
             VfsNode::File { .. } => {
-                Err(FileSystemError::new(format!("'{}' не является директорией", path)))
+                Err(FileSystemError::not_a_directory(format!("'{}' не является директорией", path)))
             }
         }
     }
@@ -861,16 +980,7 @@ impl FileSystem for VirtualFileSystem {
         let name = normalized.split('/').filter(|s| !s.is_empty()).last()
             .unwrap_or("root").to_string();
 
-        Ok(FileSystemEntry {
-            path: normalized.clone(),
-            name,
-            is_dir: node.is_dir(),
-            is_file: !node.is_dir(),
-            size: Some(node.size()),
-            modified: Some(node.modified()),
-            created: Some(node.created()),
-            accessed: Some(node.modified()),
-        })
+        Ok(node_entry(normalized.clone(), name, &node))
     }
 
     fn delete_item(&self, path: &str) -> FileSystemResult<()> {
@@ -886,10 +996,10 @@ impl FileSystem for VirtualFileSystem {
             match current {
                 VfsNode::Directory { children, .. } => {
                     current = children.get_mut(part)
-                        .ok_or_else(|| FileSystemError::new("Родительская директория не найдена"))?;
+                        .ok_or_else(|| FileSystemError::not_found("Родительская директория не найдена"))?;
                 }
                 VfsNode::File { .. } => {
-                    return Err(FileSystemError::new("Путь содержит файл вместо директории"));
+                    return Err(FileSystemError::not_a_directory("Путь содержит файл вместо директории"));
                 }
             }
         }
@@ -898,7 +1008,7 @@ impl FileSystem for VirtualFileSystem {
             VfsNode::Directory { children, .. } => {
                 // Get the node to be removed
                 let node = children.remove(&name)
-                    .ok_or_else(|| FileSystemError::new(format!("Элемент '{}' не найден", name)))?;
+                    .ok_or_else(|| FileSystemError::not_found(format!("Элемент '{}' не найден", name)))?;
                 
                 // Recursively delete contents from blob store if vault is unlocked
                 // Note: If vault is locked, we can't theoretically be here because state is empty?
@@ -907,17 +1017,7 @@ impl FileSystem for VirtualFileSystem {
                 
                 // Collect file IDs to delete
                 let mut files_to_delete = Vec::new();
-                fn collect_ids(node: &VfsNode, ids: &mut Vec<String>) {
-                    match node {
-                        VfsNode::File { file_id, .. } => ids.push(file_id.clone()),
-                        VfsNode::Directory { children, .. } => {
-                            for child in children.values() {
-                                collect_ids(child, ids);
-                            }
-                        }
-                    }
-                }
-                collect_ids(&node, &mut files_to_delete);
+                collect_file_ids(&node, &mut files_to_delete);
                 
                 // Delete blobs
                 for file_id in files_to_delete {
@@ -925,15 +1025,166 @@ impl FileSystem for VirtualFileSystem {
                 }
             }
             VfsNode::File { .. } => {
-                return Err(FileSystemError::new("Родитель не является директорией"));
+                return Err(FileSystemError::not_a_directory("Родитель не является директорией"));
             }
         }
 
         drop(state);
         self.save_state()?;
+        self.notify_change(path, ChangeType::Deleted);
         Ok(())
     }
 
+    fn trash_item(&self, path: &str) -> FileSystemResult<TrashEntry> {
+        let normalized = self.normalize_path_internal(path);
+        if normalized == "/" || normalized == format!("/{}", TRASH_DIR) || normalized.starts_with(&format!("/{}/", TRASH_DIR)) {
+            return Err(FileSystemError::permission_denied(format!("Невозможно переместить в корзину: {}", path)));
+        }
+
+        let session = self.current_session();
+        let (parent_parts, name) = self.find_parent_and_name(&normalized)?;
+
+        let mut state = self.state.write()
+            .map_err(|_| FileSystemError::new("Не удалось получить блокировку"))?;
+
+        let parent = directory_children_mut(&mut state.root, &parent_parts)?;
+        if !parent.contains_key(&name) {
+            return Err(FileSystemError::not_found(format!("Элемент '{}' не найден", name)));
+        }
+
+        // Уникальный идентификатор в корзине: "name", "name.2", ...
+        let mut id = name.clone();
+        let mut attempt = 1;
+        loop {
+            let info_name = format!("{}.{}", id, TRASH_INFO_EXTENSION);
+            let taken = trash_children_mut(&mut state.root, "files")?.contains_key(&id)
+                || trash_children_mut(&mut state.root, "info")?.contains_key(&info_name);
+            if !taken {
+                break;
+            }
+            attempt += 1;
+            id = format!("{}.{}", name, attempt);
+        }
+
+        let info = TrashInfo::new(normalized.clone());
+        let info_content = info.to_trashinfo();
+        let info_blob = self.blob_store.write(info_content.as_bytes(), session.as_ref(), None)
+            .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to write trash info: {:?}", e)))?;
+
+        // Узел переносится целиком — его блобы остаются в хранилище до очистки корзины
+        let node = directory_children_mut(&mut state.root, &parent_parts)?
+            .remove(&name)
+            .expect("presence checked above");
+        let entry = trash::to_entry(&id, info, node.is_dir(), (!node.is_dir()).then_some(node.size()));
+
+        trash_children_mut(&mut state.root, "files")?.insert(id.clone(), node);
+        trash_children_mut(&mut state.root, "info")?.insert(
+            format!("{}.{}", id, TRASH_INFO_EXTENSION),
+            VfsNode::new_file(info_blob, info_content.len() as u64),
+        );
+
+        drop(state);
+        self.save_state()?;
+        self.notify_change(&normalized, ChangeType::Deleted);
+        Ok(entry)
+    }
+
+    fn list_trash(&self) -> FileSystemResult<Vec<TrashEntry>> {
+        let files = match self.find_node(&format!("/{}/files", TRASH_DIR)) {
+            Ok(VfsNode::Directory { children, .. }) => children,
+            _ => return Ok(Vec::new()),
+        };
+
+        let session = self.current_session();
+        let mut entries = Vec::new();
+
+        for (id, node) in &files {
+            // Элементы с поврежденным .trashinfo пропускаем
+            if let Ok(info) = self.read_trash_info(id, session.as_ref()) {
+                entries.push(trash::to_entry(id, info, node.is_dir(), (!node.is_dir()).then_some(node.size())));
+            }
+        }
+
+        entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(entries)
+    }
+
+    fn restore_from_trash(&self, id: &str) -> FileSystemResult<TrashEntry> {
+        let session = self.current_session();
+        let info = self.read_trash_info(id, session.as_ref())?;
+        let target = self.normalize_path_internal(&info.original_path);
+        let (parent_parts, name) = self.find_parent_and_name(&target)?;
+        let info_name = format!("{}.{}", id, TRASH_INFO_EXTENSION);
+
+        let mut state = self.state.write()
+            .map_err(|_| FileSystemError::new("Не удалось получить блокировку"))?;
+
+        if !trash_children_mut(&mut state.root, "files")?.contains_key(id) {
+            return Err(FileSystemError::not_found(format!("Trash item not found: {}", id)));
+        }
+
+        // Сначала только проверка: при отказе в дереве не должно остаться созданных директорий
+        let mut current = Some(&state.root);
+        for part in &parent_parts {
+            current = match current {
+                Some(VfsNode::Directory { children, .. }) => children.get(part),
+                Some(VfsNode::File { .. }) => {
+                    return Err(FileSystemError::not_a_directory("Путь содержит файл вместо директории"));
+                }
+                None => None,
+            };
+        }
+        match current {
+            Some(VfsNode::Directory { children, .. }) if children.contains_key(&name) => {
+                return Err(FileSystemError::already_exists(format!(
+                    "Невозможно восстановить, путь уже существует: {}",
+                    target
+                )));
+            }
+            Some(VfsNode::File { .. }) => {
+                return Err(FileSystemError::not_a_directory("Родитель не является директорией"));
+            }
+            _ => {}
+        }
+
+        let node = trash_children_mut(&mut state.root, "files")?
+            .remove(id)
+            .expect("presence checked above");
+        let info_node = trash_children_mut(&mut state.root, "info")?.remove(&info_name);
+        let entry = trash::to_entry(id, info, node.is_dir(), (!node.is_dir()).then_some(node.size()));
+
+        // Недостающие родительские директории создаются заново
+        let mut current = &mut state.root;
+        for part in &parent_parts {
+            let VfsNode::Directory { children, .. } = current else {
+                unreachable!("parents checked above");
+            };
+            current = children.entry(part.clone()).or_insert_with(VfsNode::new_directory);
+        }
+        let VfsNode::Directory { children, .. } = current else {
+            unreachable!("parent checked above");
+        };
+        children.insert(name, node);
+
+        drop(state);
+        self.save_state()?;
+
+        if let Some(VfsNode::File { file_id, .. }) = info_node {
+            let _ = self.blob_store.delete(&file_id);
+        }
+
+        self.notify_change(&target, ChangeType::Created);
+        Ok(entry)
+    }
+
+    fn empty_trash(&self) -> FileSystemResult<usize> {
+        self.purge_trash_where(|_| true)
+    }
+
+    fn purge_trash_older_than(&self, cutoff: u64) -> FileSystemResult<usize> {
+        self.purge_trash_where(|entry| entry.deleted_at < cutoff)
+    }
+
     fn rename_item(&self, old_path: &str, new_name: &str) -> FileSystemResult<()> {
         if new_name.contains('/') {
             return Err(FileSystemError::new("Новое имя не должно содержать '/'"));
@@ -951,10 +1202,10 @@ impl FileSystem for VirtualFileSystem {
             match current {
                 VfsNode::Directory { children, .. } => {
                     current = children.get_mut(part)
-                        .ok_or_else(|| FileSystemError::new("Родительская директория не найдена"))?;
+                        .ok_or_else(|| FileSystemError::not_found("Родительская директория не найдена"))?;
                 }
                 VfsNode::File { .. } => {
-                    return Err(FileSystemError::new("Путь содержит файл"));
+                    return Err(FileSystemError::not_a_directory("Путь содержит файл"));
                 }
             }
         }
@@ -962,21 +1213,27 @@ impl FileSystem for VirtualFileSystem {
         match current {
             VfsNode::Directory { children, .. } => {
                 if children.contains_key(new_name) {
-                    return Err(FileSystemError::new(format!("Элемент '{}' уже существует", new_name)));
+                    return Err(FileSystemError::already_exists(format!("Элемент '{}' уже существует", new_name)));
                 }
 
                 let node = children.remove(&old_name)
-                    .ok_or_else(|| FileSystemError::new(format!("Элемент '{}' не найден", old_name)))?;
+                    .ok_or_else(|| FileSystemError::not_found(format!("Элемент '{}' не найден", old_name)))?;
 
                 children.insert(new_name.to_string(), node);
             }
             VfsNode::File { .. } => {
-                return Err(FileSystemError::new("Родитель не является директорией"));
+                return Err(FileSystemError::not_a_directory("Родитель не является директорией"));
             }
         }
 
         drop(state);
         self.save_state()?;
+        WATCHER.notify_rename(
+            WatchBackend::Virtual,
+            None,
+            &self.normalize_path_internal(old_path),
+            &child_path(&format!("/{}", parent_parts.join("/")), new_name),
+        );
         Ok(())
     }
 
@@ -998,10 +1255,10 @@ impl FileSystem for VirtualFileSystem {
             match current {
                 VfsNode::Directory { children, .. } => {
                     current = children.get_mut(part)
-                        .ok_or_else(|| FileSystemError::new(format!("Путь не найден: {}", path)))?;
+                        .ok_or_else(|| FileSystemError::not_found(format!("Путь не найден: {}", path)))?;
                 }
                 VfsNode::File { .. } => {
-                    return Err(FileSystemError::new("Путь содержит файл"));
+                    return Err(FileSystemError::not_a_directory("Путь содержит файл"));
                 }
             }
         }
@@ -1009,19 +1266,20 @@ impl FileSystem for VirtualFileSystem {
         match current {
             VfsNode::Directory { children, modified, .. } => {
                 if children.contains_key(name) {
-                    return Err(FileSystemError::new(format!("Папка '{}' уже существует", name)));
+                    return Err(FileSystemError::already_exists(format!("Папка '{}' уже существует", name)));
                 }
 
                 children.insert(name.to_string(), VfsNode::new_directory());
                 *modified = current_timestamp();
             }
             VfsNode::File { .. } => {
-                return Err(FileSystemError::new("Не является директорией"));
+                return Err(FileSystemError::not_a_directory("Не является директорией"));
             }
         }
 
         drop(state);
         self.save_state()?;
+        self.notify_change(&child_path(&normalized, name), ChangeType::Created);
         Ok(())
     }
 
@@ -1051,10 +1309,10 @@ impl FileSystem for VirtualFileSystem {
             match current {
                 VfsNode::Directory { children, .. } => {
                     current = children.get_mut(part)
-                        .ok_or_else(|| FileSystemError::new(format!("Путь не найден: {}", path)))?;
+                        .ok_or_else(|| FileSystemError::not_found(format!("Путь не найден: {}", path)))?;
                 }
                 VfsNode::File { .. } => {
-                    return Err(FileSystemError::new("Путь содержит файл"));
+                    return Err(FileSystemError::not_a_directory("Путь содержит файл"));
                 }
             }
         }
@@ -1062,14 +1320,14 @@ impl FileSystem for VirtualFileSystem {
         match current {
             VfsNode::Directory { children, modified, .. } => {
                 if children.contains_key(name) {
-                    return Err(FileSystemError::new(format!("Файл '{}' уже существует", name)));
+                    return Err(FileSystemError::already_exists(format!("Файл '{}' уже существует", name)));
                 }
 
                 let file_content = content.unwrap_or("").as_bytes();
                 
                 // Write to blob store
                 let file_id = self.blob_store.write(file_content, session.as_ref(), None)
-                    .map_err(|e| FileSystemError::new(format!("Failed to write blob: {:?}", e)))?;
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to write blob: {:?}", e)))?;
 
                 children.insert(name.to_string(), VfsNode::File {
                     file_id,
@@ -1080,12 +1338,13 @@ impl FileSystem for VirtualFileSystem {
                 *modified = current_timestamp();
             }
             VfsNode::File { .. } => {
-                return Err(FileSystemError::new("Не является директорией"));
+                return Err(FileSystemError::not_a_directory("Не является директорией"));
             }
         }
 
         drop(state);
         self.save_state()?;
+        self.notify_change(&child_path(&normalized, name), ChangeType::Created);
         Ok(())
     }
 
@@ -1128,6 +1387,9 @@ impl FileSystem for VirtualFileSystem {
 
         // Вставляем в место назначения
         let normalized_dest = self.normalize_path_internal(destination);
+        let copied_paths: Vec<String> = nodes_to_insert.iter()
+            .map(|(name, _)| child_path(&normalized_dest, name))
+            .collect();
         let mut state = self.state.write()
             .map_err(|_| FileSystemError::new("Не удалось получить блокировку"))?;
 
@@ -1138,10 +1400,10 @@ impl FileSystem for VirtualFileSystem {
             match current {
                 VfsNode::Directory { children, .. } => {
                     current = children.get_mut(part)
-                        .ok_or_else(|| FileSystemError::new("Директория назначения не найдена"))?;
+                        .ok_or_else(|| FileSystemError::not_found("Директория назначения не найдена"))?;
                 }
                 VfsNode::File { .. } => {
-                    return Err(FileSystemError::new("Назначение не является директорией"));
+                    return Err(FileSystemError::not_a_directory("Назначение не является директорией"));
                 }
             }
         }
@@ -1161,12 +1423,15 @@ impl FileSystem for VirtualFileSystem {
                 *modified = current_timestamp();
             }
             VfsNode::File { .. } => {
-                return Err(FileSystemError::new("Назначение не является директорией"));
+                return Err(FileSystemError::not_a_directory("Назначение не является директорией"));
             }
         }
 
         drop(state);
         self.save_state()?;
+        for copied in &copied_paths {
+            self.notify_change(copied, ChangeType::Created);
+        }
         Ok(())
     }
 
@@ -1201,10 +1466,10 @@ impl FileSystem for VirtualFileSystem {
             match current {
                 VfsNode::Directory { children, .. } => {
                     current = children.get_mut(part)
-                        .ok_or_else(|| FileSystemError::new("Директория назначения не найдена"))?;
+                        .ok_or_else(|| FileSystemError::not_found("Директория назначения не найдена"))?;
                 }
                 VfsNode::File { .. } => {
-                    return Err(FileSystemError::new("Назначение не является директорией"));
+                    return Err(FileSystemError::not_a_directory("Назначение не является директорией"));
                 }
             }
         }
@@ -1222,12 +1487,13 @@ impl FileSystem for VirtualFileSystem {
                 *modified = current_timestamp();
             }
             VfsNode::File { .. } => {
-                return Err(FileSystemError::new("Назначение не является директорией"));
+                return Err(FileSystemError::not_a_directory("Назначение не является директорией"));
             }
         }
 
         drop(state);
         self.save_state()?;
+        self.notify_change(&child_path(&normalized_dest, new_name), ChangeType::Created);
         Ok(())
     }
 
@@ -1261,7 +1527,7 @@ impl FileSystem for VirtualFileSystem {
             VfsNode::File { file_id, size, .. } => {
                 if let Some(max) = max_size {
                     if size > max {
-                        return Err(FileSystemError::new(format!("Файл слишком большой (>{} байт)", max)));
+                        return Err(FileSystemError::quota_exceeded(format!("Файл слишком большой (>{} байт)", max)));
                     }
                 }
                 
@@ -1276,7 +1542,7 @@ impl FileSystem for VirtualFileSystem {
 
                 // Read from blob store
                 let content = self.blob_store.read(&file_id, session.as_ref())
-                    .map_err(|e| FileSystemError::new(format!("Failed to read blob: {:?}", e)))?;
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to read blob: {:?}", e)))?;
 
                 // Пытаемся интерпретировать как UTF-8 текст
                 match String::from_utf8(content.clone()) {
@@ -1288,7 +1554,7 @@ impl FileSystem for VirtualFileSystem {
                 }
             }
             VfsNode::Directory { .. } => {
-                Err(FileSystemError::new(format!("'{}' является директорией", path)))
+                Err(FileSystemError::is_a_directory(format!("'{}' является директорией", path)))
             }
         }
     }
@@ -1308,10 +1574,10 @@ impl FileSystem for VirtualFileSystem {
                 };
 
                 self.blob_store.read(&file_id, session.as_ref())
-                    .map_err(|e| FileSystemError::new(format!("Failed to read blob: {:?}", e)))
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to read blob: {:?}", e)))
             },
             VfsNode::Directory { .. } => {
-                Err(FileSystemError::new(format!("'{}' является директорией", path)))
+                Err(FileSystemError::is_a_directory(format!("'{}' является директорией", path)))
             }
         }
     }
@@ -1346,10 +1612,10 @@ impl FileSystem for VirtualFileSystem {
             match current {
                 VfsNode::Directory { children, .. } => {
                     current = children.get_mut(&part.to_string())
-                        .ok_or_else(|| FileSystemError::new(format!("Директория не найдена: {}", part)))?;
+                        .ok_or_else(|| FileSystemError::not_found(format!("Директория не найдена: {}", part)))?;
                 }
                 VfsNode::File { .. } => {
-                    return Err(FileSystemError::new("Путь содержит файл вместо директории"));
+                    return Err(FileSystemError::not_a_directory("Путь содержит файл вместо директории"));
                 }
             }
         }
@@ -1367,7 +1633,7 @@ impl FileSystem for VirtualFileSystem {
                 };
 
                 let file_id = self.blob_store.write(bytes, session.as_ref(), existing_id)
-                    .map_err(|e| FileSystemError::new(format!("Failed to write blob: {:?}", e)))?;
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to write blob: {:?}", e)))?;
 
                 if let Some(existing_node) = children.get_mut(*file_name) {
                      match existing_node {
@@ -1376,7 +1642,7 @@ impl FileSystem for VirtualFileSystem {
                             *size = bytes.len() as u64;
                             *file_modified = current_timestamp();
                         }
-                        VfsNode::Directory { .. } => return Err(FileSystemError::new("Путь указывает на директорию")),
+                        VfsNode::Directory { .. } => return Err(FileSystemError::is_a_directory("Путь указывает на директорию")),
                     }
                 } else {
                      children.insert(file_name.to_string(), VfsNode::File {
@@ -1391,10 +1657,11 @@ impl FileSystem for VirtualFileSystem {
 
                 drop(state);
                 self.save_state()?;
+                self.notify_change(&normalized, ChangeType::Modified);
                 Ok(())
             }
             VfsNode::File { .. } => {
-                Err(FileSystemError::new("Родительский путь не является директорией"))
+                Err(FileSystemError::not_a_directory("Родительский путь не является директорией"))
             }
         }
     }
@@ -1427,10 +1694,10 @@ impl FileSystem for VirtualFileSystem {
             match current {
                 VfsNode::Directory { children, .. } => {
                     current = children.get_mut(&part.to_string())
-                        .ok_or_else(|| FileSystemError::new(format!("Директория не найдена: {}", part)))?;
+                        .ok_or_else(|| FileSystemError::not_found(format!("Директория не найдена: {}", part)))?;
                 }
                 VfsNode::File { .. } => {
-                    return Err(FileSystemError::new("Путь содержит файл вместо директории"));
+                    return Err(FileSystemError::not_a_directory("Путь содержит файл вместо директории"));
                 }
             }
         }
@@ -1445,7 +1712,7 @@ impl FileSystem for VirtualFileSystem {
                 };
 
                 let file_id = self.blob_store.write(content, session.as_ref(), existing_id)
-                    .map_err(|e| FileSystemError::new(format!("Failed to write blob: {:?}", e)))?;
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to write blob: {:?}", e)))?;
 
                 if let Some(existing_node) = children.get_mut(*file_name) {
                     match existing_node {
@@ -1455,7 +1722,7 @@ impl FileSystem for VirtualFileSystem {
                             *file_modified = current_timestamp();
                         }
                         VfsNode::Directory { .. } => {
-                            return Err(FileSystemError::new("Путь указывает на директорию"));
+                            return Err(FileSystemError::is_a_directory("Путь указывает на директорию"));
                         }
                     }
                 } else {
@@ -1470,14 +1737,77 @@ impl FileSystem for VirtualFileSystem {
 
                 drop(state);
                 self.save_state()?;
+                self.notify_change(&normalized, ChangeType::Modified);
                 Ok(())
             }
             VfsNode::File { .. } => {
-                Err(FileSystemError::new("Родительский путь не является директорией"))
+                Err(FileSystemError::not_a_directory("Родительский путь не является директорией"))
+            }
+        }
+    }
+
+    fn open_read(&self, path: &str) -> FileSystemResult<Box<dyn FileReader>> {
+        let node = self.find_node(path)?;
+
+        match node {
+            VfsNode::File { file_id, .. } => {
+                // Get session
+                let session = {
+                    let guard = self.vault_status.read().unwrap();
+                    match *guard {
+                        crate::api::security::VfsStatus::Unlocked { ref session, .. } => Some(session.clone()),
+                        _ => None,
+                    }
+                };
+
+                self.blob_store.open_read(&file_id, session.as_ref())
+                    .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to open blob: {:?}", e)))
+            }
+            VfsNode::Directory { .. } => {
+                Err(FileSystemError::is_a_directory(format!("'{}' является директорией", path)))
             }
         }
     }
 
+    fn open_write(&self, path: &str) -> FileSystemResult<Box<dyn FileWriter>> {
+        let (parent_parts, name) = self.find_parent_and_name(path)?;
+        let parent_path = format!("/{}", parent_parts.join("/"));
+
+        // Переиспользуем ID существующего файла (блоб заменяется атомарно при finish)
+        let existing_id = match self.find_node(&parent_path)? {
+            VfsNode::Directory { children, .. } => match children.get(&name) {
+                Some(VfsNode::File { file_id, .. }) => Some(file_id.clone()),
+                Some(VfsNode::Directory { .. }) => {
+                    return Err(FileSystemError::is_a_directory("Путь указывает на директорию"));
+                }
+                None => None,
+            },
+            VfsNode::File { .. } => {
+                return Err(FileSystemError::not_a_directory("Родительский путь не является директорией"));
+            }
+        };
+
+        // Get session
+        let session = {
+            let guard = self.vault_status.read().unwrap();
+            match *guard {
+                crate::api::security::VfsStatus::Unlocked { ref session, .. } => Some(session.clone()),
+                _ => None,
+            }
+        };
+
+        let blob = self.blob_store.open_write(session.as_ref(), existing_id.as_deref())
+            .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to open blob for writing: {:?}", e)))?;
+
+        Ok(Box::new(VfsFileWriter {
+            vfs: self.clone(),
+            parent_path,
+            name,
+            is_new: existing_id.is_none(),
+            blob,
+        }))
+    }
+
     fn open_file(&self, _path: &str) -> FileSystemResult<()> {
         // В виртуальной файловой системе это заглушка
         Ok(())
@@ -1530,6 +1860,163 @@ impl FileSystem for VirtualFileSystem {
     }
 }
 
+/// Потоковая запись файла виртуальной ФС
+/// Контент пишется в BlobStore, узел файла создается/обновляется в `finish`
+struct VfsFileWriter {
+    vfs: VirtualFileSystem,
+    parent_path: String,
+    name: String,
+    is_new: bool,
+    blob: BlobWriter,
+}
+
+impl std::io::Write for VfsFileWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.blob.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.blob.flush()
+    }
+}
+
+impl FileWriter for VfsFileWriter {
+    fn finish(self: Box<Self>) -> FileSystemResult<()> {
+        let VfsFileWriter { vfs, parent_path, name, is_new, blob } = *self;
+
+        let (file_id, size) = blob.commit()
+            .map_err(|e| FileSystemError::with_kind((&e).into(), format!("Failed to write blob: {:?}", e)))?;
+
+        let result = vfs.with_node_mut(&parent_path, |node| match node {
+            VfsNode::Directory { children, modified, .. } => {
+                let change = match children.get_mut(&name) {
+                    Some(VfsNode::File { file_id: id, size: file_size, modified: file_modified, .. }) => {
+                        *id = file_id.clone();
+                        *file_size = size;
+                        *file_modified = current_timestamp();
+                        ChangeType::Modified
+                    }
+                    Some(VfsNode::Directory { .. }) => {
+                        return Err(FileSystemError::is_a_directory("Путь указывает на директорию"));
+                    }
+                    None => {
+                        children.insert(name.clone(), VfsNode::File {
+                            file_id: file_id.clone(),
+                            size,
+                            modified: current_timestamp(),
+                            created: current_timestamp(),
+                        });
+                        ChangeType::Created
+                    }
+                };
+                *modified = current_timestamp();
+                Ok(change)
+            }
+            VfsNode::File { .. } => {
+                Err(FileSystemError::not_a_directory("Родительский путь не является директорией"))
+            }
+        });
+
+        match result {
+            Ok(change) => {
+                let path = format!("{}/{}", parent_path.trim_end_matches('/'), name);
+                vfs.notify_change(&path, change);
+                Ok(())
+            }
+            Err(e) => {
+                // Не оставляем «осиротевший» блоб, если узел не удалось записать
+                if is_new {
+                    let _ = vfs.blob_store.delete(&file_id);
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Запись для узла виртуальной ФС
+///
+/// Права, владелец и ссылки в хранилище не поддерживаются — соответствующие поля пустые.
+fn node_entry(path: String, name: String, node: &VfsNode) -> FileSystemEntry {
+    FileSystemEntry {
+        path,
+        is_dir: node.is_dir(),
+        is_file: !node.is_dir(),
+        size: Some(node.size()),
+        modified: Some(node.modified()),
+        created: Some(node.created()),
+        accessed: Some(node.modified()),
+        is_hidden: name.starts_with('.'),
+        mime_type: guess_mime_type(&name, node.is_dir()),
+        name,
+        ..Default::default()
+    }
+}
+
+/// Дочерние элементы директории по пути из частей
+fn directory_children_mut<'a>(
+    root: &'a mut VfsNode,
+    parts: &[String],
+) -> FileSystemResult<&'a mut HashMap<String, VfsNode>> {
+    let mut current = root;
+    for part in parts {
+        match current {
+            VfsNode::Directory { children, .. } => {
+                current = children.get_mut(part)
+                    .ok_or_else(|| FileSystemError::not_found("Родительская директория не найдена"))?;
+            }
+            VfsNode::File { .. } => {
+                return Err(FileSystemError::not_a_directory("Путь содержит файл вместо директории"));
+            }
+        }
+    }
+
+    match current {
+        VfsNode::Directory { children, .. } => Ok(children),
+        VfsNode::File { .. } => Err(FileSystemError::not_a_directory("Родитель не является директорией")),
+    }
+}
+
+/// Поддиректория корзины (`files` или `info`), создается при первом обращении
+fn trash_children_mut<'a>(
+    root: &'a mut VfsNode,
+    section: &str,
+) -> FileSystemResult<&'a mut HashMap<String, VfsNode>> {
+    let mut current = root;
+    for part in [TRASH_DIR, section] {
+        match current {
+            VfsNode::Directory { children, .. } => {
+                current = children.entry(part.to_string()).or_insert_with(VfsNode::new_directory);
+            }
+            VfsNode::File { .. } => {
+                return Err(FileSystemError::not_a_directory("Корзина повреждена"));
+            }
+        }
+    }
+
+    match current {
+        VfsNode::Directory { children, .. } => Ok(children),
+        VfsNode::File { .. } => Err(FileSystemError::not_a_directory("Корзина повреждена")),
+    }
+}
+
+/// Собрать идентификаторы блобов всех файлов поддерева
+fn collect_file_ids(node: &VfsNode, ids: &mut Vec<String>) {
+    match node {
+        VfsNode::File { file_id, .. } => ids.push(file_id.clone()),
+        VfsNode::Directory { children, .. } => {
+            for child in children.values() {
+                collect_file_ids(child, ids);
+            }
+        }
+    }
+}
+
+/// Путь дочернего элемента в виртуальной ФС
+fn child_path(parent: &str, name: &str) -> String {
+    format!("{}/{}", parent.trim_end_matches('/'), name)
+}
+
 /// Получить текущий timestamp в миллисекундах
 fn current_timestamp() -> u64 {
     SystemTime::now()
@@ -1577,6 +2064,68 @@ mod tests {
         assert!(entries.iter().any(|e| e.name == "test_folder" && e.is_dir));
     }
 
+    #[test]
+    fn test_hidden_files_and_metadata() {
+        use tempfile::tempdir;
+        let dir = tempdir().unwrap();
+
+        #[allow(deprecated)]
+        let vfs = VirtualFileSystem::new(dir.path().join("fs.json")).unwrap();
+        let home = vfs.get_home_directory().unwrap();
+
+        vfs.create_file(&home, ".secret", Some("x")).unwrap();
+        vfs.create_file(&home, "photo.png", None).unwrap();
+
+        let visible = vfs.read_directory(&home).unwrap();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].mime_type.as_deref(), Some("image/png"));
+        assert!(!visible[0].is_symlink);
+        assert_eq!(visible[0].mode, None);
+
+        let all = vfs
+            .read_directory_with_options(&home, &ListOptions { show_hidden: Some(true), ..Default::default() })
+            .unwrap()
+            .entries;
+        assert_eq!(all.len(), 2);
+        assert!(all.iter().any(|e| e.name == ".secret" && e.is_hidden));
+    }
+
+    #[test]
+    fn test_read_directory_sort_filter_and_paginate() {
+        use crate::core::SortKey;
+        use tempfile::tempdir;
+        let dir = tempdir().unwrap();
+
+        #[allow(deprecated)]
+        let vfs = VirtualFileSystem::new(dir.path().join("fs.json")).unwrap();
+        let home = vfs.get_home_directory().unwrap();
+
+        for name in ["img10.jpg", "img2.jpg", "notes.txt"] {
+            vfs.create_file(&home, name, None).unwrap();
+        }
+        vfs.create_folder(&home, "img_archive.jpg").unwrap();
+
+        let mut options = ListOptions {
+            glob: Some("img*.jpg".to_string()),
+            sort_by: Some(SortKey::Name),
+            natural_sort: true,
+            directories_first: true,
+            limit: Some(2),
+            ..Default::default()
+        };
+
+        let first = vfs.read_directory_with_options(&home, &options).unwrap();
+        assert_eq!(first.total, 3);
+        let names: Vec<_> = first.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["img_archive.jpg", "img2.jpg"]);
+
+        options.cursor = first.next_cursor;
+        let second = vfs.read_directory_with_options(&home, &options).unwrap();
+        assert_eq!(second.entries.len(), 1);
+        assert_eq!(second.entries[0].name, "img10.jpg");
+        assert!(second.next_cursor.is_none());
+    }
+
     #[test]
     fn test_delete_folder() {
         let vfs = VirtualFileSystem::new("test_vfs_state2.json").unwrap();
@@ -1657,6 +2206,132 @@ mod tests {
         assert_eq!(content2, "Top Secret Content");
     }
 
+    #[test]
+    fn test_error_kinds() {
+        use crate::core::ErrorKind;
+        use tempfile::tempdir;
+        let dir = tempdir().unwrap();
+
+        #[allow(deprecated)]
+        let vfs = VirtualFileSystem::new(dir.path().join("fs.json")).unwrap();
+        let home = vfs.get_home_directory().unwrap();
+
+        vfs.create_folder(&home, "docs").unwrap();
+        vfs.create_file(&home, "note.txt", Some("text")).unwrap();
+
+        let err = vfs.create_folder(&home, "docs").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AlreadyExists);
+
+        let err = vfs.read_directory(&format!("{}/missing", home)).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotFound);
+
+        let err = vfs.read_file_bytes(&format!("{}/docs", home)).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::IsADirectory);
+    }
+
+    #[test]
+    fn test_trash_keeps_blobs_until_purged() {
+        use crate::core::ErrorKind;
+        use tempfile::tempdir;
+        let dir = tempdir().unwrap();
+
+        #[allow(deprecated)]
+        let vfs = VirtualFileSystem::new(dir.path().join("fs.json")).unwrap();
+        let home = vfs.get_home_directory().unwrap();
+
+        vfs.create_folder(&home, "docs").unwrap();
+        vfs.create_file(&format!("{}/docs", home), "note.txt", Some("keep me")).unwrap();
+
+        let entry = vfs.trash_item(&format!("{}/docs", home)).unwrap();
+        assert!(entry.is_dir);
+        assert_eq!(entry.original_path, format!("{}/docs", home));
+        assert!(vfs.read_directory(&home).unwrap().is_empty());
+        assert!(!vfs.read_directory("/").unwrap().iter().any(|e| e.name == TRASH_DIR));
+
+        let err = vfs.trash_item(&format!("/{}", TRASH_DIR)).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::PermissionDenied);
+
+        vfs.create_folder(&home, "docs").unwrap();
+        let err = vfs.restore_from_trash(&entry.id).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::AlreadyExists);
+        vfs.delete_item(&format!("{}/docs", home)).unwrap();
+
+        vfs.restore_from_trash(&entry.id).unwrap();
+        assert_eq!(vfs.read_file_content(&format!("{}/docs/note.txt", home), None).unwrap(), "keep me");
+        assert!(vfs.list_trash().unwrap().is_empty());
+
+        vfs.trash_item(&format!("{}/docs/note.txt", home)).unwrap();
+        assert_eq!(vfs.list_trash().unwrap().len(), 1);
+        assert_eq!(vfs.purge_trash_older_than(0).unwrap(), 0);
+        assert_eq!(vfs.empty_trash().unwrap(), 1);
+        assert!(vfs.list_trash().unwrap().is_empty());
+
+        // Неудачное восстановление не меняет дерево
+        vfs.create_folder(&home, "a").unwrap();
+        vfs.create_folder(&format!("{}/a", home), "b").unwrap();
+        vfs.create_file(&format!("{}/a/b", home), "x.txt", Some("x")).unwrap();
+        let entry = vfs.trash_item(&format!("{}/a/b/x.txt", home)).unwrap();
+        vfs.delete_item(&format!("{}/a", home)).unwrap();
+        vfs.create_file(&home, "a", Some("file")).unwrap();
+        let err = vfs.restore_from_trash(&entry.id).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotADirectory);
+        let names: Vec<String> = vfs.read_directory(&home).unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["a".to_string()]);
+        assert_eq!(vfs.list_trash().unwrap().len(), 1);
+
+        vfs.delete_item(&format!("{}/a", home)).unwrap();
+        vfs.restore_from_trash(&entry.id).unwrap();
+        assert_eq!(vfs.read_file_content(&format!("{}/a/b/x.txt", home), None).unwrap(), "x");
+    }
+
+    #[test]
+    fn test_streaming_write_and_read() {
+        use std::io::{Read, Seek, SeekFrom, Write};
+        use tempfile::tempdir;
+        let dir = tempdir().unwrap();
+
+        #[allow(deprecated)]
+        let vfs = VirtualFileSystem::new(dir.path().join("fs.json")).unwrap();
+        vfs.initialize_vault("secret_password").unwrap();
+
+        let home = vfs.get_home_directory().unwrap();
+        let path = format!("{}/stream.bin", home);
+
+        let mut writer = vfs.open_write(&path).unwrap();
+        writer.write_all(b"hello ").unwrap();
+        writer.write_all(b"streaming world").unwrap();
+        writer.finish().unwrap();
+
+        let info = vfs.get_file_info(&path).unwrap();
+        assert_eq!(info.size, Some(21));
+
+        let mut reader = vfs.open_read(&path).unwrap();
+        reader.seek(SeekFrom::Start(6)).unwrap();
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "streaming world");
+
+        // Незавершенная запись не меняет файл
+        let mut aborted = vfs.open_write(&path).unwrap();
+        aborted.write_all(b"partial").unwrap();
+        drop(aborted);
+        assert_eq!(vfs.read_file_bytes(&path).unwrap(), b"hello streaming world");
+
+        // Копия внутри хранилища переносит несколько блоков шифрования
+        let big_path = format!("{}/big.bin", home);
+        let data: Vec<u8> = (0..(5 * 1024 * 1024 / 2)).map(|i| (i % 251) as u8).collect();
+        let mut writer = vfs.open_write(&big_path).unwrap();
+        writer.write_all(&data).unwrap();
+        writer.finish().unwrap();
+
+        vfs.create_folder(&home, "copies").unwrap();
+        let copies = format!("{}/copies", home);
+        vfs.copy_items(&[big_path], &copies).unwrap();
+        let copied = format!("{}/big.bin", copies);
+        assert_eq!(vfs.get_file_info(&copied).unwrap().size, Some(data.len() as u64));
+        assert_eq!(vfs.read_file_bytes(&copied).unwrap(), data);
+    }
+
     #[test]
     fn test_legacy_mode_operations() {
         use tempfile::tempdir;
@@ -0,0 +1,446 @@
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Время «тишины» по пути, после которого накопленное событие отправляется клиентам
+pub const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Global directory watcher shared by all backends and clients
+pub static WATCHER: Lazy<Arc<DirectoryWatcher>> = Lazy::new(|| Arc::new(DirectoryWatcher::new()));
+
+// --- Data Structures ---
+
+/// Тип изменения в файловой системе
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeType {
+    Created,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+impl ChangeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeType::Created => "created",
+            ChangeType::Modified => "modified",
+            ChangeType::Deleted => "deleted",
+            ChangeType::Renamed => "renamed",
+        }
+    }
+}
+
+/// Семейство бэкендов, в котором произошло изменение
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchBackend {
+    Real,
+    Virtual,
+    Temporary,
+    /// Удаленные ФС (SFTP, WebDAV, S3): видны только изменения, сделанные через приложение
+    Remote,
+    /// Открытые архивы: только чтение, изменений не бывает
+    Archive,
+}
+
+impl WatchBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WatchBackend::Real => "real",
+            WatchBackend::Virtual => "virtual",
+            WatchBackend::Temporary => "temporary",
+            WatchBackend::Remote => "remote",
+            WatchBackend::Archive => "archive",
+        }
+    }
+}
+
+/// Debounced change delivered to subscribers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSystemChange {
+    pub backend: WatchBackend,
+    /// Метка сессии (`panel_fs`) для временных и удаленных ФС
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    pub path: String,
+    pub change_type: ChangeType,
+    /// Previous path for `Renamed`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    /// Subscriptions this change matched
+    pub subscription_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Subscription {
+    backend: WatchBackend,
+    session: Option<String>,
+    path: PathBuf,
+    recursive: bool,
+}
+
+impl Subscription {
+    /// Путь совпадает с самой директорией, ее прямым потомком или (рекурсивно) любым вложенным
+    ///
+    /// Пути разных сессий одного семейства бэкендов (два SFTP-сервера, две временные ФС)
+    /// не пересекаются, поэтому сессия должна совпадать вместе с бэкендом.
+    fn matches(&self, backend: WatchBackend, session: Option<&str>, path: &Path) -> bool {
+        if backend != self.backend || session != self.session.as_deref() {
+            return false;
+        }
+        if path == self.path || path.parent() == Some(self.path.as_path()) {
+            return true;
+        }
+        self.recursive && path.starts_with(&self.path)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PendingChange {
+    change_type: ChangeType,
+    old_path: Option<String>,
+    last_seen: Instant,
+}
+
+/// Объединяет новое событие с уже накопленным по тому же пути.
+/// Returns None, если события взаимно погасились (создан и сразу удален).
+fn merge_change(previous: ChangeType, next: ChangeType) -> Option<ChangeType> {
+    match (previous, next) {
+        (ChangeType::Created, ChangeType::Modified) => Some(ChangeType::Created),
+        (ChangeType::Created, ChangeType::Deleted) => None,
+        (ChangeType::Deleted, ChangeType::Created) => Some(ChangeType::Modified),
+        (ChangeType::Renamed, ChangeType::Modified) => Some(ChangeType::Renamed),
+        (_, next) => Some(next),
+    }
+}
+
+// --- Logic & State ---
+
+struct WatcherState {
+    subscriptions: HashMap<String, Subscription>,
+    pending: HashMap<(WatchBackend, Option<String>, String), PendingChange>,
+    flusher_running: bool,
+}
+
+/// Kept behind its own lock: the OS watcher thread calls back into `WatcherState`,
+/// so (un)registering watches must never happen while holding that lock.
+#[derive(Default)]
+struct OsWatchState {
+    /// OS watcher for RealFileSystem (inotify on Linux), created lazily
+    watcher: Option<RecommendedWatcher>,
+    /// Number of subscriptions per watched real path
+    watches: HashMap<PathBuf, (usize, RecursiveMode)>,
+}
+
+/// Directory watcher: collects changes from all backends, debounces them
+/// and broadcasts them to subscribers (WebSocket clients, Tauri panels).
+pub struct DirectoryWatcher {
+    state: Mutex<WatcherState>,
+    os: Mutex<OsWatchState>,
+    tx: broadcast::Sender<FileSystemChange>,
+}
+
+impl DirectoryWatcher {
+    fn new() -> Self {
+        let (tx, _) = broadcast::channel(256);
+        Self {
+            state: Mutex::new(WatcherState {
+                subscriptions: HashMap::new(),
+                pending: HashMap::new(),
+                flusher_running: false,
+            }),
+            os: Mutex::new(OsWatchState::default()),
+            tx,
+        }
+    }
+
+    /// Receive debounced changes for all subscriptions
+    pub fn subscribe_events(&self) -> broadcast::Receiver<FileSystemChange> {
+        self.tx.subscribe()
+    }
+
+    /// Start watching `path` of `backend` (and `session` for per-session backends).
+    /// Returns the subscription ID.
+    pub fn subscribe(&self, backend: WatchBackend, session: Option<&str>, path: &str, recursive: bool) -> Result<String, String> {
+        let id = Uuid::new_v4().to_string();
+        let watched = PathBuf::from(path);
+
+        if backend == WatchBackend::Real {
+            let mode = if recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+            self.add_os_watch(&watched, mode)?;
+        }
+
+        let mut state = self.state.lock().map_err(|_| "Failed to lock watcher state")?;
+        let session = session.map(str::to_string);
+        tracing::debug!("Watching {:?} {:?} path '{}' (subscription {})", backend, session, path, id);
+        state.subscriptions.insert(id.clone(), Subscription { backend, session, path: watched, recursive });
+        Ok(id)
+    }
+
+    /// Stop watching. Unknown IDs are ignored.
+    pub fn unsubscribe(&self, id: &str) {
+        let subscription = {
+            let Ok(mut state) = self.state.lock() else { return };
+            let Some(subscription) = state.subscriptions.remove(id) else { return };
+            subscription
+        };
+
+        if subscription.backend == WatchBackend::Real {
+            self.remove_os_watch(&subscription.path);
+        }
+        tracing::debug!("Stopped watching '{}' (subscription {})", subscription.path.display(), id);
+    }
+
+    /// Internal mutation hook used by backends that change state in-process
+    pub fn notify_change(&self, backend: WatchBackend, session: Option<&str>, path: &str, change_type: ChangeType) {
+        self.push(backend, session, path.to_string(), change_type, None);
+    }
+
+    /// Internal mutation hook for renames/moves
+    pub fn notify_rename(&self, backend: WatchBackend, session: Option<&str>, old_path: &str, new_path: &str) {
+        self.push(backend, session, new_path.to_string(), ChangeType::Renamed, Some(old_path.to_string()));
+    }
+
+    fn push(&self, backend: WatchBackend, session: Option<&str>, path: String, change_type: ChangeType, old_path: Option<String>) {
+        let Ok(mut state) = self.state.lock() else { return };
+
+        let is_watched = state.subscriptions.values().any(|s| {
+            s.matches(backend, session, Path::new(&path))
+                || old_path.as_deref().is_some_and(|old| s.matches(backend, session, Path::new(old)))
+        });
+        if !is_watched {
+            return;
+        }
+
+        let key = (backend, session.map(str::to_string), path);
+        let merged = match state.pending.get(&key) {
+            Some(previous) => merge_change(previous.change_type, change_type),
+            None => Some(change_type),
+        };
+
+        match merged {
+            Some(change_type) => {
+                let old_path = old_path.or_else(|| state.pending.get(&key).and_then(|p| p.old_path.clone()));
+                state.pending.insert(key, PendingChange { change_type, old_path, last_seen: Instant::now() });
+            }
+            None => {
+                state.pending.remove(&key);
+            }
+        }
+
+        if !state.flusher_running {
+            state.flusher_running = true;
+            let watcher = WATCHER.clone();
+            std::thread::spawn(move || watcher.run_flusher());
+        }
+    }
+
+    /// Periodically emits changes that have been quiet for DEBOUNCE_INTERVAL.
+    /// Stops itself once nothing is pending.
+    fn run_flusher(&self) {
+        loop {
+            std::thread::sleep(DEBOUNCE_INTERVAL / 2);
+
+            let ready = {
+                let Ok(mut state) = self.state.lock() else { return };
+                if state.pending.is_empty() {
+                    state.flusher_running = false;
+                    return;
+                }
+
+                let now = Instant::now();
+                let ready_keys: Vec<_> = state.pending.iter()
+                    .filter(|(_, p)| now.duration_since(p.last_seen) >= DEBOUNCE_INTERVAL)
+                    .map(|(k, _)| k.clone())
+                    .collect();
+
+                let mut ready = Vec::new();
+                for key in ready_keys {
+                    if let Some(pending) = state.pending.remove(&key) {
+                        let (backend, session, path) = key;
+                        let subscription_ids = state.subscriptions.iter()
+                            .filter(|(_, s)| {
+                                s.matches(backend, session.as_deref(), Path::new(&path))
+                                    || pending.old_path.as_deref().is_some_and(|old| s.matches(backend, session.as_deref(), Path::new(old)))
+                            })
+                            .map(|(id, _)| id.clone())
+                            .collect::<Vec<_>>();
+
+                        if !subscription_ids.is_empty() {
+                            ready.push(FileSystemChange {
+                                backend,
+                                session,
+                                path,
+                                change_type: pending.change_type,
+                                old_path: pending.old_path,
+                                subscription_ids,
+                            });
+                        }
+                    }
+                }
+                ready
+            };
+
+            for change in ready {
+                // No receivers is not an error: nobody is listening right now
+                let _ = self.tx.send(change);
+            }
+        }
+    }
+
+    fn add_os_watch(&self, path: &Path, mode: RecursiveMode) -> Result<(), String> {
+        let mut os = self.os.lock().map_err(|_| "Failed to lock watcher state")?;
+        let os = &mut *os;
+
+        if os.watcher.is_none() {
+            let watcher = notify::recommended_watcher(|result: notify::Result<Event>| match result {
+                Ok(event) => WATCHER.handle_os_event(event),
+                Err(e) => tracing::warn!("Filesystem watcher error: {}", e),
+            })
+            .map_err(|e| format!("Failed to start filesystem watcher: {}", e))?;
+            os.watcher = Some(watcher);
+        }
+
+        let entry = os.watches.entry(path.to_path_buf()).or_insert((0, RecursiveMode::NonRecursive));
+        // Re-register when a recursive subscription upgrades an existing non-recursive watch
+        let needs_watch = entry.0 == 0 || (mode == RecursiveMode::Recursive && entry.1 == RecursiveMode::NonRecursive);
+
+        if needs_watch {
+            let watcher = os.watcher.as_mut().expect("watcher initialized above");
+            if entry.0 > 0 {
+                let _ = watcher.unwatch(path);
+            }
+            watcher.watch(path, mode)
+                .map_err(|e| format!("Failed to watch '{}': {}", path.display(), e))?;
+            entry.1 = mode;
+        }
+        entry.0 += 1;
+        Ok(())
+    }
+
+    fn remove_os_watch(&self, path: &Path) {
+        let Ok(mut os) = self.os.lock() else { return };
+        let os = &mut *os;
+
+        let remove = match os.watches.get_mut(path) {
+            Some(entry) => {
+                entry.0 = entry.0.saturating_sub(1);
+                entry.0 == 0
+            }
+            None => false,
+        };
+
+        if remove {
+            os.watches.remove(path);
+            if let Some(watcher) = os.watcher.as_mut() {
+                let _ = watcher.unwatch(path);
+            }
+        }
+    }
+
+    fn handle_os_event(&self, event: Event) {
+        let path_str = |p: &PathBuf| p.to_string_lossy().to_string();
+
+        match event.kind {
+            EventKind::Create(_) => {
+                for path in &event.paths {
+                    self.notify_change(WatchBackend::Real, None, &path_str(path), ChangeType::Created);
+                }
+            }
+            EventKind::Remove(_) => {
+                for path in &event.paths {
+                    self.notify_change(WatchBackend::Real, None, &path_str(path), ChangeType::Deleted);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                self.notify_rename(WatchBackend::Real, None, &path_str(&event.paths[0]), &path_str(&event.paths[1]));
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                for path in &event.paths {
+                    self.notify_change(WatchBackend::Real, None, &path_str(path), ChangeType::Deleted);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                for path in &event.paths {
+                    self.notify_change(WatchBackend::Real, None, &path_str(path), ChangeType::Created);
+                }
+            }
+            EventKind::Modify(_) => {
+                for path in &event.paths {
+                    self.notify_change(WatchBackend::Real, None, &path_str(path), ChangeType::Modified);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_change() {
+        assert_eq!(merge_change(ChangeType::Created, ChangeType::Modified), Some(ChangeType::Created));
+        assert_eq!(merge_change(ChangeType::Created, ChangeType::Deleted), None);
+        assert_eq!(merge_change(ChangeType::Deleted, ChangeType::Created), Some(ChangeType::Modified));
+        assert_eq!(merge_change(ChangeType::Modified, ChangeType::Deleted), Some(ChangeType::Deleted));
+    }
+
+    #[test]
+    fn test_subscription_matching() {
+        let sub = Subscription { backend: WatchBackend::Virtual, session: None, path: PathBuf::from("/home/docs"), recursive: false };
+
+        assert!(sub.matches(WatchBackend::Virtual, None, Path::new("/home/docs")));
+        assert!(sub.matches(WatchBackend::Virtual, None, Path::new("/home/docs/a.txt")));
+        assert!(!sub.matches(WatchBackend::Virtual, None, Path::new("/home/docs/nested/a.txt")));
+        assert!(!sub.matches(WatchBackend::Real, None, Path::new("/home/docs/a.txt")));
+        assert!(!sub.matches(WatchBackend::Virtual, None, Path::new("/home/docs2/a.txt")));
+
+        let recursive = Subscription { recursive: true, ..sub };
+        assert!(recursive.matches(WatchBackend::Virtual, None, Path::new("/home/docs/nested/a.txt")));
+
+        let remote = Subscription { backend: WatchBackend::Remote, session: Some("sftp:a".to_string()), ..recursive };
+        assert!(remote.matches(WatchBackend::Remote, Some("sftp:a"), Path::new("/home/docs/a.txt")));
+        assert!(!remote.matches(WatchBackend::Remote, Some("sftp:b"), Path::new("/home/docs/a.txt")));
+        assert!(!remote.matches(WatchBackend::Remote, None, Path::new("/home/docs/a.txt")));
+    }
+
+    #[test]
+    fn test_debounced_delivery() {
+        let mut rx = WATCHER.subscribe_events();
+        let id = WATCHER.subscribe(WatchBackend::Temporary, Some("temp-a"), "/watch-test", false).unwrap();
+
+        // Burst of changes on one path collapses into a single event
+        WATCHER.notify_change(WatchBackend::Temporary, Some("temp-a"), "/watch-test/file.txt", ChangeType::Created);
+        WATCHER.notify_change(WatchBackend::Temporary, Some("temp-a"), "/watch-test/file.txt", ChangeType::Modified);
+        WATCHER.notify_change(WatchBackend::Temporary, Some("temp-a"), "/other/file.txt", ChangeType::Created);
+        // Same path in another session is not delivered to this subscription
+        WATCHER.notify_change(WatchBackend::Temporary, Some("temp-b"), "/watch-test/other.txt", ChangeType::Created);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let change = loop {
+            match rx.try_recv() {
+                Ok(change) if change.subscription_ids.contains(&id) => break change,
+                Ok(_) => continue,
+                Err(_) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(20)),
+                Err(e) => panic!("no change received: {:?}", e),
+            }
+        };
+
+        assert_eq!(change.path, "/watch-test/file.txt");
+        assert_eq!(change.session.as_deref(), Some("temp-a"));
+        assert_eq!(change.change_type, ChangeType::Created);
+
+        WATCHER.unsubscribe(&id);
+    }
+}
@@ -0,0 +1,1167 @@
+//! Файловая система WebDAV (Nextcloud, NAS, Apache mod_dav)
+//!
+//! Панель выбирает подключение через `panel_fs = "webdav:<id профиля>"`. Пути панели
+//! отсчитываются от URL профиля: `/` — сам URL.
+//!
+//! Корзина — скрытая коллекция `/.trash` внутри корня в формате freedesktop.org.
+
+use crate::api::remote::{
+    normalize_remote_path, pipe, remote_join, remote_name, remote_parent, Body as RemoteBody, PipeWriter,
+    RangeReader, RemoteTrash, TrashStore,
+};
+use crate::api::watcher::{ChangeType, WatchBackend, WATCHER};
+use crate::config::{WebDavAuth, WebDavProfile};
+use crate::core::{
+    guess_mime_type, DirectoryPage, ErrorKind, FileReader, FileSystem, FileSystemEntry,
+    FileSystemError, FileSystemResult, FileWriter, ListOptions, TrashEntry,
+};
+use base64::{engine::general_purpose, Engine as _};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+const TRASH_DIR: &str = "/.trash";
+/// Сколько блоков записи ждут отправки, пока загрузка не догонит писателя
+const UPLOAD_QUEUE_CHUNKS: usize = 8;
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:resourcetype/>
+    <d:getcontentlength/>
+    <d:getlastmodified/>
+    <d:getcontenttype/>
+  </d:prop>
+</d:propfind>"#;
+
+/// Категория ошибки по HTTP-статусу
+pub(crate) fn status_kind(status: u16) -> ErrorKind {
+    match status {
+        // 409 — нет родительской коллекции
+        404 | 409 | 410 => ErrorKind::NotFound,
+        401 | 403 => ErrorKind::PermissionDenied,
+        // 405 на MKCOL и 412 при `Overwrite: F` / `If-None-Match: *` — цель уже есть
+        405 | 412 => ErrorKind::AlreadyExists,
+        423 => ErrorKind::Locked,
+        413 | 507 => ErrorKind::QuotaExceeded,
+        400 => ErrorKind::InvalidInput,
+        _ => ErrorKind::Io,
+    }
+}
+
+/// Ресурс из ответа PROPFIND; `path` — декодированный путь на сервере без `/` в конце
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct DavResource {
+    pub path: String,
+    pub is_dir: bool,
+    pub size: Option<u64>,
+    pub modified: Option<u64>,
+    pub content_type: Option<String>,
+}
+
+/// Путь из `href` (полный URL или абсолютный путь)
+fn href_path(href: &str) -> String {
+    let path = match href.find("://") {
+        Some(index) => {
+            let rest = &href[index + 3..];
+            rest.find('/').map(|slash| &rest[slash..]).unwrap_or("/")
+        }
+        None => href,
+    };
+    let decoded = urlencoding::decode(path)
+        .map(|p| p.into_owned())
+        .unwrap_or_else(|_| path.to_string());
+    let trimmed = decoded.trim_end_matches('/');
+    if trimmed.is_empty() {
+        "/".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Разбор ответа 207 Multi-Status
+pub(crate) fn parse_multistatus(xml: &str) -> FileSystemResult<Vec<DavResource>> {
+    let doc = roxmltree::Document::parse(xml)
+        .map_err(|e| FileSystemError::new(format!("Invalid PROPFIND response: {}", e)))?;
+    let is_dav = |node: &roxmltree::Node, name: &str| {
+        node.is_element() && node.tag_name().name() == name && node.tag_name().namespace() == Some("DAV:")
+    };
+
+    let mut resources = Vec::new();
+    for response in doc.descendants().filter(|n| is_dav(n, "response")) {
+        let Some(href) = response.children().find(|n| is_dav(n, "href")).and_then(|n| n.text()) else {
+            continue;
+        };
+        let mut resource = DavResource {
+            path: href_path(href.trim()),
+            ..Default::default()
+        };
+
+        for propstat in response.children().filter(|n| is_dav(n, "propstat")) {
+            // Свойства, которых у ресурса нет, приходят отдельным propstat с 404
+            let found = propstat
+                .children()
+                .find(|n| is_dav(n, "status"))
+                .and_then(|n| n.text())
+                .map(|status| status.contains(" 200 "))
+                .unwrap_or(true);
+            if !found {
+                continue;
+            }
+            let Some(prop) = propstat.children().find(|n| is_dav(n, "prop")) else {
+                continue;
+            };
+
+            for property in prop.children().filter(|n| n.is_element()) {
+                let text = property.text().map(str::trim).filter(|t| !t.is_empty());
+                match property.tag_name().name() {
+                    "resourcetype" => resource.is_dir = property.children().any(|n| is_dav(&n, "collection")),
+                    "getcontentlength" => resource.size = text.and_then(|t| t.parse().ok()),
+                    "getlastmodified" => {
+                        resource.modified = text
+                            .and_then(|t| chrono::DateTime::parse_from_rfc2822(t).ok())
+                            .map(|date| date.timestamp().max(0) as u64)
+                    }
+                    "getcontenttype" => resource.content_type = text.map(str::to_string),
+                    _ => {}
+                }
+            }
+        }
+        resources.push(resource);
+    }
+    Ok(resources)
+}
+
+/// URL ресурса: `base_url` (без `/` в конце) + закодированные сегменты пути
+pub(crate) fn dav_url(base_url: &str, path: &str, collection: bool) -> String {
+    let mut url = base_url.to_string();
+    let mut is_root = true;
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        url.push('/');
+        url.push_str(&urlencoding::encode(segment));
+        is_root = false;
+    }
+    // Корень профиля — всегда коллекция
+    if collection || is_root {
+        url.push('/');
+    }
+    url
+}
+
+/// Путь панели по пути на сервере; `None` — ресурс вне корня профиля
+pub(crate) fn panel_path(base_path: &str, server_path: &str) -> Option<String> {
+    if server_path == base_path || (base_path.is_empty() && server_path == "/") {
+        return Some("/".to_string());
+    }
+    server_path
+        .strip_prefix(base_path)
+        .filter(|rest| rest.starts_with('/'))
+        .map(str::to_string)
+}
+
+/// Путь запроса (request-target) из полного URL
+fn request_target(url: &str) -> &str {
+    let rest = url.find("://").map(|i| &url[i + 3..]).unwrap_or(url);
+    rest.find('/').map(|i| &rest[i..]).unwrap_or("/")
+}
+
+/// Параметры заголовка `WWW-Authenticate` после схемы (`realm="x", nonce="y", ...`)
+pub(crate) fn parse_auth_params(params: &str) -> HashMap<String, String> {
+    let mut result = HashMap::new();
+    let mut rest = params.trim();
+
+    while !rest.is_empty() {
+        let Some(eq) = rest.find('=') else { break };
+        let key = rest[..eq].trim().trim_start_matches(',').trim().to_ascii_lowercase();
+        rest = rest[eq + 1..].trim_start();
+
+        let value;
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            value = quoted[..end].to_string();
+            rest = quoted.get(end + 1..).unwrap_or("");
+        } else {
+            let end = rest.find(',').unwrap_or(rest.len());
+            value = rest[..end].trim().to_string();
+            rest = &rest[end..];
+        }
+        result.insert(key, value);
+        rest = rest.trim_start().trim_start_matches(',').trim_start();
+    }
+    result
+}
+
+/// Challenge Digest-аутентификации (RFC 7616)
+#[derive(Debug, Clone)]
+pub(crate) struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: String,
+    /// Сервер поддерживает `qop=auth`
+    qop_auth: bool,
+    count: u32,
+}
+
+impl DigestChallenge {
+    pub(crate) fn parse(header: &str) -> Option<Self> {
+        let (scheme, params) = header.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("digest") {
+            return None;
+        }
+        let params = parse_auth_params(params);
+
+        Some(Self {
+            realm: params.get("realm").cloned().unwrap_or_default(),
+            nonce: params.get("nonce")?.clone(),
+            opaque: params.get("opaque").cloned(),
+            algorithm: params.get("algorithm").cloned().unwrap_or_else(|| "MD5".to_string()),
+            qop_auth: params
+                .get("qop")
+                .map(|qop| qop.split(',').any(|q| q.trim() == "auth"))
+                .unwrap_or(false),
+            count: 0,
+        })
+    }
+
+    fn hash(&self, data: &str) -> String {
+        let digest: Vec<u8> = if self.algorithm.to_ascii_uppercase().starts_with("SHA-256") {
+            Sha256::digest(data.as_bytes()).to_vec()
+        } else {
+            md5::Md5::digest(data.as_bytes()).to_vec()
+        };
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Значение заголовка `Authorization`
+    pub(crate) fn authorization(&self, username: &str, password: &str, method: &str, uri: &str, cnonce: &str) -> String {
+        let nc = format!("{:08x}", self.count);
+        let mut ha1 = self.hash(&format!("{}:{}:{}", username, self.realm, password));
+        if self.algorithm.to_ascii_uppercase().ends_with("-SESS") {
+            ha1 = self.hash(&format!("{}:{}:{}", ha1, self.nonce, cnonce));
+        }
+        let ha2 = self.hash(&format!("{}:{}", method, uri));
+        let response = if self.qop_auth {
+            self.hash(&format!("{}:{}:{}:{}:auth:{}", ha1, self.nonce, nc, cnonce, ha2))
+        } else {
+            self.hash(&format!("{}:{}:{}", ha1, self.nonce, ha2))
+        };
+
+        let mut header = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+            username, self.realm, self.nonce, uri, self.algorithm, response
+        );
+        if self.qop_auth {
+            header.push_str(&format!(", qop=auth, nc={}, cnonce=\"{}\"", nc, cnonce));
+        }
+        if let Some(opaque) = &self.opaque {
+            header.push_str(&format!(", opaque=\"{}\"", opaque));
+        }
+        header
+    }
+}
+
+enum Credentials {
+    None,
+    Basic { username: String, password: String },
+    Digest { username: String, password: String },
+}
+
+/// Тело запроса
+enum Body<'a> {
+    Empty,
+    Bytes(&'a [u8]),
+    /// Поток (chunked transfer encoding); его нельзя перечитать, поэтому запрос не повторяется
+    Stream(&'a mut (dyn Read + Send)),
+}
+
+/// Basic-аутентификация по http:// без явного разрешения в профиле
+pub(crate) fn is_insecure_basic(profile: &WebDavProfile) -> bool {
+    matches!(profile.auth, WebDavAuth::Basic { .. })
+        && profile.url.trim().starts_with("http://")
+        && !profile.allow_insecure_basic
+}
+
+/// HTTP-клиент одного подключения
+struct DavClient {
+    agent: ureq::Agent,
+    /// URL профиля без `/` в конце
+    base_url: String,
+    /// Декодированный путь URL профиля без `/` в конце (`""` для корня сайта)
+    base_path: String,
+    credentials: Credentials,
+    digest: Mutex<Option<DigestChallenge>>,
+}
+
+impl DavClient {
+    fn new(profile: &WebDavProfile, secret: Option<&str>) -> FileSystemResult<Self> {
+        let url = profile.url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(FileSystemError::with_kind(
+                ErrorKind::InvalidInput,
+                format!("WebDAV URL must start with http:// or https://: {}", url),
+            ));
+        }
+        if is_insecure_basic(profile) {
+            return Err(FileSystemError::with_kind(
+                ErrorKind::InvalidInput,
+                format!("Basic authentication over http:// sends the password in clear text: {}", url),
+            ));
+        }
+        let base_url = url.trim_end_matches('/').to_string();
+        let base_path = href_path(&base_url).trim_end_matches('/').to_string();
+
+        let password = |saved: &Option<String>| {
+            secret
+                .or(saved.as_deref())
+                .map(str::to_string)
+                .ok_or_else(|| FileSystemError::permission_denied(format!("Password required for {}", profile.name)))
+        };
+        let credentials = match &profile.auth {
+            WebDavAuth::None => Credentials::None,
+            WebDavAuth::Basic { username, password: saved } => Credentials::Basic {
+                username: username.clone(),
+                password: password(saved)?,
+            },
+            WebDavAuth::Digest { username, password: saved } => Credentials::Digest {
+                username: username.clone(),
+                password: password(saved)?,
+            },
+        };
+
+        Ok(Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout_connect(CONNECT_TIMEOUT)
+                .timeout_read(IO_TIMEOUT)
+                .timeout_write(IO_TIMEOUT)
+                .build(),
+            base_url,
+            base_path,
+            credentials,
+            digest: Mutex::new(None),
+        })
+    }
+
+    fn url(&self, path: &str, collection: bool) -> String {
+        dav_url(&self.base_url, path, collection)
+    }
+
+    fn authorization(&self, method: &str, url: &str) -> Option<String> {
+        match &self.credentials {
+            Credentials::None => None,
+            Credentials::Basic { username, password } => Some(format!(
+                "Basic {}",
+                general_purpose::STANDARD.encode(format!("{}:{}", username, password))
+            )),
+            Credentials::Digest { username, password } => {
+                let mut guard = self.digest.lock().unwrap();
+                let challenge = guard.as_mut()?;
+                challenge.count += 1;
+                let cnonce = uuid::Uuid::new_v4().simple().to_string();
+                Some(challenge.authorization(username, password, method, request_target(url), &cnonce))
+            }
+        }
+    }
+
+    /// Запомнить Digest-challenge из ответа 401; `false` — повторять запрос бессмысленно
+    fn accept_challenge(&self, response: &ureq::Response) -> bool {
+        if !matches!(self.credentials, Credentials::Digest { .. }) {
+            return false;
+        }
+        match response.all("WWW-Authenticate").into_iter().find_map(DigestChallenge::parse) {
+            Some(challenge) => {
+                *self.digest.lock().unwrap() = Some(challenge);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn request(&self, method: &str, url: &str, headers: &[(&str, &str)], mut body: Body<'_>) -> FileSystemResult<ureq::Response> {
+        for attempt in 0..2 {
+            let mut request = self.agent.request(method, url);
+            for (name, value) in headers {
+                request = request.set(name, value);
+            }
+            if let Some(authorization) = self.authorization(method, url) {
+                request = request.set("Authorization", &authorization);
+            }
+
+            let result = match &mut body {
+                Body::Empty => request.call(),
+                Body::Bytes(data) => request.send_bytes(data),
+                Body::Stream(reader) => request.send(&mut **reader),
+            };
+
+            let target = urlencoding::decode(request_target(url))
+                .map(|p| p.into_owned())
+                .unwrap_or_else(|_| url.to_string());
+            match result {
+                Ok(response) => return Ok(response),
+                // Первый 401 с Digest — получить nonce и повторить
+                Err(ureq::Error::Status(401, response))
+                    if attempt == 0 && !matches!(body, Body::Stream(_)) && self.accept_challenge(&response) =>
+                {
+                    continue
+                }
+                Err(ureq::Error::Status(status, response)) => {
+                    return Err(FileSystemError::with_kind(
+                        status_kind(status),
+                        format!("{} {} failed: {} {}", method, target, status, response.status_text()),
+                    ));
+                }
+                Err(ureq::Error::Transport(err)) => {
+                    return Err(FileSystemError::new(format!("{} {} failed: {}", method, target, err)));
+                }
+            }
+        }
+        Err(FileSystemError::permission_denied("WebDAV authentication failed"))
+    }
+
+    fn propfind(&self, path: &str, depth: &str) -> FileSystemResult<Vec<DavResource>> {
+        let url = self.url(path, depth != "0");
+        let response = self.request(
+            "PROPFIND",
+            &url,
+            &[("Depth", depth), ("Content-Type", "application/xml; charset=utf-8")],
+            Body::Bytes(PROPFIND_BODY.as_bytes()),
+        )?;
+
+        let mut xml = String::new();
+        response
+            .into_reader()
+            .read_to_string(&mut xml)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read PROPFIND response: {}", e)))?;
+        parse_multistatus(&xml)
+    }
+}
+
+fn to_entry(path: &str, resource: &DavResource) -> FileSystemEntry {
+    let name = if path == "/" { "/".to_string() } else { remote_name(path).to_string() };
+    let mime_type = resource
+        .content_type
+        .clone()
+        .filter(|mime| !resource.is_dir && mime != "application/octet-stream")
+        .or_else(|| guess_mime_type(&name, resource.is_dir));
+
+    FileSystemEntry {
+        path: path.to_string(),
+        is_dir: resource.is_dir,
+        is_file: !resource.is_dir,
+        size: if resource.is_dir { None } else { resource.size },
+        modified: resource.modified,
+        is_hidden: name.starts_with('.'),
+        mime_type,
+        name,
+        ..Default::default()
+    }
+}
+
+/// Подключение к WebDAV-серверу по сохраненному профилю
+///
+/// Как и у SFTP, экземпляр без подключения отвечает на все вызовы ошибкой `Locked`.
+#[derive(Clone)]
+pub struct WebDavFileSystem {
+    label: String,
+    client: Option<Arc<DavClient>>,
+}
+
+impl WebDavFileSystem {
+    /// Подключиться и проверить доступ к корню профиля
+    ///
+    /// `secret` заменяет сохраненный пароль.
+    pub fn connect(label: &str, profile: &WebDavProfile, secret: Option<&str>) -> FileSystemResult<Self> {
+        tracing::info!("Connecting to WebDAV {}", profile.url);
+
+        let fs = Self {
+            label: label.to_string(),
+            client: Some(Arc::new(DavClient::new(profile, secret)?)),
+        };
+        if !fs.stat("/")?.is_dir {
+            return Err(FileSystemError::not_a_directory(format!("{} is not a WebDAV collection", profile.url)));
+        }
+        Ok(fs)
+    }
+
+    /// Заглушка для панели, чей сервер не подключен
+    pub fn disconnected(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            client: None,
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.client.is_some()
+    }
+
+    fn client(&self) -> FileSystemResult<&Arc<DavClient>> {
+        self.client
+            .as_ref()
+            .ok_or_else(|| FileSystemError::locked(format!("WebDAV session '{}' is not connected", self.label)))
+    }
+
+    /// Метка сессии (`panel_fs`)
+    pub(crate) fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn notify_change(&self, path: &str, change_type: ChangeType) {
+        WATCHER.notify_change(WatchBackend::Remote, Some(&self.label), path, change_type);
+    }
+
+    fn stat(&self, path: &str) -> FileSystemResult<FileSystemEntry> {
+        let client = self.client()?;
+        let resource = client
+            .propfind(path, "0")?
+            .into_iter()
+            .next()
+            .ok_or_else(|| FileSystemError::not_found(format!("Path does not exist: {}", path)))?;
+        Ok(to_entry(path, &resource))
+    }
+
+    fn exists(&self, path: &str) -> FileSystemResult<bool> {
+        match self.stat(path) {
+            Ok(_) => Ok(true),
+            Err(err) if err.kind == ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn list(&self, dir: &str) -> FileSystemResult<Vec<FileSystemEntry>> {
+        let client = self.client()?;
+        Ok(client
+            .propfind(dir, "1")?
+            .iter()
+            .filter_map(|resource| {
+                let path = panel_path(&client.base_path, &resource.path)?;
+                (path != dir).then(|| to_entry(&path, resource))
+            })
+            .collect())
+    }
+
+    fn mkcol(&self, path: &str) -> FileSystemResult<()> {
+        let client = self.client()?;
+        client.request("MKCOL", &client.url(path, true), &[], Body::Empty)?;
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &str) -> FileSystemResult<()> {
+        if path == "/" || self.exists(path)? {
+            return Ok(());
+        }
+        self.create_dir_all(remote_parent(path))?;
+        self.mkcol(path)
+    }
+
+    /// MOVE или COPY без перезаписи
+    fn transfer(&self, method: &str, from: &str, to: &str) -> FileSystemResult<()> {
+        let client = self.client()?;
+        let is_dir = self.stat(from)?.is_dir;
+        let destination = client.url(to, is_dir);
+        client.request(
+            method,
+            &client.url(from, is_dir),
+            &[("Destination", destination.as_str()), ("Overwrite", "F"), ("Depth", "infinity")],
+            Body::Empty,
+        )?;
+        Ok(())
+    }
+
+    fn delete(&self, path: &str) -> FileSystemResult<()> {
+        let client = self.client()?;
+        let is_dir = self.stat(path)?.is_dir;
+        client.request("DELETE", &client.url(path, is_dir), &[], Body::Empty)?;
+        Ok(())
+    }
+
+    fn put(&self, path: &str, content: &[u8], create_new: bool) -> FileSystemResult<()> {
+        let client = self.client()?;
+        let headers: &[(&str, &str)] = if create_new { &[("If-None-Match", "*")] } else { &[] };
+        client.request("PUT", &client.url(path, false), headers, Body::Bytes(content))?;
+        Ok(())
+    }
+
+    fn get(&self, path: &str) -> FileSystemResult<Vec<u8>> {
+        let client = self.client()?;
+        let response = client.request("GET", &client.url(path, false), &[], Body::Empty)?;
+        let mut content = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut content)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read {}: {}", path, e)))?;
+        Ok(content)
+    }
+
+    fn write_all(&self, path: &str, content: &[u8]) -> FileSystemResult<()> {
+        if !self.exists(remote_parent(path))? {
+            return Err(FileSystemError::not_found("Parent directory does not exist"));
+        }
+        self.put(path, content, false)?;
+        self.notify_change(path, ChangeType::Modified);
+        Ok(())
+    }
+
+    fn trash(&self) -> RemoteTrash<'_, Self> {
+        RemoteTrash::new(self, TRASH_DIR)
+    }
+}
+
+impl TrashStore for WebDavFileSystem {
+    fn exists(&self, path: &str) -> FileSystemResult<bool> {
+        WebDavFileSystem::exists(self, path)
+    }
+
+    fn create_dir_all(&self, path: &str) -> FileSystemResult<()> {
+        WebDavFileSystem::create_dir_all(self, path)
+    }
+
+    fn create_new(&self, path: &str, content: &[u8]) -> FileSystemResult<bool> {
+        match self.put(path, content, true) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind == ErrorKind::AlreadyExists => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn read(&self, path: &str) -> FileSystemResult<Vec<u8>> {
+        self.get(path)
+    }
+
+    fn metadata(&self, path: &str) -> FileSystemResult<(bool, Option<u64>)> {
+        let entry = self.stat(path)?;
+        Ok((entry.is_dir, entry.size))
+    }
+
+    fn rename(&self, from: &str, to: &str) -> FileSystemResult<()> {
+        self.transfer("MOVE", from, to)
+    }
+
+    fn remove_all(&self, path: &str) -> FileSystemResult<()> {
+        self.delete(path)
+    }
+
+    fn list_names(&self, dir: &str) -> FileSystemResult<Vec<String>> {
+        Ok(self.list(dir)?.into_iter().map(|entry| entry.name).collect())
+    }
+}
+
+/// Поток ресурса с заданного смещения
+fn open_at(client: &DavClient, url: &str, offset: u64) -> io::Result<RemoteBody> {
+    let range = format!("bytes={}-", offset);
+    let headers: Vec<(&str, &str)> = if offset > 0 { vec![("Range", range.as_str())] } else { Vec::new() };
+    let response = client
+        .request("GET", url, &headers, Body::Empty)
+        .map_err(|e| io::Error::other(e.message))?;
+
+    let partial = response.status() == 206;
+    RangeReader::skip_to(response.into_reader(), offset, partial)
+}
+
+/// Потоковая загрузка: PUT идет в фоновом потоке, данные передаются ему через канал
+/// и не сохраняются на диск (файл может быть из хранилища). Писатель, брошенный без
+/// `finish`, обрывает запрос, и недописанный файл на сервере не сохраняется.
+struct WebDavWriter {
+    fs: WebDavFileSystem,
+    path: String,
+    pipe: Option<PipeWriter>,
+    upload: Option<JoinHandle<FileSystemResult<()>>>,
+}
+
+impl WebDavWriter {
+    /// Дождаться конца загрузки
+    fn wait(&mut self) -> FileSystemResult<()> {
+        self.pipe = None;
+        match self.upload.take() {
+            Some(upload) => upload
+                .join()
+                .unwrap_or_else(|_| Err(FileSystemError::new("WebDAV upload thread panicked"))),
+            None => Err(FileSystemError::new("WebDAV upload has already failed")),
+        }
+    }
+}
+
+impl Write for WebDavWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let sent = match &mut self.pipe {
+            Some(pipe) => pipe.write(buf),
+            None => Err(io::Error::from(io::ErrorKind::BrokenPipe)),
+        };
+        if sent.is_ok() {
+            return sent;
+        }
+        // Загрузка оборвалась раньше писателя — вернуть ее ошибку
+        match self.wait() {
+            Err(err) => Err(io::Error::other(err.message)),
+            Ok(()) => Err(io::Error::from(io::ErrorKind::BrokenPipe)),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl FileWriter for WebDavWriter {
+    fn finish(mut self: Box<Self>) -> FileSystemResult<()> {
+        // Если загрузка уже завершилась, ее результат вернет `wait`
+        if let Some(pipe) = self.pipe.take() {
+            pipe.close();
+        }
+        self.wait()?;
+        self.fs.notify_change(&self.path, ChangeType::Modified);
+        Ok(())
+    }
+}
+
+impl FileSystem for WebDavFileSystem {
+    fn read_directory_with_options(
+        &self,
+        path: &str,
+        options: &ListOptions,
+    ) -> FileSystemResult<DirectoryPage> {
+        let dir = normalize_remote_path("/", path);
+        let name_filter = options.name_filter()?;
+
+        let mut result: Vec<FileSystemEntry> = self
+            .list(&dir)?
+            .into_iter()
+            // Корзина подключения никогда не показывается
+            .filter(|entry| entry.path != TRASH_DIR)
+            .filter(|entry| name_filter.matches(&entry.name))
+            .filter(|entry| !entry.is_hidden || options.include_hidden())
+            .collect();
+
+        result.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        });
+
+        options.apply(result)
+    }
+
+    fn get_file_info(&self, path: &str) -> FileSystemResult<FileSystemEntry> {
+        self.stat(&normalize_remote_path("/", path))
+    }
+
+    fn delete_item(&self, path: &str) -> FileSystemResult<()> {
+        let path = normalize_remote_path("/", path);
+        if path == "/" {
+            return Err(FileSystemError::permission_denied("Cannot delete the root directory"));
+        }
+        self.delete(&path)?;
+        self.notify_change(&path, ChangeType::Deleted);
+        Ok(())
+    }
+
+    fn trash_item(&self, path: &str) -> FileSystemResult<TrashEntry> {
+        let path = normalize_remote_path("/", path);
+        if path == "/" {
+            return Err(FileSystemError::permission_denied("Cannot move the root to trash"));
+        }
+        let entry = self.trash().trash(&path)?;
+        self.notify_change(&path, ChangeType::Deleted);
+        Ok(entry)
+    }
+
+    fn list_trash(&self) -> FileSystemResult<Vec<TrashEntry>> {
+        self.trash().list()
+    }
+
+    fn restore_from_trash(&self, id: &str) -> FileSystemResult<TrashEntry> {
+        let (entry, target) = self.trash().restore(id)?;
+        self.notify_change(&target, ChangeType::Created);
+        Ok(entry)
+    }
+
+    fn empty_trash(&self) -> FileSystemResult<usize> {
+        self.trash().purge_where(|_| true)
+    }
+
+    fn purge_trash_older_than(&self, cutoff: u64) -> FileSystemResult<usize> {
+        self.trash().purge_where(|entry| entry.deleted_at < cutoff)
+    }
+
+    fn rename_item(&self, old_path: &str, new_name: &str) -> FileSystemResult<()> {
+        let old_path = normalize_remote_path("/", old_path);
+        if new_name.is_empty() || new_name.contains('/') {
+            return Err(FileSystemError::with_kind(ErrorKind::InvalidInput, format!("Invalid name: {}", new_name)));
+        }
+
+        let new_path = remote_join(remote_parent(&old_path), new_name);
+        if self.exists(&new_path)? {
+            return Err(FileSystemError::already_exists(format!("File already exists: {}", new_name)));
+        }
+        self.transfer("MOVE", &old_path, &new_path)?;
+
+        WATCHER.notify_rename(WatchBackend::Remote, Some(&self.label), &old_path, &new_path);
+        Ok(())
+    }
+
+    fn create_folder(&self, path: &str, name: &str) -> FileSystemResult<()> {
+        let parent = normalize_remote_path("/", path);
+        if !self.exists(&parent)? {
+            return Err(FileSystemError::not_found(format!("Parent directory does not exist: {}", path)));
+        }
+
+        let new_path = remote_join(&parent, name);
+        if self.exists(&new_path)? {
+            return Err(FileSystemError::already_exists(format!("Folder already exists: {}", name)));
+        }
+        self.mkcol(&new_path)?;
+
+        self.notify_change(&new_path, ChangeType::Created);
+        Ok(())
+    }
+
+    fn create_file(&self, path: &str, name: &str, content: Option<&str>) -> FileSystemResult<()> {
+        let new_path = remote_join(&normalize_remote_path("/", path), name);
+        if self.exists(&new_path)? {
+            return Err(FileSystemError::already_exists(format!("File already exists: {}", name)));
+        }
+
+        self.put(&new_path, content.unwrap_or_default().as_bytes(), true)?;
+        self.notify_change(&new_path, ChangeType::Created);
+        Ok(())
+    }
+
+    fn create_files_batch(&self, path: &str, files: &[(String, Option<String>)]) -> FileSystemResult<Vec<FileSystemResult<()>>> {
+        Ok(files
+            .iter()
+            .map(|(name, content)| self.create_file(path, name, content.as_deref()))
+            .collect())
+    }
+
+    fn copy_items(&self, sources: &[String], destination: &str) -> FileSystemResult<()> {
+        let destination = normalize_remote_path("/", destination);
+        if !self.stat(&destination)?.is_dir {
+            return Err(FileSystemError::not_a_directory("Destination is not a directory"));
+        }
+
+        for source in sources {
+            let source = normalize_remote_path("/", source);
+            let target = remote_join(&destination, remote_name(&source));
+            self.transfer("COPY", &source, &target)?;
+            self.notify_change(&target, ChangeType::Created);
+        }
+        Ok(())
+    }
+
+    fn copy_with_custom_name(&self, source: &str, destination_dir: &str, new_name: &str) -> FileSystemResult<()> {
+        let source = normalize_remote_path("/", source);
+        let target = remote_join(&normalize_remote_path("/", destination_dir), new_name);
+        self.transfer("COPY", &source, &target)?;
+        self.notify_change(&target, ChangeType::Created);
+        Ok(())
+    }
+
+    fn move_items(&self, sources: &[String], destination: &str) -> FileSystemResult<()> {
+        let destination = normalize_remote_path("/", destination);
+
+        for source in sources {
+            let source = normalize_remote_path("/", source);
+            let target = remote_join(&destination, remote_name(&source));
+            self.transfer("MOVE", &source, &target)?;
+            WATCHER.notify_rename(WatchBackend::Remote, Some(&self.label), &source, &target);
+        }
+        Ok(())
+    }
+
+    fn get_home_directory(&self) -> FileSystemResult<String> {
+        self.client()?;
+        Ok("/".to_string())
+    }
+
+    fn get_system_folders(&self) -> FileSystemResult<Vec<FileSystemEntry>> {
+        let mut root = self.stat("/")?;
+        root.name = "Home".to_string();
+        Ok(vec![root])
+    }
+
+    fn read_file_content(&self, path: &str, max_size: Option<u64>) -> FileSystemResult<String> {
+        let entry = self.get_file_info(path)?;
+        if entry.is_dir {
+            return Err(FileSystemError::is_a_directory(format!("Path is not a file: {}", path)));
+        }
+
+        let limit = max_size.unwrap_or(10_000_000);
+        let size = entry.size.unwrap_or(0);
+        if size > limit {
+            return Err(FileSystemError::quota_exceeded(format!(
+                "File too large: {} bytes (limit: {} bytes)",
+                size, limit
+            )));
+        }
+
+        let content = self.get(&entry.path)?;
+        match String::from_utf8(content) {
+            Ok(text) => Ok(text),
+            Err(err) => Ok(general_purpose::STANDARD.encode(err.as_bytes())),
+        }
+    }
+
+    fn read_file_bytes(&self, path: &str) -> FileSystemResult<Vec<u8>> {
+        self.get(&normalize_remote_path("/", path))
+    }
+
+    fn write_file_content(&self, path: &str, content: &str) -> FileSystemResult<()> {
+        self.write_all(&normalize_remote_path("/", path), content.as_bytes())
+    }
+
+    fn write_file_bytes(&self, path: &str, content: &[u8]) -> FileSystemResult<()> {
+        self.write_all(&normalize_remote_path("/", path), content)
+    }
+
+    fn open_read(&self, path: &str) -> FileSystemResult<Box<dyn FileReader>> {
+        let entry = self.get_file_info(path)?;
+        if entry.is_dir {
+            return Err(FileSystemError::is_a_directory("Path is not a file"));
+        }
+        let client = self.client()?.clone();
+        let url = client.url(&entry.path, false);
+        Ok(Box::new(RangeReader::new(entry.size, move |offset| open_at(&client, &url, offset))))
+    }
+
+    fn open_write(&self, path: &str) -> FileSystemResult<Box<dyn FileWriter>> {
+        let path = normalize_remote_path("/", path);
+        if !self.exists(remote_parent(&path))? {
+            return Err(FileSystemError::not_found("Parent directory does not exist"));
+        }
+        // Проверка родителя выше уже получила Digest-nonce: потоковый PUT не повторяется после 401
+        let client = self.client()?.clone();
+        let url = client.url(&path, false);
+        let (writer, mut body) = pipe(UPLOAD_QUEUE_CHUNKS);
+        let upload = std::thread::spawn(move || client.request("PUT", &url, &[], Body::Stream(&mut body)).map(|_| ()));
+
+        Ok(Box::new(WebDavWriter {
+            fs: self.clone(),
+            path,
+            pipe: Some(writer),
+            upload: Some(upload),
+        }))
+    }
+
+    fn open_file(&self, _path: &str) -> FileSystemResult<()> {
+        Err(FileSystemError::with_kind(ErrorKind::InvalidInput, "Opening remote files is not supported"))
+    }
+
+    fn reveal_in_finder(&self, _path: &str) -> FileSystemResult<()> {
+        Err(FileSystemError::with_kind(ErrorKind::InvalidInput, "Revealing remote files is not supported"))
+    }
+
+    fn normalize_path(&self, path: &str) -> FileSystemResult<String> {
+        let normalized = normalize_remote_path("/", path);
+        self.stat(&normalized)?;
+        Ok(normalized)
+    }
+
+    fn get_path_suggestions(&self, partial_path: &str) -> FileSystemResult<Vec<String>> {
+        let (dir, prefix) = if partial_path.is_empty() || partial_path.ends_with('/') {
+            (normalize_remote_path("/", partial_path), String::new())
+        } else {
+            let path = normalize_remote_path("/", partial_path);
+            (remote_parent(&path).to_string(), remote_name(&path).to_lowercase())
+        };
+
+        let Ok(entries) = self.list(&dir) else {
+            return Ok(Vec::new());
+        };
+
+        let mut suggestions: Vec<String> = entries
+            .into_iter()
+            .filter(|entry| entry.is_dir && !entry.is_hidden && entry.name.to_lowercase().starts_with(&prefix))
+            .map(|entry| entry.path)
+            .collect();
+        suggestions.sort();
+        Ok(suggestions)
+    }
+
+    fn open_terminal(&self, _path: &str) -> FileSystemResult<()> {
+        Err(FileSystemError::with_kind(ErrorKind::InvalidInput, "Opening a terminal on a remote server is not supported"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Seek, SeekFrom};
+
+    const NEXTCLOUD_LISTING: &str = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns">
+  <d:response>
+    <d:href>/remote.php/dav/files/alice/Documents/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype><d:collection/></d:resourcetype>
+        <d:getlastmodified>Tue, 14 May 2024 09:25:56 GMT</d:getlastmodified>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:getcontentlength/><d:getcontenttype/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>https://cloud.example.com/remote.php/dav/files/alice/Documents/Q1%20report.pdf</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype/>
+        <d:getcontentlength>52341</d:getcontentlength>
+        <d:getcontenttype>application/pdf</d:getcontenttype>
+        <d:getlastmodified>Wed, 15 May 2024 10:00:00 GMT</d:getlastmodified>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+    #[test]
+    fn test_parse_multistatus() {
+        let resources = parse_multistatus(NEXTCLOUD_LISTING).unwrap();
+        assert_eq!(resources.len(), 2);
+
+        assert_eq!(resources[0].path, "/remote.php/dav/files/alice/Documents");
+        assert!(resources[0].is_dir);
+        assert_eq!(resources[0].size, None);
+        assert_eq!(resources[0].modified, Some(1715678756));
+
+        assert_eq!(resources[1].path, "/remote.php/dav/files/alice/Documents/Q1 report.pdf");
+        assert!(!resources[1].is_dir);
+        assert_eq!(resources[1].size, Some(52341));
+        assert_eq!(resources[1].content_type.as_deref(), Some("application/pdf"));
+
+        let base = "/remote.php/dav/files/alice";
+        assert_eq!(panel_path(base, &resources[0].path).as_deref(), Some("/Documents"));
+        assert_eq!(panel_path(base, base).as_deref(), Some("/"));
+        assert_eq!(panel_path(base, "/remote.php/dav/files/alice2/x"), None);
+        assert_eq!(panel_path("", "/Documents").as_deref(), Some("/Documents"));
+    }
+
+    #[test]
+    fn test_dav_url_and_status_kind() {
+        let base = "https://cloud.example.com/remote.php/dav/files/alice";
+        assert_eq!(dav_url(base, "/", false), format!("{}/", base));
+        assert_eq!(dav_url(base, "/Q1 report.pdf", false), format!("{}/Q1%20report.pdf", base));
+        assert_eq!(dav_url(base, "/a#b/c", true), format!("{}/a%23b/c/", base));
+        assert_eq!(dav_url("http://nas.local", "/", false), "http://nas.local/");
+        assert_eq!(request_target("http://nas.local:8080/dav/a%20b"), "/dav/a%20b");
+
+        assert_eq!(status_kind(404), ErrorKind::NotFound);
+        assert_eq!(status_kind(412), ErrorKind::AlreadyExists);
+        assert_eq!(status_kind(423), ErrorKind::Locked);
+        assert_eq!(status_kind(507), ErrorKind::QuotaExceeded);
+    }
+
+    #[test]
+    fn test_digest_authorization_rfc2617() {
+        let mut challenge = DigestChallenge::parse(
+            r#"Digest realm="testrealm@host.com", qop="auth,auth-int", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", opaque="5ccc069c403ebaf9f0171e9517f40e41""#,
+        )
+        .unwrap();
+        challenge.count = 1;
+
+        let header = challenge.authorization("Mufasa", "Circle Of Life", "GET", "/dir/index.html", "0a4f113b");
+        assert!(header.contains(r#"response="6629fae49393a05397450978507c4ef1""#));
+        assert!(header.contains("nc=00000001"));
+        assert!(header.contains(r#"opaque="5ccc069c403ebaf9f0171e9517f40e41""#));
+
+        assert!(DigestChallenge::parse(r#"Basic realm="x""#).is_none());
+    }
+
+    #[test]
+    fn test_basic_over_http_needs_opt_in() {
+        let mut profile = WebDavProfile {
+            id: "test".to_string(),
+            name: "test".to_string(),
+            url: "http://dav.example.com".to_string(),
+            auth: WebDavAuth::Basic { username: "u".to_string(), password: None },
+            secret_saved: false,
+            allow_insecure_basic: false,
+        };
+        assert!(is_insecure_basic(&profile));
+        let err = WebDavFileSystem::connect("webdav:test", &profile, Some("p")).err().unwrap();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+
+        profile.allow_insecure_basic = true;
+        assert!(!is_insecure_basic(&profile));
+        profile.allow_insecure_basic = false;
+        profile.url = "https://dav.example.com".to_string();
+        assert!(!is_insecure_basic(&profile));
+        profile.url = "http://dav.example.com".to_string();
+        profile.auth = WebDavAuth::Digest { username: "u".to_string(), password: None };
+        assert!(!is_insecure_basic(&profile));
+    }
+
+    #[test]
+    fn test_disconnected_is_locked() {
+        let fs = WebDavFileSystem::disconnected("webdav:missing");
+        let err = fs.read_directory("/").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Locked);
+        assert!(fs.open_write("/a.txt").is_err());
+    }
+
+    /// Against a real server, e.g. `rclone serve webdav --user u --pass p /tmp/dav`:
+    /// `VFDIR_WEBDAV_TEST=http://u:p@127.0.0.1:8080 cargo test webdav -- --ignored`
+    #[test]
+    #[ignore]
+    fn test_roundtrip_against_server() {
+        let spec = std::env::var("VFDIR_WEBDAV_TEST").expect("VFDIR_WEBDAV_TEST not set");
+        let (scheme, rest) = spec.split_once("://").unwrap();
+        let (auth, url) = match rest.split_once('@') {
+            Some((credentials, host)) => {
+                let (username, password) = credentials.split_once(':').unwrap();
+                (
+                    WebDavAuth::Basic { username: username.to_string(), password: Some(password.to_string()) },
+                    format!("{}://{}", scheme, host),
+                )
+            }
+            None => (WebDavAuth::None, spec.clone()),
+        };
+        let profile = WebDavProfile {
+            id: "test".to_string(),
+            name: "test".to_string(),
+            url,
+            auth,
+            secret_saved: false,
+            // Тестовый сервер обычно слушает localhost по http://
+            allow_insecure_basic: true,
+        };
+        let fs = WebDavFileSystem::connect("webdav:test", &profile, None).unwrap();
+
+        let dir = format!("vfdir-test-{}", uuid::Uuid::new_v4());
+        fs.create_folder("/", &dir).unwrap();
+        let dir = format!("/{}", dir);
+        fs.create_file(&dir, "a b.txt", Some("hello")).unwrap();
+
+        let mut writer = fs.open_write(&format!("{}/b.bin", dir)).unwrap();
+        writer.write_all(&[0u8, 159, 146, 150]).unwrap();
+        writer.finish().unwrap();
+
+        let names: Vec<String> = fs.read_directory(&dir).unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["a b.txt", "b.bin"]);
+
+        let mut reader = fs.open_read(&format!("{}/b.bin", dir)).unwrap();
+        reader.seek(SeekFrom::Start(2)).unwrap();
+        let mut tail = Vec::new();
+        reader.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, vec![146, 150]);
+
+        fs.rename_item(&format!("{}/a b.txt", dir), "c.txt").unwrap();
+        assert_eq!(fs.read_file_content(&format!("{}/c.txt", dir), None).unwrap(), "hello");
+
+        let trashed = fs.trash_item(&format!("{}/c.txt", dir)).unwrap();
+        assert!(fs.get_file_info(&format!("{}/c.txt", dir)).is_err());
+        fs.restore_from_trash(&trashed.id).unwrap();
+        assert!(fs.get_file_info(&format!("{}/c.txt", dir)).is_ok());
+
+        fs.delete_item(&dir).unwrap();
+        assert!(fs.get_file_info(&dir).is_err());
+    }
+}
@@ -9,3 +9,9 @@ pub mod batch;
 pub mod bookmarks;
 pub mod system;
 pub mod config;
+pub mod search;
+pub mod duplicates;
+pub mod checksums;
+pub mod compare;
+pub mod sync;
+pub mod remote;
@@ -0,0 +1,170 @@
+/**
+ * Checksum Handlers
+ */
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use super::files::error_response;
+use crate::api_server::{models::*, state::AppState};
+use crate::api_service::{ApiError, API};
+use crate::progress::{emit_progress_websocket, OperationType, OPERATIONS_MANAGER};
+
+/// Compute checksums of files and directory trees
+///
+/// Progress is streamed over `/ws/operations`; the response is sent when hashing ends.
+#[utoipa::path(
+    post,
+    path = "/api/v1/checksums",
+    request_body = CalculateChecksumsRequest,
+    responses(
+        (status = 200, description = "Checksums", body = ChecksumResult),
+        (status = 404, description = "File not found", body = ErrorResponse),
+    ),
+    tag = "checksums"
+)]
+pub async fn calculate_checksums(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CalculateChecksumsRequest>,
+) -> impl IntoResponse {
+    use uuid::Uuid;
+
+    let operation_id = req.operation_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let tracker = OPERATIONS_MANAGER.create_operation(operation_id.clone(), OperationType::Checksum, 0, 0);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let result = API.checksums.compute(&req.request, req.panel_fs.as_deref(), &tracker, || {
+            emit_progress_websocket(&state, &tracker)
+        });
+        match &result {
+            Ok(result) if result.cancelled => tracker.mark_cancelled(),
+            Ok(_) => tracker.mark_completed(),
+            Err(err) => tracker.mark_failed(err.to_string()),
+        }
+        emit_progress_websocket(&state, &tracker);
+        OPERATIONS_MANAGER.remove_operation(&operation_id);
+        result
+    })
+    .await
+    .unwrap_or_else(|e| Err(ApiError::Internal { message: e.to_string() }));
+
+    match result {
+        Ok(result) => Json(result).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Verify files listed in a checksum manifest
+#[utoipa::path(
+    post,
+    path = "/api/v1/checksums/verify",
+    request_body = VerifyChecksumsRequest,
+    responses(
+        (status = 200, description = "Per-file OK/FAILED/MISSING report", body = VerifyReport),
+        (status = 400, description = "Unknown manifest format", body = ErrorResponse),
+        (status = 404, description = "Manifest not found", body = ErrorResponse),
+    ),
+    tag = "checksums"
+)]
+pub async fn verify_checksums(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<VerifyChecksumsRequest>,
+) -> impl IntoResponse {
+    use uuid::Uuid;
+
+    let operation_id = req.operation_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let tracker = OPERATIONS_MANAGER.create_operation(operation_id.clone(), OperationType::Checksum, 0, 0);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let result = API.checksums.verify(&req.request, req.panel_fs.as_deref(), &tracker, || {
+            emit_progress_websocket(&state, &tracker)
+        });
+        match &result {
+            Ok(report) if report.cancelled => tracker.mark_cancelled(),
+            Ok(_) => tracker.mark_completed(),
+            Err(err) => tracker.mark_failed(err.to_string()),
+        }
+        emit_progress_websocket(&state, &tracker);
+        OPERATIONS_MANAGER.remove_operation(&operation_id);
+        result
+    })
+    .await
+    .unwrap_or_else(|e| Err(ApiError::Internal { message: e.to_string() }));
+
+    match result {
+        Ok(report) => Json(report).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Cancel a running checksum calculation or verification
+#[utoipa::path(
+    delete,
+    path = "/api/v1/checksums/{id}",
+    params(("id" = String, Path, description = "Checksum operation id")),
+    responses(
+        (status = 204, description = "Operation cancelled"),
+        (status = 404, description = "Operation not found", body = ErrorResponse),
+    ),
+    tag = "checksums"
+)]
+pub async fn cancel_checksums(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if OPERATIONS_MANAGER.cancel_operation(&id) {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("NOT_FOUND", format!("Checksum operation not found: {}", id))),
+        ).into_response()
+    }
+}
+
+/// Queue a long-running checksum calculation or verification
+#[utoipa::path(
+    post,
+    path = "/api/v1/checksums/jobs",
+    request_body = QueueChecksumJobRequest,
+    responses(
+        (status = 202, description = "Job queued", body = QueuedOperationResponse),
+        (status = 400, description = "Invalid job", body = ErrorResponse),
+    ),
+    tag = "checksums"
+)]
+pub async fn queue_checksum_job(
+    State(_state): State<Arc<AppState>>,
+    Json(req): Json<QueueChecksumJobRequest>,
+) -> impl IntoResponse {
+    match API.checksums.enqueue(req.job, req.panel_fs) {
+        Ok(operation_id) => (StatusCode::ACCEPTED, Json(QueuedOperationResponse { operation_id })).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Get the result of a finished queued checksum job
+#[utoipa::path(
+    get,
+    path = "/api/v1/checksums/jobs/{id}",
+    params(("id" = String, Path, description = "Queue operation id")),
+    responses(
+        (status = 200, description = "Job result", body = ChecksumJobResult),
+        (status = 404, description = "No result for this operation", body = ErrorResponse),
+    ),
+    tag = "checksums"
+)]
+pub async fn get_checksum_job_result(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match API.checksums.job_result(&id) {
+        Ok(result) => Json(result).into_response(),
+        Err(err) => error_response(err),
+    }
+}
@@ -0,0 +1,85 @@
+/**
+ * Directory Compare Handlers
+ */
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use super::files::error_response;
+use crate::api_server::{models::*, state::AppState};
+use crate::api_service::{ApiError, API};
+use crate::progress::{emit_progress_websocket, OperationType, OPERATIONS_MANAGER};
+
+/// Recursively compare two directory trees
+///
+/// Both sides may live on different backends (`leftFs` / `rightFs`). Progress is streamed
+/// over `/ws/operations`; the response is sent when the comparison ends.
+#[utoipa::path(
+    post,
+    path = "/api/v1/compare",
+    request_body = CompareDirectoriesRequest,
+    responses(
+        (status = 200, description = "Structured diff of both trees", body = DirectoryComparison),
+        (status = 400, description = "A root is not a directory", body = ErrorResponse),
+        (status = 404, description = "A root does not exist", body = ErrorResponse),
+    ),
+    tag = "compare"
+)]
+pub async fn compare_directories(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CompareDirectoriesRequest>,
+) -> impl IntoResponse {
+    use uuid::Uuid;
+
+    let operation_id = req.operation_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let tracker = OPERATIONS_MANAGER.create_operation(operation_id.clone(), OperationType::Compare, 0, 0);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let result = API.compare.compare(&req.request, &tracker, || emit_progress_websocket(&state, &tracker));
+        match &result {
+            Ok(comparison) if comparison.summary.cancelled => tracker.mark_cancelled(),
+            Ok(_) => tracker.mark_completed(),
+            Err(err) => tracker.mark_failed(err.to_string()),
+        }
+        emit_progress_websocket(&state, &tracker);
+        OPERATIONS_MANAGER.remove_operation(&operation_id);
+        result
+    })
+    .await
+    .unwrap_or_else(|e| Err(ApiError::Internal { message: e.to_string() }));
+
+    match result {
+        Ok(comparison) => Json(comparison).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Cancel a running comparison
+#[utoipa::path(
+    delete,
+    path = "/api/v1/compare/{id}",
+    params(("id" = String, Path, description = "Compare operation id")),
+    responses(
+        (status = 204, description = "Comparison cancelled"),
+        (status = 404, description = "Comparison not found", body = ErrorResponse),
+    ),
+    tag = "compare"
+)]
+pub async fn cancel_compare(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if OPERATIONS_MANAGER.cancel_operation(&id) {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("NOT_FOUND", format!("Comparison not found: {}", id))),
+        ).into_response()
+    }
+}
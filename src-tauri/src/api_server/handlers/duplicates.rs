@@ -0,0 +1,104 @@
+/**
+ * Duplicate Finder Handlers
+ */
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use super::files::error_response;
+use crate::api_server::{models::*, state::AppState};
+use crate::api_service::{ApiError, API};
+use crate::progress::{emit_progress_websocket, OperationType, OPERATIONS_MANAGER};
+
+/// Find files with identical content
+///
+/// Progress is streamed over `/ws/operations`; the response is sent when the scan ends.
+#[utoipa::path(
+    post,
+    path = "/api/v1/duplicates",
+    request_body = FindDuplicatesRequest,
+    responses(
+        (status = 200, description = "Duplicate groups", body = DuplicateReport),
+        (status = 400, description = "No sources", body = ErrorResponse),
+    ),
+    tag = "duplicates"
+)]
+pub async fn find_duplicates(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<FindDuplicatesRequest>,
+) -> impl IntoResponse {
+    use uuid::Uuid;
+
+    let operation_id = req.operation_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let tracker = OPERATIONS_MANAGER.create_operation(operation_id.clone(), OperationType::Duplicates, 0, 0);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let result = API.duplicates.find(&req.request, &tracker, || emit_progress_websocket(&state, &tracker));
+        match &result {
+            Ok(report) if report.cancelled => tracker.mark_cancelled(),
+            Ok(_) => tracker.mark_completed(),
+            Err(err) => tracker.mark_failed(err.to_string()),
+        }
+        emit_progress_websocket(&state, &tracker);
+        OPERATIONS_MANAGER.remove_operation(&operation_id);
+        result
+    })
+    .await
+    .unwrap_or_else(|e| Err(ApiError::Internal { message: e.to_string() }));
+
+    match result {
+        Ok(report) => Json(report).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Cancel a running duplicate search
+#[utoipa::path(
+    delete,
+    path = "/api/v1/duplicates/{id}",
+    params(("id" = String, Path, description = "Duplicate search operation id")),
+    responses(
+        (status = 204, description = "Search cancelled"),
+        (status = 404, description = "Search not found", body = ErrorResponse),
+    ),
+    tag = "duplicates"
+)]
+pub async fn cancel_find_duplicates(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if OPERATIONS_MANAGER.cancel_operation(&id) {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("NOT_FOUND", format!("Duplicate search not found: {}", id))),
+        ).into_response()
+    }
+}
+
+/// Queue an action (delete, hardlink, keep newest) on duplicate groups
+#[utoipa::path(
+    post,
+    path = "/api/v1/duplicates/actions",
+    request_body = DuplicateActionRequest,
+    responses(
+        (status = 202, description = "Action queued", body = QueuedOperationResponse),
+        (status = 400, description = "No duplicate groups", body = ErrorResponse),
+    ),
+    tag = "duplicates"
+)]
+pub async fn queue_duplicate_action(
+    State(_state): State<Arc<AppState>>,
+    Json(req): Json<DuplicateActionRequest>,
+) -> impl IntoResponse {
+    match API.duplicates.enqueue_action(req) {
+        Ok(operation_id) => (StatusCode::ACCEPTED, Json(QueuedOperationResponse { operation_id })).into_response(),
+        Err(err) => error_response(err),
+    }
+}
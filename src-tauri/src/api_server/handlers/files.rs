@@ -6,7 +6,7 @@ use axum::{
     extract::{Query, State},
     http::StatusCode,
     Json,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
 use std::sync::Arc;
 
@@ -16,6 +16,21 @@ use crate::api_server::{
 };
 use crate::api_service::{API, ApiError};
 
+/// Map a service error to its HTTP status and typed error body
+pub(crate) fn error_response(err: ApiError) -> Response {
+    let status = match err {
+        ApiError::ValidationError { .. } | ApiError::InvalidPath { .. } => StatusCode::BAD_REQUEST,
+        ApiError::FileNotFound { .. } | ApiError::NotFound { .. } => StatusCode::NOT_FOUND,
+        ApiError::PermissionDenied { .. } => StatusCode::FORBIDDEN,
+        ApiError::AlreadyExists { .. } | ApiError::UnknownHostKey { .. } => StatusCode::CONFLICT,
+        ApiError::NotADirectory { .. } | ApiError::IsADirectory { .. } => StatusCode::BAD_REQUEST,
+        ApiError::Locked { .. } => StatusCode::LOCKED,
+        ApiError::QuotaExceeded { .. } => StatusCode::INSUFFICIENT_STORAGE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ErrorResponse::from(&err))).into_response()
+}
+
 /// List directory contents
 #[utoipa::path(
     get,
@@ -23,6 +38,7 @@ use crate::api_service::{API, ApiError};
     params(ListDirectoryQuery),
     responses(
         (status = 200, description = "Directory listing", body = ListDirectoryResponse),
+        (status = 400, description = "Invalid filter pattern or cursor", body = ErrorResponse),
         (status = 404, description = "Directory not found", body = ErrorResponse),
     ),
     tag = "files"
@@ -31,15 +47,9 @@ pub async fn list_directory(
     State(_state): State<Arc<AppState>>,
     Query(query): Query<ListDirectoryQuery>,
 ) -> impl IntoResponse {
-    match API.files.list_directory(&query.path, query.panel_fs.as_deref()) {
-        Ok(files) => Json(ListDirectoryResponse { files }).into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::FileNotFound { .. } => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+    match API.files.list_directory_page(&query.path, query.list_options(), query.panel_fs.as_deref()) {
+        Ok(page) => Json(ListDirectoryResponse::from(page)).into_response(),
+        Err(err) => error_response(err),
     }
 }
 
@@ -60,13 +70,7 @@ pub async fn get_file_info(
 ) -> impl IntoResponse {
     match API.files.get_file_info(&query.path, query.panel_fs.as_deref()) {
         Ok(info) => Json(info).into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::FileNotFound { .. } => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+        Err(err) => error_response(err),
     }
 }
 
@@ -87,14 +91,7 @@ pub async fn create_folder(
 ) -> impl IntoResponse {
     match API.files.create_folder(&req.path, &req.name, req.panel_fs.as_deref()) {
         Ok(_) => StatusCode::OK.into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::ValidationError { .. } => StatusCode::BAD_REQUEST,
-                ApiError::AlreadyExists { .. } => StatusCode::CONFLICT,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+        Err(err) => error_response(err),
     }
 }
 
@@ -116,14 +113,7 @@ pub async fn copy_items(
 ) -> impl IntoResponse {
     match API.files.copy_items(&req.sources, &req.destination, req.source_file_system.as_deref()) {
         Ok(_) => StatusCode::OK.into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::ValidationError { .. } => StatusCode::BAD_REQUEST,
-                ApiError::FileNotFound { .. } => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+        Err(err) => error_response(err),
     }
 }
 
@@ -145,14 +135,7 @@ pub async fn move_items(
 ) -> impl IntoResponse {
     match API.files.move_items(&req.sources, &req.destination, req.source_file_system.as_deref()) {
         Ok(_) => StatusCode::OK.into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::ValidationError { .. } => StatusCode::BAD_REQUEST,
-                ApiError::FileNotFound { .. } => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+        Err(err) => error_response(err),
     }
 }
 
@@ -173,14 +156,7 @@ pub async fn rename_item(
 ) -> impl IntoResponse {
     match API.files.rename_item(&req.old_path, &req.new_name, req.panel_fs.as_deref()) {
         Ok(_) => StatusCode::OK.into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::ValidationError { .. } => StatusCode::BAD_REQUEST,
-                ApiError::FileNotFound { .. } => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+        Err(err) => error_response(err),
     }
 }
 
@@ -199,19 +175,153 @@ pub async fn delete_items(
     State(_state): State<Arc<AppState>>,
     Json(req): Json<DeleteItemsRequest>,
 ) -> impl IntoResponse {
-    match API.files.delete_items(&req.paths, req.panel_fs.as_deref()) {
+    match API.files.delete_items(&req.paths, req.permanent, req.panel_fs.as_deref()) {
         Ok(_) => StatusCode::OK.into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::ValidationError { .. } => StatusCode::BAD_REQUEST,
-                ApiError::FileNotFound { .. } => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+        Err(err) => error_response(err),
     }
 }
 
+/// List trash contents
+#[utoipa::path(
+    get,
+    path = "/api/v1/trash",
+    params(TrashQuery),
+    responses(
+        (status = 200, description = "Trash contents", body = ListTrashResponse),
+    ),
+    tag = "files"
+)]
+pub async fn list_trash(
+    State(_state): State<Arc<AppState>>,
+    Query(query): Query<TrashQuery>,
+) -> impl IntoResponse {
+    match API.files.list_trash(query.panel_fs.as_deref()) {
+        Ok(items) => Json(ListTrashResponse { items }).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Restore items from trash
+#[utoipa::path(
+    post,
+    path = "/api/v1/trash/restore",
+    request_body = RestoreFromTrashRequest,
+    responses(
+        (status = 200, description = "Restored items", body = ListTrashResponse),
+        (status = 404, description = "Trash item not found", body = ErrorResponse),
+        (status = 409, description = "Original path is occupied", body = ErrorResponse),
+    ),
+    tag = "files"
+)]
+pub async fn restore_from_trash(
+    State(_state): State<Arc<AppState>>,
+    Json(req): Json<RestoreFromTrashRequest>,
+) -> impl IntoResponse {
+    match API.files.restore_from_trash(&req.ids, req.panel_fs.as_deref()) {
+        Ok(items) => Json(ListTrashResponse { items }).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Empty trash
+#[utoipa::path(
+    delete,
+    path = "/api/v1/trash",
+    params(TrashQuery),
+    responses(
+        (status = 200, description = "Trash emptied", body = PurgeTrashResponse),
+    ),
+    tag = "files"
+)]
+pub async fn empty_trash(
+    State(_state): State<Arc<AppState>>,
+    Query(query): Query<TrashQuery>,
+) -> impl IntoResponse {
+    match API.files.empty_trash(query.panel_fs.as_deref()) {
+        Ok(purged) => Json(PurgeTrashResponse { purged }).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Purge trash items older than given age
+#[utoipa::path(
+    post,
+    path = "/api/v1/trash/purge",
+    request_body = PurgeTrashRequest,
+    responses(
+        (status = 200, description = "Old items purged", body = PurgeTrashResponse),
+    ),
+    tag = "files"
+)]
+pub async fn purge_trash(
+    State(_state): State<Arc<AppState>>,
+    Json(req): Json<PurgeTrashRequest>,
+) -> impl IntoResponse {
+    match API.files.purge_trash_older_than(req.older_than_secs, req.panel_fs.as_deref()) {
+        Ok(purged) => Json(PurgeTrashResponse { purged }).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Undo the most recent file operation
+#[utoipa::path(
+    post,
+    path = "/api/v1/history/undo",
+    request_body = UndoRedoRequest,
+    responses(
+        (status = 200, description = "Undone operation", body = JournalEntry),
+        (status = 404, description = "Nothing to undo", body = ErrorResponse),
+    ),
+    tag = "files"
+)]
+pub async fn undo_last_operation(
+    State(_state): State<Arc<AppState>>,
+    Json(req): Json<UndoRedoRequest>,
+) -> impl IntoResponse {
+    match API.files.undo_last(req.panel_fs.as_deref()) {
+        Ok(entry) => Json(entry).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Redo the most recently undone file operation
+#[utoipa::path(
+    post,
+    path = "/api/v1/history/redo",
+    request_body = UndoRedoRequest,
+    responses(
+        (status = 200, description = "Redone operation", body = JournalEntry),
+        (status = 404, description = "Nothing to redo", body = ErrorResponse),
+    ),
+    tag = "files"
+)]
+pub async fn redo_operation(
+    State(_state): State<Arc<AppState>>,
+    Json(req): Json<UndoRedoRequest>,
+) -> impl IntoResponse {
+    match API.files.redo(req.panel_fs.as_deref()) {
+        Ok(entry) => Json(entry).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Undo/redo history
+#[utoipa::path(
+    get,
+    path = "/api/v1/history",
+    params(HistoryQuery),
+    responses(
+        (status = 200, description = "Undo and redo stacks, most recent first", body = JournalHistory),
+    ),
+    tag = "files"
+)]
+pub async fn get_operation_history(
+    State(_state): State<Arc<AppState>>,
+    Query(query): Query<HistoryQuery>,
+) -> impl IntoResponse {
+    Json(API.files.operation_history(query.panel_fs.as_deref()))
+}
+
 /// Read file content
 pub async fn read_file_content(
     State(_state): State<Arc<AppState>>,
@@ -219,13 +329,7 @@ pub async fn read_file_content(
 ) -> impl IntoResponse {
     match API.files.read_file_content(&query.path, query.max_size, query.panel_fs.as_deref()) {
         Ok(content) => Json(ReadFileContentResponse { content }).into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::FileNotFound { .. } => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+        Err(err) => error_response(err),
     }
 }
 
@@ -236,14 +340,7 @@ pub async fn write_file_content(
 ) -> impl IntoResponse {
     match API.files.write_file_content(&req.path, &req.content, req.panel_fs.as_deref()) {
         Ok(_) => StatusCode::OK.into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::FileNotFound { .. } => StatusCode::NOT_FOUND,
-                ApiError::ValidationError { .. } => StatusCode::BAD_REQUEST,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+        Err(err) => error_response(err),
     }
 }
 
@@ -254,13 +351,7 @@ pub async fn open_file(
 ) -> impl IntoResponse {
     match API.files.open_file(&req.path, req.panel_fs.as_deref()) {
         Ok(_) => StatusCode::OK.into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::FileNotFound { .. } => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+        Err(err) => error_response(err),
     }
 }
 
@@ -271,13 +362,7 @@ pub async fn reveal_in_finder(
 ) -> impl IntoResponse {
     match API.files.reveal_in_finder(&req.path, req.panel_fs.as_deref()) {
         Ok(_) => StatusCode::OK.into_response(),
-        Err(err) => {
-            let status = match err {
-                ApiError::FileNotFound { .. } => StatusCode::NOT_FOUND,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            (status, Json(ErrorResponse::operation_failed(err.to_string()))).into_response()
-        }
+        Err(err) => error_response(err),
     }
 }
 
@@ -404,11 +489,13 @@ pub async fn delete_items_with_progress(
 
     let state_clone = state.clone();
     let paths = req.paths.clone();
+    let permanent = req.permanent;
 
     tokio::spawn(async move {
         use crate::file_operations_async::delete_items_with_progress_async;
         let _ = delete_items_with_progress_async(
             paths,
+            permanent,
             tracker,
             state_clone,
         ).await;
@@ -416,3 +503,26 @@ pub async fn delete_items_with_progress(
 
     Json(serde_json::json!({ "operationId": operation_id })).into_response()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_errors_keep_their_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("big.bin");
+        std::fs::write(&file, vec![0u8; 64]).unwrap();
+        let path = file.to_string_lossy().to_string();
+
+        let too_large = API.files.read_file_content(&path, Some(16), Some("real")).unwrap_err();
+        assert_eq!(error_response(too_large).status(), StatusCode::INSUFFICIENT_STORAGE);
+
+        // Неподключённый бэкенд — 423, а не 404
+        let locked = API.files.get_file_info("/etc", Some("sftp:not-connected")).unwrap_err();
+        assert_eq!(error_response(locked).status(), StatusCode::LOCKED);
+
+        let missing = API.files.get_file_info(&dir.path().join("missing").to_string_lossy(), Some("real")).unwrap_err();
+        assert_eq!(error_response(missing).status(), StatusCode::NOT_FOUND);
+    }
+}
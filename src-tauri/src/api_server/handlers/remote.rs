@@ -0,0 +1,371 @@
+/**
+ * Remote Connection Handlers
+ */
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use super::files::error_response;
+use crate::api_server::{models::*, state::AppState};
+use crate::api_service::{ApiError, API};
+
+/// List saved SFTP connections (secrets are never returned)
+#[utoipa::path(
+    get,
+    path = "/api/v1/remote/sftp",
+    responses(
+        (status = 200, description = "Saved SFTP connections", body = SftpProfilesResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn get_sftp_profiles(
+    State(_state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match API.remote.list_sftp_profiles() {
+        Ok(profiles) => Json(SftpProfilesResponse { profiles }).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Create or update an SFTP connection
+///
+/// A profile without `id` is created and gets a new id. A password or key passphrase is
+/// sealed in the vault (which must be unlocked) and is not returned.
+#[utoipa::path(
+    post,
+    path = "/api/v1/remote/sftp",
+    request_body = SftpProfile,
+    responses(
+        (status = 200, description = "Saved connection", body = SftpProfile),
+        (status = 400, description = "Invalid connection", body = ErrorResponse),
+        (status = 423, description = "Vault is locked, the secret cannot be sealed", body = ErrorResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn save_sftp_profile(
+    State(_state): State<Arc<AppState>>,
+    Json(profile): Json<SftpProfile>,
+) -> impl IntoResponse {
+    match API.remote.save_sftp_profile(profile) {
+        Ok(profile) => Json(profile).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Delete an SFTP connection and close its session
+#[utoipa::path(
+    delete,
+    path = "/api/v1/remote/sftp/{id}",
+    params(("id" = String, Path, description = "SFTP profile id")),
+    responses(
+        (status = 204, description = "Connection deleted"),
+        (status = 404, description = "Connection not found", body = ErrorResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn delete_sftp_profile(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match API.remote.delete_sftp_profile(&id) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Connect a saved SFTP connection
+///
+/// Pass the returned `panelFs` as `panel_fs` to any file endpoint. An unknown server is
+/// rejected with `UNKNOWN_HOST_KEY`; repeat the request with the confirmed `hostKey`.
+/// The key is then pinned in the profile.
+#[utoipa::path(
+    post,
+    path = "/api/v1/remote/sftp/{id}/connect",
+    params(("id" = String, Path, description = "SFTP profile id")),
+    request_body = ConnectSftpRequest,
+    responses(
+        (status = 200, description = "Open session", body = RemoteSession),
+        (status = 403, description = "Authentication failed or host key mismatch", body = ErrorResponse),
+        (status = 404, description = "Connection not found", body = ErrorResponse),
+        (status = 409, description = "Unknown host key, confirmation required", body = ErrorResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn connect_sftp(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<ConnectSftpRequest>,
+) -> impl IntoResponse {
+    let result = tokio::task::spawn_blocking(move || {
+        API.remote.connect_sftp(&id, req.secret.as_deref(), req.host_key.as_deref())
+    })
+        .await
+        .unwrap_or_else(|e| Err(ApiError::Internal { message: e.to_string() }));
+
+    match result {
+        Ok(session) => Json(session).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Close an SFTP session
+#[utoipa::path(
+    delete,
+    path = "/api/v1/remote/sftp/{id}/connect",
+    params(("id" = String, Path, description = "SFTP profile id")),
+    responses(
+        (status = 204, description = "Session closed"),
+    ),
+    tag = "remote"
+)]
+pub async fn disconnect_sftp(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    API.remote.disconnect_sftp(&id);
+    StatusCode::NO_CONTENT
+}
+
+/// List saved WebDAV connections (passwords are never returned)
+#[utoipa::path(
+    get,
+    path = "/api/v1/remote/webdav",
+    responses(
+        (status = 200, description = "Saved WebDAV connections", body = WebDavProfilesResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn get_webdav_profiles(
+    State(_state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match API.remote.list_webdav_profiles() {
+        Ok(profiles) => Json(WebDavProfilesResponse { profiles }).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Create or update a WebDAV connection
+///
+/// A profile without `id` is created and gets a new id. A password is sealed in the vault
+/// (which must be unlocked) and is not returned. Basic auth over http:// requires
+/// `allow_insecure_basic`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/remote/webdav",
+    request_body = WebDavProfile,
+    responses(
+        (status = 200, description = "Saved connection", body = WebDavProfile),
+        (status = 400, description = "Invalid connection or Basic auth over http://", body = ErrorResponse),
+        (status = 423, description = "Vault is locked, the password cannot be sealed", body = ErrorResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn save_webdav_profile(
+    State(_state): State<Arc<AppState>>,
+    Json(profile): Json<WebDavProfile>,
+) -> impl IntoResponse {
+    match API.remote.save_webdav_profile(profile) {
+        Ok(profile) => Json(profile).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Delete a WebDAV connection and close its session
+#[utoipa::path(
+    delete,
+    path = "/api/v1/remote/webdav/{id}",
+    params(("id" = String, Path, description = "WebDAV profile id")),
+    responses(
+        (status = 204, description = "Connection deleted"),
+        (status = 404, description = "Connection not found", body = ErrorResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn delete_webdav_profile(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match API.remote.delete_webdav_profile(&id) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Connect a saved WebDAV connection
+///
+/// Pass the returned `panelFs` as `panel_fs` to any file endpoint.
+#[utoipa::path(
+    post,
+    path = "/api/v1/remote/webdav/{id}/connect",
+    params(("id" = String, Path, description = "WebDAV profile id")),
+    request_body = ConnectWebDavRequest,
+    responses(
+        (status = 200, description = "Open session", body = RemoteSession),
+        (status = 403, description = "Authentication failed", body = ErrorResponse),
+        (status = 404, description = "Connection or URL not found", body = ErrorResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn connect_webdav(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<ConnectWebDavRequest>,
+) -> impl IntoResponse {
+    let result = tokio::task::spawn_blocking(move || API.remote.connect_webdav(&id, req.secret.as_deref()))
+        .await
+        .unwrap_or_else(|e| Err(ApiError::Internal { message: e.to_string() }));
+
+    match result {
+        Ok(session) => Json(session).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Close a WebDAV session
+#[utoipa::path(
+    delete,
+    path = "/api/v1/remote/webdav/{id}/connect",
+    params(("id" = String, Path, description = "WebDAV profile id")),
+    responses(
+        (status = 204, description = "Session closed"),
+    ),
+    tag = "remote"
+)]
+pub async fn disconnect_webdav(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    API.remote.disconnect_webdav(&id);
+    StatusCode::NO_CONTENT
+}
+
+/// List saved S3 buckets (credentials are never returned)
+#[utoipa::path(
+    get,
+    path = "/api/v1/remote/s3",
+    responses(
+        (status = 200, description = "Saved S3 buckets", body = S3ProfilesResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn get_s3_profiles(
+    State(_state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match API.remote.list_s3_profiles() {
+        Ok(profiles) => Json(S3ProfilesResponse { profiles }).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Create or update an S3 bucket
+///
+/// Credentials are sealed in the vault, which must be unlocked; omit them to keep the
+/// stored ones of an existing profile.
+#[utoipa::path(
+    post,
+    path = "/api/v1/remote/s3",
+    request_body = SaveS3ProfileRequest,
+    responses(
+        (status = 200, description = "Saved bucket", body = S3Profile),
+        (status = 400, description = "Invalid bucket", body = ErrorResponse),
+        (status = 423, description = "Vault is locked", body = ErrorResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn save_s3_profile(
+    State(_state): State<Arc<AppState>>,
+    Json(req): Json<SaveS3ProfileRequest>,
+) -> impl IntoResponse {
+    match API.remote.save_s3_profile(req.profile, req.credentials) {
+        Ok(profile) => Json(profile).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Delete an S3 bucket profile and close its session
+#[utoipa::path(
+    delete,
+    path = "/api/v1/remote/s3/{id}",
+    params(("id" = String, Path, description = "S3 profile id")),
+    responses(
+        (status = 204, description = "Bucket deleted"),
+        (status = 404, description = "Bucket not found", body = ErrorResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn delete_s3_profile(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match API.remote.delete_s3_profile(&id) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Connect a saved S3 bucket using credentials from the vault
+///
+/// Pass the returned `panelFs` as `panel_fs` to any file endpoint.
+#[utoipa::path(
+    post,
+    path = "/api/v1/remote/s3/{id}/connect",
+    params(("id" = String, Path, description = "S3 profile id")),
+    responses(
+        (status = 200, description = "Open session", body = RemoteSession),
+        (status = 403, description = "Access denied", body = ErrorResponse),
+        (status = 404, description = "Bucket or credentials not found", body = ErrorResponse),
+        (status = 423, description = "Vault is locked", body = ErrorResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn connect_s3(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let result = tokio::task::spawn_blocking(move || API.remote.connect_s3(&id))
+        .await
+        .unwrap_or_else(|e| Err(ApiError::Internal { message: e.to_string() }));
+
+    match result {
+        Ok(session) => Json(session).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Close an S3 session
+#[utoipa::path(
+    delete,
+    path = "/api/v1/remote/s3/{id}/connect",
+    params(("id" = String, Path, description = "S3 profile id")),
+    responses(
+        (status = 204, description = "Session closed"),
+    ),
+    tag = "remote"
+)]
+pub async fn disconnect_s3(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    API.remote.disconnect_s3(&id);
+    StatusCode::NO_CONTENT
+}
+
+/// List open remote sessions
+#[utoipa::path(
+    get,
+    path = "/api/v1/remote/sessions",
+    responses(
+        (status = 200, description = "Open sessions", body = RemoteSessionsResponse),
+    ),
+    tag = "remote"
+)]
+pub async fn get_remote_sessions(
+    State(_state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    Json(RemoteSessionsResponse { sessions: API.remote.sessions() })
+}
@@ -0,0 +1,160 @@
+/**
+ * Search Handlers
+ */
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api_server::{models::*, state::AppState};
+use crate::api_service::{ApiResult, API};
+use crate::progress::{emit_progress_websocket, OperationType, ProgressTracker, OPERATIONS_MANAGER};
+
+/// Start a recursive filename search
+///
+/// Results are streamed over `/ws/operations` as `searchResults` messages.
+#[utoipa::path(
+    post,
+    path = "/api/v1/search",
+    request_body = SearchFilesRequest,
+    responses(
+        (status = 200, description = "Search started", body = SearchStartedResponse),
+    ),
+    tag = "search"
+)]
+pub async fn start_search(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SearchFilesRequest>,
+) -> impl IntoResponse {
+    let operation_id = spawn_search(
+        state,
+        req.operation_id,
+        move |tracker, on_batch| {
+            API.search.search(&req.query, req.panel_fs.as_deref(), tracker.cancellation_token(), on_batch)
+        },
+        |operation_id, hits, done, summary, error| WebSocketMessage::SearchResults {
+            data: SearchResultsData { operation_id, hits, done, summary, error },
+        },
+    );
+
+    Json(SearchStartedResponse { operation_id }).into_response()
+}
+
+/// Start a full-text search inside files
+///
+/// Matching lines are streamed over `/ws/operations` as `grepResults` messages.
+#[utoipa::path(
+    post,
+    path = "/api/v1/search/content",
+    request_body = ContentSearchRequest,
+    responses(
+        (status = 200, description = "Search started", body = SearchStartedResponse),
+    ),
+    tag = "search"
+)]
+pub async fn start_content_search(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ContentSearchRequest>,
+) -> impl IntoResponse {
+    let operation_id = spawn_search(
+        state,
+        req.operation_id,
+        move |tracker, on_batch| {
+            API.search.grep(&req.query, req.panel_fs.as_deref(), tracker.cancellation_token(), on_batch)
+        },
+        |operation_id, matches, done, summary, error| WebSocketMessage::GrepResults {
+            data: GrepResultsData { operation_id, matches, done, summary, error },
+        },
+    );
+
+    Json(SearchStartedResponse { operation_id }).into_response()
+}
+
+/// Итог поиска, который мог быть отменен
+trait SearchOutcome {
+    fn cancelled(&self) -> bool;
+}
+
+impl SearchOutcome for SearchSummary {
+    fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+}
+
+impl SearchOutcome for ContentSearchSummary {
+    fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+}
+
+/// Запуск поиска в фоне: трекер операции, пачки результатов и финальное сообщение
+/// (`done: true` с итогом или ошибкой) по WebSocket. Возвращает ID операции.
+fn spawn_search<T, S, F, M>(state: Arc<AppState>, operation_id: Option<String>, run: F, message: M) -> String
+where
+    T: Send + 'static,
+    S: SearchOutcome + Send + 'static,
+    F: FnOnce(&ProgressTracker, &mut dyn FnMut(Vec<T>)) -> ApiResult<S> + Send + 'static,
+    M: Fn(String, Vec<T>, bool, Option<S>, Option<String>) -> WebSocketMessage + Send + 'static,
+{
+    let operation_id = operation_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let tracker = OPERATIONS_MANAGER.create_operation(operation_id.clone(), OperationType::Search, 0, 0);
+
+    let task_id = operation_id.clone();
+    tokio::task::spawn_blocking(move || {
+        let result = run(&tracker, &mut |batch: Vec<T>| {
+            tracker.add_items(batch.len() as u64);
+            state.broadcast(message(task_id.clone(), batch, false, None, None));
+            emit_progress_websocket(&state, &tracker);
+        });
+
+        let (summary, error) = match result {
+            Ok(summary) if summary.cancelled() => {
+                tracker.mark_cancelled();
+                (Some(summary), None)
+            }
+            Ok(summary) => {
+                tracker.mark_completed();
+                (Some(summary), None)
+            }
+            Err(err) => {
+                tracker.mark_failed(err.to_string());
+                (None, Some(err.to_string()))
+            }
+        };
+
+        emit_progress_websocket(&state, &tracker);
+        state.broadcast(message(task_id.clone(), Vec::new(), true, summary, error));
+        OPERATIONS_MANAGER.remove_operation(&task_id);
+    });
+
+    operation_id
+}
+
+/// Cancel a running search
+#[utoipa::path(
+    delete,
+    path = "/api/v1/search/{id}",
+    params(("id" = String, Path, description = "Search operation id")),
+    responses(
+        (status = 204, description = "Search cancelled"),
+        (status = 404, description = "Search not found", body = ErrorResponse),
+    ),
+    tag = "search"
+)]
+pub async fn cancel_search(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if OPERATIONS_MANAGER.cancel_operation(&id) {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("NOT_FOUND", format!("Search not found: {}", id))),
+        ).into_response()
+    }
+}
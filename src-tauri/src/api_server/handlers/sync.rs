@@ -0,0 +1,168 @@
+/**
+ * Folder Sync Handlers
+ */
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use super::files::error_response;
+use crate::api_server::{models::*, state::AppState};
+use crate::api_service::{ApiError, API};
+use crate::progress::{emit_progress_websocket, OperationType, OPERATIONS_MANAGER};
+
+/// List saved sync profiles
+#[utoipa::path(
+    get,
+    path = "/api/v1/sync/profiles",
+    responses(
+        (status = 200, description = "Saved sync profiles", body = SyncProfilesResponse),
+    ),
+    tag = "sync"
+)]
+pub async fn get_sync_profiles(
+    State(_state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match API.sync.list_profiles() {
+        Ok(profiles) => Json(SyncProfilesResponse { profiles }).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Create or update a sync profile
+///
+/// A profile without `id` is created and gets a new id.
+#[utoipa::path(
+    post,
+    path = "/api/v1/sync/profiles",
+    request_body = SyncProfile,
+    responses(
+        (status = 200, description = "Saved profile", body = SyncProfile),
+        (status = 400, description = "Invalid profile", body = ErrorResponse),
+    ),
+    tag = "sync"
+)]
+pub async fn save_sync_profile(
+    State(_state): State<Arc<AppState>>,
+    Json(profile): Json<SyncProfile>,
+) -> impl IntoResponse {
+    match API.sync.save_profile(profile) {
+        Ok(profile) => Json(profile).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Delete a sync profile and its sync state
+#[utoipa::path(
+    delete,
+    path = "/api/v1/sync/profiles/{id}",
+    params(("id" = String, Path, description = "Sync profile id")),
+    responses(
+        (status = 204, description = "Profile deleted"),
+        (status = 404, description = "Profile not found", body = ErrorResponse),
+    ),
+    tag = "sync"
+)]
+pub async fn delete_sync_profile(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match API.sync.delete_profile(&id) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Dry-run a sync: list the actions without changing anything
+///
+/// Progress is streamed over `/ws/operations`; the response is sent when planning ends.
+#[utoipa::path(
+    post,
+    path = "/api/v1/sync/plan",
+    request_body = PlanSyncRequest,
+    responses(
+        (status = 200, description = "Sync plan", body = SyncPlan),
+        (status = 400, description = "A root is not a directory", body = ErrorResponse),
+        (status = 404, description = "A root does not exist", body = ErrorResponse),
+    ),
+    tag = "sync"
+)]
+pub async fn plan_sync(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PlanSyncRequest>,
+) -> impl IntoResponse {
+    use uuid::Uuid;
+
+    let operation_id = req.operation_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let tracker = OPERATIONS_MANAGER.create_operation(operation_id.clone(), OperationType::Sync, 0, 0);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let result = API.sync.plan(&req.profile, &tracker, || emit_progress_websocket(&state, &tracker));
+        match &result {
+            Ok(plan) if plan.cancelled => tracker.mark_cancelled(),
+            Ok(_) => tracker.mark_completed(),
+            Err(err) => tracker.mark_failed(err.to_string()),
+        }
+        emit_progress_websocket(&state, &tracker);
+        OPERATIONS_MANAGER.remove_operation(&operation_id);
+        result
+    })
+    .await
+    .unwrap_or_else(|e| Err(ApiError::Internal { message: e.to_string() }));
+
+    match result {
+        Ok(plan) => Json(plan).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Cancel a running sync dry run
+#[utoipa::path(
+    delete,
+    path = "/api/v1/sync/plan/{id}",
+    params(("id" = String, Path, description = "Plan operation id")),
+    responses(
+        (status = 204, description = "Planning cancelled"),
+        (status = 404, description = "Planning not found", body = ErrorResponse),
+    ),
+    tag = "sync"
+)]
+pub async fn cancel_plan_sync(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if OPERATIONS_MANAGER.cancel_operation(&id) {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("NOT_FOUND", format!("Sync planning not found: {}", id))),
+        ).into_response()
+    }
+}
+
+/// Queue a sync of a saved profile, optionally at a scheduled time
+#[utoipa::path(
+    post,
+    path = "/api/v1/sync/jobs",
+    request_body = QueueSyncJobRequest,
+    responses(
+        (status = 202, description = "Sync queued", body = QueuedOperationResponse),
+        (status = 400, description = "Invalid scheduled time", body = ErrorResponse),
+        (status = 404, description = "Profile not found", body = ErrorResponse),
+    ),
+    tag = "sync"
+)]
+pub async fn queue_sync_job(
+    State(_state): State<Arc<AppState>>,
+    Json(req): Json<QueueSyncJobRequest>,
+) -> impl IntoResponse {
+    match API.sync.enqueue(&req.profile_id, req.scheduled_at.as_deref()) {
+        Ok(operation_id) => (StatusCode::ACCEPTED, Json(QueuedOperationResponse { operation_id })).into_response(),
+        Err(err) => error_response(err),
+    }
+}
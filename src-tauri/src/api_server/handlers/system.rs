@@ -3,15 +3,17 @@
  */
 
 use axum::{
-    extract::State,
+    extract::{Path, State},
     http::StatusCode,
     Json,
     response::IntoResponse,
 };
 use std::sync::Arc;
 
+use super::files::error_response;
 use crate::api_server::{models::*, state::AppState};
 use crate::api_service::{API, ApiError};
+use crate::progress::{emit_progress_websocket, OperationType, OPERATIONS_MANAGER};
 
 #[utoipa::path(
     get,
@@ -74,3 +76,132 @@ pub async fn open_terminal(
         }
     }
 }
+
+/// Get state of the filename index for every indexed root
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/index",
+    responses(
+        (status = 200, description = "Index status per root", body = Vec<IndexRootStatus>),
+    ),
+    tag = "system"
+)]
+pub async fn get_index_status(
+    State(_state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match API.system.get_index_status() {
+        Ok(status) => Json(status).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Rebuild the filename index in the background
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/index/rebuild",
+    request_body = RebuildIndexRequest,
+    responses(
+        (status = 202, description = "Rebuild started"),
+        (status = 404, description = "Root is not indexed", body = ErrorResponse),
+    ),
+    tag = "system"
+)]
+pub async fn rebuild_index(
+    State(_state): State<Arc<AppState>>,
+    Json(req): Json<RebuildIndexRequest>,
+) -> impl IntoResponse {
+    match API.system.rebuild_index(req.root.as_deref()) {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Query the filename index
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/index/query",
+    request_body = IndexQuery,
+    responses(
+        (status = 200, description = "Matching paths, best first", body = Vec<IndexHit>),
+        (status = 400, description = "Empty query", body = ErrorResponse),
+    ),
+    tag = "system"
+)]
+pub async fn query_index(
+    State(_state): State<Arc<AppState>>,
+    Json(query): Json<IndexQuery>,
+) -> impl IntoResponse {
+    match API.system.query_index(&query) {
+        Ok(hits) => Json(hits).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Analyze disk usage of a directory: size tree, largest files, breakdown by type
+///
+/// Progress is streamed over `/ws/operations`; the response is sent when the scan ends.
+/// Unchanged subtrees are taken from the cache of earlier scans unless `refresh` is set.
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/disk-usage",
+    request_body = AnalyzeDiskUsageRequest,
+    responses(
+        (status = 200, description = "Disk usage report", body = DiskUsageReport),
+        (status = 400, description = "Root is not a directory", body = ErrorResponse),
+        (status = 404, description = "Root does not exist", body = ErrorResponse),
+    ),
+    tag = "system"
+)]
+pub async fn analyze_disk_usage(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<AnalyzeDiskUsageRequest>,
+) -> impl IntoResponse {
+    use uuid::Uuid;
+
+    let operation_id = req.operation_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let tracker = OPERATIONS_MANAGER.create_operation(operation_id.clone(), OperationType::DiskUsage, 0, 0);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let result = API.system.analyze_disk_usage(&req.request, &tracker, || emit_progress_websocket(&state, &tracker));
+        match &result {
+            Ok(report) if report.cancelled => tracker.mark_cancelled(),
+            Ok(_) => tracker.mark_completed(),
+            Err(err) => tracker.mark_failed(err.to_string()),
+        }
+        emit_progress_websocket(&state, &tracker);
+        OPERATIONS_MANAGER.remove_operation(&operation_id);
+        result
+    })
+    .await
+    .unwrap_or_else(|e| Err(ApiError::Internal { message: e.to_string() }));
+
+    match result {
+        Ok(report) => Json(report).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+/// Cancel a running disk usage scan
+#[utoipa::path(
+    delete,
+    path = "/api/v1/system/disk-usage/{id}",
+    params(("id" = String, Path, description = "Disk usage operation id")),
+    responses(
+        (status = 204, description = "Scan cancelled"),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    ),
+    tag = "system"
+)]
+pub async fn cancel_disk_usage(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if OPERATIONS_MANAGER.cancel_operation(&id) {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("NOT_FOUND", format!("Disk usage scan not found: {}", id))),
+        ).into_response()
+    }
+}
@@ -34,23 +34,166 @@ pub use state::AppState;
         handlers::files::move_items,
         handlers::files::rename_item,
         handlers::files::delete_items,
+        handlers::files::list_trash,
+        handlers::files::restore_from_trash,
+        handlers::files::empty_trash,
+        handlers::files::purge_trash,
+        handlers::files::undo_last_operation,
+        handlers::files::redo_operation,
+        handlers::files::get_operation_history,
+        handlers::search::start_search,
+        handlers::search::start_content_search,
+        handlers::search::cancel_search,
+        handlers::duplicates::find_duplicates,
+        handlers::duplicates::cancel_find_duplicates,
+        handlers::duplicates::queue_duplicate_action,
+        handlers::checksums::calculate_checksums,
+        handlers::checksums::verify_checksums,
+        handlers::checksums::cancel_checksums,
+        handlers::checksums::queue_checksum_job,
+        handlers::checksums::get_checksum_job_result,
+        handlers::compare::compare_directories,
+        handlers::compare::cancel_compare,
+        handlers::sync::get_sync_profiles,
+        handlers::sync::save_sync_profile,
+        handlers::sync::delete_sync_profile,
+        handlers::sync::plan_sync,
+        handlers::sync::cancel_plan_sync,
+        handlers::sync::queue_sync_job,
+        handlers::remote::get_sftp_profiles,
+        handlers::remote::save_sftp_profile,
+        handlers::remote::delete_sftp_profile,
+        handlers::remote::connect_sftp,
+        handlers::remote::disconnect_sftp,
+        handlers::remote::get_webdav_profiles,
+        handlers::remote::save_webdav_profile,
+        handlers::remote::delete_webdav_profile,
+        handlers::remote::connect_webdav,
+        handlers::remote::disconnect_webdav,
+        handlers::remote::get_s3_profiles,
+        handlers::remote::save_s3_profile,
+        handlers::remote::delete_s3_profile,
+        handlers::remote::connect_s3,
+        handlers::remote::disconnect_s3,
+        handlers::remote::get_remote_sessions,
         handlers::bookmarks::get_bookmarks,
         handlers::system::get_home_directory,
+        handlers::system::get_index_status,
+        handlers::system::rebuild_index,
+        handlers::system::query_index,
+        handlers::system::analyze_disk_usage,
+        handlers::system::cancel_disk_usage,
     ),
     components(
         schemas(
             models::FileSystemEntry,
+            models::ListDirectoryResponse,
+            models::SortKey,
+            models::SortOrder,
+            models::EntryTypeFilter,
             models::ErrorResponse,
             models::CreateFolderRequest,
             models::CopyItemsRequest,
             models::MoveItemsRequest,
             models::RenameItemRequest,
             models::DeleteItemsRequest,
+            models::TrashEntry,
+            models::ListTrashResponse,
+            models::RestoreFromTrashRequest,
+            models::PurgeTrashRequest,
+            models::PurgeTrashResponse,
+            models::JournalEntry,
+            models::JournalHistory,
+            models::OperationKind,
+            models::UndoRedoRequest,
+            models::SearchQuery,
+            models::NameMatchMode,
+            models::SearchFilesRequest,
+            models::SearchStartedResponse,
+            models::SearchHit,
+            models::SearchSummary,
+            models::SearchResultsData,
+            models::ContentSearchQuery,
+            models::ContentSearchRequest,
+            models::ContentMatch,
+            models::MatchRange,
+            models::ContentSearchSummary,
+            models::GrepResultsData,
+            models::DuplicateSource,
+            models::DuplicateScanRequest,
+            models::FindDuplicatesRequest,
+            models::DuplicateFile,
+            models::DuplicateGroup,
+            models::DuplicateReport,
+            models::DuplicateAction,
+            models::DuplicateActionRequest,
+            models::QueuedOperationResponse,
+            models::ChecksumAlgorithm,
+            models::ChecksumRequest,
+            models::CalculateChecksumsRequest,
+            models::FileChecksum,
+            models::ChecksumResult,
+            models::VerifyRequest,
+            models::VerifyChecksumsRequest,
+            models::VerifyStatus,
+            models::VerifyEntry,
+            models::VerifyReport,
+            models::ChecksumJob,
+            models::ChecksumJobResult,
+            models::QueueChecksumJobRequest,
+            models::CompareRequest,
+            models::CompareDirectoriesRequest,
+            models::CompareStatus,
+            models::CompareSide,
+            models::CompareEntry,
+            models::CompareSummary,
+            models::DirectoryComparison,
+            models::SyncMode,
+            models::SyncProfile,
+            models::SyncProfilesResponse,
+            models::PlanSyncRequest,
+            models::QueueSyncJobRequest,
+            models::SyncActionKind,
+            models::SyncAction,
+            models::SyncPlan,
+            models::SyncReport,
+            models::SftpAuth,
+            models::SftpProfile,
+            models::SftpProfilesResponse,
+            models::ConnectSftpRequest,
+            models::WebDavAuth,
+            models::WebDavProfile,
+            models::WebDavProfilesResponse,
+            models::ConnectWebDavRequest,
+            models::S3Profile,
+            models::S3Credentials,
+            models::S3ProfilesResponse,
+            models::SaveS3ProfileRequest,
+            models::RemoteSession,
+            models::RemoteSessionsResponse,
+            models::IndexScope,
+            models::IndexQueryMode,
+            models::IndexQuery,
+            models::IndexHit,
+            models::IndexRootStatus,
+            models::RebuildIndexRequest,
+            models::DiskUsageRequest,
+            models::AnalyzeDiskUsageRequest,
+            models::DiskUsageNode,
+            models::LargestFile,
+            models::TypeUsage,
+            models::DiskUsageReport,
         )
     ),
     tags(
         (name = "files", description = "File system operations"),
         (name = "batch", description = "Batch operations"),
+        (name = "search", description = "Filename and content search"),
+        (name = "duplicates", description = "Duplicate file finder"),
+        (name = "checksums", description = "Checksum calculation and verification"),
+        (name = "compare", description = "Directory comparison"),
+        (name = "sync", description = "Folder synchronization jobs"),
+        (name = "remote", description = "Remote (SFTP, WebDAV, S3) connections"),
         (name = "bookmarks", description = "Bookmark management"),
         (name = "system", description = "System operations"),
         (name = "config", description = "Configuration"),
@@ -75,6 +218,64 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/files/open", post(handlers::files::open_file))
         .route("/files/reveal", post(handlers::files::reveal_in_finder))
 
+        // Trash
+        .route("/trash", get(handlers::files::list_trash))
+        .route("/trash", delete(handlers::files::empty_trash))
+        .route("/trash/restore", post(handlers::files::restore_from_trash))
+        .route("/trash/purge", post(handlers::files::purge_trash))
+
+        // Undo / redo
+        .route("/history", get(handlers::files::get_operation_history))
+        .route("/history/undo", post(handlers::files::undo_last_operation))
+        .route("/history/redo", post(handlers::files::redo_operation))
+
+        // Search
+        .route("/search", post(handlers::search::start_search))
+        .route("/search/content", post(handlers::search::start_content_search))
+        .route("/search/:id", delete(handlers::search::cancel_search))
+
+        // Duplicates
+        .route("/duplicates", post(handlers::duplicates::find_duplicates))
+        .route("/duplicates/actions", post(handlers::duplicates::queue_duplicate_action))
+        .route("/duplicates/:id", delete(handlers::duplicates::cancel_find_duplicates))
+
+        // Checksums
+        .route("/checksums", post(handlers::checksums::calculate_checksums))
+        .route("/checksums/verify", post(handlers::checksums::verify_checksums))
+        .route("/checksums/jobs", post(handlers::checksums::queue_checksum_job))
+        .route("/checksums/jobs/:id", get(handlers::checksums::get_checksum_job_result))
+        .route("/checksums/:id", delete(handlers::checksums::cancel_checksums))
+
+        // Compare
+        .route("/compare", post(handlers::compare::compare_directories))
+        .route("/compare/:id", delete(handlers::compare::cancel_compare))
+
+        // Sync
+        .route("/sync/profiles", get(handlers::sync::get_sync_profiles))
+        .route("/sync/profiles", post(handlers::sync::save_sync_profile))
+        .route("/sync/profiles/:id", delete(handlers::sync::delete_sync_profile))
+        .route("/sync/plan", post(handlers::sync::plan_sync))
+        .route("/sync/plan/:id", delete(handlers::sync::cancel_plan_sync))
+        .route("/sync/jobs", post(handlers::sync::queue_sync_job))
+
+        // Remote connections
+        .route("/remote/sftp", get(handlers::remote::get_sftp_profiles))
+        .route("/remote/sftp", post(handlers::remote::save_sftp_profile))
+        .route("/remote/sftp/:id", delete(handlers::remote::delete_sftp_profile))
+        .route("/remote/sftp/:id/connect", post(handlers::remote::connect_sftp))
+        .route("/remote/sftp/:id/connect", delete(handlers::remote::disconnect_sftp))
+        .route("/remote/webdav", get(handlers::remote::get_webdav_profiles))
+        .route("/remote/webdav", post(handlers::remote::save_webdav_profile))
+        .route("/remote/webdav/:id", delete(handlers::remote::delete_webdav_profile))
+        .route("/remote/webdav/:id/connect", post(handlers::remote::connect_webdav))
+        .route("/remote/webdav/:id/connect", delete(handlers::remote::disconnect_webdav))
+        .route("/remote/s3", get(handlers::remote::get_s3_profiles))
+        .route("/remote/s3", post(handlers::remote::save_s3_profile))
+        .route("/remote/s3/:id", delete(handlers::remote::delete_s3_profile))
+        .route("/remote/s3/:id/connect", post(handlers::remote::connect_s3))
+        .route("/remote/s3/:id/connect", delete(handlers::remote::disconnect_s3))
+        .route("/remote/sessions", get(handlers::remote::get_remote_sessions))
+
         // File operations with progress (WebSocket updates)
         .route("/files/copy-with-progress", post(handlers::files::copy_items_with_progress))
         .route("/files/move-with-progress", post(handlers::files::move_items_with_progress))
@@ -100,6 +301,11 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/system/folders", get(handlers::system::get_system_folders))
         .route("/system/stats", get(handlers::system::get_system_stats))
         .route("/system/terminal", post(handlers::system::open_terminal))
+        .route("/system/index", get(handlers::system::get_index_status))
+        .route("/system/index/rebuild", post(handlers::system::rebuild_index))
+        .route("/system/index/query", post(handlers::system::query_index))
+        .route("/system/disk-usage", post(handlers::system::analyze_disk_usage))
+        .route("/system/disk-usage/:id", delete(handlers::system::cancel_disk_usage))
 
         // Config
         .route("/config", get(handlers::config::get_config))
@@ -131,6 +337,8 @@ pub async fn start_server(addr: SocketAddr) -> Result<(), Box<dyn std::error::Er
     let state = Arc::new(AppState::new());
     let app = create_router(state);
 
+    crate::api_service::index::FILE_INDEX.configure();
+
     println!("🚀 VFDir API Server starting on http://{}", addr);
     println!("📚 API Documentation: http://{}/swagger-ui/", addr);
     println!("🔌 WebSocket Operations: ws://{}/api/v1/ws/operations", addr);
@@ -8,7 +8,10 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 // Re-export common types
-pub use crate::core::FileSystemEntry;
+pub use crate::core::{
+    DirectoryPage, EntryTypeFilter, ErrorKind, FileSystemEntry, ListOptions, SortKey, SortOrder,
+    TrashEntry,
+};
 pub use crate::config::{Bookmark, AppConfig, UIState};
 
 // ===== Error Responses =====
@@ -22,6 +25,9 @@ pub struct ErrorResponse {
 pub struct ErrorDetail {
     pub code: String,
     pub message: String,
+    /// Filesystem error kind, present when the error comes from a filesystem backend
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ErrorKind>,
 }
 
 impl ErrorResponse {
@@ -30,6 +36,7 @@ impl ErrorResponse {
             error: ErrorDetail {
                 code: code.into(),
                 message: message.into(),
+                kind: None,
             },
         }
     }
@@ -55,17 +62,87 @@ impl ErrorResponse {
     }
 }
 
+impl From<&crate::api_service::ApiError> for ErrorResponse {
+    fn from(err: &crate::api_service::ApiError) -> Self {
+        Self {
+            error: ErrorDetail {
+                code: err.code().to_string(),
+                message: err.to_string(),
+                kind: err.kind(),
+            },
+        }
+    }
+}
+
 // ===== File Operations =====
 
 #[derive(Debug, Deserialize, utoipa::IntoParams)]
 pub struct ListDirectoryQuery {
     pub path: String,
-    pub panel_fs: Option<String>
+    pub panel_fs: Option<String>,
+    /// Include hidden files; defaults to the `show_hidden_files` setting
+    #[serde(alias = "showHidden")]
+    pub show_hidden: Option<bool>,
+    /// Sort key; without it the backend order is kept
+    #[serde(alias = "sortBy")]
+    pub sort_by: Option<SortKey>,
+    #[serde(default, alias = "sortOrder")]
+    pub sort_order: SortOrder,
+    /// Natural name ordering ("file2" before "file10")
+    #[serde(default, alias = "naturalSort")]
+    pub natural_sort: bool,
+    #[serde(default, alias = "directoriesFirst", alias = "dirsFirst")]
+    pub directories_first: bool,
+    /// Glob pattern matched against entry names
+    pub glob: Option<String>,
+    /// Regular expression matched against entry names
+    pub regex: Option<String>,
+    #[serde(default, alias = "caseSensitive")]
+    pub case_sensitive: bool,
+    #[serde(alias = "entryType", alias = "type")]
+    pub entry_type: Option<EntryTypeFilter>,
+    /// `nextCursor` of the previous page
+    pub cursor: Option<String>,
+    /// Page size (at most 10000)
+    pub limit: Option<usize>,
+}
+
+impl ListDirectoryQuery {
+    pub fn list_options(&self) -> ListOptions {
+        ListOptions {
+            show_hidden: self.show_hidden,
+            sort_by: self.sort_by,
+            sort_order: self.sort_order,
+            natural_sort: self.natural_sort,
+            directories_first: self.directories_first,
+            glob: self.glob.clone(),
+            regex: self.regex.clone(),
+            case_sensitive: self.case_sensitive,
+            entry_type: self.entry_type,
+            cursor: self.cursor.clone(),
+            limit: self.limit,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct ListDirectoryResponse {
     pub files: Vec<FileSystemEntry>,
+    /// Number of matching entries across all pages
+    pub total: usize,
+    /// Cursor for the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+impl From<DirectoryPage> for ListDirectoryResponse {
+    fn from(page: DirectoryPage) -> Self {
+        Self {
+            files: page.entries,
+            total: page.total,
+            next_cursor: page.next_cursor,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, utoipa::IntoParams)]
@@ -113,7 +190,10 @@ pub struct RenameItemRequest {
 #[serde(rename_all = "camelCase")]
 pub struct DeleteItemsRequest {
     pub paths: Vec<String>,
-    pub panel_fs: Option<String>
+    pub panel_fs: Option<String>,
+    /// Delete bypassing the trash
+    #[serde(default)]
+    pub permanent: bool,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -138,6 +218,259 @@ pub struct DeleteItemsWithProgressRequest {
     pub paths: Vec<String>,
     
     pub operation_id: Option<String>,
+    /// Delete bypassing the trash
+    #[serde(default)]
+    pub permanent: bool,
+}
+
+// ===== Trash =====
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashQuery {
+    pub panel_fs: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListTrashResponse {
+    pub items: Vec<TrashEntry>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreFromTrashRequest {
+    pub ids: Vec<String>,
+    pub panel_fs: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeTrashRequest {
+    /// Purge items deleted more than this many seconds ago
+    pub older_than_secs: u64,
+    pub panel_fs: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PurgeTrashResponse {
+    pub purged: usize,
+}
+
+// ===== Undo / Redo =====
+
+pub use crate::api_service::journal::{JournalEntry, JournalHistory, OperationKind};
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryQuery {
+    pub panel_fs: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoRedoRequest {
+    pub panel_fs: Option<String>,
+}
+
+// ===== Filename index =====
+
+pub use crate::api_service::index::{IndexHit, IndexQuery, IndexQueryMode, IndexRootStatus, IndexScope};
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RebuildIndexRequest {
+    /// Indexed root (`"vault"` for the vault tree); all roots if omitted
+    pub root: Option<String>,
+}
+
+// ===== Disk usage =====
+
+pub use crate::api_service::disk_usage::{DiskUsageNode, DiskUsageReport, DiskUsageRequest, LargestFile, TypeUsage};
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzeDiskUsageRequest {
+    #[serde(flatten)]
+    pub request: DiskUsageRequest,
+    /// Progress arrives on `/ws/operations` with this id
+    pub operation_id: Option<String>,
+}
+
+// ===== Duplicates =====
+
+pub use crate::api_service::duplicates::{
+    DuplicateAction, DuplicateActionRequest, DuplicateFile, DuplicateGroup, DuplicateReport,
+    DuplicateScanRequest, DuplicateSource,
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FindDuplicatesRequest {
+    #[serde(flatten)]
+    pub request: DuplicateScanRequest,
+    /// Progress arrives on `/ws/operations` with this id
+    pub operation_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedOperationResponse {
+    /// ID of the operation in the queue
+    pub operation_id: String,
+}
+
+// ===== Checksums =====
+
+pub use crate::api_service::checksum::{
+    ChecksumAlgorithm, ChecksumJob, ChecksumJobResult, ChecksumRequest, ChecksumResult, FileChecksum,
+    VerifyEntry, VerifyReport, VerifyRequest, VerifyStatus,
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CalculateChecksumsRequest {
+    #[serde(flatten)]
+    pub request: ChecksumRequest,
+    pub panel_fs: Option<String>,
+    /// Progress arrives on `/ws/operations` with this id
+    pub operation_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyChecksumsRequest {
+    #[serde(flatten)]
+    pub request: VerifyRequest,
+    pub panel_fs: Option<String>,
+    /// Progress arrives on `/ws/operations` with this id
+    pub operation_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueChecksumJobRequest {
+    pub job: ChecksumJob,
+    pub panel_fs: Option<String>,
+}
+
+// ===== Compare =====
+
+pub use crate::api_service::compare::{
+    CompareEntry, CompareRequest, CompareSide, CompareStatus, CompareSummary, DirectoryComparison,
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareDirectoriesRequest {
+    #[serde(flatten)]
+    pub request: CompareRequest,
+    /// Progress arrives on `/ws/operations` with this id
+    pub operation_id: Option<String>,
+}
+
+// ===== Sync =====
+
+pub use crate::api_service::sync::{SyncAction, SyncActionKind, SyncPlan, SyncReport};
+pub use crate::config::{SyncMode, SyncProfile};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SyncProfilesResponse {
+    pub profiles: Vec<SyncProfile>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanSyncRequest {
+    #[serde(flatten)]
+    pub profile: SyncProfile,
+    /// Progress arrives on `/ws/operations` with this id
+    pub operation_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueSyncJobRequest {
+    pub profile_id: String,
+    /// RFC 3339 time to run at; runs as soon as possible if omitted
+    pub scheduled_at: Option<String>,
+}
+
+// ===== Remote =====
+
+pub use crate::api_service::remote::RemoteSession;
+pub use crate::api::s3_fs::S3Credentials;
+pub use crate::config::{S3Profile, SftpAuth, SftpProfile, WebDavAuth, WebDavProfile};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SftpProfilesResponse {
+    pub profiles: Vec<SftpProfile>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectSftpRequest {
+    /// Password or key passphrase; the one sealed in the vault is used if omitted
+    pub secret: Option<String>,
+    /// Fingerprint of an unknown server key confirmed by the user (from `UNKNOWN_HOST_KEY`)
+    pub host_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebDavProfilesResponse {
+    pub profiles: Vec<WebDavProfile>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectWebDavRequest {
+    /// Password; the one sealed in the vault is used if omitted
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct S3ProfilesResponse {
+    pub profiles: Vec<S3Profile>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveS3ProfileRequest {
+    pub profile: S3Profile,
+    /// Sealed in the vault; the stored keys are kept if omitted
+    pub credentials: Option<S3Credentials>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RemoteSessionsResponse {
+    pub sessions: Vec<RemoteSession>,
+}
+
+// ===== Search =====
+
+pub use crate::api_service::search::{NameMatchMode, SearchHit, SearchQuery, SearchSummary};
+pub use crate::api_service::grep::{ContentMatch, ContentSearchQuery, ContentSearchSummary, MatchRange};
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilesRequest {
+    pub query: SearchQuery,
+    pub panel_fs: Option<String>,
+    pub operation_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentSearchRequest {
+    pub query: ContentSearchQuery,
+    pub panel_fs: Option<String>,
+    pub operation_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchStartedResponse {
+    /// Results arrive on `/ws/operations` as `searchResults` / `grepResults` messages with this id
+    pub operation_id: String,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -354,6 +687,14 @@ pub enum WebSocketMessage {
     Progress { data: ProgressData },
     #[serde(rename = "change")]
     FileSystemChange { data: FileSystemChangeData },
+    #[serde(rename = "subscribed")]
+    Subscribed { data: WatchSubscriptionData },
+    #[serde(rename = "error")]
+    Error { data: WebSocketErrorData },
+    #[serde(rename = "searchResults")]
+    SearchResults { data: SearchResultsData },
+    #[serde(rename = "grepResults")]
+    GrepResults { data: GrepResultsData },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -382,9 +723,87 @@ pub struct ProgressData {
     pub error_message: Option<String>,
 }
 
+/// A batch of search results; the last message of a search has `done: true`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultsData {
+    pub operation_id: String,
+    pub hits: Vec<SearchHit>,
+    pub done: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<SearchSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A batch of content matches; the last message of a search has `done: true`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepResultsData {
+    pub operation_id: String,
+    pub matches: Vec<ContentMatch>,
+    pub done: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ContentSearchSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FileSystemChangeData {
     pub path: String,
     #[serde(rename = "changeType")]
-    pub change_type: String, // "created" | "modified" | "deleted"
+    pub change_type: String, // "created" | "modified" | "deleted" | "renamed"
+    #[serde(rename = "oldPath")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub backend: String, // "real" | "virtual" | "temporary" | "remote"
+    /// Session label (`panel_fs`) of temporary and remote backends
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    #[serde(rename = "subscriptionIds")]
+    pub subscription_ids: Vec<String>,
+}
+
+impl From<crate::api::watcher::FileSystemChange> for FileSystemChangeData {
+    fn from(change: crate::api::watcher::FileSystemChange) -> Self {
+        Self {
+            path: change.path,
+            change_type: change.change_type.as_str().to_string(),
+            old_path: change.old_path,
+            backend: change.backend.as_str().to_string(),
+            session: change.session,
+            subscription_ids: change.subscription_ids,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WatchSubscriptionData {
+    #[serde(rename = "subscriptionId")]
+    pub subscription_id: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebSocketErrorData {
+    pub code: String,
+    pub message: String,
+}
+
+/// Client → server messages on `/ws/filesystem`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum FileSystemWatchRequest {
+    Subscribe {
+        path: String,
+        #[serde(default)]
+        recursive: bool,
+        #[serde(rename = "panelFs")]
+        panel_fs: Option<String>,
+    },
+    Unsubscribe {
+        #[serde(rename = "subscriptionId")]
+        subscription_id: String,
+    },
 }
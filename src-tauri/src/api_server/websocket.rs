@@ -10,9 +10,19 @@ use axum::{
     response::Response,
 };
 use futures_util::{sink::SinkExt, stream::StreamExt};
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
 
-use crate::api_server::{models::WebSocketMessage, state::AppState};
+use crate::api::watcher::WATCHER;
+use crate::api_service::API;
+use crate::api_server::{
+    models::{
+        FileSystemChangeData, FileSystemWatchRequest, WatchSubscriptionData, WebSocketErrorData,
+        WebSocketMessage,
+    },
+    state::AppState,
+};
 
 /// WebSocket handler for operation progress updates
 pub async fn operations_websocket_handler(
@@ -29,7 +39,10 @@ async fn operations_websocket(stream: WebSocket, state: Arc<AppState>) {
     // Task for receiving messages from broadcast channel and sending to client
     let mut send_task = tokio::spawn(async move {
         while let Ok(msg) = rx.recv().await {
-            if let WebSocketMessage::Progress { .. } = msg {
+            if let WebSocketMessage::Progress { .. }
+            | WebSocketMessage::SearchResults { .. }
+            | WebSocketMessage::GrepResults { .. } = msg
+            {
                 if let Ok(json) = serde_json::to_string(&msg) {
                     if sender.send(Message::Text(json)).await.is_err() {
                         break;
@@ -71,29 +84,100 @@ pub async fn filesystem_websocket_handler(
     ws.on_upgrade(|socket| filesystem_websocket(socket, state))
 }
 
-async fn filesystem_websocket(stream: WebSocket, state: Arc<AppState>) {
+async fn filesystem_websocket(stream: WebSocket, _state: Arc<AppState>) {
     let (mut sender, mut receiver) = stream.split();
-    let mut rx = state.ws_tx.subscribe();
+    let mut changes = WATCHER.subscribe_events();
+    let (reply_tx, mut reply_rx) = mpsc::unbounded_channel::<WebSocketMessage>();
 
-    // Task for receiving messages from broadcast channel and sending to client
+    // Подписки, созданные этим сокетом; снимаются при отключении клиента
+    let subscriptions: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
+
+    // Task for forwarding matching changes and command replies to client
+    let send_subscriptions = subscriptions.clone();
     let mut send_task = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
-            if let WebSocketMessage::FileSystemChange { .. } = msg {
-                if let Ok(json) = serde_json::to_string(&msg) {
-                    if sender.send(Message::Text(json)).await.is_err() {
-                        break;
+        loop {
+            let msg = tokio::select! {
+                change = changes.recv() => match change {
+                    Ok(change) => {
+                        let ids: Vec<String> = {
+                            let owned = send_subscriptions.lock().unwrap();
+                            change
+                                .subscription_ids
+                                .iter()
+                                .filter(|id| owned.contains(*id))
+                                .cloned()
+                                .collect()
+                        };
+                        if ids.is_empty() {
+                            continue;
+                        }
+                        let mut data = FileSystemChangeData::from(change);
+                        data.subscription_ids = ids;
+                        WebSocketMessage::FileSystemChange { data }
                     }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Filesystem websocket lagged, skipped {} changes", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                reply = reply_rx.recv() => match reply {
+                    Some(reply) => reply,
+                    None => break,
+                },
+            };
+
+            if let Ok(json) = serde_json::to_string(&msg) {
+                if sender.send(Message::Text(json)).await.is_err() {
+                    break;
                 }
             }
         }
     });
 
-    // Task for receiving messages from client
+    // Task for receiving subscribe/unsubscribe commands from client
+    let recv_subscriptions = subscriptions.clone();
     let mut recv_task = tokio::spawn(async move {
         while let Some(Ok(msg)) = receiver.next().await {
-            match msg {
+            let text = match msg {
+                Message::Text(text) => text,
                 Message::Close(_) => break,
-                _ => {}
+                _ => continue,
+            };
+
+            let reply = match serde_json::from_str::<FileSystemWatchRequest>(&text) {
+                Ok(FileSystemWatchRequest::Subscribe { path, recursive, panel_fs }) => {
+                    match API.files.watch_directory(&path, recursive, panel_fs.as_deref()) {
+                        Ok(subscription_id) => {
+                            recv_subscriptions.lock().unwrap().insert(subscription_id.clone());
+                            WebSocketMessage::Subscribed {
+                                data: WatchSubscriptionData { subscription_id, path },
+                            }
+                        }
+                        Err(err) => WebSocketMessage::Error {
+                            data: WebSocketErrorData {
+                                code: err.code().to_string(),
+                                message: err.to_string(),
+                            },
+                        },
+                    }
+                }
+                Ok(FileSystemWatchRequest::Unsubscribe { subscription_id }) => {
+                    if recv_subscriptions.lock().unwrap().remove(&subscription_id) {
+                        API.files.unwatch_directory(&subscription_id);
+                    }
+                    continue;
+                }
+                Err(err) => WebSocketMessage::Error {
+                    data: WebSocketErrorData {
+                        code: "VALIDATION_ERROR".to_string(),
+                        message: format!("Invalid watch request: {}", err),
+                    },
+                },
+            };
+
+            if reply_tx.send(reply).is_err() {
+                break;
             }
         }
     });
@@ -107,4 +191,9 @@ async fn filesystem_websocket(stream: WebSocket, state: Arc<AppState>) {
             send_task.abort();
         }
     }
+
+    let ids: Vec<String> = subscriptions.lock().unwrap().drain().collect();
+    for id in ids {
+        API.files.unwatch_directory(&id);
+    }
 }
@@ -0,0 +1,702 @@
+/**
+ * Checksum Service
+ *
+ * Computes SHA-256 / SHA-512 / BLAKE3 / MD5 checksums of files and directory trees
+ * on any FileSystem backend, writes coreutils-compatible manifests (`sha256sum`
+ * format: `<hash>  <path>`) and verifies existing manifests.
+ *
+ * Files are streamed through `FileSystem::open_read`, so large images are hashed
+ * without loading them into memory and without the 30 second limit of shell commands.
+ */
+
+use super::journal::{join_path, split_path};
+use super::search::{walk_tree, Visit, WalkOptions};
+use super::{ApiError, ApiResult, API};
+use crate::core::{ErrorKind, FileSystem, FileSystemError, FileSystemResult};
+use crate::progress::ProgressTracker;
+use crate::queue::{OperationParams, OperationPriority, QueuedOperation, QueuedOperationType, QUEUE_MANAGER};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::VecDeque;
+use std::io::Read;
+use std::sync::Mutex;
+
+const HASH_BUFFER_SIZE: usize = 256 * 1024;
+
+/// Сколько результатов заданий очереди хранится для `job_result`
+const MAX_STORED_RESULTS: usize = 32;
+
+/// Результаты завершенных заданий очереди (ID операции → результат)
+static JOB_RESULTS: Lazy<Mutex<VecDeque<(String, ChecksumJobResult)>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+/// Алгоритм контрольной суммы
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum ChecksumAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+    Blake3,
+    Md5,
+}
+
+impl ChecksumAlgorithm {
+    /// Длина хеша в hex-символах
+    fn hex_len(&self) -> usize {
+        match self {
+            ChecksumAlgorithm::Sha256 | ChecksumAlgorithm::Blake3 => 64,
+            ChecksumAlgorithm::Sha512 => 128,
+            ChecksumAlgorithm::Md5 => 32,
+        }
+    }
+
+    /// Алгоритм по имени манифеста (`ISO.sha256sum`, `SHA512SUMS`, `files.md5`)
+    fn from_manifest_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        [
+            (ChecksumAlgorithm::Sha256, ["sha256", "sha256sum"]),
+            (ChecksumAlgorithm::Sha512, ["sha512", "sha512sum"]),
+            (ChecksumAlgorithm::Blake3, ["b3", "b3sum"]),
+            (ChecksumAlgorithm::Md5, ["md5", "md5sum"]),
+        ]
+        .into_iter()
+        .find(|(_, names)| {
+            names.iter().any(|suffix| name.ends_with(&format!(".{}", suffix)) || name == format!("{}s", suffix))
+        })
+        .map(|(algorithm, _)| algorithm)
+    }
+
+    fn hasher(&self) -> ChecksumHasher {
+        match self {
+            ChecksumAlgorithm::Sha256 => ChecksumHasher::Sha256(Sha256::new()),
+            ChecksumAlgorithm::Sha512 => ChecksumHasher::Sha512(Sha512::new()),
+            ChecksumAlgorithm::Blake3 => ChecksumHasher::Blake3(Box::new(blake3::Hasher::new())),
+            ChecksumAlgorithm::Md5 => ChecksumHasher::Md5(md5::Md5::new()),
+        }
+    }
+}
+
+enum ChecksumHasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Blake3(Box<blake3::Hasher>),
+    Md5(md5::Md5),
+}
+
+impl ChecksumHasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            ChecksumHasher::Sha256(hasher) => hasher.update(data),
+            ChecksumHasher::Sha512(hasher) => hasher.update(data),
+            ChecksumHasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+            ChecksumHasher::Md5(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            ChecksumHasher::Sha256(hasher) => to_hex(&hasher.finalize()),
+            ChecksumHasher::Sha512(hasher) => to_hex(&hasher.finalize()),
+            ChecksumHasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+            ChecksumHasher::Md5(hasher) => to_hex(&hasher.finalize()),
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Расчет контрольных сумм
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct ChecksumRequest {
+    /// Файлы и директории (директории обходятся рекурсивно)
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub algorithm: ChecksumAlgorithm,
+    /// Записать манифест сюда; пути в нем относительны директории манифеста
+    #[serde(default)]
+    pub manifest_path: Option<String>,
+    /// Включать скрытые файлы; `None` — по настройке `show_hidden_files`
+    #[serde(default)]
+    pub include_hidden: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct FileChecksum {
+    pub path: String,
+    pub size: u64,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct ChecksumResult {
+    pub algorithm: ChecksumAlgorithm,
+    pub files: Vec<FileChecksum>,
+    /// Файлы, которые не удалось прочитать
+    pub errors: Vec<String>,
+    /// Записанный манифест
+    pub manifest_path: Option<String>,
+    pub cancelled: bool,
+}
+
+/// Проверка манифеста
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct VerifyRequest {
+    pub manifest_path: String,
+    /// По умолчанию определяется по имени манифеста или длине хешей
+    #[serde(default)]
+    pub algorithm: Option<ChecksumAlgorithm>,
+}
+
+/// Результат проверки файла (как в выводе `sha256sum -c`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum VerifyStatus {
+    Ok,
+    Failed,
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct VerifyEntry {
+    /// Путь в том виде, в каком он записан в манифесте
+    pub path: String,
+    pub status: VerifyStatus,
+    pub expected: String,
+    pub actual: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct VerifyReport {
+    pub algorithm: ChecksumAlgorithm,
+    pub entries: Vec<VerifyEntry>,
+    pub ok: u64,
+    pub failed: u64,
+    pub missing: u64,
+    /// Строки манифеста, которые не удалось разобрать
+    pub malformed_lines: u64,
+    pub cancelled: bool,
+}
+
+/// Задание для очереди операций
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum ChecksumJob {
+    Compute(ChecksumRequest),
+    Verify(VerifyRequest),
+}
+
+/// Результат задания очереди
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum ChecksumJobResult {
+    Compute(ChecksumResult),
+    Verify(VerifyReport),
+}
+
+/// Service for checksum calculation and manifest verification
+pub struct ChecksumService;
+
+impl ChecksumService {
+    pub fn new() -> Self {
+        tracing::debug!("Initializing ChecksumService");
+        Self
+    }
+
+    /// Compute checksums of `request.paths` and optionally write a manifest
+    ///
+    /// Progress goes to `tracker`; `on_progress` is called after each update.
+    pub fn compute<F>(
+        &self,
+        request: &ChecksumRequest,
+        panel_fs: Option<&str>,
+        tracker: &ProgressTracker,
+        on_progress: F,
+    ) -> ApiResult<ChecksumResult>
+    where
+        F: FnMut(),
+    {
+        tracing::info!("Computing {:?} checksums of {} paths with backend: {:?}", request.algorithm, request.paths.len(), panel_fs);
+
+        let instance = API.files.get_filesystem_by_backend(panel_fs);
+        compute_checksums(instance.as_trait(), request, tracker, on_progress)
+    }
+
+    /// Verify files listed in a manifest
+    pub fn verify<F>(
+        &self,
+        request: &VerifyRequest,
+        panel_fs: Option<&str>,
+        tracker: &ProgressTracker,
+        on_progress: F,
+    ) -> ApiResult<VerifyReport>
+    where
+        F: FnMut(),
+    {
+        tracing::info!("Verifying manifest {} with backend: {:?}", request.manifest_path, panel_fs);
+
+        let instance = API.files.get_filesystem_by_backend(panel_fs);
+        verify_manifest(instance.as_trait(), request, tracker, on_progress)
+    }
+
+    /// Queue a long-running checksum job; returns the queue operation ID
+    ///
+    /// The result can be fetched with `job_result` once the operation has finished.
+    pub fn enqueue(&self, job: ChecksumJob, panel_fs: Option<String>) -> ApiResult<String> {
+        let description = match &job {
+            ChecksumJob::Compute(request) => {
+                if request.paths.is_empty() {
+                    return Err(ApiError::ValidationError {
+                        message: "No files specified for checksum".to_string(),
+                    });
+                }
+                format!("{:?} checksums of {} items", request.algorithm, request.paths.len())
+            }
+            ChecksumJob::Verify(request) => format!("Verify '{}'", split_path(&request.manifest_path).1),
+        };
+
+        // Повторная проверка даст тот же результат
+        let retry = matches!(job, ChecksumJob::Compute(_));
+        let mut operation = QueuedOperation::new(
+            QueuedOperationType::Checksum,
+            OperationParams::Checksum { job, source_fs: panel_fs },
+            OperationPriority::Normal,
+        );
+        operation.retry_policy.enabled = retry;
+        operation.description = Some(description);
+
+        QUEUE_MANAGER
+            .enqueue(operation)
+            .map_err(|message| ApiError::OperationFailed { message })
+    }
+
+    /// Run a queued job (inside the queue executor) and keep its result for `job_result`
+    pub fn run_job(
+        &self,
+        operation_id: &str,
+        job: &ChecksumJob,
+        panel_fs: Option<&str>,
+        tracker: &ProgressTracker,
+    ) -> ApiResult<ChecksumJobResult> {
+        let result = match job {
+            ChecksumJob::Compute(request) => ChecksumJobResult::Compute(self.compute(request, panel_fs, tracker, || {})?),
+            ChecksumJob::Verify(request) => ChecksumJobResult::Verify(self.verify(request, panel_fs, tracker, || {})?),
+        };
+
+        let mut results = JOB_RESULTS.lock().unwrap();
+        results.retain(|(id, _)| id != operation_id);
+        if results.len() >= MAX_STORED_RESULTS {
+            results.pop_front();
+        }
+        results.push_back((operation_id.to_string(), result.clone()));
+        Ok(result)
+    }
+
+    /// Result of a finished queued job
+    pub fn job_result(&self, operation_id: &str) -> ApiResult<ChecksumJobResult> {
+        JOB_RESULTS
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(id, _)| id == operation_id)
+            .map(|(_, result)| result.clone())
+            .ok_or_else(|| ApiError::NotFound {
+                resource: format!("Checksum result: {}", operation_id),
+            })
+    }
+}
+
+impl Default for ChecksumService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn compute_checksums<F>(
+    fs: &dyn FileSystem,
+    request: &ChecksumRequest,
+    tracker: &ProgressTracker,
+    mut on_progress: F,
+) -> ApiResult<ChecksumResult>
+where
+    F: FnMut(),
+{
+    if request.paths.is_empty() {
+        return Err(ApiError::ValidationError {
+            message: "No files specified for checksum".to_string(),
+        });
+    }
+
+    let mut result = ChecksumResult {
+        algorithm: request.algorithm,
+        ..Default::default()
+    };
+
+    let files = collect_files(fs, request, tracker)?;
+    if tracker.is_cancelled() {
+        result.cancelled = true;
+        return Ok(result);
+    }
+    tracker.set_total_bytes(files.iter().map(|(_, size)| size).sum());
+
+    for (path, size) in files {
+        // Манифест, который сейчас перезаписывается, в себя не входит
+        if request.manifest_path.as_deref() == Some(path.as_str()) {
+            continue;
+        }
+        tracker.update_current_file(Some(path.clone()));
+
+        match hash_file(fs, &path, request.algorithm, tracker, &mut on_progress) {
+            Ok(Some(hash)) => {
+                tracker.add_item();
+                result.files.push(FileChecksum { path, size, hash });
+            }
+            Ok(None) => {
+                result.cancelled = true;
+                return Ok(result);
+            }
+            Err(err) => result.errors.push(format!("{}: {}", path, err)),
+        }
+        on_progress();
+    }
+
+    if let Some(manifest_path) = &request.manifest_path {
+        let (dir, _) = split_path(manifest_path);
+        let content: String = result
+            .files
+            .iter()
+            .map(|file| format_manifest_line(&file.hash, &manifest_relative(&dir, &file.path)))
+            .collect();
+        fs.write_file_bytes(manifest_path, content.as_bytes()).map_err(ApiError::from)?;
+        result.manifest_path = Some(manifest_path.clone());
+    }
+
+    tracing::info!("Computed {} checksums, {} errors", result.files.len(), result.errors.len());
+    Ok(result)
+}
+
+pub(crate) fn verify_manifest<F>(
+    fs: &dyn FileSystem,
+    request: &VerifyRequest,
+    tracker: &ProgressTracker,
+    mut on_progress: F,
+) -> ApiResult<VerifyReport>
+where
+    F: FnMut(),
+{
+    let content = fs.read_file_bytes(&request.manifest_path).map_err(ApiError::from)?;
+    let content = String::from_utf8(content).map_err(|_| ApiError::ValidationError {
+        message: format!("Manifest is not valid UTF-8: {}", request.manifest_path),
+    })?;
+
+    let mut report = VerifyReport::default();
+    let lines: Vec<(String, String)> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let parsed = parse_manifest_line(line);
+            if parsed.is_none() {
+                report.malformed_lines += 1;
+            }
+            parsed
+        })
+        .collect();
+
+    let (manifest_dir, manifest_name) = split_path(&request.manifest_path);
+    let algorithm = request
+        .algorithm
+        .or_else(|| ChecksumAlgorithm::from_manifest_name(&manifest_name))
+        .or_else(|| lines.first().and_then(|(hash, _)| algorithm_for_hex_len(hash.len())))
+        .ok_or_else(|| ApiError::ValidationError {
+            message: "Cannot determine checksum algorithm of the manifest".to_string(),
+        })?;
+    report.algorithm = algorithm;
+
+    let mut resolved = Vec::with_capacity(lines.len());
+    let mut total_bytes = 0;
+    for (expected, name) in lines {
+        if expected.len() != algorithm.hex_len() || !expected.chars().all(|c| c.is_ascii_hexdigit()) {
+            report.malformed_lines += 1;
+            continue;
+        }
+        let path = resolve_manifest_path(&manifest_dir, &name);
+        let size = fs.get_file_info(&path).ok().filter(|info| info.is_file).and_then(|info| info.size);
+        total_bytes += size.unwrap_or(0);
+        resolved.push((expected.to_lowercase(), name, path, size));
+    }
+    tracker.set_total_bytes(total_bytes);
+
+    for (expected, name, path, size) in resolved {
+        tracker.update_current_file(Some(path.clone()));
+
+        let actual = match size {
+            None => None,
+            Some(_) => match hash_file(fs, &path, algorithm, tracker, &mut on_progress) {
+                Ok(Some(hash)) => Some(hash),
+                Ok(None) => {
+                    report.cancelled = true;
+                    break;
+                }
+                Err(err) if err.kind == ErrorKind::NotFound => None,
+                Err(err) => {
+                    tracing::debug!("Failed to read {}: {}", path, err);
+                    report.failed += 1;
+                    report.entries.push(VerifyEntry { path: name, status: VerifyStatus::Failed, expected, actual: None });
+                    continue;
+                }
+            },
+        };
+
+        let status = match &actual {
+            None => VerifyStatus::Missing,
+            Some(actual) if *actual == expected => VerifyStatus::Ok,
+            Some(_) => VerifyStatus::Failed,
+        };
+        match status {
+            VerifyStatus::Ok => report.ok += 1,
+            VerifyStatus::Failed => report.failed += 1,
+            VerifyStatus::Missing => report.missing += 1,
+        }
+        tracker.add_item();
+        report.entries.push(VerifyEntry { path: name, status, expected, actual });
+        on_progress();
+    }
+
+    tracing::info!(
+        "Manifest {} verified: {} OK, {} FAILED, {} MISSING",
+        request.manifest_path,
+        report.ok,
+        report.failed,
+        report.missing
+    );
+    Ok(report)
+}
+
+/// Файлы запроса с размерами; директории раскрываются рекурсивно (в порядке путей)
+fn collect_files(fs: &dyn FileSystem, request: &ChecksumRequest, tracker: &ProgressTracker) -> ApiResult<Vec<(String, u64)>> {
+    let options = WalkOptions {
+        include_hidden: request.include_hidden,
+        respect_gitignore: false,
+        max_depth: None,
+    };
+
+    let mut files = Vec::new();
+    for path in &request.paths {
+        let info = fs.get_file_info(path).map_err(ApiError::from)?;
+        if !info.is_dir {
+            files.push((info.path, info.size.unwrap_or(0)));
+            continue;
+        }
+
+        let mut tree = Vec::new();
+        walk_tree(fs, path, &options, tracker.cancellation_token(), |entry| {
+            if entry.is_file && !entry.is_symlink {
+                tree.push((entry.path, entry.size.unwrap_or(0)));
+            }
+            Visit::Continue
+        })?;
+        tree.sort();
+        files.extend(tree);
+    }
+    Ok(files)
+}
+
+/// Хеш файла в hex; `Ok(None)`, если операция отменена
+pub(crate) fn hash_file(
+    fs: &dyn FileSystem,
+    path: &str,
+    algorithm: ChecksumAlgorithm,
+    tracker: &ProgressTracker,
+    on_progress: &mut dyn FnMut(),
+) -> FileSystemResult<Option<String>> {
+    let mut reader = fs.open_read(path)?;
+    let mut hasher = algorithm.hasher();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+
+    loop {
+        if tracker.is_cancelled() {
+            return Ok(None);
+        }
+
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(FileSystemError::from_io(&e, format!("Failed to read {}: {}", path, e))),
+        };
+
+        hasher.update(&buffer[..read]);
+        tracker.add_bytes(read as u64);
+        on_progress();
+    }
+
+    Ok(Some(hasher.finalize_hex()))
+}
+
+fn algorithm_for_hex_len(len: usize) -> Option<ChecksumAlgorithm> {
+    match len {
+        32 => Some(ChecksumAlgorithm::Md5),
+        64 => Some(ChecksumAlgorithm::Sha256),
+        128 => Some(ChecksumAlgorithm::Sha512),
+        _ => None,
+    }
+}
+
+/// Путь файла относительно директории манифеста (если файл внутри нее)
+fn manifest_relative(dir: &str, path: &str) -> String {
+    let prefix = dir.trim_end_matches(['/', '\\']);
+    match path.strip_prefix(prefix) {
+        Some(rest) if rest.starts_with(['/', '\\']) => rest[1..].to_string(),
+        _ => path.to_string(),
+    }
+}
+
+fn resolve_manifest_path(dir: &str, name: &str) -> String {
+    let name = name.strip_prefix("./").unwrap_or(name);
+    let is_absolute = name.starts_with(['/', '\\']) || name.get(1..2) == Some(":");
+    if is_absolute {
+        name.to_string()
+    } else {
+        join_path(dir, name)
+    }
+}
+
+/// Строка манифеста в формате coreutils; имена с `\` и переводами строк экранируются
+fn format_manifest_line(hash: &str, name: &str) -> String {
+    if name.contains(['\\', '\n', '\r']) {
+        let escaped = name.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r");
+        format!("\\{}  {}\n", hash, escaped)
+    } else {
+        format!("{}  {}\n", hash, name)
+    }
+}
+
+/// Разбор строки `<hash>  <name>` (текстовый режим) или `<hash> *<name>` (двоичный)
+fn parse_manifest_line(line: &str) -> Option<(String, String)> {
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (hash, rest) = line.split_once(' ')?;
+    let name = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('*'))?;
+    if hash.is_empty() || name.is_empty() {
+        return None;
+    }
+
+    let name = if escaped { unescape_name(name)? } else { name.to_string() };
+    Some((hash.to_string(), name))
+}
+
+fn unescape_name(name: &str) -> Option<String> {
+    let mut result = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => result.push('\\'),
+            'n' => result.push('\n'),
+            'r' => result.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::temporary_fs::TemporaryFileSystem;
+    use crate::progress::OperationType;
+
+    fn tracker() -> ProgressTracker {
+        ProgressTracker::new("checksum-test".to_string(), OperationType::Checksum, 0, 0)
+    }
+
+    #[test]
+    fn test_known_digests() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("abc.txt"), "abc").unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+
+        let digest = |algorithm| hash_file(&fs, "/abc.txt", algorithm, &tracker(), &mut || {}).unwrap().unwrap();
+        assert_eq!(digest(ChecksumAlgorithm::Sha256), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(digest(ChecksumAlgorithm::Md5), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(digest(ChecksumAlgorithm::Blake3), "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+        assert!(digest(ChecksumAlgorithm::Sha512).starts_with("ddaf35a193617aba"));
+    }
+
+    #[test]
+    fn test_manifest_roundtrip_and_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("data/sub")).unwrap();
+        std::fs::write(dir.path().join("data/a.txt"), "alpha").unwrap();
+        std::fs::write(dir.path().join("data/sub/b.txt"), "beta").unwrap();
+        std::fs::write(dir.path().join("data/sub/c.txt"), "gamma").unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+
+        let request = ChecksumRequest {
+            paths: vec!["/data".to_string()],
+            manifest_path: Some("/data/files.sha256sum".to_string()),
+            include_hidden: Some(true),
+            ..Default::default()
+        };
+        let result = compute_checksums(&fs, &request, &tracker(), || {}).unwrap();
+        assert_eq!(result.files.len(), 3);
+
+        let manifest = std::fs::read_to_string(dir.path().join("data/files.sha256sum")).unwrap();
+        assert!(manifest.contains("  sub/b.txt\n"));
+        assert!(manifest.starts_with(&format!("{}  a.txt\n", result.files[0].hash)));
+
+        std::fs::write(dir.path().join("data/sub/b.txt"), "BETA").unwrap();
+        std::fs::remove_file(dir.path().join("data/sub/c.txt")).unwrap();
+
+        let verify = VerifyRequest {
+            manifest_path: "/data/files.sha256sum".to_string(),
+            algorithm: None,
+        };
+        let report = verify_manifest(&fs, &verify, &tracker(), || {}).unwrap();
+        assert_eq!(report.algorithm, ChecksumAlgorithm::Sha256);
+        let statuses: Vec<_> = report.entries.iter().map(|e| (e.path.as_str(), e.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![("a.txt", VerifyStatus::Ok), ("sub/b.txt", VerifyStatus::Failed), ("sub/c.txt", VerifyStatus::Missing)]
+        );
+        assert_eq!((report.ok, report.failed, report.missing), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_manifest_line_escaping() {
+        let hash = "900150983cd24fb0d6963f7d28e17f72";
+        let line = format_manifest_line(hash, "odd\\name\nx");
+        assert_eq!(line, format!("\\{}  odd\\\\name\\nx\n", hash));
+        assert_eq!(parse_manifest_line(line.trim_end_matches('\n')), Some((hash.to_string(), "odd\\name\nx".to_string())));
+
+        assert_eq!(parse_manifest_line(&format!("{} *bin.iso", hash)), Some((hash.to_string(), "bin.iso".to_string())));
+        assert_eq!(parse_manifest_line("garbage"), None);
+        assert_eq!(ChecksumAlgorithm::from_manifest_name("SHA512SUMS"), Some(ChecksumAlgorithm::Sha512));
+        assert_eq!(ChecksumAlgorithm::from_manifest_name("image.iso.b3sum"), Some(ChecksumAlgorithm::Blake3));
+    }
+}
@@ -0,0 +1,463 @@
+/**
+ * Directory Compare
+ *
+ * Recursively compares two trees, possibly on different backends (real FS, vault,
+ * temporary session), for the dual-panel "compare directories" view.
+ * Files are matched by relative path and compared by size and mtime, optionally
+ * by content (BLAKE3).
+ */
+
+use super::checksum::{hash_file, ChecksumAlgorithm};
+use super::search::{walk_tree, Visit, WalkOptions};
+use super::{ApiError, ApiResult, API};
+use crate::core::FileSystem;
+use crate::progress::ProgressTracker;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Разница mtime, которая считается совпадением (FAT хранит время с точностью 2 с)
+pub(crate) const DEFAULT_MTIME_TOLERANCE_SECS: u64 = 2;
+
+/// Параметры сравнения директорий
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct CompareRequest {
+    pub left_root: String,
+    /// Backend левой панели ("real", "virtual", vault window label or temporary session label)
+    #[serde(default)]
+    pub left_fs: Option<String>,
+    pub right_root: String,
+    #[serde(default)]
+    pub right_fs: Option<String>,
+    /// Сравнивать содержимое файлов одинакового размера
+    #[serde(default)]
+    pub compare_content: bool,
+    /// Допустимая разница mtime в секундах (по умолчанию 2)
+    #[serde(default)]
+    pub mtime_tolerance_secs: Option<u64>,
+    /// Включать скрытые файлы; `None` — по настройке `show_hidden_files`
+    #[serde(default)]
+    pub include_hidden: Option<bool>,
+    #[serde(default)]
+    pub respect_gitignore: bool,
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+}
+
+/// Результат сравнения пути
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum CompareStatus {
+    LeftOnly,
+    RightOnly,
+    NewerLeft,
+    NewerRight,
+    Identical,
+    /// Содержимое или тип отличаются, но по mtime не понять, какая сторона новее
+    Different,
+}
+
+/// Запись с одной стороны сравнения
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct CompareSide {
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct CompareEntry {
+    /// Путь относительно корней сравнения (через `/`)
+    pub relative_path: String,
+    pub status: CompareStatus,
+    pub left: Option<CompareSide>,
+    pub right: Option<CompareSide>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct CompareSummary {
+    pub left_only: u64,
+    pub right_only: u64,
+    pub newer_left: u64,
+    pub newer_right: u64,
+    pub identical: u64,
+    pub different: u64,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DirectoryComparison {
+    /// Записи в порядке путей; содержимое папок, которые есть только с одной стороны, не перечисляется
+    pub entries: Vec<CompareEntry>,
+    pub summary: CompareSummary,
+}
+
+/// Service for comparing two directory trees
+pub struct CompareService;
+
+impl CompareService {
+    pub fn new() -> Self {
+        tracing::debug!("Initializing CompareService");
+        Self
+    }
+
+    /// Compare `request.left_root` with `request.right_root`
+    ///
+    /// Progress goes to `tracker`; `on_progress` is called after each update.
+    pub fn compare<F>(
+        &self,
+        request: &CompareRequest,
+        tracker: &ProgressTracker,
+        on_progress: F,
+    ) -> ApiResult<DirectoryComparison>
+    where
+        F: FnMut(),
+    {
+        tracing::info!(
+            "Comparing {} ({:?}) with {} ({:?})",
+            request.left_root,
+            request.left_fs,
+            request.right_root,
+            request.right_fs
+        );
+
+        let left = API.files.get_filesystem_by_backend(request.left_fs.as_deref());
+        let right = API.files.get_filesystem_by_backend(request.right_fs.as_deref());
+        compare_trees(left.as_trait(), right.as_trait(), request, tracker, on_progress)
+    }
+}
+
+impl Default for CompareService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn compare_trees<F>(
+    left_fs: &dyn FileSystem,
+    right_fs: &dyn FileSystem,
+    request: &CompareRequest,
+    tracker: &ProgressTracker,
+    mut on_progress: F,
+) -> ApiResult<DirectoryComparison>
+where
+    F: FnMut(),
+{
+    for (fs, root) in [(left_fs, &request.left_root), (right_fs, &request.right_root)] {
+        let info = fs.get_file_info(root).map_err(ApiError::from)?;
+        if !info.is_dir {
+            return Err(ApiError::NotADirectory {
+                message: format!("Not a directory: {}", root),
+            });
+        }
+    }
+
+    let mut comparison = DirectoryComparison::default();
+    let options = WalkOptions {
+        include_hidden: request.include_hidden,
+        respect_gitignore: request.respect_gitignore,
+        max_depth: request.max_depth,
+    };
+
+    let Some(left) = collect_tree(left_fs, &request.left_root, &options, tracker, &mut on_progress)? else {
+        comparison.summary.cancelled = true;
+        return Ok(comparison);
+    };
+    let Some(right) = collect_tree(right_fs, &request.right_root, &options, tracker, &mut on_progress)? else {
+        comparison.summary.cancelled = true;
+        return Ok(comparison);
+    };
+
+    let tolerance = request.mtime_tolerance_secs.unwrap_or(DEFAULT_MTIME_TOLERANCE_SECS);
+    let paths: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+
+    if request.compare_content {
+        let bytes: u64 = paths
+            .iter()
+            .filter_map(|path| match (left.get(*path), right.get(*path)) {
+                (Some(l), Some(r)) if !l.is_dir && !r.is_dir && l.size == r.size => Some(l.size * 2),
+                _ => None,
+            })
+            .sum();
+        tracker.set_total_bytes(bytes);
+    }
+
+    // Папки, которые есть только с одной стороны, представляют все свое содержимое.
+    // Соседи вроде `dir.txt` сортируются между `dir` и `dir/…`, поэтому хранятся все такие папки
+    let mut one_sided_dirs = HashSet::new();
+
+    for relative in paths {
+        if has_ancestor_in(&one_sided_dirs, relative) {
+            continue;
+        }
+        if tracker.is_cancelled() {
+            comparison.summary.cancelled = true;
+            break;
+        }
+
+        let left_side = left.get(relative).cloned();
+        let right_side = right.get(relative).cloned();
+
+        let status = match (&left_side, &right_side) {
+            (Some(l), None) => {
+                if l.is_dir {
+                    one_sided_dirs.insert(relative.clone());
+                }
+                CompareStatus::LeftOnly
+            }
+            (None, Some(r)) => {
+                if r.is_dir {
+                    one_sided_dirs.insert(relative.clone());
+                }
+                CompareStatus::RightOnly
+            }
+            (Some(l), Some(r)) => {
+                let same_content = if l.is_dir || r.is_dir {
+                    Some(l.is_dir == r.is_dir)
+                } else if l.size != r.size {
+                    Some(false)
+                } else if request.compare_content {
+                    tracker.update_current_file(Some(l.path.clone()));
+                    let hashes = hash_file(left_fs, &l.path, ChecksumAlgorithm::Blake3, tracker, &mut on_progress)
+                        .and_then(|left_hash| {
+                            let right_hash = hash_file(right_fs, &r.path, ChecksumAlgorithm::Blake3, tracker, &mut on_progress)?;
+                            Ok(left_hash.zip(right_hash))
+                        });
+                    match hashes {
+                        Ok(Some((left_hash, right_hash))) => Some(left_hash == right_hash),
+                        Ok(None) => {
+                            comparison.summary.cancelled = true;
+                            break;
+                        }
+                        Err(err) => {
+                            tracing::debug!("Failed to hash {}: {}", relative, err);
+                            None
+                        }
+                    }
+                } else {
+                    // Без сравнения содержимого одинаковый размер и mtime считаются совпадением
+                    within_tolerance(l.modified, r.modified, tolerance).then_some(true)
+                };
+
+                if same_content == Some(true) {
+                    CompareStatus::Identical
+                } else if l.is_dir != r.is_dir {
+                    CompareStatus::Different
+                } else {
+                    newer_side(l.modified, r.modified, tolerance)
+                }
+            }
+            (None, None) => continue,
+        };
+
+        let summary = &mut comparison.summary;
+        match status {
+            CompareStatus::LeftOnly => summary.left_only += 1,
+            CompareStatus::RightOnly => summary.right_only += 1,
+            CompareStatus::NewerLeft => summary.newer_left += 1,
+            CompareStatus::NewerRight => summary.newer_right += 1,
+            CompareStatus::Identical => summary.identical += 1,
+            CompareStatus::Different => summary.different += 1,
+        }
+        comparison.entries.push(CompareEntry {
+            relative_path: relative.clone(),
+            status,
+            left: left_side,
+            right: right_side,
+        });
+    }
+
+    tracing::info!("Directory compare finished: {:?}", comparison.summary);
+    Ok(comparison)
+}
+
+/// Все записи дерева по относительным путям; `None`, если операция отменена
+pub(crate) fn collect_tree(
+    fs: &dyn FileSystem,
+    root: &str,
+    options: &WalkOptions,
+    tracker: &ProgressTracker,
+    on_progress: &mut dyn FnMut(),
+) -> ApiResult<Option<BTreeMap<String, CompareSide>>> {
+    let prefix = root.trim_end_matches(['/', '\\']);
+    let mut entries = BTreeMap::new();
+
+    let stats = walk_tree(fs, root, options, tracker.cancellation_token(), |entry| {
+        let relative = entry
+            .path
+            .strip_prefix(prefix)
+            .unwrap_or(&entry.path)
+            .trim_start_matches(['/', '\\'])
+            .replace('\\', "/");
+        entries.insert(
+            relative,
+            CompareSide {
+                is_dir: entry.is_dir,
+                size: if entry.is_dir { 0 } else { entry.size.unwrap_or(0) },
+                modified: entry.modified,
+                path: entry.path,
+            },
+        );
+        tracker.add_item();
+        on_progress();
+        Visit::Continue
+    })?;
+
+    Ok((!stats.cancelled).then_some(entries))
+}
+
+/// Одна из родительских папок относительного пути входит в `dirs`
+pub(crate) fn has_ancestor_in(dirs: &HashSet<String>, relative: &str) -> bool {
+    relative.match_indices('/').any(|(index, _)| dirs.contains(&relative[..index]))
+}
+
+pub(crate) fn within_tolerance(left: Option<u64>, right: Option<u64>, tolerance: u64) -> bool {
+    match (left, right) {
+        (Some(l), Some(r)) => l.abs_diff(r) <= tolerance,
+        _ => false,
+    }
+}
+
+fn newer_side(left: Option<u64>, right: Option<u64>, tolerance: u64) -> CompareStatus {
+    match (left, right) {
+        (Some(l), Some(r)) if l > r + tolerance => CompareStatus::NewerLeft,
+        (Some(l), Some(r)) if r > l + tolerance => CompareStatus::NewerRight,
+        _ => CompareStatus::Different,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::temporary_fs::TemporaryFileSystem;
+    use crate::progress::OperationType;
+    use filetime::FileTime;
+    use std::path::Path;
+
+    fn write(root: &Path, path: &str, content: &str, mtime: i64) {
+        let full = root.join(path);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::write(&full, content).unwrap();
+        filetime::set_file_mtime(&full, FileTime::from_unix_time(mtime, 0)).unwrap();
+    }
+
+    fn statuses(comparison: &DirectoryComparison) -> Vec<(&str, CompareStatus)> {
+        comparison
+            .entries
+            .iter()
+            .filter(|entry| !entry.left.as_ref().or(entry.right.as_ref()).unwrap().is_dir || entry.status != CompareStatus::Identical)
+            .map(|entry| (entry.relative_path.as_str(), entry.status))
+            .collect()
+    }
+
+    #[test]
+    fn test_compare_by_size_and_mtime() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        write(left.path(), "same.txt", "abc", 1_000);
+        write(right.path(), "same.txt", "abc", 1_001);
+        write(left.path(), "edited.txt", "new content", 5_000);
+        write(right.path(), "edited.txt", "old", 1_000);
+        write(left.path(), "stale.txt", "old", 1_000);
+        write(right.path(), "stale.txt", "new content", 5_000);
+        write(left.path(), "only-left/deep/a.txt", "a", 1_000);
+        write(right.path(), "only-right.txt", "b", 1_000);
+        write(left.path(), "kind", "file", 1_000);
+        std::fs::create_dir(right.path().join("kind")).unwrap();
+
+        let left_fs = TemporaryFileSystem::new(left.path().to_path_buf());
+        let right_fs = TemporaryFileSystem::new(right.path().to_path_buf());
+        let request = CompareRequest {
+            left_root: "/".to_string(),
+            right_root: "/".to_string(),
+            include_hidden: Some(true),
+            ..Default::default()
+        };
+        let tracker = ProgressTracker::new("compare-test".to_string(), OperationType::Compare, 0, 0);
+        let comparison = compare_trees(&left_fs, &right_fs, &request, &tracker, || {}).unwrap();
+
+        assert_eq!(
+            statuses(&comparison),
+            vec![
+                ("edited.txt", CompareStatus::NewerLeft),
+                ("kind", CompareStatus::Different),
+                ("only-left", CompareStatus::LeftOnly),
+                ("only-right.txt", CompareStatus::RightOnly),
+                ("same.txt", CompareStatus::Identical),
+                ("stale.txt", CompareStatus::NewerRight),
+            ]
+        );
+        assert_eq!(comparison.summary.left_only, 1);
+    }
+
+    #[test]
+    fn test_one_sided_dir_with_sibling() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        write(left.path(), "dir/a.txt", "a", 1_000);
+        write(left.path(), "dir/sub/b.txt", "b", 1_000);
+        // `dir.txt` и `dir-x` сортируются между `dir` и `dir/a.txt`
+        write(left.path(), "dir.txt", "same", 1_000);
+        write(right.path(), "dir.txt", "same", 1_000);
+        write(right.path(), "dir-x", "x", 1_000);
+
+        let left_fs = TemporaryFileSystem::new(left.path().to_path_buf());
+        let right_fs = TemporaryFileSystem::new(right.path().to_path_buf());
+        let request = CompareRequest {
+            left_root: "/".to_string(),
+            right_root: "/".to_string(),
+            ..Default::default()
+        };
+        let tracker = ProgressTracker::new("compare-test".to_string(), OperationType::Compare, 0, 0);
+        let comparison = compare_trees(&left_fs, &right_fs, &request, &tracker, || {}).unwrap();
+
+        assert_eq!(
+            statuses(&comparison),
+            vec![
+                ("dir", CompareStatus::LeftOnly),
+                ("dir-x", CompareStatus::RightOnly),
+                ("dir.txt", CompareStatus::Identical),
+            ]
+        );
+        assert_eq!(comparison.summary.left_only, 1);
+    }
+
+    #[test]
+    fn test_compare_content() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        // Одинаковое содержимое, разное время
+        write(left.path(), "copy.txt", "same", 1_000);
+        write(right.path(), "copy.txt", "same", 9_000);
+        // Одинаковые размер и время, разное содержимое
+        write(left.path(), "sneaky.txt", "aaaa", 1_000);
+        write(right.path(), "sneaky.txt", "bbbb", 1_000);
+
+        let left_fs = TemporaryFileSystem::new(left.path().to_path_buf());
+        let right_fs = TemporaryFileSystem::new(right.path().to_path_buf());
+        let request = CompareRequest {
+            left_root: "/".to_string(),
+            right_root: "/".to_string(),
+            compare_content: true,
+            ..Default::default()
+        };
+        let tracker = ProgressTracker::new("compare-test".to_string(), OperationType::Compare, 0, 0);
+        let comparison = compare_trees(&left_fs, &right_fs, &request, &tracker, || {}).unwrap();
+
+        assert_eq!(
+            statuses(&comparison),
+            vec![("copy.txt", CompareStatus::Identical), ("sneaky.txt", CompareStatus::Different)]
+        );
+    }
+}
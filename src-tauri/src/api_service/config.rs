@@ -6,6 +6,7 @@
 
 use super::{ApiResult, ApiError};
 use super::models::{AppConfig, UIState};
+use super::index::FILE_INDEX;
 use crate::state::APP_CONFIG;
 
 pub struct ConfigService {}
@@ -34,8 +35,8 @@ impl ConfigService {
             }
         })?;
 
-        let mut config = APP_CONFIG.write().unwrap();
-        *config = new_config;
+        *APP_CONFIG.write().unwrap() = new_config;
+        FILE_INDEX.configure();
 
         Ok(())
     }
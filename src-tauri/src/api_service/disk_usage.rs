@@ -0,0 +1,664 @@
+/**
+ * Disk Usage Analyzer
+ *
+ * Hierarchical size breakdown of a folder on any backend (real FS, vault, temporary
+ * session): a size tree with per-directory aggregates for treemap / sunburst views,
+ * the largest files and a breakdown by extension and file type.
+ *
+ * Scanned directories are cached in memory together with their mtime. A rescan reuses
+ * a cached subtree when neither its directory nor any directory below it changed,
+ * which only costs a stat per directory. Files rewritten in place do not touch the
+ * directory mtime, so cached entries also expire after `CACHE_TTL_SECS`; `refresh`
+ * bypasses the cache.
+ */
+
+use super::files::FileSystemInstance;
+use super::{ApiError, ApiResult, API};
+use crate::core::{guess_mime_type, FileSystem, ListOptions};
+use crate::progress::ProgressTracker;
+use crate::state::APP_CONFIG;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Предел `top_files` (столько файлов на папку хранит кеш)
+pub const MAX_TOP_FILES: usize = 100;
+const DEFAULT_TOP_FILES: usize = 20;
+const DEFAULT_TREE_DEPTH: usize = 3;
+const DEFAULT_MAX_CHILDREN: usize = 50;
+/// Время жизни записи кеша в секундах
+const CACHE_TTL_SECS: u64 = 600;
+
+const ARCHIVE_SUBTYPES: &[&str] = &[
+    "zip",
+    "gzip",
+    "x-tar",
+    "x-bzip2",
+    "x-xz",
+    "zstd",
+    "x-7z-compressed",
+    "vnd.rar",
+    "x-rar-compressed",
+    "java-archive",
+];
+
+pub static DISK_USAGE_CACHE: Lazy<DiskUsageCache> = Lazy::new(DiskUsageCache::new);
+
+/// Параметры анализа занятого места
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DiskUsageRequest {
+    pub root: String,
+    #[serde(default)]
+    pub panel_fs: Option<String>,
+    /// Включать скрытые файлы; `None` — по настройке `show_hidden_files`
+    #[serde(default)]
+    pub include_hidden: Option<bool>,
+    /// Сколько самых больших файлов вернуть (по умолчанию 20, не больше 100)
+    #[serde(default)]
+    pub top_files: Option<usize>,
+    /// Глубина возвращаемого дерева (по умолчанию 3)
+    #[serde(default)]
+    pub tree_depth: Option<usize>,
+    /// Детей на узел; остальные суммируются в `otherBytes` (по умолчанию 50)
+    #[serde(default)]
+    pub max_children: Option<usize>,
+    /// Сканировать заново, не используя кеш
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Узел дерева размеров
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DiskUsageNode {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    /// Суммарный размер (для папки — всего поддерева)
+    pub bytes: u64,
+    /// Файлов в поддереве
+    pub files: u64,
+    /// Папок в поддереве (без самой папки)
+    pub dirs: u64,
+    pub modified: Option<u64>,
+    /// Самые большие дети по убыванию размера
+    pub children: Vec<DiskUsageNode>,
+    /// Размер и количество непосредственных детей, не попавших в `children`
+    pub other_bytes: u64,
+    pub other_count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct LargestFile {
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<u64>,
+}
+
+/// Сколько занимают файлы одного расширения или типа
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct TypeUsage {
+    /// Расширение в нижнем регистре (пустое — без расширения) или тип:
+    /// image, video, audio, text, document, archive, font, other
+    pub key: String,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DiskUsageReport {
+    pub tree: DiskUsageNode,
+    pub largest_files: Vec<LargestFile>,
+    /// По убыванию размера
+    pub by_extension: Vec<TypeUsage>,
+    pub by_category: Vec<TypeUsage>,
+    /// Папок, взятых из кеша без повторного чтения
+    pub cached_dirs: u64,
+    pub cancelled: bool,
+}
+
+/// Просканированная папка
+#[derive(Debug, Default)]
+struct DirNode {
+    path: String,
+    name: String,
+    modified: Option<u64>,
+    scanned_at: u64,
+    /// Итоги поддерева
+    bytes: u64,
+    files: u64,
+    dirs: u64,
+    /// Вложенные папки по убыванию размера (симлинки не обходятся)
+    subdirs: Vec<Arc<DirNode>>,
+    /// Файлов непосредственно в папке
+    direct_files: u64,
+    /// Самые большие из них по убыванию: (имя, размер, mtime)
+    top_files: Vec<(String, u64, Option<u64>)>,
+    /// Расширение -> (байт, файлов) для файлов непосредственно в папке
+    extensions: HashMap<String, (u64, u64)>,
+}
+
+/// In-memory cache of scanned directories
+pub struct DiskUsageCache {
+    dirs: Mutex<HashMap<String, Arc<DirNode>>>,
+}
+
+impl DiskUsageCache {
+    fn new() -> Self {
+        Self {
+            dirs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn key(backend: &str, include_hidden: bool, path: &str) -> String {
+        format!("{}|{}|{}", backend, include_hidden as u8, path)
+    }
+
+    fn get(&self, key: &str) -> Option<Arc<DirNode>> {
+        self.dirs.lock().unwrap().get(key).cloned()
+    }
+
+    fn insert(&self, key: String, node: Arc<DirNode>) {
+        self.dirs.lock().unwrap().insert(key, node);
+    }
+
+    fn prune(&self, now: u64) {
+        self.dirs
+            .lock()
+            .unwrap()
+            .retain(|_, node| now.saturating_sub(node.scanned_at) < CACHE_TTL_SECS);
+    }
+
+    /// Forget cached trees of one backend (`"vault"` when the vault is locked)
+    pub fn forget_backend(&self, backend: &str) {
+        let prefix = format!("{}|", backend);
+        self.dirs.lock().unwrap().retain(|key, _| !key.starts_with(&prefix));
+    }
+}
+
+/// Analyze `request.root` on the backend of `request.panel_fs`
+pub fn analyze_disk_usage<F>(
+    request: &DiskUsageRequest,
+    tracker: &ProgressTracker,
+    on_progress: F,
+) -> ApiResult<DiskUsageReport>
+where
+    F: FnMut(),
+{
+    let instance = API.files.get_filesystem_by_backend(request.panel_fs.as_deref());
+    let backend = match &instance {
+        FileSystemInstance::Real(_) => "real".to_string(),
+        FileSystemInstance::Virtual(_) => "vault".to_string(),
+        FileSystemInstance::Temporary(_) => format!("temp:{}", request.panel_fs.as_deref().unwrap_or_default()),
+        FileSystemInstance::Sftp(_)
+        | FileSystemInstance::WebDav(_)
+        | FileSystemInstance::S3(_)
+        | FileSystemInstance::Archive(_) => {
+            request.panel_fs.clone().unwrap_or_default()
+        }
+    };
+    analyze(instance.as_trait(), &backend, request, tracker, on_progress)
+}
+
+struct ScanContext<'a> {
+    fs: &'a dyn FileSystem,
+    backend: &'a str,
+    include_hidden: bool,
+    refresh: bool,
+    now: u64,
+    tracker: &'a ProgressTracker,
+    on_progress: &'a mut dyn FnMut(),
+    cached_dirs: u64,
+}
+
+/// `backend` отделяет записи кеша разных файловых систем
+pub(crate) fn analyze<F>(
+    fs: &dyn FileSystem,
+    backend: &str,
+    request: &DiskUsageRequest,
+    tracker: &ProgressTracker,
+    mut on_progress: F,
+) -> ApiResult<DiskUsageReport>
+where
+    F: FnMut(),
+{
+    tracing::info!("Analyzing disk usage of {} ({})", request.root, backend);
+
+    let info = fs.get_file_info(&request.root).map_err(ApiError::from)?;
+    if !info.is_dir {
+        return Err(ApiError::NotADirectory {
+            message: format!("Not a directory: {}", request.root),
+        });
+    }
+
+    let now = now_secs();
+    DISK_USAGE_CACHE.prune(now);
+
+    let mut ctx = ScanContext {
+        fs,
+        backend,
+        include_hidden: request
+            .include_hidden
+            .unwrap_or_else(|| APP_CONFIG.read().unwrap().show_hidden_files),
+        refresh: request.refresh,
+        now,
+        tracker,
+        on_progress: &mut on_progress,
+        cached_dirs: 0,
+    };
+
+    let Some(root) = scan_dir(&mut ctx, &request.root, info.name, info.modified)? else {
+        return Ok(DiskUsageReport {
+            tree: empty_node(&request.root),
+            largest_files: Vec::new(),
+            by_extension: Vec::new(),
+            by_category: Vec::new(),
+            cached_dirs: ctx.cached_dirs,
+            cancelled: true,
+        });
+    };
+
+    let top_files = request.top_files.unwrap_or(DEFAULT_TOP_FILES).min(MAX_TOP_FILES);
+    let mut largest_files = Vec::new();
+    let mut extensions = HashMap::new();
+    collect_totals(&root, top_files, &mut largest_files, &mut extensions);
+    largest_files.sort_by(|a, b| b.size.cmp(&a.size));
+    largest_files.truncate(top_files);
+
+    let mut categories: HashMap<String, (u64, u64)> = HashMap::new();
+    for (extension, (bytes, files)) in &extensions {
+        let entry = categories.entry(category_of(extension).to_string()).or_default();
+        entry.0 += bytes;
+        entry.1 += files;
+    }
+
+    let report = DiskUsageReport {
+        tree: to_report_node(
+            &root,
+            request.tree_depth.unwrap_or(DEFAULT_TREE_DEPTH),
+            request.max_children.unwrap_or(DEFAULT_MAX_CHILDREN),
+        ),
+        largest_files,
+        by_extension: sorted_usage(extensions),
+        by_category: sorted_usage(categories),
+        cached_dirs: ctx.cached_dirs,
+        cancelled: false,
+    };
+
+    tracing::info!(
+        "Disk usage of {}: {} bytes in {} files ({} dirs from cache)",
+        request.root,
+        report.tree.bytes,
+        report.tree.files,
+        report.cached_dirs
+    );
+    Ok(report)
+}
+
+/// Просканировать папку (или взять из кеша); `None`, если операция отменена
+fn scan_dir(ctx: &mut ScanContext, path: &str, name: String, modified: Option<u64>) -> ApiResult<Option<Arc<DirNode>>> {
+    if ctx.tracker.is_cancelled() {
+        return Ok(None);
+    }
+
+    let key = DiskUsageCache::key(ctx.backend, ctx.include_hidden, path);
+    if !ctx.refresh {
+        if let Some(node) = DISK_USAGE_CACHE.get(&key) {
+            let fresh = ctx.now.saturating_sub(node.scanned_at) < CACHE_TTL_SECS;
+            if fresh && node.modified.is_some() && node.modified == modified && subdirs_unchanged(ctx.fs, &node) {
+                ctx.cached_dirs += 1 + node.dirs;
+                ctx.tracker.add_bytes(node.bytes);
+                (ctx.on_progress)();
+                return Ok(Some(node));
+            }
+        }
+    }
+
+    ctx.tracker.update_current_file(Some(path.to_string()));
+    let options = ListOptions {
+        show_hidden: Some(ctx.include_hidden),
+        ..Default::default()
+    };
+    let entries = ctx.fs.read_directory_with_options(path, &options).map_err(ApiError::from)?.entries;
+
+    let mut node = DirNode {
+        path: path.to_string(),
+        name,
+        modified,
+        scanned_at: ctx.now,
+        ..Default::default()
+    };
+
+    for entry in entries {
+        if ctx.tracker.is_cancelled() {
+            return Ok(None);
+        }
+        ctx.tracker.add_item();
+
+        if entry.is_dir {
+            node.dirs += 1;
+            if entry.is_symlink {
+                continue;
+            }
+            match scan_dir(ctx, &entry.path, entry.name, entry.modified) {
+                Ok(Some(child)) => {
+                    node.bytes += child.bytes;
+                    node.files += child.files;
+                    node.dirs += child.dirs;
+                    node.subdirs.push(child);
+                }
+                Ok(None) => return Ok(None),
+                Err(err) => tracing::debug!("Skipping unreadable directory {}: {}", entry.path, err),
+            }
+            continue;
+        }
+
+        let size = entry.size.unwrap_or(0);
+        node.bytes += size;
+        node.files += 1;
+        node.direct_files += 1;
+        ctx.tracker.add_bytes(size);
+
+        let usage = node.extensions.entry(extension_of(&entry.name)).or_default();
+        usage.0 += size;
+        usage.1 += 1;
+
+        node.top_files.push((entry.name, size, entry.modified));
+        if node.top_files.len() >= MAX_TOP_FILES * 2 {
+            truncate_top_files(&mut node.top_files);
+        }
+    }
+
+    truncate_top_files(&mut node.top_files);
+    node.subdirs.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+
+    let node = Arc::new(node);
+    DISK_USAGE_CACHE.insert(key, node.clone());
+    (ctx.on_progress)();
+    Ok(Some(node))
+}
+
+/// Не изменилась ли ни одна вложенная папка (проверяется только mtime папок)
+fn subdirs_unchanged(fs: &dyn FileSystem, node: &DirNode) -> bool {
+    node.subdirs.iter().all(|sub| {
+        sub.modified.is_some()
+            && fs
+                .get_file_info(&sub.path)
+                .is_ok_and(|info| info.is_dir && info.modified == sub.modified)
+            && subdirs_unchanged(fs, sub)
+    })
+}
+
+fn truncate_top_files(files: &mut Vec<(String, u64, Option<u64>)>) {
+    files.sort_by(|a, b| b.1.cmp(&a.1));
+    files.truncate(MAX_TOP_FILES);
+}
+
+fn collect_totals(
+    node: &DirNode,
+    top_files: usize,
+    largest: &mut Vec<LargestFile>,
+    extensions: &mut HashMap<String, (u64, u64)>,
+) {
+    for (extension, (bytes, files)) in &node.extensions {
+        let entry = extensions.entry(extension.clone()).or_default();
+        entry.0 += bytes;
+        entry.1 += files;
+    }
+
+    for (name, size, modified) in node.top_files.iter().take(top_files) {
+        largest.push(LargestFile {
+            path: join_child(&node.path, name),
+            size: *size,
+            modified: *modified,
+        });
+    }
+    if largest.len() > top_files * 4 {
+        largest.sort_by(|a, b| b.size.cmp(&a.size));
+        largest.truncate(top_files);
+    }
+
+    for sub in &node.subdirs {
+        collect_totals(sub, top_files, largest, extensions);
+    }
+}
+
+fn to_report_node(node: &DirNode, depth: usize, max_children: usize) -> DiskUsageNode {
+    let mut children = Vec::new();
+    if depth > 0 {
+        enum Child<'a> {
+            Dir(&'a DirNode),
+            File(&'a (String, u64, Option<u64>)),
+        }
+
+        // Оба списка уже отсортированы по убыванию; берем лучшие max_children
+        let mut candidates: Vec<(u64, Child)> = node
+            .subdirs
+            .iter()
+            .take(max_children)
+            .map(|sub| (sub.bytes, Child::Dir(sub)))
+            .chain(node.top_files.iter().take(max_children).map(|file| (file.1, Child::File(file))))
+            .collect();
+        candidates.sort_by(|a, b| b.0.cmp(&a.0));
+        candidates.truncate(max_children);
+
+        children = candidates
+            .into_iter()
+            .map(|(_, child)| match child {
+                Child::Dir(sub) => to_report_node(sub, depth - 1, max_children),
+                Child::File((name, size, modified)) => DiskUsageNode {
+                    name: name.clone(),
+                    path: join_child(&node.path, name),
+                    is_dir: false,
+                    bytes: *size,
+                    files: 1,
+                    dirs: 0,
+                    modified: *modified,
+                    children: Vec::new(),
+                    other_bytes: 0,
+                    other_count: 0,
+                },
+            })
+            .collect();
+    }
+
+    let listed_bytes: u64 = children.iter().map(|child| child.bytes).sum();
+    let direct_children = node.subdirs.len() as u64 + node.direct_files;
+    DiskUsageNode {
+        name: node.name.clone(),
+        path: node.path.clone(),
+        is_dir: true,
+        bytes: node.bytes,
+        files: node.files,
+        dirs: node.dirs,
+        modified: node.modified,
+        other_bytes: node.bytes.saturating_sub(listed_bytes),
+        other_count: direct_children.saturating_sub(children.len() as u64),
+        children,
+    }
+}
+
+fn empty_node(path: &str) -> DiskUsageNode {
+    DiskUsageNode {
+        name: String::new(),
+        path: path.to_string(),
+        is_dir: true,
+        bytes: 0,
+        files: 0,
+        dirs: 0,
+        modified: None,
+        children: Vec::new(),
+        other_bytes: 0,
+        other_count: 0,
+    }
+}
+
+fn sorted_usage(map: HashMap<String, (u64, u64)>) -> Vec<TypeUsage> {
+    let mut usage: Vec<TypeUsage> = map
+        .into_iter()
+        .map(|(key, (bytes, files))| TypeUsage { key, bytes, files })
+        .collect();
+    usage.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.key.cmp(&b.key)));
+    usage
+}
+
+/// Расширение в нижнем регистре; у файлов вида ".bashrc" расширения нет
+fn extension_of(name: &str) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => extension.to_lowercase(),
+        _ => String::new(),
+    }
+}
+
+fn category_of(extension: &str) -> &'static str {
+    if extension.is_empty() {
+        return "other";
+    }
+    let Some(mime) = guess_mime_type(&format!("file.{}", extension), false) else {
+        return "other";
+    };
+    let (kind, subtype) = mime.split_once('/').unwrap_or((mime.as_str(), ""));
+    match kind {
+        "image" => "image",
+        "video" => "video",
+        "audio" => "audio",
+        "text" => "text",
+        "font" => "font",
+        "application" if ARCHIVE_SUBTYPES.contains(&subtype) => "archive",
+        "application"
+            if subtype == "pdf"
+                || subtype == "msword"
+                || subtype == "rtf"
+                || subtype.starts_with("vnd.openxmlformats")
+                || subtype.starts_with("vnd.oasis")
+                || subtype.starts_with("vnd.ms-") =>
+        {
+            "document"
+        }
+        "application" if matches!(subtype, "json" | "xml" | "javascript" | "toml" | "x-sh") => "text",
+        _ => "other",
+    }
+}
+
+fn join_child(dir: &str, name: &str) -> String {
+    super::journal::join_path(dir, name)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::temporary_fs::TemporaryFileSystem;
+    use crate::progress::OperationType;
+    use filetime::FileTime;
+    use std::path::Path;
+
+    fn write(root: &Path, path: &str, size: usize) {
+        let full = root.join(path);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::write(&full, vec![b'x'; size]).unwrap();
+    }
+
+    fn request() -> DiskUsageRequest {
+        DiskUsageRequest {
+            root: "/".to_string(),
+            include_hidden: Some(false),
+            top_files: Some(2),
+            ..Default::default()
+        }
+    }
+
+    fn run(fs: &TemporaryFileSystem, backend: &str, request: &DiskUsageRequest) -> DiskUsageReport {
+        let tracker = ProgressTracker::new("disk-usage-test".to_string(), OperationType::DiskUsage, 0, 0);
+        analyze(fs, backend, request, &tracker, || {}).unwrap()
+    }
+
+    #[test]
+    fn test_size_tree_and_breakdown() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "video/movie.mp4", 5_000);
+        write(dir.path(), "video/clip.MP4", 1_000);
+        write(dir.path(), "photos/2024/a.jpg", 300);
+        write(dir.path(), "photos/2024/b.jpg", 200);
+        write(dir.path(), "notes.txt", 10);
+        write(dir.path(), "Makefile", 5);
+
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+        let report = run(&fs, "test-tree", &request());
+
+        assert_eq!(report.tree.bytes, 6_515);
+        assert_eq!(report.tree.files, 6);
+        assert_eq!(report.tree.dirs, 3);
+
+        let children: Vec<(&str, u64)> = report.tree.children.iter().map(|c| (c.name.as_str(), c.bytes)).collect();
+        assert_eq!(children, vec![("video", 6_000), ("photos", 500), ("notes.txt", 10), ("Makefile", 5)]);
+        assert_eq!(report.tree.children[1].children[0].name, "2024");
+
+        let largest: Vec<(&str, u64)> = report.largest_files.iter().map(|f| (f.path.as_str(), f.size)).collect();
+        assert_eq!(largest, vec![("/video/movie.mp4", 5_000), ("/video/clip.MP4", 1_000)]);
+
+        let extensions: Vec<(&str, u64, u64)> = report.by_extension.iter().map(|u| (u.key.as_str(), u.bytes, u.files)).collect();
+        assert_eq!(extensions, vec![("mp4", 6_000, 2), ("jpg", 500, 2), ("txt", 10, 1), ("", 5, 1)]);
+        assert_eq!(report.by_category[0].key, "video");
+        assert_eq!(report.by_category[1].key, "image");
+    }
+
+    #[test]
+    fn test_max_children_folds_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        for (i, size) in [40, 30, 20, 10].iter().enumerate() {
+            write(dir.path(), &format!("f{}.bin", i), *size);
+        }
+
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+        let mut request = request();
+        request.max_children = Some(2);
+        let report = run(&fs, "test-fold", &request);
+
+        assert_eq!(report.tree.children.len(), 2);
+        assert_eq!((report.tree.other_bytes, report.tree.other_count), (30, 2));
+    }
+
+    #[test]
+    fn test_rescan_reuses_unchanged_subtrees() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "a/one.bin", 100);
+        write(dir.path(), "b/two.bin", 200);
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+
+        let first = run(&fs, "test-cache", &request());
+        assert_eq!(first.cached_dirs, 0);
+
+        let second = run(&fs, "test-cache", &request());
+        assert_eq!(second.tree.bytes, 300);
+        assert_eq!(second.cached_dirs, 3);
+
+        // Новый файл меняет mtime папки: пересканируется только она и ее предки
+        write(dir.path(), "b/three.bin", 50);
+        filetime::set_file_mtime(dir.path().join("b"), FileTime::from_unix_time(1_000, 0)).unwrap();
+        let third = run(&fs, "test-cache", &request());
+        assert_eq!(third.tree.bytes, 350);
+        assert_eq!(third.cached_dirs, 1);
+
+        let mut refresh = request();
+        refresh.refresh = true;
+        assert_eq!(run(&fs, "test-cache", &refresh).cached_dirs, 0);
+    }
+}
@@ -0,0 +1,723 @@
+/**
+ * Duplicate Finder
+ *
+ * Finds files with identical content in one or more FileSystem backends, e.g.
+ * "which vault files also exist on disk". Candidates are narrowed down in three
+ * passes: size, SHA-256 of the first 64 KiB, full SHA-256. Only files that share
+ * both size and prefix are read completely.
+ *
+ * Actions on found groups (trash, hardlink, keep newest) run as queued operations
+ * and re-check every file against the scanned hash before touching it.
+ */
+
+use super::files::FileSystemInstance;
+use super::search::{walk_tree, Visit, WalkOptions};
+use super::{ApiError, ApiResult, API};
+use crate::core::{FileSystem, FileSystemEntry, FileSystemError, FileSystemResult};
+use crate::progress::ProgressTracker;
+use crate::queue::{OperationParams, OperationPriority, QueuedOperation, QueuedOperationType, QUEUE_MANAGER};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+/// Сколько байт в начале файла хешируется во втором проходе
+const PARTIAL_HASH_LEN: u64 = 64 * 1024;
+
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Папка, в которой ищутся дубликаты
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DuplicateSource {
+    pub root: String,
+    /// Backend ("real", "virtual", vault window label or temporary session label)
+    #[serde(default)]
+    pub panel_fs: Option<String>,
+}
+
+/// Параметры поиска дубликатов
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DuplicateScanRequest {
+    pub sources: Vec<DuplicateSource>,
+    /// Файлы меньше этого размера не сравниваются (по умолчанию пропускаются только пустые)
+    #[serde(default)]
+    pub min_size: Option<u64>,
+    /// Включать скрытые файлы; `None` — по настройке `show_hidden_files`
+    #[serde(default)]
+    pub include_hidden: Option<bool>,
+    #[serde(default)]
+    pub respect_gitignore: bool,
+    /// Только группы, в которых есть файлы из разных источников
+    #[serde(default)]
+    pub across_sources_only: bool,
+}
+
+/// Файл в группе дубликатов
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DuplicateFile {
+    pub path: String,
+    #[serde(default)]
+    pub panel_fs: Option<String>,
+    /// Индекс источника в запросе
+    #[serde(default)]
+    pub source: usize,
+    pub modified: Option<u64>,
+}
+
+/// Файлы с одинаковым содержимым
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DuplicateGroup {
+    /// SHA-256 содержимого (hex)
+    pub hash: String,
+    pub size: u64,
+    pub files: Vec<DuplicateFile>,
+}
+
+/// Итог поиска дубликатов
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DuplicateReport {
+    /// Группы по убыванию занимаемого копиями места
+    pub groups: Vec<DuplicateGroup>,
+    pub files_scanned: u64,
+    pub files_hashed: u64,
+    pub bytes_hashed: u64,
+    /// Место, которое освободится, если оставить по одному файлу в группе
+    pub wasted_bytes: u64,
+    /// Файлы, которые не удалось прочитать
+    pub unreadable: u64,
+    pub cancelled: bool,
+}
+
+/// Действие над группами дубликатов
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum DuplicateAction {
+    /// Оставить первый файл группы, остальные переместить в корзину
+    Delete,
+    /// Заменить остальные файлы группы жесткими ссылками на первый (только реальная ФС)
+    Hardlink,
+    /// Оставить самый новый файл группы, остальные переместить в корзину
+    KeepNewest,
+}
+
+/// Запрос действия над группами из `DuplicateReport`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DuplicateActionRequest {
+    pub action: DuplicateAction,
+    pub groups: Vec<DuplicateGroup>,
+}
+
+/// Итог действия над группами
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DuplicateActionSummary {
+    /// Файлы, перемещенные в корзину или замененные ссылками
+    pub processed: u64,
+    /// Файлы, которых уже нет
+    pub skipped: u64,
+    pub bytes_freed: u64,
+    pub errors: Vec<String>,
+    pub cancelled: bool,
+}
+
+/// Устройство и inode: жесткие ссылки на один файл не являются копиями
+type FileIdentity = (u64, u64);
+
+struct Candidate {
+    source: usize,
+    path: String,
+    size: u64,
+    modified: Option<u64>,
+    identity: Option<FileIdentity>,
+}
+
+/// Service for finding and resolving duplicate files
+pub struct DuplicateService;
+
+impl DuplicateService {
+    pub fn new() -> Self {
+        tracing::debug!("Initializing DuplicateService");
+        Self
+    }
+
+    /// Find files with identical content under all `request.sources`
+    ///
+    /// Progress (files scanned, bytes hashed) goes to `tracker`; `on_progress` is called
+    /// after each update so the caller can forward it. Cancellation is read from `tracker`.
+    pub fn find<F>(
+        &self,
+        request: &DuplicateScanRequest,
+        tracker: &ProgressTracker,
+        on_progress: F,
+    ) -> ApiResult<DuplicateReport>
+    where
+        F: FnMut(),
+    {
+        tracing::info!("Searching duplicates in {} sources", request.sources.len());
+
+        let instances: Vec<FileSystemInstance> = request
+            .sources
+            .iter()
+            .map(|source| API.files.get_filesystem_by_backend(source.panel_fs.as_deref()))
+            .collect();
+        let filesystems: Vec<&dyn FileSystem> = instances.iter().map(FileSystemInstance::as_trait).collect();
+
+        find_duplicates_in(&filesystems, request, tracker, on_progress)
+    }
+
+    /// Queue an action on found groups; returns the queue operation ID
+    ///
+    /// The action is not retried automatically: the groups are a snapshot of the scan.
+    pub fn enqueue_action(&self, request: DuplicateActionRequest) -> ApiResult<String> {
+        if request.groups.iter().all(|group| group.files.len() < 2) {
+            return Err(ApiError::ValidationError {
+                message: "No duplicate groups specified".to_string(),
+            });
+        }
+
+        let files: usize = request.groups.iter().map(|group| group.files.len()).sum();
+        let mut operation = QueuedOperation::new(
+            QueuedOperationType::Deduplicate,
+            OperationParams::Deduplicate {
+                action: request.action,
+                groups: request.groups,
+            },
+            OperationPriority::Normal,
+        );
+        operation.retry_policy.enabled = false;
+        operation.description = Some(format!("{:?} duplicates ({} files)", request.action, files));
+
+        QUEUE_MANAGER
+            .enqueue(operation)
+            .map_err(|message| ApiError::OperationFailed { message })
+    }
+
+    /// Apply an action to duplicate groups (runs inside the queue executor)
+    ///
+    /// Every file is re-hashed first; files that changed since the scan are left alone.
+    pub fn apply(&self, request: &DuplicateActionRequest, tracker: &ProgressTracker) -> DuplicateActionSummary {
+        let mut summary = DuplicateActionSummary::default();
+
+        for group in request.groups.iter().filter(|group| group.files.len() >= 2) {
+            if tracker.is_cancelled() {
+                summary.cancelled = true;
+                break;
+            }
+
+            let keep = match request.action {
+                DuplicateAction::Delete | DuplicateAction::Hardlink => 0,
+                DuplicateAction::KeepNewest => newest_index(&group.files),
+            };
+            let keeper = &group.files[keep];
+
+            let keeper_identity = match self.verify(keeper, group, tracker) {
+                Ok(Some(info)) => file_identity(&info),
+                Ok(None) => {
+                    summary.errors.push(format!("{}: changed since the scan, group skipped", keeper.path));
+                    continue;
+                }
+                Err(err) => {
+                    summary.errors.push(format!("{}: {}, group skipped", keeper.path, err));
+                    continue;
+                }
+            };
+
+            // Файлы, место которых уже учтено в `bytes_freed`
+            let mut freed: HashSet<FileIdentity> = keeper_identity.into_iter().collect();
+            for (index, file) in group.files.iter().enumerate() {
+                if index == keep {
+                    continue;
+                }
+                if tracker.is_cancelled() {
+                    summary.cancelled = true;
+                    break;
+                }
+                tracker.update_current_file(Some(file.path.clone()));
+
+                let identity = match self.verify(file, group, tracker) {
+                    Ok(Some(info)) => file_identity(&info),
+                    Ok(None) => {
+                        summary.errors.push(format!("{}: changed since the scan", file.path));
+                        continue;
+                    }
+                    Err(ApiError::FileNotFound { .. }) | Err(ApiError::NotFound { .. }) => {
+                        summary.skipped += 1;
+                        continue;
+                    }
+                    Err(err) => {
+                        summary.errors.push(format!("{}: {}", file.path, err));
+                        continue;
+                    }
+                };
+
+                // Уже жесткая ссылка на оставляемый файл: удалять или связывать нечего
+                if identity.is_some() && identity == keeper_identity {
+                    summary.skipped += 1;
+                    continue;
+                }
+
+                let result = match request.action {
+                    DuplicateAction::Delete | DuplicateAction::KeepNewest => {
+                        API.files.delete_item(&file.path, false, file.panel_fs.as_deref())
+                    }
+                    DuplicateAction::Hardlink => link_duplicate(keeper, file),
+                };
+
+                match result {
+                    Ok(()) => {
+                        summary.processed += 1;
+                        if identity.is_none_or(|identity| freed.insert(identity)) {
+                            summary.bytes_freed += group.size;
+                        }
+                        tracker.add_item();
+                    }
+                    Err(err) => summary.errors.push(format!("{}: {}", file.path, err)),
+                }
+            }
+        }
+
+        tracing::info!(
+            "Duplicate action {:?} finished: {} processed, {} skipped, {} errors",
+            request.action,
+            summary.processed,
+            summary.skipped,
+            summary.errors.len()
+        );
+        summary
+    }
+
+    /// Сведения о файле, если он все еще совпадает с хешем группы
+    fn verify(
+        &self,
+        file: &DuplicateFile,
+        group: &DuplicateGroup,
+        tracker: &ProgressTracker,
+    ) -> ApiResult<Option<FileSystemEntry>> {
+        let instance = API.files.get_filesystem_by_backend(file.panel_fs.as_deref());
+        let fs = instance.as_trait();
+
+        let info = fs.get_file_info(&file.path).map_err(ApiError::from)?;
+        if info.size != Some(group.size) {
+            return Ok(None);
+        }
+        let hash = hash_file(fs, &file.path, None, tracker, &mut || {}).map_err(ApiError::from)?;
+        Ok(hash.is_some_and(|hash| hash == group.hash).then_some(info))
+    }
+}
+
+impl Default for DuplicateService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Поиск дубликатов; `filesystems[i]` — backend источника `request.sources[i]`
+pub(crate) fn find_duplicates_in<F>(
+    filesystems: &[&dyn FileSystem],
+    request: &DuplicateScanRequest,
+    tracker: &ProgressTracker,
+    mut on_progress: F,
+) -> ApiResult<DuplicateReport>
+where
+    F: FnMut(),
+{
+    if request.sources.is_empty() {
+        return Err(ApiError::ValidationError {
+            message: "At least one source is required".to_string(),
+        });
+    }
+
+    let mut report = DuplicateReport::default();
+    let min_size = request.min_size.unwrap_or(1);
+    let walk_options = WalkOptions {
+        include_hidden: request.include_hidden,
+        respect_gitignore: request.respect_gitignore,
+        max_depth: None,
+    };
+
+    // 1. Размер
+    let mut seen = HashSet::new();
+    let mut by_size: HashMap<u64, Vec<Candidate>> = HashMap::new();
+    for (source, (spec, fs)) in request.sources.iter().zip(filesystems).enumerate() {
+        let stats = walk_tree(*fs, &spec.root, &walk_options, tracker.cancellation_token(), |entry| {
+            let size = entry.size.unwrap_or(0);
+            // Пересекающиеся корни одного backend не должны давать файл дважды
+            if entry.is_file && !entry.is_symlink && size >= min_size && seen.insert((spec.panel_fs.clone(), entry.path.clone())) {
+                report.files_scanned += 1;
+                tracker.add_item();
+                by_size.entry(size).or_default().push(Candidate {
+                    source,
+                    path: entry.path,
+                    size,
+                    modified: entry.modified,
+                    identity: file_identity(&entry),
+                });
+                on_progress();
+            }
+            Visit::Continue
+        })?;
+
+        if stats.cancelled {
+            report.cancelled = true;
+            return Ok(report);
+        }
+    }
+
+    let sizes: Vec<Vec<Candidate>> = by_size
+        .into_values()
+        .filter(|group| is_candidate_group(group, request.across_sources_only))
+        .collect();
+
+    // 2. Начало файла; для файлов не больше PARTIAL_HASH_LEN это уже полный хеш
+    tracker.set_total_bytes(sizes.iter().flatten().map(|c| c.size.min(PARTIAL_HASH_LEN)).sum());
+
+    let mut by_prefix: HashMap<(u64, String), Vec<Candidate>> = HashMap::new();
+    for candidate in sizes.into_iter().flatten() {
+        let fs = filesystems[candidate.source];
+        let Some(hash) = hash_candidate(fs, &candidate, Some(PARTIAL_HASH_LEN), tracker, &mut on_progress, &mut report) else {
+            if report.cancelled {
+                return Ok(report);
+            }
+            continue;
+        };
+        by_prefix.entry((candidate.size, hash)).or_default().push(candidate);
+    }
+
+    // 3. Полный хеш для файлов, совпавших по началу
+    let mut by_hash: HashMap<(u64, String), Vec<Candidate>> = HashMap::new();
+    let mut to_rehash = Vec::new();
+    for ((size, hash), group) in by_prefix {
+        if !is_candidate_group(&group, request.across_sources_only) {
+            continue;
+        }
+        if size <= PARTIAL_HASH_LEN {
+            by_hash.insert((size, hash), group);
+        } else {
+            to_rehash.extend(group);
+        }
+    }
+
+    tracker.set_total_bytes(tracker.get_current_bytes() + to_rehash.iter().map(|c| c.size).sum::<u64>());
+    for candidate in to_rehash {
+        let fs = filesystems[candidate.source];
+        let Some(hash) = hash_candidate(fs, &candidate, None, tracker, &mut on_progress, &mut report) else {
+            if report.cancelled {
+                return Ok(report);
+            }
+            continue;
+        };
+        by_hash.entry((candidate.size, hash)).or_default().push(candidate);
+    }
+
+    for ((size, hash), group) in by_hash {
+        if !is_candidate_group(&group, request.across_sources_only) {
+            continue;
+        }
+
+        report.wasted_bytes += size * (distinct_copies(&group) - 1);
+        let mut files: Vec<DuplicateFile> = group
+            .into_iter()
+            .map(|candidate| DuplicateFile {
+                path: candidate.path,
+                panel_fs: request.sources[candidate.source].panel_fs.clone(),
+                source: candidate.source,
+                modified: candidate.modified,
+            })
+            .collect();
+        files.sort_by(|a, b| a.source.cmp(&b.source).then_with(|| a.path.cmp(&b.path)));
+        report.groups.push(DuplicateGroup { hash, size, files });
+    }
+
+    report.groups.sort_by(|a, b| {
+        let wasted = |group: &DuplicateGroup| group.size * (group.files.len() as u64 - 1);
+        wasted(b).cmp(&wasted(a)).then_with(|| a.files[0].path.cmp(&b.files[0].path))
+    });
+
+    tracing::info!(
+        "Duplicate search finished: {} groups, {} files scanned, {} bytes hashed",
+        report.groups.len(),
+        report.files_scanned,
+        report.bytes_hashed
+    );
+    Ok(report)
+}
+
+/// Группа может содержать дубликаты
+fn is_candidate_group(group: &[Candidate], across_sources_only: bool) -> bool {
+    distinct_copies(group) >= 2 && (!across_sources_only || group.iter().any(|c| c.source != group[0].source))
+}
+
+/// Количество физических копий: жесткие ссылки на один файл считаются одной
+fn distinct_copies(group: &[Candidate]) -> u64 {
+    let mut identities = HashSet::new();
+    group
+        .iter()
+        .filter(|candidate| candidate.identity.is_none_or(|identity| identities.insert(identity)))
+        .count() as u64
+}
+
+/// Устройство и inode файла, если backend их сообщает
+fn file_identity(entry: &FileSystemEntry) -> Option<FileIdentity> {
+    entry.dev.zip(entry.inode)
+}
+
+/// Хеш кандидата; нечитаемые файлы учитываются в отчете и пропускаются
+fn hash_candidate(
+    fs: &dyn FileSystem,
+    candidate: &Candidate,
+    limit: Option<u64>,
+    tracker: &ProgressTracker,
+    on_progress: &mut dyn FnMut(),
+    report: &mut DuplicateReport,
+) -> Option<String> {
+    tracker.update_current_file(Some(candidate.path.clone()));
+    let before = tracker.get_current_bytes();
+
+    match hash_file(fs, &candidate.path, limit, tracker, on_progress) {
+        Ok(Some(hash)) => {
+            report.files_hashed += 1;
+            report.bytes_hashed += tracker.get_current_bytes() - before;
+            Some(hash)
+        }
+        Ok(None) => {
+            report.cancelled = true;
+            None
+        }
+        Err(err) => {
+            tracing::debug!("Skipping unreadable file {}: {}", candidate.path, err);
+            report.unreadable += 1;
+            None
+        }
+    }
+}
+
+/// SHA-256 первых `limit` байт файла (всего файла, если `limit` не задан) в hex;
+/// `Ok(None)`, если операция отменена
+fn hash_file(
+    fs: &dyn FileSystem,
+    path: &str,
+    limit: Option<u64>,
+    tracker: &ProgressTracker,
+    on_progress: &mut dyn FnMut(),
+) -> FileSystemResult<Option<String>> {
+    let mut reader = fs.open_read(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    let mut remaining = limit.unwrap_or(u64::MAX);
+
+    while remaining > 0 {
+        if tracker.is_cancelled() {
+            return Ok(None);
+        }
+
+        let wanted = remaining.min(buffer.len() as u64) as usize;
+        let read = match reader.read(&mut buffer[..wanted]) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(FileSystemError::from_io(&e, format!("Failed to read {}: {}", path, e))),
+        };
+
+        hasher.update(&buffer[..read]);
+        remaining -= read as u64;
+        tracker.add_bytes(read as u64);
+        on_progress();
+    }
+
+    Ok(Some(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()))
+}
+
+/// Индекс самого нового файла (при равенстве — первого)
+fn newest_index(files: &[DuplicateFile]) -> usize {
+    let mut newest = 0;
+    for (index, file) in files.iter().enumerate() {
+        if file.modified > files[newest].modified {
+            newest = index;
+        }
+    }
+    newest
+}
+
+/// Замена `target` жесткой ссылкой на `keeper`
+fn link_duplicate(keeper: &DuplicateFile, target: &DuplicateFile) -> ApiResult<()> {
+    for file in [keeper, target] {
+        let instance = API.files.get_filesystem_by_backend(file.panel_fs.as_deref());
+        if !matches!(instance, FileSystemInstance::Real(_)) {
+            return Err(ApiError::ValidationError {
+                message: "Hardlinks are only supported on the real file system".to_string(),
+            });
+        }
+    }
+
+    replace_with_hardlink(Path::new(&keeper.path), Path::new(&target.path))
+        .map_err(|err| ApiError::from(FileSystemError::from_io(&err, format!("Failed to link {}: {}", target.path, err))))
+}
+
+/// Атомарная замена: ссылка создается рядом с `target` и переименовывается поверх него
+fn replace_with_hardlink(keeper: &Path, target: &Path) -> std::io::Result<()> {
+    let name = target.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let temp = target.with_file_name(format!(".{}.vfdir-link", name));
+
+    std::fs::hard_link(keeper, &temp)?;
+    std::fs::rename(&temp, target).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::temporary_fs::TemporaryFileSystem;
+    use crate::progress::OperationType;
+
+    fn tracker() -> ProgressTracker {
+        ProgressTracker::new("duplicates-test".to_string(), OperationType::Duplicates, 0, 0)
+    }
+
+    fn source(root: &str) -> DuplicateSource {
+        DuplicateSource {
+            root: root.to_string(),
+            panel_fs: None,
+        }
+    }
+
+    fn group_paths(report: &DuplicateReport) -> Vec<Vec<&str>> {
+        report
+            .groups
+            .iter()
+            .map(|group| group.files.iter().map(|file| file.path.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_groups_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("a/nested")).unwrap();
+        std::fs::write(root.join("a/one.txt"), "same content").unwrap();
+        std::fs::write(root.join("a/nested/two.txt"), "same content").unwrap();
+        // Тот же размер, другое содержимое
+        std::fs::write(root.join("a/three.txt"), "other conten").unwrap();
+        std::fs::write(root.join("a/empty1"), "").unwrap();
+        std::fs::write(root.join("a/empty2"), "").unwrap();
+
+        // Совпадают первые 64 KiB, но не конец
+        let mut big = vec![7u8; PARTIAL_HASH_LEN as usize + 10];
+        std::fs::write(root.join("a/big1.bin"), &big).unwrap();
+        std::fs::write(root.join("a/big2.bin"), &big).unwrap();
+        big[PARTIAL_HASH_LEN as usize + 5] = 8;
+        std::fs::write(root.join("a/big3.bin"), &big).unwrap();
+
+        let fs = TemporaryFileSystem::new(root.to_path_buf());
+        let request = DuplicateScanRequest {
+            sources: vec![source("/a")],
+            include_hidden: Some(true),
+            ..Default::default()
+        };
+        let report = find_duplicates_in(&[&fs], &request, &tracker(), || {}).unwrap();
+
+        assert_eq!(
+            group_paths(&report),
+            vec![vec!["/a/big1.bin", "/a/big2.bin"], vec!["/a/nested/two.txt", "/a/one.txt"]]
+        );
+        assert_eq!(report.files_scanned, 6);
+        assert_eq!(report.wasted_bytes, PARTIAL_HASH_LEN + 10 + 12);
+        assert!(!report.cancelled);
+    }
+
+    #[test]
+    fn test_across_sources_only() {
+        let disk = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        std::fs::write(disk.path().join("x.txt"), "shared").unwrap();
+        std::fs::write(disk.path().join("y.txt"), "local!").unwrap();
+        std::fs::write(disk.path().join("z.txt"), "local!").unwrap();
+        std::fs::write(other.path().join("copy.txt"), "shared").unwrap();
+
+        let first = TemporaryFileSystem::new(disk.path().to_path_buf());
+        let second = TemporaryFileSystem::new(other.path().to_path_buf());
+        let request = DuplicateScanRequest {
+            sources: vec![source("/"), source("/")],
+            across_sources_only: true,
+            ..Default::default()
+        };
+        let report = find_duplicates_in(&[&first, &second], &request, &tracker(), || {}).unwrap();
+
+        assert_eq!(group_paths(&report), vec![vec!["/x.txt", "/copy.txt"]]);
+        assert_eq!(report.groups[0].files[1].source, 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hardlinks_are_not_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "linked").unwrap();
+        std::fs::hard_link(dir.path().join("a.txt"), dir.path().join("b.txt")).unwrap();
+        std::fs::write(dir.path().join("x.txt"), "copied").unwrap();
+        std::fs::hard_link(dir.path().join("x.txt"), dir.path().join("y.txt")).unwrap();
+        std::fs::write(dir.path().join("z.txt"), "copied").unwrap();
+
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+        let request = DuplicateScanRequest {
+            sources: vec![source("/")],
+            ..Default::default()
+        };
+        let report = find_duplicates_in(&[&fs], &request, &tracker(), || {}).unwrap();
+
+        assert_eq!(group_paths(&report), vec![vec!["/x.txt", "/y.txt", "/z.txt"]]);
+        assert_eq!(report.wasted_bytes, 6);
+    }
+
+    #[test]
+    fn test_cancelled_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a"), "1").unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+
+        let tracker = tracker();
+        tracker.cancellation_token().cancel();
+        let request = DuplicateScanRequest {
+            sources: vec![source("/")],
+            ..Default::default()
+        };
+        let report = find_duplicates_in(&[&fs], &request, &tracker, || {}).unwrap();
+        assert!(report.cancelled);
+        assert!(report.groups.is_empty());
+    }
+
+    #[test]
+    fn test_replace_with_hardlink_and_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let keeper = dir.path().join("keep.txt");
+        let target = dir.path().join("dup.txt");
+        std::fs::write(&keeper, "data").unwrap();
+        std::fs::write(&target, "data").unwrap();
+
+        replace_with_hardlink(&keeper, &target).unwrap();
+        std::fs::write(&keeper, "changed").unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "changed");
+
+        let file = |modified| DuplicateFile {
+            path: String::new(),
+            panel_fs: None,
+            source: 0,
+            modified,
+        };
+        assert_eq!(newest_index(&[file(Some(1)), file(Some(3)), file(None), file(Some(3))]), 1);
+    }
+}
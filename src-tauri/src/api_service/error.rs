@@ -6,6 +6,7 @@
 
 use thiserror::Error;
 use serde::{Serialize, Deserialize};
+use crate::core::{ErrorKind, FileSystemError};
 
 /// Main error type for API operations
 #[derive(Error, Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +36,21 @@ pub enum ApiError {
     #[error("Already exists: {resource}")]
     AlreadyExists { resource: String },
 
+    #[error("Not a directory: {message}")]
+    NotADirectory { message: String },
+
+    #[error("Is a directory: {message}")]
+    IsADirectory { message: String },
+
+    #[error("Locked: {message}")]
+    Locked { message: String },
+
+    #[error("Quota exceeded: {message}")]
+    QuotaExceeded { message: String },
+
+    #[error("Unknown host key for {host}: {fingerprint}")]
+    UnknownHostKey { host: String, fingerprint: String },
+
     #[error("Internal error: {message}")]
     Internal { message: String },
 
@@ -57,12 +73,33 @@ impl ApiError {
             ApiError::IoError { .. } => "IO_ERROR",
             ApiError::NotFound { .. } => "NOT_FOUND",
             ApiError::AlreadyExists { .. } => "ALREADY_EXISTS",
+            ApiError::NotADirectory { .. } => "NOT_A_DIRECTORY",
+            ApiError::IsADirectory { .. } => "IS_A_DIRECTORY",
+            ApiError::Locked { .. } => "LOCKED",
+            ApiError::QuotaExceeded { .. } => "QUOTA_EXCEEDED",
+            ApiError::UnknownHostKey { .. } => "UNKNOWN_HOST_KEY",
             ApiError::Internal { .. } => "INTERNAL_ERROR",
             ApiError::System(_) => "SYSTEM_ERROR",
             ApiError::Validation(_) => "VALIDATION_ERROR",
         }
     }
 
+    /// Filesystem error kind behind this error, if it originates from a filesystem backend
+    pub fn kind(&self) -> Option<ErrorKind> {
+        match self {
+            ApiError::FileNotFound { .. } => Some(ErrorKind::NotFound),
+            ApiError::PermissionDenied { .. } => Some(ErrorKind::PermissionDenied),
+            ApiError::AlreadyExists { .. } => Some(ErrorKind::AlreadyExists),
+            ApiError::NotADirectory { .. } => Some(ErrorKind::NotADirectory),
+            ApiError::IsADirectory { .. } => Some(ErrorKind::IsADirectory),
+            ApiError::Locked { .. } => Some(ErrorKind::Locked),
+            ApiError::QuotaExceeded { .. } => Some(ErrorKind::QuotaExceeded),
+            ApiError::ValidationError { .. } => Some(ErrorKind::InvalidInput),
+            ApiError::IoError { .. } => Some(ErrorKind::Io),
+            _ => None,
+        }
+    }
+
     /// Convert to user-friendly message
     pub fn user_message(&self) -> String {
         match self {
@@ -106,6 +143,25 @@ impl From<std::io::Error> for ApiError {
     }
 }
 
+/// Convert from backend errors, one ApiError variant per ErrorKind
+impl From<FileSystemError> for ApiError {
+    fn from(err: FileSystemError) -> Self {
+        let message = err.message;
+
+        match err.kind {
+            ErrorKind::NotFound => ApiError::FileNotFound { path: message },
+            ErrorKind::PermissionDenied => ApiError::PermissionDenied { path: message },
+            ErrorKind::AlreadyExists => ApiError::AlreadyExists { resource: message },
+            ErrorKind::NotADirectory => ApiError::NotADirectory { message },
+            ErrorKind::IsADirectory => ApiError::IsADirectory { message },
+            ErrorKind::Locked => ApiError::Locked { message },
+            ErrorKind::QuotaExceeded => ApiError::QuotaExceeded { message },
+            ErrorKind::InvalidInput => ApiError::ValidationError { message },
+            ErrorKind::Io => ApiError::IoError { message },
+        }
+    }
+}
+
 /// Convert from filesystem errors
 impl From<crate::error::FsError> for ApiError {
     fn from(err: crate::error::FsError) -> Self {
@@ -142,4 +198,32 @@ mod tests {
         let msg = err.user_message();
         assert!(msg.contains("file.txt"));
     }
+
+    #[test]
+    fn test_filesystem_error_kinds_map_to_codes() {
+        let cases = [
+            (ErrorKind::NotFound, "FILE_NOT_FOUND"),
+            (ErrorKind::PermissionDenied, "PERMISSION_DENIED"),
+            (ErrorKind::AlreadyExists, "ALREADY_EXISTS"),
+            (ErrorKind::NotADirectory, "NOT_A_DIRECTORY"),
+            (ErrorKind::IsADirectory, "IS_A_DIRECTORY"),
+            (ErrorKind::Locked, "LOCKED"),
+            (ErrorKind::QuotaExceeded, "QUOTA_EXCEEDED"),
+            (ErrorKind::InvalidInput, "VALIDATION_ERROR"),
+            (ErrorKind::Io, "IO_ERROR"),
+        ];
+
+        for (kind, code) in cases {
+            let err = ApiError::from(FileSystemError::with_kind(kind, "boom"));
+            assert_eq!(err.code(), code);
+            assert_eq!(err.kind(), Some(kind));
+        }
+    }
+
+    #[test]
+    fn test_io_error_kind_is_preserved() {
+        let io = std::io::Error::new(std::io::ErrorKind::AlreadyExists, "exists");
+        let err = FileSystemError::from(io);
+        assert_eq!(err.kind, ErrorKind::AlreadyExists);
+    }
 }
@@ -5,11 +5,23 @@
  */
 
 use super::{ApiResult, ApiError};
-use super::models::FileSystemEntry;
+use super::models::{FileSystemEntry, TrashEntry};
+use super::journal::{
+    join_path, split_path, JournalEntry, JournalHistory, JournalStep, OperationKind, TrashedItem,
+    OPERATION_JOURNAL,
+};
 use crate::api::{RealFileSystem, virtual_fs::VirtualFileSystem, temporary_fs::TemporaryFileSystem};
+use crate::api::sftp_fs::SftpFileSystem;
+use crate::api::webdav_fs::WebDavFileSystem;
+use crate::api::s3_fs::S3FileSystem;
+use crate::api::archive_fs::{ArchiveFileSystem, ArchiveSource};
+use crate::api::trash;
+use crate::api::watcher::{WatchBackend, WATCHER};
+use crate::archives::ArchiveFormat;
 use crate::config::FileSystemBackend;
-use crate::core::FileSystem;
+use crate::core::{DirectoryPage, FileReader, FileSystem, FileWriter, ListOptions};
 use crate::state::APP_CONFIG;
+use crate::api_service::remote::{s3_filesystem, sftp_filesystem, webdav_filesystem, S3_PREFIX, SFTP_PREFIX, WEBDAV_PREFIX};
 use crate::api_service::vault::VAULT_FS;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -22,19 +34,68 @@ pub static TEMP_FS_SESSIONS: Lazy<Arc<Mutex<HashMap<String, TemporaryFileSystem>
     Arc::new(Mutex::new(HashMap::new()))
 });
 
+/// Префикс `panel_fs` для архивов, открытых как панель
+pub const ARCHIVE_PREFIX: &str = "archive:";
+
+/// Global storage for archives opened as read-only filesystems
+pub static ARCHIVE_FS_SESSIONS: Lazy<Arc<Mutex<HashMap<String, ArchiveFileSystem>>>> = Lazy::new(|| {
+    Arc::new(Mutex::new(HashMap::new()))
+});
+
+/// Сессия архива по метке `archive:<id>`; закрытая сессия возвращает ошибку на любую операцию
+pub(crate) fn archive_filesystem(label: &str) -> ArchiveFileSystem {
+    ARCHIVE_FS_SESSIONS
+        .lock()
+        .unwrap()
+        .get(label)
+        .cloned()
+        .unwrap_or_else(|| ArchiveFileSystem::closed(label))
+}
+
 /// Enum для хранения разных типов файловых систем
-enum FileSystemInstance {
+pub(crate) enum FileSystemInstance {
     Real(RealFileSystem),
     Virtual(VirtualFileSystem),
     Temporary(TemporaryFileSystem),
+    Sftp(SftpFileSystem),
+    WebDav(WebDavFileSystem),
+    S3(S3FileSystem),
+    Archive(ArchiveFileSystem),
 }
 
 impl FileSystemInstance {
-    fn as_trait(&self) -> &dyn FileSystem {
+    pub(crate) fn as_trait(&self) -> &dyn FileSystem {
         match self {
             FileSystemInstance::Real(fs) => fs,
             FileSystemInstance::Virtual(fs) => fs,
             FileSystemInstance::Temporary(fs) => fs,
+            FileSystemInstance::Sftp(fs) => fs,
+            FileSystemInstance::WebDav(fs) => fs,
+            FileSystemInstance::S3(fs) => fs,
+            FileSystemInstance::Archive(fs) => fs,
+        }
+    }
+
+    /// Метка сессии для наблюдателя: у временных и удаленных ФС пути разных сессий не связаны
+    fn watch_session(&self) -> Option<&str> {
+        match self {
+            FileSystemInstance::Temporary(fs) => fs.label(),
+            FileSystemInstance::Sftp(fs) => Some(fs.label()),
+            FileSystemInstance::WebDav(fs) => Some(fs.label()),
+            FileSystemInstance::S3(fs) => Some(fs.label()),
+            FileSystemInstance::Real(_) | FileSystemInstance::Virtual(_) | FileSystemInstance::Archive(_) => None,
+        }
+    }
+
+    fn watch_backend(&self) -> WatchBackend {
+        match self {
+            FileSystemInstance::Real(_) => WatchBackend::Real,
+            FileSystemInstance::Virtual(_) => WatchBackend::Virtual,
+            FileSystemInstance::Temporary(_) => WatchBackend::Temporary,
+            FileSystemInstance::Sftp(_) | FileSystemInstance::WebDav(_) | FileSystemInstance::S3(_) => {
+                WatchBackend::Remote
+            }
+            FileSystemInstance::Archive(_) => WatchBackend::Archive,
         }
     }
 }
@@ -55,6 +116,18 @@ pub struct BatchCreateError {
     pub error: String,
 }
 
+/// Архив, открытый как файловая система
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSession {
+    /// Значение `panel_fs` для панели внутри архива
+    pub panel_fs: String,
+    pub archive_path: String,
+    /// Бэкенд, на котором лежит сам архив
+    pub source_fs: Option<String>,
+    pub name: String,
+}
+
 /// Service for file system operations
 ///
 /// Thread-safe and can be shared across async contexts
@@ -71,7 +144,7 @@ impl FileService {
     ///
     /// # Arguments
     /// * `backend` - Optional backend type ("real" or "virtual" or a window label). If None, uses global config.
-    fn get_filesystem_by_backend(&self, backend: Option<&str>) -> FileSystemInstance {
+    pub(crate) fn get_filesystem_by_backend(&self, backend: Option<&str>) -> FileSystemInstance {
         // Check if backend is a window label in VAULT_FS_SESSIONS
         if let Some(label) = backend {
             let sessions = crate::api_service::vault::VAULT_FS_SESSIONS.lock().unwrap();
@@ -90,6 +163,24 @@ impl FileService {
             }
         }
 
+        // Remote and archive sessions never fall back to a local backend, even when closed
+        if let Some(label) = backend.filter(|label| label.starts_with(SFTP_PREFIX)) {
+            tracing::debug!("Using SFTP session for label: {}", label);
+            return FileSystemInstance::Sftp(sftp_filesystem(label));
+        }
+        if let Some(label) = backend.filter(|label| label.starts_with(WEBDAV_PREFIX)) {
+            tracing::debug!("Using WebDAV session for label: {}", label);
+            return FileSystemInstance::WebDav(webdav_filesystem(label));
+        }
+        if let Some(label) = backend.filter(|label| label.starts_with(S3_PREFIX)) {
+            tracing::debug!("Using S3 session for label: {}", label);
+            return FileSystemInstance::S3(s3_filesystem(label));
+        }
+        if let Some(label) = backend.filter(|label| label.starts_with(ARCHIVE_PREFIX)) {
+            tracing::debug!("Using archive session for label: {}", label);
+            return FileSystemInstance::Archive(archive_filesystem(label));
+        }
+
         let backend_enum = match backend {
             Some("real") => FileSystemBackend::Real,
             Some("virtual") => FileSystemBackend::Virtual,
@@ -132,25 +223,57 @@ impl FileService {
         }
     }
 
+    /// Journal key for undo/redo history: the session label or the resolved backend name
+    fn journal_key(&self, panel_fs: Option<&str>) -> String {
+        match panel_fs {
+            Some(label) => label.to_string(),
+            None => match APP_CONFIG.read().unwrap().filesystem_backend {
+                FileSystemBackend::Real => "real".to_string(),
+                FileSystemBackend::Virtual => "virtual".to_string(),
+            },
+        }
+    }
+
     /// List directory contents
     ///
     /// # Arguments
     /// * `path` - Directory path to list
+    /// * `show_hidden` - Include hidden files; `None` uses `AppConfig.show_hidden_files`
     /// * `panel_fs` - Optional filesystem backend ("real" or "virtual")
     ///
     /// # Returns
     /// Vector of FileSystemEntry or error if directory cannot be read
-    pub fn list_directory(&self, path: &str, panel_fs: Option<&str>) -> ApiResult<Vec<FileSystemEntry>> {
+    pub fn list_directory(&self, path: &str, show_hidden: Option<bool>, panel_fs: Option<&str>) -> ApiResult<Vec<FileSystemEntry>> {
+        let options = ListOptions {
+            show_hidden,
+            ..Default::default()
+        };
+
+        self.list_directory_page(path, options, panel_fs).map(|page| page.entries)
+    }
+
+    /// List one page of directory contents with sorting and filtering
+    ///
+    /// `options.show_hidden == None` uses `AppConfig.show_hidden_files`.
+    /// Pass `next_cursor` of the returned page as `options.cursor` to get the next page.
+    pub fn list_directory_page(
+        &self,
+        path: &str,
+        mut options: ListOptions,
+        panel_fs: Option<&str>,
+    ) -> ApiResult<DirectoryPage> {
         tracing::debug!("Listing directory: {} with backend: {:?}", path, panel_fs);
 
+        if options.show_hidden.is_none() {
+            options.show_hidden = Some(APP_CONFIG.read().unwrap().show_hidden_files);
+        }
+
         self.get_filesystem_by_backend(panel_fs)
             .as_trait()
-            .read_directory(path)
+            .read_directory_with_options(path, &options)
             .map_err(|err| {
                 tracing::error!("Failed to list directory '{}': {}", path, err.message);
-                ApiError::OperationFailed {
-                    message: err.message,
-                }
+                ApiError::from(err)
             })
     }
 
@@ -159,10 +282,8 @@ impl FileService {
         tracing::debug!("Getting file info: {} with backend: {:?}", path, panel_fs);
 
         self.get_filesystem_by_backend(panel_fs).as_trait().get_file_info(path).map_err(|err| {
-            tracing::warn!("File not found: {}", path);
-            ApiError::FileNotFound {
-                path: path.to_string(),
-            }
+            tracing::warn!("Failed to get file info '{}': {}", path, err.message);
+            ApiError::from(err)
         })
     }
 
@@ -190,10 +311,16 @@ impl FileService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().create_folder(path, name).map_err(|err| {
             tracing::error!("Failed to create folder: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
-        })
+            ApiError::from(err)
+        })?;
+
+        OPERATION_JOURNAL.record(
+            &self.journal_key(panel_fs),
+            OperationKind::CreateFolder,
+            format!("Create folder '{}'", name),
+            JournalStep::TrashCreated(vec![join_path(path, name)]),
+        );
+        Ok(())
     }
 
     /// Create new file with optional content
@@ -221,10 +348,16 @@ impl FileService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().create_file(path, name, content).map_err(|err| {
             tracing::error!("Failed to create file: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
-        })
+            ApiError::from(err)
+        })?;
+
+        OPERATION_JOURNAL.record(
+            &self.journal_key(panel_fs),
+            OperationKind::CreateFile,
+            format!("Create file '{}'", name),
+            JournalStep::TrashCreated(vec![join_path(path, name)]),
+        );
+        Ok(())
     }
 
     /// Batch create multiple files
@@ -252,9 +385,7 @@ impl FileService {
             .create_files_batch(path, files)
             .map_err(|err| {
                 tracing::error!("Batch create failed: {}", err.message);
-                ApiError::OperationFailed {
-                    message: err.message,
-                }
+                ApiError::from(err)
             })?;
 
         let mut created = Vec::new();
@@ -270,6 +401,15 @@ impl FileService {
             }
         }
 
+        if !created.is_empty() {
+            OPERATION_JOURNAL.record(
+                &self.journal_key(panel_fs),
+                OperationKind::CreateFile,
+                format!("Create {} files", created.len()),
+                JournalStep::TrashCreated(created.iter().map(|name| join_path(path, name)).collect()),
+            );
+        }
+
         Ok(BatchCreateResult { created, failed })
     }
 
@@ -289,12 +429,29 @@ impl FileService {
             });
         }
 
-        self.get_filesystem_by_backend(panel_fs).as_trait().copy_items(sources, destination).map_err(|err| {
+        let instance = self.get_filesystem_by_backend(panel_fs);
+
+        // Only items that did not exist before can be undone by trashing them
+        let new_targets: Vec<String> = sources
+            .iter()
+            .map(|source| join_path(destination, &split_path(source).1))
+            .filter(|target| instance.as_trait().get_file_info(target).is_err())
+            .collect();
+
+        instance.as_trait().copy_items(sources, destination).map_err(|err| {
             tracing::error!("Copy operation failed: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
-        })
+            ApiError::from(err)
+        })?;
+
+        if !new_targets.is_empty() {
+            OPERATION_JOURNAL.record(
+                &self.journal_key(panel_fs),
+                OperationKind::Copy,
+                format!("Copy {} items to '{}'", sources.len(), destination),
+                JournalStep::TrashCreated(new_targets),
+            );
+        }
+        Ok(())
     }
 
     /// Move files/folders to destination
@@ -314,10 +471,21 @@ impl FileService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().move_items(sources, destination).map_err(|err| {
             tracing::error!("Move operation failed: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
-        })
+            ApiError::from(err)
+        })?;
+
+        OPERATION_JOURNAL.record(
+            &self.journal_key(panel_fs),
+            OperationKind::Move,
+            format!("Move {} items to '{}'", sources.len(), destination),
+            JournalStep::Relocate(
+                sources
+                    .iter()
+                    .map(|source| (join_path(destination, &split_path(source).1), source.clone()))
+                    .collect(),
+            ),
+        );
+        Ok(())
     }
 
     /// Rename file or folder
@@ -332,29 +500,32 @@ impl FileService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().rename_item(old_path, new_name).map_err(|err| {
             tracing::error!("Rename failed: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
-        })
+            ApiError::from(err)
+        })?;
+
+        let (parent, old_name) = split_path(old_path);
+        OPERATION_JOURNAL.record(
+            &self.journal_key(panel_fs),
+            OperationKind::Rename,
+            format!("Rename '{}' to '{}'", old_name, new_name),
+            JournalStep::Relocate(vec![(join_path(&parent, new_name), old_path.to_string())]),
+        );
+        Ok(())
     }
 
     /// Delete single file or folder
-    pub fn delete_item(&self, path: &str, panel_fs: Option<&str>) -> ApiResult<()> {
-        tracing::info!("Deleting '{}' with backend: {:?}", path, panel_fs);
-
-        self.get_filesystem_by_backend(panel_fs).as_trait().delete_item(path).map_err(|err| {
-            tracing::error!("Delete failed: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
-        })
+    ///
+    /// Moves the item to the backend's trash unless `permanent` is set
+    pub fn delete_item(&self, path: &str, permanent: bool, panel_fs: Option<&str>) -> ApiResult<()> {
+        self.delete_items(&[path.to_string()], permanent, panel_fs)
     }
 
     /// Delete multiple files/folders
     ///
-    /// Stops on first error encountered
-    pub fn delete_items(&self, paths: &[String], panel_fs: Option<&str>) -> ApiResult<()> {
-        tracing::info!("Deleting {} items with backend: {:?}", paths.len(), panel_fs);
+    /// Stops on first error encountered. Items moved to trash are journaled for undo,
+    /// permanent deletion is not.
+    pub fn delete_items(&self, paths: &[String], permanent: bool, panel_fs: Option<&str>) -> ApiResult<()> {
+        tracing::info!("Deleting {} items (permanent: {}) with backend: {:?}", paths.len(), permanent, panel_fs);
 
         if paths.is_empty() {
             return Err(ApiError::ValidationError {
@@ -362,11 +533,144 @@ impl FileService {
             });
         }
 
+        let instance = self.get_filesystem_by_backend(panel_fs);
+
+        if permanent {
+            for path in paths {
+                instance.as_trait().delete_item(path).map_err(|err| {
+                    tracing::error!("Delete failed: {}", err.message);
+                    ApiError::from(err)
+                })?;
+            }
+            return Ok(());
+        }
+
+        let mut trashed = Vec::new();
+        let mut result = Ok(());
         for path in paths {
-            self.delete_item(path, panel_fs)?;
+            match instance.as_trait().trash_item(path) {
+                Ok(entry) => trashed.push(TrashedItem {
+                    path: path.clone(),
+                    trash_id: entry.id,
+                }),
+                Err(err) => {
+                    tracing::error!("Delete failed: {}", err.message);
+                    result = Err(ApiError::from(err));
+                    break;
+                }
+            }
         }
 
-        Ok(())
+        // Already trashed items stay undoable even if a later one failed
+        if !trashed.is_empty() {
+            let description = match trashed.as_slice() {
+                [item] => format!("Delete '{}'", split_path(&item.path).1),
+                items => format!("Delete {} items", items.len()),
+            };
+            OPERATION_JOURNAL.record(
+                &self.journal_key(panel_fs),
+                OperationKind::Delete,
+                description,
+                JournalStep::RestoreTrashed(trashed),
+            );
+        }
+
+        result
+    }
+
+    /// Revert the most recent journaled operation
+    pub fn undo_last(&self, panel_fs: Option<&str>) -> ApiResult<JournalEntry> {
+        tracing::info!("Undoing last operation with backend: {:?}", panel_fs);
+
+        let instance = self.get_filesystem_by_backend(panel_fs);
+        OPERATION_JOURNAL
+            .undo_last(&self.journal_key(panel_fs), instance.as_trait())
+            .map_err(|err| {
+                tracing::error!("Undo failed: {}", err.message);
+                ApiError::from(err)
+            })?
+            .ok_or_else(|| ApiError::NotFound {
+                resource: "operation to undo".to_string(),
+            })
+    }
+
+    /// Re-apply the most recently undone operation
+    pub fn redo(&self, panel_fs: Option<&str>) -> ApiResult<JournalEntry> {
+        tracing::info!("Redoing operation with backend: {:?}", panel_fs);
+
+        let instance = self.get_filesystem_by_backend(panel_fs);
+        OPERATION_JOURNAL
+            .redo(&self.journal_key(panel_fs), instance.as_trait())
+            .map_err(|err| {
+                tracing::error!("Redo failed: {}", err.message);
+                ApiError::from(err)
+            })?
+            .ok_or_else(|| ApiError::NotFound {
+                resource: "operation to redo".to_string(),
+            })
+    }
+
+    /// Undo/redo history, most recent first
+    pub fn operation_history(&self, panel_fs: Option<&str>) -> JournalHistory {
+        OPERATION_JOURNAL.history(&self.journal_key(panel_fs))
+    }
+
+    /// List trash contents, most recently deleted first
+    pub fn list_trash(&self, panel_fs: Option<&str>) -> ApiResult<Vec<TrashEntry>> {
+        tracing::debug!("Listing trash with backend: {:?}", panel_fs);
+
+        self.get_filesystem_by_backend(panel_fs).as_trait().list_trash().map_err(|err| {
+            tracing::error!("Failed to list trash: {}", err.message);
+            ApiError::from(err)
+        })
+    }
+
+    /// Restore items from trash to their original locations
+    ///
+    /// Stops on first error encountered
+    pub fn restore_from_trash(&self, ids: &[String], panel_fs: Option<&str>) -> ApiResult<Vec<TrashEntry>> {
+        tracing::info!("Restoring {} items from trash with backend: {:?}", ids.len(), panel_fs);
+
+        if ids.is_empty() {
+            return Err(ApiError::ValidationError {
+                message: "No trash items specified for restore".to_string(),
+            });
+        }
+
+        let instance = self.get_filesystem_by_backend(panel_fs);
+        ids.iter()
+            .map(|id| {
+                instance.as_trait().restore_from_trash(id).map_err(|err| {
+                    tracing::error!("Restore failed: {}", err.message);
+                    ApiError::from(err)
+                })
+            })
+            .collect()
+    }
+
+    /// Permanently delete everything in trash
+    ///
+    /// Returns number of purged items
+    pub fn empty_trash(&self, panel_fs: Option<&str>) -> ApiResult<usize> {
+        tracing::info!("Emptying trash with backend: {:?}", panel_fs);
+
+        self.get_filesystem_by_backend(panel_fs).as_trait().empty_trash().map_err(|err| {
+            tracing::error!("Failed to empty trash: {}", err.message);
+            ApiError::from(err)
+        })
+    }
+
+    /// Permanently delete trash items deleted more than `max_age_secs` seconds ago
+    ///
+    /// Returns number of purged items
+    pub fn purge_trash_older_than(&self, max_age_secs: u64, panel_fs: Option<&str>) -> ApiResult<usize> {
+        tracing::info!("Purging trash items older than {}s with backend: {:?}", max_age_secs, panel_fs);
+
+        let cutoff = trash::now_secs().saturating_sub(max_age_secs);
+        self.get_filesystem_by_backend(panel_fs).as_trait().purge_trash_older_than(cutoff).map_err(|err| {
+            tracing::error!("Failed to purge trash: {}", err.message);
+            ApiError::from(err)
+        })
     }
 
     /// Read file content as string
@@ -380,9 +684,7 @@ impl FileService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().read_file_content(path, max_size).map_err(|err| {
             tracing::error!("Failed to read file: {}", err.message);
-            ApiError::FileNotFound {
-                path: path.to_string(),
-            }
+            ApiError::from(err)
         })
     }
 
@@ -396,9 +698,7 @@ impl FileService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().read_file_bytes(path).map_err(|err| {
             tracing::error!("Failed to read file bytes: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
+            ApiError::from(err)
         })
     }
 
@@ -420,9 +720,7 @@ impl FileService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().write_file_content(path, content).map_err(|err| {
             tracing::error!("Failed to write file: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
+            ApiError::from(err)
         })
     }
 
@@ -444,21 +742,87 @@ impl FileService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().write_file_bytes(path, content).map_err(|err| {
             tracing::error!("Failed to write file bytes: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
+            ApiError::from(err)
+        })
+    }
+
+    /// Open file for streaming read
+    ///
+    /// # Arguments
+    /// * `path` - File path to read
+    /// * `panel_fs` - Optional filesystem backend ("real" or "virtual")
+    pub fn open_read(&self, path: &str, panel_fs: Option<&str>) -> ApiResult<Box<dyn FileReader>> {
+        tracing::debug!("Opening file for reading: {} with backend: {:?}", path, panel_fs);
+
+        self.get_filesystem_by_backend(panel_fs).as_trait().open_read(path).map_err(|err| {
+            tracing::error!("Failed to open file for reading: {}", err.message);
+            ApiError::from(err)
+        })
+    }
+
+    /// Open file for streaming write
+    ///
+    /// Data is committed only after `FileWriter::finish` is called.
+    ///
+    /// # Arguments
+    /// * `path` - File path to write
+    /// * `panel_fs` - Optional filesystem backend ("real" or "virtual")
+    pub fn open_write(&self, path: &str, panel_fs: Option<&str>) -> ApiResult<Box<dyn FileWriter>> {
+        tracing::debug!("Opening file for writing: {} with backend: {:?}", path, panel_fs);
+
+        // Validate path
+        if path.is_empty() {
+            return Err(ApiError::ValidationError {
+                message: "File path cannot be empty".to_string(),
+            });
+        }
+
+        self.get_filesystem_by_backend(panel_fs).as_trait().open_write(path).map_err(|err| {
+            tracing::error!("Failed to open file for writing: {}", err.message);
+            ApiError::from(err)
         })
     }
 
+    /// Subscribe to changes in a directory
+    ///
+    /// Changes are delivered through `api::watcher::WATCHER` (WebSocket / Tauri events).
+    ///
+    /// # Arguments
+    /// * `path` - Directory to watch
+    /// * `recursive` - Also report changes in nested directories
+    /// * `panel_fs` - Optional filesystem backend ("real" or "virtual")
+    ///
+    /// # Returns
+    /// Subscription ID for `unwatch_directory`
+    pub fn watch_directory(&self, path: &str, recursive: bool, panel_fs: Option<&str>) -> ApiResult<String> {
+        tracing::debug!("Watching directory: {} (recursive: {}) with backend: {:?}", path, recursive, panel_fs);
+
+        let instance = self.get_filesystem_by_backend(panel_fs);
+        let info = instance.as_trait().get_file_info(path).map_err(ApiError::from)?;
+        if !info.is_dir {
+            return Err(ApiError::NotADirectory {
+                message: path.to_string(),
+            });
+        }
+
+        WATCHER.subscribe(instance.watch_backend(), instance.watch_session(), path, recursive).map_err(|message| {
+            tracing::error!("Failed to watch directory: {}", message);
+            ApiError::OperationFailed { message }
+        })
+    }
+
+    /// Cancel a directory subscription
+    pub fn unwatch_directory(&self, subscription_id: &str) {
+        WATCHER.unsubscribe(subscription_id);
+    }
+
     /// Open file with system default application
     pub fn open_file(&self, path: &str, panel_fs: Option<&str>) -> ApiResult<()> {
         tracing::info!("Opening file: {} with backend: {:?}", path, panel_fs);
 
         self.get_filesystem_by_backend(panel_fs).as_trait().open_file(path).map_err(|err| {
             tracing::error!("Failed to open file: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
+            ApiError::from(err)
         })
     }
 
@@ -468,9 +832,7 @@ impl FileService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().reveal_in_finder(path).map_err(|err| {
             tracing::error!("Failed to reveal: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
+            ApiError::from(err)
         })
     }
 
@@ -487,9 +849,7 @@ impl FileService {
     pub fn get_path_suggestions(&self, partial_path: &str, panel_fs: Option<&str>) -> ApiResult<Vec<String>> {
         self.get_filesystem_by_backend(panel_fs).as_trait()
             .get_path_suggestions(partial_path)
-            .map_err(|err| ApiError::OperationFailed {
-                message: err.message,
-            })
+            .map_err(ApiError::from)
     }
 
     /// Copy file with custom name (for conflict resolution)
@@ -538,14 +898,10 @@ impl FileService {
         if is_real_source != is_real_dest || !is_real_source {
              tracing::info!("Performing cross-filesystem copy with custom name");
              
-             // 1. Read content from source
-             let content = self.read_file_bytes(source_path, source_fs)?;
+             // 1. Open source for streaming read
+             let mut source = self.open_read(source_path, source_fs)?;
 
-             // 2. Create file in destination with new name and content
-             // Construct full path logic is needed if write_file_bytes expects full path
-             // But create_file handles dir + name.
-             // write_file_bytes expects full path.
-             
+             // 2. Stream content into destination with new name
              // Construct path:
              // Note: simplistic join. For proper cross-platform/virtual path join we might need more logic
              // but here we assume simple slash join if not real path manipulation.
@@ -554,9 +910,13 @@ impl FileService {
              } else {
                  format!("{}/{}", destination_dir, new_name)
              };
-             
-             self.write_file_bytes(&dest_path, &content, dest_fs)?;
-             
+
+             let mut destination = self.open_write(&dest_path, dest_fs)?;
+             std::io::copy(&mut source, &mut destination).map_err(|e| ApiError::OperationFailed {
+                 message: format!("Failed to copy file content: {}", e),
+             })?;
+             destination.finish().map_err(ApiError::from)?;
+
              return Ok(())
         }
 
@@ -565,12 +925,65 @@ impl FileService {
             .copy_with_custom_name(source_path, destination_dir, new_name)
             .map_err(|err| {
                 tracing::error!("Failed to copy with custom name: {}", err.message);
-                ApiError::OperationFailed {
-                    message: err.message,
-                }
+                ApiError::from(err)
             })
     }
 
+    /// Open an archive as a read-only filesystem session
+    ///
+    /// Entries are read on demand from the archive file, which must stay in place while
+    /// the session is open.
+    ///
+    /// # Arguments
+    /// * `archive_path` - Path to a zip, tar (optionally gz/xz/zst/bz2 compressed) or 7z archive
+    /// * `panel_fs` - Optional filesystem backend the archive lives on
+    pub fn open_archive(&self, archive_path: &str, panel_fs: Option<&str>) -> ApiResult<ArchiveSession> {
+        tracing::debug!("Opening archive: {} with backend: {:?}", archive_path, panel_fs);
+
+        let name = Path::new(archive_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| archive_path.to_string());
+
+        let path = archive_path.to_string();
+        let source_fs = panel_fs.map(str::to_string);
+        let source: ArchiveSource = {
+            let source_fs = source_fs.clone();
+            Box::new(move || FileService.get_filesystem_by_backend(source_fs.as_deref()).as_trait().open_read(&path))
+        };
+
+        // Сигнатура важнее расширения: переименованный архив тоже открывается
+        let mut probe = source().map_err(ApiError::from)?;
+        let format = ArchiveFormat::identify(&mut probe, archive_path)
+            .map_err(|e| ApiError::OperationFailed {
+                message: format!("Failed to read archive: {}", e),
+            })?
+            .ok_or_else(|| ApiError::ValidationError {
+                message: format!("Unsupported archive format: {}", archive_path),
+            })?;
+        drop(probe);
+
+        let label = format!("{}{}", ARCHIVE_PREFIX, uuid::Uuid::new_v4());
+        let fs = ArchiveFileSystem::open(label.clone(), name.clone(), format, source).map_err(|err| {
+            tracing::error!("Failed to open archive: {}", err.message);
+            ApiError::from(err)
+        })?;
+        ARCHIVE_FS_SESSIONS.lock().unwrap().insert(label.clone(), fs);
+
+        Ok(ArchiveSession {
+            panel_fs: label,
+            archive_path: archive_path.to_string(),
+            source_fs,
+            name,
+        })
+    }
+
+    /// Close an archive session opened by `open_archive`
+    pub fn close_archive(&self, panel_fs: &str) {
+        tracing::debug!("Closing archive session: {}", panel_fs);
+        ARCHIVE_FS_SESSIONS.lock().unwrap().remove(panel_fs);
+    }
+
     /// Check if file exists
     pub fn file_exists(&self, path: &str) -> bool {
         std::path::Path::new(path).exists()
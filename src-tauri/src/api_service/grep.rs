@@ -0,0 +1,346 @@
+/**
+ * Content Search (grep)
+ *
+ * Searches file contents across a directory tree of any FileSystem backend.
+ * Vault files are read decrypted through the backend (`BlobStore::read`), so the
+ * same code covers the real FS, the vault and temporary (stego) sessions.
+ */
+
+use super::search::{walk_tree, Batcher, Visit, WalkOptions};
+use super::{ApiError, ApiResult};
+use crate::core::{FileSystem, FileSystemEntry, ListOptions};
+use crate::progress::CancellationToken;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Файлы больше этого размера пропускаются, если в запросе не указано иное
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Максимум строк контекста до и после совпадения
+const MAX_CONTEXT_LINES: usize = 10;
+
+/// Длинные строки (минифицированный код и т.п.) обрезаются в ответе
+const MAX_LINE_CHARS: usize = 500;
+
+/// Сколько байт в начале файла проверяется на признаки бинарного содержимого
+const BINARY_SNIFF_LEN: usize = 8 * 1024;
+
+/// Параметры поиска по содержимому
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct ContentSearchQuery {
+    /// Директория, с которой начинается обход
+    pub root: String,
+    /// Искомый текст или регулярное выражение
+    pub pattern: String,
+    /// `pattern` — регулярное выражение, а не литерал
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Совпадение только целыми словами
+    #[serde(default)]
+    pub whole_word: bool,
+    /// Glob для имен файлов (`*.rs`); без него просматриваются все файлы
+    #[serde(default)]
+    pub file_glob: Option<String>,
+    /// Строк контекста до и после совпадения (не больше 10)
+    #[serde(default)]
+    pub context_lines: usize,
+    /// Файлы больше этого размера пропускаются (по умолчанию 10 МБ)
+    #[serde(default)]
+    pub max_file_size: Option<u64>,
+    /// Включать скрытые файлы; `None` — по настройке `show_hidden_files`
+    #[serde(default)]
+    pub include_hidden: Option<bool>,
+    #[serde(default)]
+    pub respect_gitignore: bool,
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// Остановить поиск после стольких совпадающих строк
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+/// Совпадение внутри строки (в символах, не в байтах)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct MatchRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Строка файла с совпадением и ее контекст
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct ContentMatch {
+    pub path: String,
+    /// Номер строки (с 1)
+    pub line_number: usize,
+    pub line: String,
+    pub ranges: Vec<MatchRange>,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+/// Итог поиска по содержимому
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct ContentSearchSummary {
+    pub files_searched: u64,
+    pub files_matched: u64,
+    /// Количество совпавших строк
+    pub matches: u64,
+    pub skipped_binary: u64,
+    pub skipped_too_large: u64,
+    pub cancelled: bool,
+    /// Поиск остановлен по `max_results`
+    pub truncated: bool,
+}
+
+fn build_regex(query: &ContentSearchQuery) -> ApiResult<Regex> {
+    if query.pattern.is_empty() {
+        return Err(ApiError::ValidationError {
+            message: "Search pattern must not be empty".to_string(),
+        });
+    }
+
+    let mut pattern = if query.regex {
+        query.pattern.clone()
+    } else {
+        regex::escape(&query.pattern)
+    };
+    if query.whole_word {
+        pattern = format!(r"\b(?:{})\b", pattern);
+    }
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!query.case_sensitive)
+        .build()
+        .map_err(|e| ApiError::ValidationError {
+            message: format!("Invalid regex: {}", e),
+        })
+}
+
+/// Бинарный файл: NUL-байт в начале содержимого
+fn looks_binary(content: &[u8]) -> bool {
+    content[..content.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+fn truncate_line(line: &str) -> String {
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((cut, _)) => format!("{}…", &line[..cut]),
+        None => line.to_string(),
+    }
+}
+
+/// Поиск совпадений в тексте одного файла
+fn search_text(path: &str, text: &str, regex: &Regex, context: usize) -> Vec<ContentMatch> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut matches = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let ranges: Vec<MatchRange> = regex
+            .find_iter(line)
+            .filter(|m| m.start() < m.end())
+            .map(|m| MatchRange {
+                start: line[..m.start()].chars().count(),
+                end: line[..m.end()].chars().count(),
+            })
+            .filter(|range| range.start < MAX_LINE_CHARS)
+            .collect();
+
+        if ranges.is_empty() {
+            continue;
+        }
+
+        let before = index.saturating_sub(context);
+        let after = (index + 1 + context).min(lines.len());
+
+        matches.push(ContentMatch {
+            path: path.to_string(),
+            line_number: index + 1,
+            line: truncate_line(line),
+            ranges,
+            context_before: lines[before..index].iter().map(|l| truncate_line(l)).collect(),
+            context_after: lines[index + 1..after].iter().map(|l| truncate_line(l)).collect(),
+        });
+    }
+
+    matches
+}
+
+/// Поиск по содержимому файлов под `query.root`
+pub(crate) fn grep_filesystem<F>(
+    fs: &dyn FileSystem,
+    query: &ContentSearchQuery,
+    token: &CancellationToken,
+    on_matches: F,
+) -> ApiResult<ContentSearchSummary>
+where
+    F: FnMut(Vec<ContentMatch>),
+{
+    let regex = build_regex(query)?;
+    let file_filter = ListOptions {
+        glob: query.file_glob.clone(),
+        ..Default::default()
+    }
+    .name_filter()
+    .map_err(ApiError::from)?;
+
+    let context = query.context_lines.min(MAX_CONTEXT_LINES);
+    let max_file_size = query.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
+    let walk_options = WalkOptions {
+        include_hidden: query.include_hidden,
+        respect_gitignore: query.respect_gitignore,
+        max_depth: query.max_depth,
+    };
+
+    let mut summary = ContentSearchSummary::default();
+    let mut batcher = Batcher::new(on_matches);
+
+    let stats = walk_tree(fs, &query.root, &walk_options, token, |entry: FileSystemEntry| {
+        if !entry.is_file || !file_filter.matches(&entry.name) {
+            return Visit::Continue;
+        }
+
+        if entry.size.is_some_and(|size| size > max_file_size) {
+            summary.skipped_too_large += 1;
+            return Visit::Continue;
+        }
+
+        let content = match fs.read_file_bytes(&entry.path) {
+            Ok(content) => content,
+            Err(err) => {
+                tracing::debug!("Skipping unreadable file {}: {}", entry.path, err);
+                return Visit::Continue;
+            }
+        };
+
+        if looks_binary(&content) {
+            summary.skipped_binary += 1;
+            return Visit::Continue;
+        }
+
+        summary.files_searched += 1;
+        let text = String::from_utf8_lossy(&content);
+        let mut found = search_text(&entry.path, &text, &regex, context);
+        if found.is_empty() {
+            return Visit::Continue;
+        }
+        summary.files_matched += 1;
+
+        if let Some(max) = query.max_results {
+            let remaining = max.saturating_sub(summary.matches as usize);
+            if found.len() >= remaining {
+                found.truncate(remaining);
+                summary.truncated = true;
+            }
+        }
+
+        summary.matches += found.len() as u64;
+        for item in found {
+            batcher.push(item);
+        }
+
+        if summary.truncated {
+            Visit::Stop
+        } else {
+            Visit::Continue
+        }
+    })?;
+
+    batcher.flush();
+    summary.cancelled = stats.cancelled;
+
+    tracing::info!(
+        "Content search finished: {} matches in {} files, cancelled: {}",
+        summary.matches,
+        summary.files_matched,
+        summary.cancelled
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::temporary_fs::TemporaryFileSystem;
+
+    fn grep(fs: &dyn FileSystem, query: &ContentSearchQuery) -> (Vec<ContentMatch>, ContentSearchSummary) {
+        let mut found = Vec::new();
+        let summary = grep_filesystem(fs, query, &CancellationToken::new(), |batch| found.extend(batch)).unwrap();
+        found.sort_by(|a, b| (&a.path, a.line_number).cmp(&(&b.path, b.line_number)));
+        (found, summary)
+    }
+
+    #[test]
+    fn test_grep_literal_with_context() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "use a;\nfn TODO_fix() {}\n// todo: later\n").unwrap();
+        std::fs::write(dir.path().join("image.bin"), b"todo\0\x01\x02").unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+
+        let query = ContentSearchQuery {
+            root: "/".to_string(),
+            pattern: "todo".to_string(),
+            context_lines: 1,
+            ..Default::default()
+        };
+        let (found, summary) = grep(&fs, &query);
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].path, "/src/lib.rs");
+        assert_eq!(found[0].line_number, 2);
+        assert_eq!(found[0].ranges, vec![MatchRange { start: 3, end: 7 }]);
+        assert_eq!(found[0].context_before, vec!["use a;"]);
+        assert_eq!(found[1].context_after, Vec::<String>::new());
+        assert_eq!(summary.skipped_binary, 1);
+        assert_eq!(summary.files_matched, 1);
+    }
+
+    #[test]
+    fn test_grep_regex_case_and_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "Error 1\nerror 2\nERROR 3\n").unwrap();
+        std::fs::write(dir.path().join("big.txt"), "Error\n".repeat(100)).unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+
+        let query = ContentSearchQuery {
+            root: "/".to_string(),
+            pattern: r"^Error \d".to_string(),
+            regex: true,
+            case_sensitive: true,
+            max_file_size: Some(100),
+            ..Default::default()
+        };
+        let (found, summary) = grep(&fs, &query);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].line, "Error 1");
+        assert_eq!(summary.skipped_too_large, 1);
+
+        let limited = ContentSearchQuery {
+            pattern: "error".to_string(),
+            max_results: Some(2),
+            max_file_size: None,
+            regex: false,
+            case_sensitive: false,
+            ..query
+        };
+        let (found, summary) = grep(&fs, &limited);
+        assert_eq!(found.len(), 2);
+        assert!(summary.truncated);
+
+        let invalid = ContentSearchQuery {
+            pattern: "(".to_string(),
+            regex: true,
+            ..limited
+        };
+        assert!(grep_filesystem(&fs, &invalid, &CancellationToken::new(), |_| {}).is_err());
+    }
+}
@@ -0,0 +1,794 @@
+/**
+ * Filename Index
+ *
+ * Persistent index of paths, names, sizes and mtimes for configured roots.
+ *
+ * - Real-FS roots (`AppConfig.index_roots`) are stored under the local data dir
+ *   and kept up to date by a recursive `WATCHER` subscription.
+ * - The vault tree (`AppConfig.index_vault`) is indexed only while the vault is
+ *   unlocked and persisted encrypted with the vault session key.
+ *
+ * A stored snapshot is served immediately on startup while a fresh walk runs
+ * in the background.
+ */
+
+use super::journal::split_path;
+use super::search::{fuzzy_score, walk_tree, Visit, WalkOptions};
+use super::{ApiError, ApiResult};
+use crate::api::virtual_fs::VirtualFileSystem;
+use crate::api::watcher::{ChangeType, FileSystemChange, WatchBackend, WATCHER};
+use crate::api::RealFileSystem;
+use crate::core::{ErrorKind, FileSystem, FileSystemEntry};
+use crate::progress::CancellationToken;
+use crate::state::APP_CONFIG;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::RecvError;
+
+/// Global filename index
+pub static FILE_INDEX: Lazy<Arc<FileIndex>> = Lazy::new(|| Arc::new(FileIndex::new()));
+
+/// Ключ индекса хранилища (индексы реальной ФС хранятся по пути корня)
+const VAULT_KEY: &str = "vault";
+
+/// Имя зашифрованного файла индекса рядом с vault.bin
+pub(crate) const VAULT_INDEX_FILE: &str = "index.bin";
+
+/// Версия формата снимка; снимки другой версии перестраиваются
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Как часто сохранять индексы, измененные наблюдателем
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+const DEFAULT_QUERY_LIMIT: usize = 100;
+const MAX_QUERY_LIMIT: usize = 1000;
+
+/// Источник проиндексированного дерева
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum IndexScope {
+    Real,
+    Vault,
+}
+
+/// Способ сопоставления запроса с именем
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum IndexQueryMode {
+    Prefix,
+    #[default]
+    Substring,
+    Fuzzy,
+}
+
+/// Запрос к индексу (без учета регистра)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct IndexQuery {
+    pub query: String,
+    #[serde(default)]
+    pub mode: IndexQueryMode,
+    /// Максимум результатов (по умолчанию 100, не больше 1000)
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Искать только в индексах этого источника
+    #[serde(default)]
+    pub scope: Option<IndexScope>,
+}
+
+/// Результат запроса к индексу
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct IndexHit {
+    pub path: String,
+    pub name: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<u64>,
+    pub scope: IndexScope,
+    /// Больше — лучше; порядок результатов уже учитывает оценку
+    pub score: i64,
+}
+
+/// Состояние индекса одного корня
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct IndexRootStatus {
+    pub root: String,
+    pub scope: IndexScope,
+    /// Индекс содержит данные и отвечает на запросы
+    pub ready: bool,
+    /// Идет полный обход корня
+    pub building: bool,
+    pub entries: usize,
+    /// Время завершения последнего полного обхода (секунды UNIX)
+    pub indexed_at: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexedEntry {
+    name: String,
+    is_dir: bool,
+    size: u64,
+    modified: Option<u64>,
+}
+
+impl From<&FileSystemEntry> for IndexedEntry {
+    fn from(entry: &FileSystemEntry) -> Self {
+        Self {
+            name: entry.name.clone(),
+            is_dir: entry.is_dir,
+            size: entry.size.unwrap_or(0),
+            modified: entry.modified,
+        }
+    }
+}
+
+/// Сохраняемый снимок индекса
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    root: String,
+    indexed_at: u64,
+    entries: Vec<(String, IndexedEntry)>,
+}
+
+/// Индекс одного корня
+struct RootIndex {
+    scope: IndexScope,
+    root: String,
+    /// Путь → запись; упорядочено, чтобы поддерево удалялось одним диапазоном
+    entries: BTreeMap<String, IndexedEntry>,
+    /// (имя в нижнем регистре, путь) для префиксного поиска
+    names: BTreeSet<(String, String)>,
+    indexed_at: Option<u64>,
+    error: Option<String>,
+    subscription: Option<String>,
+    /// Токен текущего полного обхода
+    build: Option<CancellationToken>,
+    /// Есть изменения, не записанные на диск
+    dirty: bool,
+}
+
+impl RootIndex {
+    fn new(scope: IndexScope, root: String) -> Self {
+        Self {
+            scope,
+            root,
+            entries: BTreeMap::new(),
+            names: BTreeSet::new(),
+            indexed_at: None,
+            error: None,
+            subscription: None,
+            build: None,
+            dirty: false,
+        }
+    }
+
+    fn insert(&mut self, path: String, entry: IndexedEntry) {
+        self.names.insert((entry.name.to_lowercase(), path.clone()));
+        if let Some(previous) = self.entries.insert(path.clone(), entry) {
+            let key = (previous.name.to_lowercase(), path);
+            if !self.entries.get(&key.1).is_some_and(|e| e.name.to_lowercase() == key.0) {
+                self.names.remove(&key);
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// Удаление пути вместе со всем поддеревом
+    fn remove_tree(&mut self, path: &str) {
+        let doomed: Vec<String> = self
+            .entries
+            .range(path.to_string()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(path))
+            .filter(|key| is_same_or_descendant(key, path))
+            .cloned()
+            .collect();
+
+        for key in doomed {
+            if let Some(entry) = self.entries.remove(&key) {
+                self.names.remove(&(entry.name.to_lowercase(), key));
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Замена содержимого результатом полного обхода
+    fn replace(&mut self, entries: Vec<(String, IndexedEntry)>, indexed_at: u64) {
+        self.entries.clear();
+        self.names.clear();
+        for (path, entry) in entries {
+            self.insert(path, entry);
+        }
+        self.indexed_at = Some(indexed_at);
+        self.error = None;
+        self.dirty = true;
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            root: self.root.clone(),
+            indexed_at: self.indexed_at.unwrap_or(0),
+            entries: self.entries.iter().map(|(path, entry)| (path.clone(), entry.clone())).collect(),
+        }
+    }
+
+    fn status(&self) -> IndexRootStatus {
+        IndexRootStatus {
+            root: self.root.clone(),
+            scope: self.scope,
+            ready: self.indexed_at.is_some(),
+            building: self.build.is_some(),
+            entries: self.entries.len(),
+            indexed_at: self.indexed_at,
+            error: self.error.clone(),
+        }
+    }
+
+    fn hit(&self, path: &str, score: i64) -> Option<IndexHit> {
+        let entry = self.entries.get(path)?;
+        Some(IndexHit {
+            path: path.to_string(),
+            name: entry.name.clone(),
+            is_dir: entry.is_dir,
+            size: entry.size,
+            modified: entry.modified,
+            scope: self.scope,
+            score,
+        })
+    }
+
+    /// Совпадения запроса `needle` (в нижнем регистре)
+    fn query(&self, needle: &str, mode: IndexQueryMode, hits: &mut Vec<IndexHit>) {
+        match mode {
+            IndexQueryMode::Prefix => {
+                let start = (needle.to_string(), String::new());
+                for (name, path) in self.names.range(start..).take_while(|(name, _)| name.starts_with(needle)) {
+                    // Точное совпадение выше, затем более короткие имена
+                    let score = -((name.len() - needle.len()) as i64);
+                    hits.extend(self.hit(path, score));
+                }
+            }
+            IndexQueryMode::Substring => {
+                for (name, path) in &self.names {
+                    if let Some(position) = name.find(needle) {
+                        let score = -(position as i64) * 4 - (name.len() - needle.len()) as i64;
+                        hits.extend(self.hit(path, score));
+                    }
+                }
+            }
+            IndexQueryMode::Fuzzy => {
+                for (name, path) in &self.names {
+                    if let Some(score) = fuzzy_score(needle, name, true) {
+                        hits.extend(self.hit(path, score));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_same_or_descendant(path: &str, ancestor: &str) -> bool {
+    if path == ancestor {
+        return true;
+    }
+    if ancestor.ends_with(['/', '\\']) {
+        return path.starts_with(ancestor);
+    }
+    path.starts_with(ancestor) && path[ancestor.len()..].starts_with(['/', '\\'])
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Файл снимка индекса реального корня
+fn snapshot_path(root: &str) -> Option<PathBuf> {
+    let digest = Sha256::digest(root.as_bytes());
+    let name: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    dirs::data_local_dir().map(|dir| dir.join("vfdir").join("index").join(format!("{}.bin", name)))
+}
+
+/// Полный обход корня; `None`, если обход отменен
+fn walk_root(
+    fs: &dyn FileSystem,
+    root: &str,
+    token: &CancellationToken,
+) -> ApiResult<Option<Vec<(String, IndexedEntry)>>> {
+    let options = WalkOptions {
+        include_hidden: None,
+        respect_gitignore: false,
+        max_depth: None,
+    };
+
+    let mut entries = Vec::new();
+    let stats = walk_tree(fs, root, &options, token, |entry| {
+        entries.push((entry.path.clone(), IndexedEntry::from(&entry)));
+        Visit::Continue
+    })?;
+
+    Ok((!stats.cancelled).then_some(entries))
+}
+
+/// Filename index over the configured roots
+pub struct FileIndex {
+    roots: RwLock<HashMap<String, RootIndex>>,
+    /// Хранилище, дерево которого индексируется (пока оно разблокировано)
+    vault: Mutex<Option<VirtualFileSystem>>,
+    started: AtomicBool,
+}
+
+impl FileIndex {
+    fn new() -> Self {
+        Self {
+            roots: RwLock::new(HashMap::new()),
+            vault: Mutex::new(None),
+            started: AtomicBool::new(false),
+        }
+    }
+
+    /// Sync indexed real-FS roots with `AppConfig.index_roots`
+    ///
+    /// New roots are loaded from their snapshot (if any) and rebuilt in the background;
+    /// removed roots stop being watched.
+    pub fn configure(&self) {
+        self.ensure_started();
+
+        let (configured, index_vault) = {
+            let config = APP_CONFIG.read().unwrap();
+            (config.index_roots.clone(), config.index_vault)
+        };
+
+        let removed: Vec<String> = {
+            let roots = self.roots.read().unwrap();
+            roots
+                .values()
+                .filter(|index| index.scope == IndexScope::Real && !configured.contains(&index.root))
+                .map(|index| index.root.clone())
+                .collect()
+        };
+        for root in removed {
+            self.drop_root(&root);
+        }
+
+        for root in configured {
+            if self.roots.read().unwrap().contains_key(&root) {
+                continue;
+            }
+
+            let mut index = RootIndex::new(IndexScope::Real, root.clone());
+            if let Some(snapshot) = snapshot_path(&root).and_then(|path| load_snapshot(&path, &root)) {
+                index.replace(snapshot.entries, snapshot.indexed_at);
+                index.dirty = false;
+            }
+            match WATCHER.subscribe(WatchBackend::Real, None, &root, true) {
+                Ok(id) => index.subscription = Some(id),
+                Err(e) => tracing::warn!("Index of {} will not be updated live: {}", root, e),
+            }
+
+            self.roots.write().unwrap().insert(root.clone(), index);
+            self.spawn_rebuild(&root);
+        }
+
+        if !index_vault {
+            self.detach_vault(false);
+        }
+    }
+
+    /// Start indexing the vault tree after it was unlocked (if enabled in config)
+    pub fn attach_vault(&self, vfs: VirtualFileSystem) {
+        if !APP_CONFIG.read().unwrap().index_vault {
+            return;
+        }
+        self.ensure_started();
+
+        let mut index = RootIndex::new(IndexScope::Vault, "/".to_string());
+        match vfs.read_sealed_file(VAULT_INDEX_FILE) {
+            Ok(Some(data)) => match bincode::deserialize::<Snapshot>(&data) {
+                Ok(snapshot) if snapshot.version == SNAPSHOT_VERSION => {
+                    index.replace(snapshot.entries, snapshot.indexed_at);
+                    index.dirty = false;
+                }
+                Ok(_) => tracing::info!("Vault index has an old format, rebuilding"),
+                Err(e) => tracing::warn!("Vault index is corrupted, rebuilding: {}", e),
+            },
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to read vault index: {}", e),
+        }
+        match WATCHER.subscribe(WatchBackend::Virtual, None, "/", true) {
+            Ok(id) => index.subscription = Some(id),
+            Err(e) => tracing::warn!("Vault index will not be updated live: {}", e),
+        }
+
+        *self.vault.lock().unwrap() = Some(vfs);
+        self.drop_root(VAULT_KEY);
+        self.roots.write().unwrap().insert(VAULT_KEY.to_string(), index);
+        self.spawn_rebuild(VAULT_KEY);
+    }
+
+    /// Stop indexing the vault; must be called before the vault is locked
+    /// so that pending changes can still be encrypted with the session key.
+    pub fn detach_vault(&self, save: bool) {
+        if save {
+            self.save(VAULT_KEY);
+        }
+        self.drop_root(VAULT_KEY);
+        *self.vault.lock().unwrap() = None;
+    }
+
+    pub fn status(&self) -> Vec<IndexRootStatus> {
+        let roots = self.roots.read().unwrap();
+        let mut status: Vec<_> = roots.values().map(RootIndex::status).collect();
+        status.sort_by(|a, b| a.root.cmp(&b.root));
+        status
+    }
+
+    /// Rebuild one root (`None` — all roots) in the background
+    pub fn rebuild(&self, root: Option<&str>) -> ApiResult<()> {
+        let keys: Vec<String> = {
+            let roots = self.roots.read().unwrap();
+            match root {
+                Some(root) => {
+                    let key = roots
+                        .iter()
+                        .find(|(_, index)| index.root == root || (index.scope == IndexScope::Vault && root == VAULT_KEY))
+                        .map(|(key, _)| key.clone())
+                        .ok_or_else(|| ApiError::NotFound {
+                            resource: format!("Index root: {}", root),
+                        })?;
+                    vec![key]
+                }
+                None => roots.keys().cloned().collect(),
+            }
+        };
+
+        for key in keys {
+            self.spawn_rebuild(&key);
+        }
+        Ok(())
+    }
+
+    pub fn query(&self, query: &IndexQuery) -> ApiResult<Vec<IndexHit>> {
+        let needle = query.query.trim().to_lowercase();
+        if needle.is_empty() {
+            return Err(ApiError::ValidationError {
+                message: "Query must not be empty".to_string(),
+            });
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT).clamp(1, MAX_QUERY_LIMIT);
+
+        let mut hits = Vec::new();
+        {
+            let roots = self.roots.read().unwrap();
+            for index in roots.values() {
+                if query.scope.is_none_or(|scope| scope == index.scope) {
+                    index.query(&needle, query.mode, &mut hits);
+                }
+            }
+        }
+
+        hits.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// Start the change listener and the periodic saver (once)
+    fn ensure_started(&self) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        std::thread::spawn(|| {
+            let mut changes = WATCHER.subscribe_events();
+            loop {
+                match changes.blocking_recv() {
+                    Ok(change) => FILE_INDEX.apply_change(change),
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Index lagged behind by {} changes, rebuilding", skipped);
+                        let _ = FILE_INDEX.rebuild(None);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        std::thread::spawn(|| loop {
+            std::thread::sleep(SAVE_INTERVAL);
+            let dirty: Vec<String> = {
+                let roots = FILE_INDEX.roots.read().unwrap();
+                roots.iter().filter(|(_, index)| index.dirty).map(|(key, _)| key.clone()).collect()
+            };
+            for key in dirty {
+                FILE_INDEX.save(&key);
+            }
+        });
+    }
+
+    fn drop_root(&self, key: &str) {
+        let Some(index) = self.roots.write().unwrap().remove(key) else { return };
+        if let Some(token) = &index.build {
+            token.cancel();
+        }
+        if let Some(id) = &index.subscription {
+            WATCHER.unsubscribe(id);
+        }
+    }
+
+    fn with_filesystem<T>(&self, scope: IndexScope, f: impl FnOnce(&dyn FileSystem) -> T) -> Option<T> {
+        match scope {
+            IndexScope::Real => Some(f(&RealFileSystem::new())),
+            IndexScope::Vault => {
+                let vfs = self.vault.lock().unwrap().clone()?;
+                Some(f(&vfs))
+            }
+        }
+    }
+
+    fn spawn_rebuild(&self, key: &str) {
+        let token = CancellationToken::new();
+        let (scope, root) = {
+            let mut roots = self.roots.write().unwrap();
+            let Some(index) = roots.get_mut(key) else { return };
+            if let Some(previous) = index.build.replace(token.clone()) {
+                previous.cancel();
+            }
+            (index.scope, index.root.clone())
+        };
+
+        let key = key.to_string();
+        std::thread::spawn(move || {
+            tracing::info!("Indexing {}", root);
+            let started = now_secs();
+            let result = FILE_INDEX
+                .with_filesystem(scope, |fs| walk_root(fs, &root, &token))
+                .unwrap_or_else(|| Err(ApiError::Locked { message: "Vault is locked".to_string() }));
+
+            {
+                let mut roots = FILE_INDEX.roots.write().unwrap();
+                let Some(index) = roots.get_mut(&key) else { return };
+                // Обход, который уже заменен новым, ничего не меняет
+                if !index.build.as_ref().is_some_and(|current| current.same_as(&token)) {
+                    return;
+                }
+                index.build = None;
+
+                match result {
+                    Ok(Some(entries)) => {
+                        tracing::info!("Indexed {} entries under {}", entries.len(), root);
+                        index.replace(entries, started);
+                    }
+                    Ok(None) => return,
+                    Err(err) => {
+                        tracing::warn!("Failed to index {}: {}", root, err);
+                        index.error = Some(err.to_string());
+                        return;
+                    }
+                }
+            }
+
+            FILE_INDEX.save(&key);
+        });
+    }
+
+    /// Write the snapshot of a root to disk (encrypted for the vault)
+    fn save(&self, key: &str) {
+        let (scope, root, data) = {
+            let mut roots = self.roots.write().unwrap();
+            let Some(index) = roots.get_mut(key) else { return };
+            if index.indexed_at.is_none() {
+                return;
+            }
+            index.dirty = false;
+            match bincode::serialize(&index.snapshot()) {
+                Ok(data) => (index.scope, index.root.clone(), data),
+                Err(e) => {
+                    tracing::error!("Failed to serialize index of {}: {}", index.root, e);
+                    return;
+                }
+            }
+        };
+
+        let result = match scope {
+            IndexScope::Real => match snapshot_path(&root) {
+                Some(path) => path
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::write(&path, &data))
+                    .map_err(|e| e.to_string()),
+                None => Err("Could not determine local data directory".to_string()),
+            },
+            IndexScope::Vault => match self.vault.lock().unwrap().as_ref() {
+                Some(vfs) => vfs.write_sealed_file(VAULT_INDEX_FILE, &data).map_err(|e| e.to_string()),
+                None => Ok(()),
+            },
+        };
+
+        if let Err(e) = result {
+            tracing::warn!("Failed to save index of {}: {}", root, e);
+        }
+    }
+
+    /// Incremental update from a watcher change
+    fn apply_change(&self, change: FileSystemChange) {
+        let target = {
+            let roots = self.roots.read().unwrap();
+            roots.iter().find_map(|(key, index)| {
+                let id = index.subscription.as_ref()?;
+                change.subscription_ids.contains(id).then(|| (key.clone(), index.scope, index.root.clone()))
+            })
+        };
+        let Some((key, scope, root)) = target else { return };
+
+        if let Some(old_path) = &change.old_path {
+            if let Some(index) = self.roots.write().unwrap().get_mut(&key) {
+                index.remove_tree(old_path);
+            }
+        }
+
+        if change.change_type == ChangeType::Deleted {
+            if let Some(index) = self.roots.write().unwrap().get_mut(&key) {
+                index.remove_tree(&change.path);
+            }
+            return;
+        }
+
+        let descend = change.change_type != ChangeType::Modified;
+        let update = self.with_filesystem(scope, |fs| collect_changed(fs, &root, &change.path, descend));
+        let Some(update) = update else { return };
+
+        let mut roots = self.roots.write().unwrap();
+        let Some(index) = roots.get_mut(&key) else { return };
+        match update {
+            Ok(entries) => {
+                // Пути вне проиндексированного дерева (например, внутри скрытых папок) пропускаются
+                let (parent, _) = split_path(&change.path);
+                if parent != root && !index.entries.contains_key(&parent) {
+                    return;
+                }
+                for (path, entry) in entries {
+                    index.insert(path, entry);
+                }
+            }
+            Err(err) if err.kind == ErrorKind::NotFound => index.remove_tree(&change.path),
+            Err(err) => tracing::debug!("Index skipped change of {}: {}", change.path, err),
+        }
+    }
+}
+
+/// Записи для измененного пути: сам путь и, для новых директорий, их содержимое
+fn collect_changed(
+    fs: &dyn FileSystem,
+    root: &str,
+    path: &str,
+    descend: bool,
+) -> crate::core::FileSystemResult<Vec<(String, IndexedEntry)>> {
+    let entry = fs.get_file_info(path)?;
+    if path == root || (entry.is_hidden && !APP_CONFIG.read().unwrap().show_hidden_files) {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = vec![(entry.path.clone(), IndexedEntry::from(&entry))];
+    if descend && entry.is_dir && !entry.is_symlink {
+        if let Ok(Some(children)) = walk_root(fs, path, &CancellationToken::new()) {
+            entries.extend(children);
+        }
+    }
+    Ok(entries)
+}
+
+fn load_snapshot(path: &std::path::Path, root: &str) -> Option<Snapshot> {
+    let data = std::fs::read(path).ok()?;
+    match bincode::deserialize::<Snapshot>(&data) {
+        Ok(snapshot) if snapshot.version == SNAPSHOT_VERSION && snapshot.root == root => Some(snapshot),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("Index snapshot {} is corrupted: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, is_dir: bool) -> IndexedEntry {
+        IndexedEntry {
+            name: name.to_string(),
+            is_dir,
+            size: 0,
+            modified: None,
+        }
+    }
+
+    fn sample() -> RootIndex {
+        let mut index = RootIndex::new(IndexScope::Real, "/root".to_string());
+        index.replace(
+            vec![
+                ("/root/src".to_string(), entry("src", true)),
+                ("/root/src/main.rs".to_string(), entry("main.rs", false)),
+                ("/root/src/mainframe.rs".to_string(), entry("Mainframe.rs", false)),
+                ("/root/src-old".to_string(), entry("src-old", true)),
+                ("/root/docs/domain.md".to_string(), entry("domain.md", false)),
+            ],
+            1,
+        );
+        index
+    }
+
+    fn paths(hits: &[IndexHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_prefix_and_substring_queries() {
+        let index = sample();
+
+        let mut hits = Vec::new();
+        index.query("main", IndexQueryMode::Prefix, &mut hits);
+        hits.sort_by(|a, b| b.score.cmp(&a.score));
+        assert_eq!(paths(&hits), vec!["/root/src/main.rs", "/root/src/mainframe.rs"]);
+
+        let mut hits = Vec::new();
+        index.query("main", IndexQueryMode::Substring, &mut hits);
+        assert_eq!(hits.len(), 3);
+
+        let mut hits = Vec::new();
+        index.query("mrs", IndexQueryMode::Fuzzy, &mut hits);
+        assert!(paths(&hits).contains(&"/root/src/main.rs"));
+    }
+
+    #[test]
+    fn test_remove_tree_keeps_siblings_with_common_prefix() {
+        let mut index = sample();
+        index.remove_tree("/root/src");
+
+        let remaining: Vec<_> = index.entries.keys().cloned().collect();
+        assert_eq!(remaining, vec!["/root/docs/domain.md", "/root/src-old"]);
+        assert_eq!(index.names.len(), 2);
+    }
+
+    #[test]
+    fn test_rename_case_updates_name_index() {
+        let mut index = sample();
+        index.insert("/root/src/main.rs".to_string(), entry("MAIN.rs", false));
+        assert_eq!(index.names.iter().filter(|(_, path)| path == "/root/src/main.rs").count(), 1);
+
+        index.insert("/root/src/main.rs".to_string(), entry("lib.rs", false));
+        let mut hits = Vec::new();
+        index.query("main.rs", IndexQueryMode::Prefix, &mut hits);
+        assert!(hits.is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let index = sample();
+        let data = bincode::serialize(&index.snapshot()).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.bin");
+        std::fs::write(&path, data).unwrap();
+
+        let snapshot = load_snapshot(&path, "/root").unwrap();
+        assert_eq!(snapshot.entries.len(), 5);
+        assert!(load_snapshot(&path, "/elsewhere").is_none());
+    }
+}
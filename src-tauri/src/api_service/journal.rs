@@ -0,0 +1,356 @@
+/**
+ * Operation Journal
+ *
+ * Undo/redo history for mutating file operations.
+ *
+ * Every journaled operation stores the step that reverts it. Applying a step
+ * yields the opposite step, so the same entry moves between the undo and redo
+ * stacks: created items go to the trash on undo and are restored on redo,
+ * deleted items are restored from the trash on undo and trashed again on redo.
+ */
+
+use crate::api::trash;
+use crate::core::{FileSystem, FileSystemResult};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Maximum number of undoable operations kept per backend/session
+pub const MAX_HISTORY: usize = 100;
+
+/// Global journal, keyed by backend or session label
+pub static OPERATION_JOURNAL: Lazy<OperationJournal> = Lazy::new(OperationJournal::new);
+
+/// Kind of journaled operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum OperationKind {
+    Rename,
+    Move,
+    Copy,
+    CreateFolder,
+    CreateFile,
+    Delete,
+}
+
+/// Item moved to the trash by a journaled operation
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashedItem {
+    pub path: String,
+    pub trash_id: String,
+}
+
+/// Step that reverts an operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum JournalStep {
+    /// Move each item from the first path back to the second
+    Relocate(Vec<(String, String)>),
+    /// Move created items to the trash
+    TrashCreated(Vec<String>),
+    /// Restore trashed items to their original paths
+    RestoreTrashed(Vec<TrashedItem>),
+}
+
+impl JournalStep {
+    /// Apply the step and return the step that reverts it
+    fn apply(&self, fs: &dyn FileSystem) -> FileSystemResult<JournalStep> {
+        match self {
+            JournalStep::Relocate(pairs) => {
+                // Reverse order so nested moves unwind correctly
+                for (from, to) in pairs.iter().rev() {
+                    relocate(fs, from, to)?;
+                }
+                Ok(JournalStep::Relocate(
+                    pairs.iter().map(|(from, to)| (to.clone(), from.clone())).collect(),
+                ))
+            }
+            JournalStep::TrashCreated(paths) => {
+                let mut trashed = Vec::with_capacity(paths.len());
+                for path in paths {
+                    let entry = fs.trash_item(path)?;
+                    trashed.push(TrashedItem {
+                        path: path.clone(),
+                        trash_id: entry.id,
+                    });
+                }
+                Ok(JournalStep::RestoreTrashed(trashed))
+            }
+            JournalStep::RestoreTrashed(items) => {
+                for item in items {
+                    fs.restore_from_trash(&item.trash_id)?;
+                }
+                Ok(JournalStep::TrashCreated(
+                    items.iter().map(|item| item.path.clone()).collect(),
+                ))
+            }
+        }
+    }
+}
+
+/// Journaled operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct JournalEntry {
+    pub id: String,
+    pub kind: OperationKind,
+    pub description: String,
+    /// Time of the original operation (UNIX seconds)
+    pub timestamp: u64,
+    #[serde(skip)]
+    step: Option<JournalStep>,
+}
+
+/// Undo and redo stacks of one backend/session, most recent first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct JournalHistory {
+    pub undo: Vec<JournalEntry>,
+    pub redo: Vec<JournalEntry>,
+}
+
+#[derive(Default)]
+struct History {
+    undo: Vec<JournalEntry>,
+    redo: Vec<JournalEntry>,
+}
+
+/// Undo/redo journal for all backends and sessions
+pub struct OperationJournal {
+    histories: Mutex<HashMap<String, History>>,
+}
+
+impl OperationJournal {
+    pub fn new() -> Self {
+        Self {
+            histories: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a completed operation
+    ///
+    /// Clears the redo stack and drops the oldest entries beyond `MAX_HISTORY`.
+    pub fn record(&self, key: &str, kind: OperationKind, description: impl Into<String>, step: JournalStep) {
+        let entry = JournalEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            description: description.into(),
+            timestamp: trash::now_secs(),
+            step: Some(step),
+        };
+
+        let mut histories = self.histories.lock().unwrap();
+        let history = histories.entry(key.to_string()).or_default();
+        history.redo.clear();
+        history.undo.push(entry);
+        if history.undo.len() > MAX_HISTORY {
+            let excess = history.undo.len() - MAX_HISTORY;
+            history.undo.drain(..excess);
+        }
+    }
+
+    /// Revert the most recent operation
+    ///
+    /// Returns `None` when there is nothing to undo. On failure the entry stays on the undo stack.
+    pub fn undo_last(&self, key: &str, fs: &dyn FileSystem) -> FileSystemResult<Option<JournalEntry>> {
+        self.transfer(key, fs, true)
+    }
+
+    /// Re-apply the most recently undone operation
+    ///
+    /// Returns `None` when there is nothing to redo. On failure the entry stays on the redo stack.
+    pub fn redo(&self, key: &str, fs: &dyn FileSystem) -> FileSystemResult<Option<JournalEntry>> {
+        self.transfer(key, fs, false)
+    }
+
+    fn transfer(&self, key: &str, fs: &dyn FileSystem, undo: bool) -> FileSystemResult<Option<JournalEntry>> {
+        let entry = {
+            let mut histories = self.histories.lock().unwrap();
+            let history = histories.entry(key.to_string()).or_default();
+            let stack = if undo { &mut history.undo } else { &mut history.redo };
+            stack.pop()
+        };
+        let Some(mut entry) = entry else { return Ok(None) };
+
+        // The filesystem call runs without holding the journal lock
+        let result = match &entry.step {
+            Some(step) => step.apply(fs),
+            None => return Ok(None),
+        };
+
+        let mut histories = self.histories.lock().unwrap();
+        let history = histories.entry(key.to_string()).or_default();
+        match result {
+            Ok(next_step) => {
+                entry.step = Some(next_step);
+                let target = if undo { &mut history.redo } else { &mut history.undo };
+                target.push(entry.clone());
+                Ok(Some(entry))
+            }
+            Err(err) => {
+                let source = if undo { &mut history.undo } else { &mut history.redo };
+                source.push(entry);
+                Err(err)
+            }
+        }
+    }
+
+    /// Undo and redo stacks for a backend/session
+    pub fn history(&self, key: &str) -> JournalHistory {
+        let histories = self.histories.lock().unwrap();
+        match histories.get(key) {
+            Some(history) => JournalHistory {
+                undo: history.undo.iter().rev().cloned().collect(),
+                redo: history.redo.iter().rev().cloned().collect(),
+            },
+            None => JournalHistory::default(),
+        }
+    }
+}
+
+impl Default for OperationJournal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Join directory and name using the separator style of the directory path
+pub fn join_path(dir: &str, name: &str) -> String {
+    let separator = if dir.contains('\\') && !dir.contains('/') { '\\' } else { '/' };
+    format!("{}{}{}", dir.trim_end_matches(['/', '\\']), separator, name)
+}
+
+/// Split path into parent directory and name
+pub fn split_path(path: &str) -> (String, String) {
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    match trimmed.rfind(['/', '\\']) {
+        Some(index) => {
+            let mut parent = trimmed[..index].to_string();
+            // Keep roots ("/", "C:\") as directories
+            if parent.is_empty() || parent.ends_with(':') {
+                parent.push_str(&trimmed[index..=index]);
+            }
+            (parent, trimmed[index + 1..].to_string())
+        }
+        None => (String::new(), trimmed.to_string()),
+    }
+}
+
+/// Move or rename a single item from `from` to `to`
+fn relocate(fs: &dyn FileSystem, from: &str, to: &str) -> FileSystemResult<()> {
+    let (from_parent, from_name) = split_path(from);
+    let (to_parent, to_name) = split_path(to);
+
+    if from_parent == to_parent {
+        return fs.rename_item(from, &to_name);
+    }
+
+    fs.move_items(&[from.to_string()], &to_parent)?;
+    if from_name != to_name {
+        fs.rename_item(&join_path(&to_parent, &from_name), &to_name)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::temporary_fs::TemporaryFileSystem;
+
+    #[test]
+    fn test_path_helpers() {
+        assert_eq!(join_path("/", "a"), "/a");
+        assert_eq!(join_path("/home/", "a"), "/home/a");
+        assert_eq!(join_path("C:\\Users", "a"), "C:\\Users\\a");
+        assert_eq!(split_path("/a"), ("/".to_string(), "a".to_string()));
+        assert_eq!(split_path("/home/user/a.txt"), ("/home/user".to_string(), "a.txt".to_string()));
+        assert_eq!(split_path("C:\\a"), ("C:\\".to_string(), "a".to_string()));
+    }
+
+    #[test]
+    fn test_undo_redo_rename_and_create() {
+        let dir = tempfile::tempdir().unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+        let journal = OperationJournal::new();
+
+        fs.create_folder("/", "docs").unwrap();
+        journal.record("tmp", OperationKind::CreateFolder, "Create docs", JournalStep::TrashCreated(vec!["/docs".to_string()]));
+
+        fs.rename_item("/docs", "papers").unwrap();
+        journal.record(
+            "tmp",
+            OperationKind::Rename,
+            "Rename docs",
+            JournalStep::Relocate(vec![("/papers".to_string(), "/docs".to_string())]),
+        );
+
+        let undone = journal.undo_last("tmp", &fs).unwrap().unwrap();
+        assert_eq!(undone.kind, OperationKind::Rename);
+        assert!(fs.get_file_info("/docs").is_ok());
+
+        journal.undo_last("tmp", &fs).unwrap();
+        assert!(fs.get_file_info("/docs").is_err());
+        assert_eq!(fs.list_trash().unwrap().len(), 1);
+        assert!(journal.undo_last("tmp", &fs).unwrap().is_none());
+
+        journal.redo("tmp", &fs).unwrap();
+        journal.redo("tmp", &fs).unwrap();
+        assert!(fs.get_file_info("/papers").is_ok());
+        assert!(fs.list_trash().unwrap().is_empty());
+
+        let history = journal.history("tmp");
+        assert_eq!(history.undo.len(), 2);
+        assert!(history.redo.is_empty());
+        assert_eq!(history.undo[0].kind, OperationKind::Rename);
+    }
+
+    #[test]
+    fn test_undo_delete_restores_from_trash() {
+        let dir = tempfile::tempdir().unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+        let journal = OperationJournal::new();
+
+        fs.create_folder("/", "sub").unwrap();
+        fs.create_file("/sub", "a.txt", Some("data")).unwrap();
+        let entry = fs.trash_item("/sub/a.txt").unwrap();
+        journal.record(
+            "tmp",
+            OperationKind::Delete,
+            "Delete a.txt",
+            JournalStep::RestoreTrashed(vec![TrashedItem { path: "/sub/a.txt".to_string(), trash_id: entry.id }]),
+        );
+
+        journal.undo_last("tmp", &fs).unwrap();
+        assert_eq!(fs.read_file_content("/sub/a.txt", None).unwrap(), "data");
+
+        // Moving across directories and back
+        fs.move_items(&["/sub/a.txt".to_string()], "/").unwrap();
+        journal.record(
+            "tmp",
+            OperationKind::Move,
+            "Move a.txt",
+            JournalStep::Relocate(vec![("/a.txt".to_string(), "/sub/a.txt".to_string())]),
+        );
+        assert!(journal.history("tmp").redo.is_empty());
+
+        journal.undo_last("tmp", &fs).unwrap();
+        assert!(fs.get_file_info("/sub/a.txt").is_ok());
+        journal.redo("tmp", &fs).unwrap();
+        assert!(fs.get_file_info("/a.txt").is_ok());
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let journal = OperationJournal::new();
+        for i in 0..MAX_HISTORY + 5 {
+            journal.record("real", OperationKind::CreateFile, format!("op {}", i), JournalStep::TrashCreated(Vec::new()));
+        }
+
+        let history = journal.history("real");
+        assert_eq!(history.undo.len(), MAX_HISTORY);
+        assert_eq!(history.undo[0].description, format!("op {}", MAX_HISTORY + 4));
+    }
+}
@@ -30,6 +30,17 @@ pub mod system;
 pub mod config;
 pub mod models;
 pub mod vault;
+pub mod journal;
+pub mod search;
+pub mod grep;
+pub mod index;
+pub mod duplicates;
+pub mod checksum;
+pub mod split;
+pub mod compare;
+pub mod sync;
+pub mod disk_usage;
+pub mod remote;
 
 pub use error::{ApiError, ApiResult};
 pub use files::FileService;
@@ -38,6 +49,13 @@ pub use bookmarks::BookmarkService;
 pub use system::SystemService;
 pub use config::ConfigService;
 pub use vault::VaultService;
+pub use search::SearchService;
+pub use duplicates::DuplicateService;
+pub use checksum::ChecksumService;
+pub use split::SplitService;
+pub use compare::CompareService;
+pub use sync::SyncService;
+pub use remote::RemoteService;
 
 /// Main API facade that groups all services
 pub struct Api {
@@ -47,6 +65,13 @@ pub struct Api {
     pub system: SystemService,
     pub config: ConfigService,
     pub vault: VaultService,
+    pub search: SearchService,
+    pub duplicates: DuplicateService,
+    pub checksums: ChecksumService,
+    pub split: SplitService,
+    pub compare: CompareService,
+    pub sync: SyncService,
+    pub remote: RemoteService,
 }
 
 impl Api {
@@ -58,6 +83,13 @@ impl Api {
             system: SystemService::new(),
             config: ConfigService::new(),
             vault: VaultService::new(),
+            search: SearchService::new(),
+            duplicates: DuplicateService::new(),
+            checksums: ChecksumService::new(),
+            split: SplitService::new(),
+            compare: CompareService::new(),
+            sync: SyncService::new(),
+            remote: RemoteService::new(),
         }
     }
 }
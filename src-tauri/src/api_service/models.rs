@@ -8,7 +8,7 @@
 use serde::{Deserialize, Serialize};
 
 // Re-export core types
-pub use crate::core::FileSystemEntry;
+pub use crate::core::{FileSystemEntry, TrashEntry};
 pub use crate::config::{Bookmark, AppConfig, UIState};
 pub use super::error::{ApiError, ApiResult};
 
@@ -0,0 +1,689 @@
+/**
+ * Remote Connections Service
+ *
+ * Saved SFTP, WebDAV and S3 connection profiles and live sessions. A connected profile is
+ * used by panels through `panel_fs = "sftp:<profile id>"` / `"webdav:<profile id>"` /
+ * `"s3:<profile id>"`, so every file operation (including cross-backend copy/move) works
+ * on it like on any other backend.
+ *
+ * Secrets are never written to the config: S3 access keys and saved SFTP / WebDAV passwords
+ * and key passphrases are sealed with the vault key, so using them requires an unlocked vault.
+ * Without a saved secret the password is asked for at connect time.
+ */
+
+use super::disk_usage::DISK_USAGE_CACHE;
+use super::vault::VAULT_FS;
+use super::{ApiError, ApiResult};
+use crate::api::s3_fs::{S3Credentials, S3FileSystem};
+use crate::api::sftp_fs::{SftpConnectError, SftpFileSystem};
+use crate::api::virtual_fs::VirtualFileSystem;
+use crate::api::webdav_fs::{is_insecure_basic, WebDavFileSystem};
+use crate::config::{S3Profile, SftpAuth, SftpProfile, WebDavAuth, WebDavProfile};
+use crate::state::APP_CONFIG;
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Префикс `panel_fs` для SFTP-подключений
+pub const SFTP_PREFIX: &str = "sftp:";
+
+/// Служебный файл хранилища с паролями SFTP (JSON `id профиля → пароль или парольная фраза`)
+const SFTP_SECRETS_FILE: &str = "sftp-secrets.bin";
+
+/// Active SFTP sessions by `panel_fs` label
+pub static SFTP_SESSIONS: Lazy<Arc<Mutex<HashMap<String, SftpFileSystem>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// `panel_fs` профиля
+pub fn sftp_label(profile_id: &str) -> String {
+    format!("{}{}", SFTP_PREFIX, profile_id)
+}
+
+/// Filesystem for a `sftp:` label; not connected sessions fail every call with `Locked`
+pub(crate) fn sftp_filesystem(label: &str) -> SftpFileSystem {
+    SFTP_SESSIONS
+        .lock()
+        .unwrap()
+        .get(label)
+        .cloned()
+        .unwrap_or_else(|| SftpFileSystem::disconnected(label))
+}
+
+/// Префикс `panel_fs` для WebDAV-подключений
+pub const WEBDAV_PREFIX: &str = "webdav:";
+
+/// Служебный файл хранилища с паролями WebDAV (JSON `id профиля → пароль`)
+const WEBDAV_SECRETS_FILE: &str = "webdav-secrets.bin";
+
+/// Active WebDAV sessions by `panel_fs` label
+pub static WEBDAV_SESSIONS: Lazy<Arc<Mutex<HashMap<String, WebDavFileSystem>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// `panel_fs` WebDAV-профиля
+pub fn webdav_label(profile_id: &str) -> String {
+    format!("{}{}", WEBDAV_PREFIX, profile_id)
+}
+
+/// Filesystem for a `webdav:` label; not connected sessions fail every call with `Locked`
+pub(crate) fn webdav_filesystem(label: &str) -> WebDavFileSystem {
+    WEBDAV_SESSIONS
+        .lock()
+        .unwrap()
+        .get(label)
+        .cloned()
+        .unwrap_or_else(|| WebDavFileSystem::disconnected(label))
+}
+
+/// Префикс `panel_fs` для бакетов S3
+pub const S3_PREFIX: &str = "s3:";
+
+/// Active S3 sessions by `panel_fs` label
+pub static S3_SESSIONS: Lazy<Arc<Mutex<HashMap<String, S3FileSystem>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// Служебный файл хранилища с ключами S3 (JSON `id профиля → ключи`, зашифрован ключом vault)
+const S3_CREDENTIALS_FILE: &str = "s3-credentials.bin";
+
+/// `panel_fs` S3-профиля
+pub fn s3_label(profile_id: &str) -> String {
+    format!("{}{}", S3_PREFIX, profile_id)
+}
+
+/// Filesystem for a `s3:` label; not connected sessions fail every call with `Locked`
+pub(crate) fn s3_filesystem(label: &str) -> S3FileSystem {
+    S3_SESSIONS
+        .lock()
+        .unwrap()
+        .get(label)
+        .cloned()
+        .unwrap_or_else(|| S3FileSystem::disconnected(label))
+}
+
+/// Close all S3 sessions: they hold keys taken from the vault, so they end when it locks
+pub(crate) fn close_s3_sessions() {
+    let labels: Vec<String> = S3_SESSIONS.lock().unwrap().drain().map(|(label, _)| label).collect();
+    for label in labels {
+        tracing::info!("Closing S3 session {}", label);
+        DISK_USAGE_CACHE.forget_backend(&label);
+    }
+}
+
+/// Main vault instance; reading and writing sealed files fails with `Locked` while it is locked
+fn vault() -> ApiResult<VirtualFileSystem> {
+    VAULT_FS.lock().unwrap().clone().ok_or_else(|| ApiError::Locked {
+        message: "Unlock the vault to access saved credentials".to_string(),
+    })
+}
+
+/// Секреты профилей из служебного файла хранилища (`id профиля → секрет`)
+fn load_secrets<T: DeserializeOwned>(vault: &VirtualFileSystem, file: &str) -> ApiResult<HashMap<String, T>> {
+    match vault.read_sealed_file(file)? {
+        Some(data) => serde_json::from_slice(&data).map_err(|e| ApiError::Internal {
+            message: format!("Corrupted credentials in {}: {}", file, e),
+        }),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn store_secrets<T: Serialize>(vault: &VirtualFileSystem, file: &str, secrets: &HashMap<String, T>) -> ApiResult<()> {
+    let data = serde_json::to_vec(secrets).map_err(|e| ApiError::Internal { message: e.to_string() })?;
+    vault.write_sealed_file(file, &data)?;
+    Ok(())
+}
+
+/// Запечатать секрет профиля; хранилище должно быть разблокировано
+fn seal_secret<T: Serialize + DeserializeOwned>(file: &str, id: &str, secret: T) -> ApiResult<()> {
+    let vault = vault()?;
+    let mut stored: HashMap<String, T> = load_secrets(&vault, file)?;
+    stored.insert(id.to_string(), secret);
+    store_secrets(&vault, file, &stored)
+}
+
+/// Секрет профиля из хранилища
+fn unseal_secret<T: DeserializeOwned>(file: &str, id: &str) -> ApiResult<Option<T>> {
+    Ok(load_secrets(&vault()?, file)?.remove(id))
+}
+
+/// Применить секрет из запроса сохранения профиля; возвращает, есть ли сохраненный секрет.
+/// `None` оставляет прежний (`kept`), пустая строка удаляет его
+fn update_secret(file: &str, id: &str, secret: Option<String>, kept: bool) -> ApiResult<bool> {
+    match secret {
+        Some(secret) if !secret.is_empty() => {
+            seal_secret(file, id, secret)?;
+            Ok(true)
+        }
+        Some(_) => {
+            forget_secret(file, id);
+            Ok(false)
+        }
+        None => Ok(kept),
+    }
+}
+
+/// Удалить секрет профиля. Заблокированное хранилище оставит его зашифрованным —
+/// без профиля он не используется
+fn forget_secret(file: &str, id: &str) {
+    let removed = vault().and_then(|vault| {
+        let mut stored: HashMap<String, serde_json::Value> = load_secrets(&vault, file)?;
+        if stored.remove(id).is_some() {
+            store_secrets(&vault, file, &stored)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = removed {
+        tracing::warn!("Credentials of {} were not removed: {}", id, e);
+    }
+}
+
+/// Открытое подключение
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct RemoteSession {
+    pub profile_id: String,
+    pub name: String,
+    /// Значение `panel_fs` для панелей и операций
+    pub panel_fs: String,
+    /// Домашняя папка на сервере — стартовый путь панели
+    pub home: String,
+}
+
+/// Service for remote filesystem connections
+pub struct RemoteService;
+
+impl RemoteService {
+    pub fn new() -> Self {
+        tracing::debug!("Initializing RemoteService");
+        Self
+    }
+
+    /// Saved SFTP profiles (secrets are never returned)
+    pub fn list_sftp_profiles(&self) -> ApiResult<Vec<SftpProfile>> {
+        Ok(APP_CONFIG.read().unwrap().sftp_profiles.clone())
+    }
+
+    /// Create or update a profile; a new profile gets an id
+    ///
+    /// A password / key passphrase in `auth` is sealed in the vault (which must be unlocked).
+    pub fn save_sftp_profile(&self, mut profile: SftpProfile) -> ApiResult<SftpProfile> {
+        if profile.host.trim().is_empty() || profile.username.trim().is_empty() {
+            return Err(ApiError::ValidationError {
+                message: "Host and username must be specified".to_string(),
+            });
+        }
+        if let SftpAuth::Key { private_key_path, .. } = &profile.auth {
+            if private_key_path.trim().is_empty() {
+                return Err(ApiError::ValidationError {
+                    message: "Private key path must be specified".to_string(),
+                });
+            }
+        }
+        if profile.id.is_empty() {
+            profile.id = format!("sftp-{}", uuid::Uuid::new_v4());
+        }
+        if profile.name.is_empty() {
+            profile.name = format!("{}@{}", profile.username, profile.host);
+        }
+        tracing::info!("Saving SFTP profile {} ({})", profile.id, profile.name);
+
+        // Секрет сохраняется первым: без разблокированного хранилища профиль не меняется
+        let existing = self.sftp_profile(&profile.id).ok();
+        // Сохраненный секрет подходит только к тому же способу входа
+        let kept = existing.as_ref().is_some_and(|existing| {
+            existing.secret_saved && std::mem::discriminant(&existing.auth) == std::mem::discriminant(&profile.auth)
+        });
+        let secret = profile.auth.take_secret();
+        profile.secret_saved = update_secret(SFTP_SECRETS_FILE, &profile.id, secret, kept)?;
+        // Закрепленный ключ остается, пока не сменился сервер
+        if let Some(existing) = &existing {
+            if profile.host_key.is_none() && existing.host == profile.host && existing.port == profile.port {
+                profile.host_key = existing.host_key.clone();
+            }
+        }
+
+        let mut config = APP_CONFIG.write().unwrap();
+        match config.sftp_profiles.iter_mut().find(|p| p.id == profile.id) {
+            Some(existing) => *existing = profile.clone(),
+            None => config.sftp_profiles.push(profile.clone()),
+        }
+        drop(config);
+
+        let config = APP_CONFIG.read().unwrap();
+        config.save().map_err(|e| {
+            tracing::error!("Failed to save config: {}", e);
+            ApiError::OperationFailed {
+                message: format!("Failed to save SFTP profile: {}", e),
+            }
+        })?;
+
+        Ok(profile)
+    }
+
+    /// Remove a profile and its sealed secret; its open session is closed
+    pub fn delete_sftp_profile(&self, id: &str) -> ApiResult<()> {
+        tracing::info!("Removing SFTP profile: {}", id);
+        let mut config = APP_CONFIG.write().unwrap();
+        let before = config.sftp_profiles.len();
+        config.sftp_profiles.retain(|p| p.id != id);
+        if config.sftp_profiles.len() == before {
+            return Err(ApiError::NotFound {
+                resource: format!("SFTP profile '{}'", id),
+            });
+        }
+        drop(config);
+
+        let config = APP_CONFIG.read().unwrap();
+        config.save().map_err(|e| {
+            tracing::error!("Failed to save config: {}", e);
+            ApiError::OperationFailed {
+                message: format!("Failed to remove SFTP profile: {}", e),
+            }
+        })?;
+        drop(config);
+
+        forget_secret(SFTP_SECRETS_FILE, id);
+        self.disconnect_sftp(id);
+        Ok(())
+    }
+
+    fn sftp_profile(&self, id: &str) -> ApiResult<SftpProfile> {
+        APP_CONFIG
+            .read()
+            .unwrap()
+            .sftp_profiles
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| ApiError::NotFound {
+                resource: format!("SFTP profile '{}'", id),
+            })
+    }
+
+    /// Connect a saved profile (reconnects if already connected)
+    ///
+    /// `secret` is used instead of the password / key passphrase sealed in the vault. A server whose key
+    /// is neither pinned in the profile nor in `known_hosts` fails with `UnknownHostKey`
+    /// until the user confirms its fingerprint as `host_key`; the key is then pinned.
+    pub fn connect_sftp(&self, id: &str, secret: Option<&str>, host_key: Option<&str>) -> ApiResult<RemoteSession> {
+        let profile = self.sftp_profile(id)?;
+        let label = sftp_label(id);
+
+        let saved = match secret {
+            None if profile.secret_saved => unseal_secret::<String>(SFTP_SECRETS_FILE, id)?,
+            _ => None,
+        };
+        let secret = secret.or(saved.as_deref());
+
+        let fs = SftpFileSystem::connect(&label, &profile, secret, host_key).map_err(|err| match err {
+            SftpConnectError::UnknownHostKey { fingerprint } => {
+                tracing::warn!("SFTP server {}:{} has an unknown host key {}", profile.host, profile.port, fingerprint);
+                ApiError::UnknownHostKey {
+                    host: format!("{}:{}", profile.host, profile.port),
+                    fingerprint,
+                }
+            }
+            SftpConnectError::Failed(err) => {
+                tracing::error!("SFTP connection to {} failed: {}", profile.host, err.message);
+                ApiError::from(err)
+            }
+        })?;
+        if profile.host_key.is_none() {
+            self.pin_sftp_host_key(id, fs.host_key().unwrap_or_default());
+        }
+        let session = RemoteSession {
+            profile_id: profile.id.clone(),
+            name: profile.name.clone(),
+            panel_fs: label.clone(),
+            home: fs.home().to_string(),
+        };
+
+        if let Some(previous) = SFTP_SESSIONS.lock().unwrap().insert(label.clone(), fs) {
+            previous.disconnect();
+        }
+        DISK_USAGE_CACHE.forget_backend(&label);
+
+        tracing::info!("SFTP session {} connected", label);
+        Ok(session)
+    }
+
+    /// Запомнить ключ сервера в профиле: следующие подключения примут только его
+    fn pin_sftp_host_key(&self, id: &str, fingerprint: &str) {
+        let mut config = APP_CONFIG.write().unwrap();
+        let Some(profile) = config.sftp_profiles.iter_mut().find(|p| p.id == id) else {
+            return;
+        };
+        profile.host_key = Some(fingerprint.to_string());
+        tracing::info!("Pinned host key {} for SFTP profile {}", fingerprint, id);
+        if let Err(e) = config.save() {
+            tracing::warn!("Failed to save the host key of {}: {}", id, e);
+        }
+    }
+
+    /// Close the session of a profile; no-op if it is not connected
+    pub fn disconnect_sftp(&self, id: &str) {
+        let label = sftp_label(id);
+        if let Some(fs) = SFTP_SESSIONS.lock().unwrap().remove(&label) {
+            tracing::info!("Closing SFTP session {}", label);
+            fs.disconnect();
+        }
+        DISK_USAGE_CACHE.forget_backend(&label);
+    }
+
+    /// Saved WebDAV profiles (passwords are never returned)
+    pub fn list_webdav_profiles(&self) -> ApiResult<Vec<WebDavProfile>> {
+        Ok(APP_CONFIG.read().unwrap().webdav_profiles.clone())
+    }
+
+    /// Create or update a WebDAV profile; a new profile gets an id
+    ///
+    /// A password in `auth` is sealed in the vault (which must be unlocked). Basic auth over
+    /// http:// is refused unless `allow_insecure_basic` is set.
+    pub fn save_webdav_profile(&self, mut profile: WebDavProfile) -> ApiResult<WebDavProfile> {
+        profile.url = profile.url.trim().trim_end_matches('/').to_string();
+        if !(profile.url.starts_with("http://") || profile.url.starts_with("https://")) {
+            return Err(ApiError::ValidationError {
+                message: "WebDAV URL must start with http:// or https://".to_string(),
+            });
+        }
+        if let WebDavAuth::Basic { username, .. } | WebDavAuth::Digest { username, .. } = &profile.auth {
+            if username.trim().is_empty() {
+                return Err(ApiError::ValidationError {
+                    message: "Username must be specified".to_string(),
+                });
+            }
+        }
+        if is_insecure_basic(&profile) {
+            return Err(ApiError::ValidationError {
+                message: "Basic authentication over http:// sends the password in clear text; use https:// or allow it explicitly"
+                    .to_string(),
+            });
+        }
+        if profile.id.is_empty() {
+            profile.id = format!("webdav-{}", uuid::Uuid::new_v4());
+        }
+        if profile.name.is_empty() {
+            profile.name = profile.url.split("://").nth(1).unwrap_or(&profile.url).to_string();
+        }
+        tracing::info!("Saving WebDAV profile {} ({})", profile.id, profile.name);
+
+        // Пароль сохраняется первым: без разблокированного хранилища профиль не меняется
+        let kept = self.webdav_profile(&profile.id).is_ok_and(|existing| {
+            existing.secret_saved && std::mem::discriminant(&existing.auth) == std::mem::discriminant(&profile.auth)
+        });
+        let secret = profile.auth.take_secret();
+        profile.secret_saved = update_secret(WEBDAV_SECRETS_FILE, &profile.id, secret, kept)?;
+
+        let mut config = APP_CONFIG.write().unwrap();
+        match config.webdav_profiles.iter_mut().find(|p| p.id == profile.id) {
+            Some(existing) => *existing = profile.clone(),
+            None => config.webdav_profiles.push(profile.clone()),
+        }
+        drop(config);
+
+        let config = APP_CONFIG.read().unwrap();
+        config.save().map_err(|e| {
+            tracing::error!("Failed to save config: {}", e);
+            ApiError::OperationFailed {
+                message: format!("Failed to save WebDAV profile: {}", e),
+            }
+        })?;
+
+        Ok(profile)
+    }
+
+    /// Remove a WebDAV profile and its sealed password; its open session is closed
+    pub fn delete_webdav_profile(&self, id: &str) -> ApiResult<()> {
+        tracing::info!("Removing WebDAV profile: {}", id);
+        let mut config = APP_CONFIG.write().unwrap();
+        let before = config.webdav_profiles.len();
+        config.webdav_profiles.retain(|p| p.id != id);
+        if config.webdav_profiles.len() == before {
+            return Err(ApiError::NotFound {
+                resource: format!("WebDAV profile '{}'", id),
+            });
+        }
+        drop(config);
+
+        let config = APP_CONFIG.read().unwrap();
+        config.save().map_err(|e| {
+            tracing::error!("Failed to save config: {}", e);
+            ApiError::OperationFailed {
+                message: format!("Failed to remove WebDAV profile: {}", e),
+            }
+        })?;
+        drop(config);
+
+        forget_secret(WEBDAV_SECRETS_FILE, id);
+        self.disconnect_webdav(id);
+        Ok(())
+    }
+
+    fn webdav_profile(&self, id: &str) -> ApiResult<WebDavProfile> {
+        APP_CONFIG
+            .read()
+            .unwrap()
+            .webdav_profiles
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| ApiError::NotFound {
+                resource: format!("WebDAV profile '{}'", id),
+            })
+    }
+
+    /// Connect a saved WebDAV profile (reconnects if already connected)
+    ///
+    /// `secret` is used instead of the password sealed in the vault.
+    pub fn connect_webdav(&self, id: &str, secret: Option<&str>) -> ApiResult<RemoteSession> {
+        let profile = self.webdav_profile(id)?;
+        let label = webdav_label(id);
+
+        let saved = match secret {
+            None if profile.secret_saved => unseal_secret::<String>(WEBDAV_SECRETS_FILE, id)?,
+            _ => None,
+        };
+        let secret = secret.or(saved.as_deref());
+
+        let fs = WebDavFileSystem::connect(&label, &profile, secret).map_err(|err| {
+            tracing::error!("WebDAV connection to {} failed: {}", profile.url, err.message);
+            ApiError::from(err)
+        })?;
+        let session = RemoteSession {
+            profile_id: profile.id.clone(),
+            name: profile.name.clone(),
+            panel_fs: label.clone(),
+            home: "/".to_string(),
+        };
+
+        WEBDAV_SESSIONS.lock().unwrap().insert(label.clone(), fs);
+        DISK_USAGE_CACHE.forget_backend(&label);
+
+        tracing::info!("WebDAV session {} connected", label);
+        Ok(session)
+    }
+
+    /// Close the WebDAV session of a profile; no-op if it is not connected
+    pub fn disconnect_webdav(&self, id: &str) {
+        let label = webdav_label(id);
+        if WEBDAV_SESSIONS.lock().unwrap().remove(&label).is_some() {
+            tracing::info!("Closing WebDAV session {}", label);
+        }
+        DISK_USAGE_CACHE.forget_backend(&label);
+    }
+
+    /// Saved S3 profiles (without credentials)
+    pub fn list_s3_profiles(&self) -> ApiResult<Vec<S3Profile>> {
+        Ok(APP_CONFIG.read().unwrap().s3_profiles.clone())
+    }
+
+    /// Create or update an S3 profile; a new profile gets an id
+    ///
+    /// `credentials` are sealed in the vault (which must be unlocked); `None` keeps the
+    /// stored ones, so it is only allowed for an existing profile.
+    pub fn save_s3_profile(&self, mut profile: S3Profile, credentials: Option<S3Credentials>) -> ApiResult<S3Profile> {
+        profile.endpoint = profile.endpoint.trim().trim_end_matches('/').to_string();
+        if !(profile.endpoint.starts_with("http://") || profile.endpoint.starts_with("https://")) {
+            return Err(ApiError::ValidationError {
+                message: "S3 endpoint must start with http:// or https://".to_string(),
+            });
+        }
+        if profile.bucket.trim().is_empty() || profile.region.trim().is_empty() {
+            return Err(ApiError::ValidationError {
+                message: "Bucket and region must be specified".to_string(),
+            });
+        }
+        if let Some(credentials) = &credentials {
+            if credentials.access_key_id.is_empty() || credentials.secret_access_key.is_empty() {
+                return Err(ApiError::ValidationError {
+                    message: "Access key and secret key must be specified".to_string(),
+                });
+            }
+        }
+        if profile.id.is_empty() {
+            if credentials.is_none() {
+                return Err(ApiError::ValidationError {
+                    message: "Credentials are required for a new S3 profile".to_string(),
+                });
+            }
+            profile.id = format!("s3-{}", uuid::Uuid::new_v4());
+        }
+        if profile.name.is_empty() {
+            profile.name = profile.bucket.clone();
+        }
+        tracing::info!("Saving S3 profile {} ({})", profile.id, profile.name);
+
+        // Ключи сохраняются первыми: без разблокированного хранилища профиль не создается
+        if let Some(credentials) = credentials {
+            seal_secret(S3_CREDENTIALS_FILE, &profile.id, credentials)?;
+        }
+
+        let mut config = APP_CONFIG.write().unwrap();
+        match config.s3_profiles.iter_mut().find(|p| p.id == profile.id) {
+            Some(existing) => *existing = profile.clone(),
+            None => config.s3_profiles.push(profile.clone()),
+        }
+        drop(config);
+
+        let config = APP_CONFIG.read().unwrap();
+        config.save().map_err(|e| {
+            tracing::error!("Failed to save config: {}", e);
+            ApiError::OperationFailed {
+                message: format!("Failed to save S3 profile: {}", e),
+            }
+        })?;
+
+        Ok(profile)
+    }
+
+    /// Remove an S3 profile and its sealed credentials; its open session is closed
+    pub fn delete_s3_profile(&self, id: &str) -> ApiResult<()> {
+        tracing::info!("Removing S3 profile: {}", id);
+        let mut config = APP_CONFIG.write().unwrap();
+        let before = config.s3_profiles.len();
+        config.s3_profiles.retain(|p| p.id != id);
+        if config.s3_profiles.len() == before {
+            return Err(ApiError::NotFound {
+                resource: format!("S3 profile '{}'", id),
+            });
+        }
+        drop(config);
+
+        let config = APP_CONFIG.read().unwrap();
+        config.save().map_err(|e| {
+            tracing::error!("Failed to save config: {}", e);
+            ApiError::OperationFailed {
+                message: format!("Failed to remove S3 profile: {}", e),
+            }
+        })?;
+        drop(config);
+
+        forget_secret(S3_CREDENTIALS_FILE, id);
+        self.disconnect_s3(id);
+        Ok(())
+    }
+
+    fn s3_profile(&self, id: &str) -> ApiResult<S3Profile> {
+        APP_CONFIG
+            .read()
+            .unwrap()
+            .s3_profiles
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| ApiError::NotFound {
+                resource: format!("S3 profile '{}'", id),
+            })
+    }
+
+    /// Connect a saved S3 profile with credentials from the vault (reconnects if already connected)
+    pub fn connect_s3(&self, id: &str) -> ApiResult<RemoteSession> {
+        let profile = self.s3_profile(id)?;
+        let label = s3_label(id);
+
+        let credentials = unseal_secret::<S3Credentials>(S3_CREDENTIALS_FILE, id)?
+            .ok_or_else(|| ApiError::NotFound {
+                resource: format!("S3 credentials for '{}'", id),
+            })?;
+        let fs = S3FileSystem::connect(&label, &profile, credentials).map_err(|err| {
+            tracing::error!("S3 connection to {} failed: {}", profile.endpoint, err.message);
+            ApiError::from(err)
+        })?;
+        let session = RemoteSession {
+            profile_id: profile.id.clone(),
+            name: profile.name.clone(),
+            panel_fs: label.clone(),
+            home: "/".to_string(),
+        };
+
+        S3_SESSIONS.lock().unwrap().insert(label.clone(), fs);
+        DISK_USAGE_CACHE.forget_backend(&label);
+
+        tracing::info!("S3 session {} connected", label);
+        Ok(session)
+    }
+
+    /// Close the S3 session of a profile; no-op if it is not connected
+    pub fn disconnect_s3(&self, id: &str) {
+        let label = s3_label(id);
+        if S3_SESSIONS.lock().unwrap().remove(&label).is_some() {
+            tracing::info!("Closing S3 session {}", label);
+        }
+        DISK_USAGE_CACHE.forget_backend(&label);
+    }
+
+    /// Open sessions of all protocols
+    pub fn sessions(&self) -> Vec<RemoteSession> {
+        let config = APP_CONFIG.read().unwrap();
+        let session = |label: &str, prefix: &str, name: Option<&String>, home: &str| {
+            let profile_id = label.trim_start_matches(prefix).to_string();
+            RemoteSession {
+                name: name.cloned().unwrap_or_else(|| profile_id.clone()),
+                profile_id,
+                panel_fs: label.to_string(),
+                home: home.to_string(),
+            }
+        };
+
+        let mut result: Vec<RemoteSession> = SFTP_SESSIONS
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(label, fs)| {
+                let profile = config.sftp_profiles.iter().find(|p| sftp_label(&p.id) == *label);
+                session(label, SFTP_PREFIX, profile.map(|p| &p.name), fs.home())
+            })
+            .collect();
+        result.extend(WEBDAV_SESSIONS.lock().unwrap().keys().map(|label| {
+            let profile = config.webdav_profiles.iter().find(|p| webdav_label(&p.id) == *label);
+            session(label, WEBDAV_PREFIX, profile.map(|p| &p.name), "/")
+        }));
+        result.extend(S3_SESSIONS.lock().unwrap().keys().map(|label| {
+            let profile = config.s3_profiles.iter().find(|p| s3_label(&p.id) == *label);
+            session(label, S3_PREFIX, profile.map(|p| &p.name), "/")
+        }));
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+        result
+    }
+}
@@ -0,0 +1,619 @@
+/**
+ * Search Service
+ *
+ * Recursive filename and content search over any FileSystem backend
+ * (real FS, vault, temporary sessions).
+ * Results are delivered in batches while the walk is running; the walk stops as soon as
+ * the operation's CancellationToken is cancelled.
+ */
+
+use super::grep::{grep_filesystem, ContentMatch, ContentSearchQuery, ContentSearchSummary};
+use super::journal::join_path;
+use super::{ApiError, ApiResult, API};
+use crate::core::{EntryTypeFilter, ErrorKind, FileSystem, FileSystemEntry, ListOptions};
+use crate::progress::CancellationToken;
+use crate::state::APP_CONFIG;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Максимальное число результатов в одной пачке
+const BATCH_SIZE: usize = 64;
+
+/// Как часто отдавать неполную пачку
+const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// .gitignore больше этого размера не читается
+const MAX_GITIGNORE_SIZE: u64 = 1024 * 1024;
+
+/// Способ сопоставления имени
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum NameMatchMode {
+    /// Подстрока имени
+    #[default]
+    Substring,
+    Glob,
+    Regex,
+    /// Символы шаблона встречаются в имени по порядку (как в fzf)
+    Fuzzy,
+}
+
+/// Параметры поиска по имени
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct SearchQuery {
+    /// Директория, с которой начинается обход
+    pub root: String,
+    /// Шаблон имени; пустой шаблон совпадает с любым именем
+    #[serde(default)]
+    pub pattern: String,
+    #[serde(default)]
+    pub match_mode: NameMatchMode,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub entry_type: Option<EntryTypeFilter>,
+    #[serde(default)]
+    pub min_size: Option<u64>,
+    #[serde(default)]
+    pub max_size: Option<u64>,
+    /// Время изменения не раньше (секунды UNIX)
+    #[serde(default)]
+    pub modified_after: Option<u64>,
+    /// Время изменения не позже (секунды UNIX)
+    #[serde(default)]
+    pub modified_before: Option<u64>,
+    /// Включать скрытые файлы; `None` — по настройке `show_hidden_files`
+    #[serde(default)]
+    pub include_hidden: Option<bool>,
+    /// Пропускать пути, исключенные .gitignore (и саму папку .git)
+    #[serde(default)]
+    pub respect_gitignore: bool,
+    /// Глубина обхода; 0 — только содержимое `root`
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// Остановить поиск после стольких совпадений
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+/// Найденная запись
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct SearchHit {
+    pub entry: FileSystemEntry,
+    /// Оценка нечеткого совпадения (больше — лучше); 0 для остальных режимов
+    pub score: i64,
+}
+
+/// Итог поиска
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct SearchSummary {
+    pub matched: u64,
+    /// Сколько записей просмотрено
+    pub scanned: u64,
+    pub cancelled: bool,
+    /// Поиск остановлен по `max_results`
+    pub truncated: bool,
+}
+
+/// Скомпилированный шаблон имени
+enum NameMatcher {
+    Any,
+    Substring { needle: String, case_sensitive: bool },
+    Pattern(crate::core::listing::NameFilter),
+    Fuzzy { pattern: String, case_sensitive: bool },
+}
+
+impl NameMatcher {
+    fn new(query: &SearchQuery) -> ApiResult<Self> {
+        if query.pattern.is_empty() {
+            return Ok(NameMatcher::Any);
+        }
+
+        let case_sensitive = query.case_sensitive;
+        let matcher = match query.match_mode {
+            NameMatchMode::Substring => NameMatcher::Substring {
+                needle: fold_case(&query.pattern, case_sensitive),
+                case_sensitive,
+            },
+            NameMatchMode::Glob | NameMatchMode::Regex => {
+                let is_glob = query.match_mode == NameMatchMode::Glob;
+                let options = ListOptions {
+                    glob: is_glob.then(|| query.pattern.clone()),
+                    regex: (!is_glob).then(|| query.pattern.clone()),
+                    case_sensitive,
+                    ..Default::default()
+                };
+                NameMatcher::Pattern(options.name_filter().map_err(ApiError::from)?)
+            }
+            NameMatchMode::Fuzzy => NameMatcher::Fuzzy {
+                pattern: query.pattern.clone(),
+                case_sensitive,
+            },
+        };
+
+        Ok(matcher)
+    }
+
+    /// Оценка совпадения или `None`, если имя не подходит
+    fn score(&self, name: &str) -> Option<i64> {
+        match self {
+            NameMatcher::Any => Some(0),
+            NameMatcher::Substring { needle, case_sensitive } => {
+                fold_case(name, *case_sensitive).contains(needle.as_str()).then_some(0)
+            }
+            NameMatcher::Pattern(filter) => filter.matches(name).then_some(0),
+            NameMatcher::Fuzzy { pattern, case_sensitive } => fuzzy_score(pattern, name, *case_sensitive),
+        }
+    }
+}
+
+fn fold_case(value: &str, case_sensitive: bool) -> String {
+    if case_sensitive {
+        value.to_string()
+    } else {
+        value.to_lowercase()
+    }
+}
+
+/// Нечеткое сопоставление: все символы шаблона должны встретиться в имени по порядку.
+///
+/// Совпадения подряд, в начале имени и в начале слов (`_`, `-`, `.`, пробел,
+/// camelCase) повышают оценку, пропуски между совпадениями — понижают.
+pub fn fuzzy_score(pattern: &str, name: &str, case_sensitive: bool) -> Option<i64> {
+    let fold = |c: char| {
+        if case_sensitive {
+            c
+        } else {
+            c.to_lowercase().next().unwrap_or(c)
+        }
+    };
+
+    let chars: Vec<char> = name.chars().collect();
+    let mut score = 0i64;
+    let mut next = 0usize;
+    let mut previous: Option<usize> = None;
+
+    for wanted in pattern.chars().map(fold) {
+        let found = next + chars[next..].iter().position(|&c| fold(c) == wanted)?;
+
+        score += 1;
+        if found == 0 {
+            score += 8;
+        } else {
+            let before = chars[found - 1];
+            if matches!(before, '_' | '-' | '.' | ' ') || (before.is_lowercase() && chars[found].is_uppercase()) {
+                score += 4;
+            }
+        }
+
+        match previous {
+            Some(prev) if prev + 1 == found => score += 6,
+            Some(prev) => score -= ((found - prev - 1) as i64).min(5),
+            None => score -= (found as i64).min(5),
+        }
+
+        previous = Some(found);
+        next = found + 1;
+    }
+
+    Some(score)
+}
+
+/// Правила .gitignore, действующие в директории (от корня обхода вглубь)
+#[derive(Clone, Default)]
+struct IgnoreStack(Vec<Arc<Gitignore>>);
+
+impl IgnoreStack {
+    /// Добавляет .gitignore из `dir`, если он есть (даже когда скрытые файлы не показываются)
+    fn enter(&self, fs: &dyn FileSystem, dir: &str) -> Self {
+        let mut stack = self.clone();
+        let path = join_path(dir, ".gitignore");
+
+        let content = match fs.read_file_content(&path, Some(MAX_GITIGNORE_SIZE)) {
+            Ok(content) => content,
+            Err(err) if err.kind == ErrorKind::NotFound => return stack,
+            Err(err) => {
+                tracing::debug!("Skipping unreadable {}: {}", path, err);
+                return stack;
+            }
+        };
+
+        let mut builder = GitignoreBuilder::new(dir);
+        for line in content.lines() {
+            // Некорректные строки игнорируются так же, как это делает git
+            let _ = builder.add_line(None, line);
+        }
+        match builder.build() {
+            Ok(gitignore) => stack.0.push(Arc::new(gitignore)),
+            Err(err) => tracing::debug!("Invalid {}: {}", path, err),
+        }
+
+        stack
+    }
+
+    /// Ближайший к файлу .gitignore, упоминающий путь, решает (включая `!`-исключения)
+    fn is_ignored(&self, entry: &FileSystemEntry) -> bool {
+        let path = Path::new(&entry.path);
+        for gitignore in self.0.iter().rev() {
+            let matched = gitignore.matched(path, entry.is_dir);
+            if matched.is_ignore() {
+                return true;
+            }
+            if matched.is_whitelist() {
+                return false;
+            }
+        }
+        false
+    }
+}
+
+/// Накопитель результатов: отдает пачки по размеру или по времени
+pub(crate) struct Batcher<T, F: FnMut(Vec<T>)> {
+    items: Vec<T>,
+    last_flush: Instant,
+    on_batch: F,
+}
+
+impl<T, F: FnMut(Vec<T>)> Batcher<T, F> {
+    pub(crate) fn new(on_batch: F) -> Self {
+        Self {
+            items: Vec::new(),
+            last_flush: Instant::now(),
+            on_batch,
+        }
+    }
+
+    pub(crate) fn push(&mut self, item: T) {
+        self.items.push(item);
+        if self.items.len() >= BATCH_SIZE || self.last_flush.elapsed() >= BATCH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    pub(crate) fn flush(&mut self) {
+        self.last_flush = Instant::now();
+        if !self.items.is_empty() {
+            (self.on_batch)(std::mem::take(&mut self.items));
+        }
+    }
+}
+
+/// Параметры обхода дерева
+pub(crate) struct WalkOptions {
+    pub include_hidden: Option<bool>,
+    pub respect_gitignore: bool,
+    pub max_depth: Option<usize>,
+}
+
+/// Решение посетителя после очередной записи
+pub(crate) enum Visit {
+    Continue,
+    Stop,
+}
+
+/// Итог обхода
+#[derive(Default)]
+pub(crate) struct WalkStats {
+    pub scanned: u64,
+    pub cancelled: bool,
+}
+
+/// Обход `fs` в глубину начиная с `root`; `visit` вызывается для каждой записи
+///
+/// Корень должен читаться; нечитаемые вложенные директории пропускаются.
+/// Ссылки на директории не раскрываются, чтобы не зациклиться.
+pub(crate) fn walk_tree<V>(
+    fs: &dyn FileSystem,
+    root: &str,
+    options: &WalkOptions,
+    token: &CancellationToken,
+    mut visit: V,
+) -> ApiResult<WalkStats>
+where
+    V: FnMut(FileSystemEntry) -> Visit,
+{
+    let list_options = ListOptions {
+        show_hidden: Some(
+            options
+                .include_hidden
+                .unwrap_or_else(|| APP_CONFIG.read().unwrap().show_hidden_files),
+        ),
+        ..Default::default()
+    };
+
+    let mut stats = WalkStats::default();
+
+    let root_entries = fs
+        .read_directory_with_options(root, &list_options)
+        .map_err(ApiError::from)?
+        .entries;
+
+    let mut stack = vec![(root.to_string(), 0usize, IgnoreStack::default(), Some(root_entries))];
+
+    'walk: while let Some((dir, depth, ignores, entries)) = stack.pop() {
+        let entries = match entries {
+            Some(entries) => entries,
+            None => match fs.read_directory_with_options(&dir, &list_options) {
+                Ok(page) => page.entries,
+                Err(err) => {
+                    tracing::debug!("Skipping unreadable directory {}: {}", dir, err);
+                    continue;
+                }
+            },
+        };
+
+        let ignores = if options.respect_gitignore {
+            ignores.enter(fs, &dir)
+        } else {
+            ignores
+        };
+
+        for entry in entries {
+            if token.is_cancelled() {
+                stats.cancelled = true;
+                break 'walk;
+            }
+            stats.scanned += 1;
+
+            if options.respect_gitignore && ((entry.is_dir && entry.name == ".git") || ignores.is_ignored(&entry)) {
+                continue;
+            }
+
+            let descend = entry.is_dir
+                && !entry.is_symlink
+                && options.max_depth.is_none_or(|max| depth < max);
+            if descend {
+                stack.push((entry.path.clone(), depth + 1, ignores.clone(), None));
+            }
+
+            if let Visit::Stop = visit(entry) {
+                break 'walk;
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Service for recursive filename search
+pub struct SearchService;
+
+impl SearchService {
+    pub fn new() -> Self {
+        tracing::debug!("Initializing SearchService");
+        Self
+    }
+
+    /// Search for entries under `query.root`
+    ///
+    /// # Arguments
+    /// * `query` - Name pattern and predicates
+    /// * `panel_fs` - Backend ("real", "virtual", vault window label or temporary session label)
+    /// * `token` - Cancellation token of the running operation
+    /// * `on_hits` - Receives results in batches while the walk is running
+    pub fn search<F>(
+        &self,
+        query: &SearchQuery,
+        panel_fs: Option<&str>,
+        token: &CancellationToken,
+        on_hits: F,
+    ) -> ApiResult<SearchSummary>
+    where
+        F: FnMut(Vec<SearchHit>),
+    {
+        tracing::info!("Searching '{}' under {} with backend: {:?}", query.pattern, query.root, panel_fs);
+
+        let instance = API.files.get_filesystem_by_backend(panel_fs);
+        search_filesystem(instance.as_trait(), query, token, on_hits)
+    }
+
+    /// Search file contents under `query.root`
+    ///
+    /// Binary files and files above `query.max_file_size` are skipped.
+    /// Matching lines are delivered to `on_matches` in batches while the walk is running.
+    pub fn grep<F>(
+        &self,
+        query: &ContentSearchQuery,
+        panel_fs: Option<&str>,
+        token: &CancellationToken,
+        on_matches: F,
+    ) -> ApiResult<ContentSearchSummary>
+    where
+        F: FnMut(Vec<ContentMatch>),
+    {
+        tracing::info!("Searching contents for '{}' under {} with backend: {:?}", query.pattern, query.root, panel_fs);
+
+        let instance = API.files.get_filesystem_by_backend(panel_fs);
+        grep_filesystem(instance.as_trait(), query, token, on_matches)
+    }
+}
+
+impl Default for SearchService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Обход `fs` в глубину начиная с `query.root`
+pub(crate) fn search_filesystem<F>(
+    fs: &dyn FileSystem,
+    query: &SearchQuery,
+    token: &CancellationToken,
+    on_hits: F,
+) -> ApiResult<SearchSummary>
+where
+    F: FnMut(Vec<SearchHit>),
+{
+    if let (Some(min), Some(max)) = (query.min_size, query.max_size) {
+        if min > max {
+            return Err(ApiError::ValidationError {
+                message: "minSize must not exceed maxSize".to_string(),
+            });
+        }
+    }
+
+    let matcher = NameMatcher::new(query)?;
+    let walk_options = WalkOptions {
+        include_hidden: query.include_hidden,
+        respect_gitignore: query.respect_gitignore,
+        max_depth: query.max_depth,
+    };
+
+    let mut summary = SearchSummary::default();
+    let mut batcher = Batcher::new(on_hits);
+
+    let stats = walk_tree(fs, &query.root, &walk_options, token, |entry| {
+        let Some(score) = matches_query(query, &matcher, &entry) else {
+            return Visit::Continue;
+        };
+
+        summary.matched += 1;
+        batcher.push(SearchHit { entry, score });
+
+        if query.max_results.is_some_and(|max| summary.matched as usize >= max) {
+            summary.truncated = true;
+            return Visit::Stop;
+        }
+        Visit::Continue
+    })?;
+
+    batcher.flush();
+    summary.scanned = stats.scanned;
+    summary.cancelled = stats.cancelled;
+
+    tracing::info!(
+        "Search finished: {} matches, {} scanned, cancelled: {}",
+        summary.matched,
+        summary.scanned,
+        summary.cancelled
+    );
+    Ok(summary)
+}
+
+fn matches_query(query: &SearchQuery, matcher: &NameMatcher, entry: &FileSystemEntry) -> Option<i64> {
+    let type_ok = match query.entry_type {
+        Some(EntryTypeFilter::Files) => entry.is_file,
+        Some(EntryTypeFilter::Directories) => entry.is_dir,
+        Some(EntryTypeFilter::Symlinks) => entry.is_symlink,
+        None => true,
+    };
+    if !type_ok {
+        return None;
+    }
+
+    // Размер есть только у файлов; директории с ограничением по размеру не проходят
+    if query.min_size.is_some() || query.max_size.is_some() {
+        let size = entry.size.filter(|_| entry.is_file)?;
+        if query.min_size.is_some_and(|min| size < min) || query.max_size.is_some_and(|max| size > max) {
+            return None;
+        }
+    }
+
+    if query.modified_after.is_some() || query.modified_before.is_some() {
+        let modified = entry.modified?;
+        if query.modified_after.is_some_and(|after| modified < after)
+            || query.modified_before.is_some_and(|before| modified > before)
+        {
+            return None;
+        }
+    }
+
+    matcher.score(&entry.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::temporary_fs::TemporaryFileSystem;
+
+    fn collect(fs: &dyn FileSystem, query: &SearchQuery) -> (Vec<String>, SearchSummary) {
+        let mut paths = Vec::new();
+        let summary = search_filesystem(fs, query, &CancellationToken::new(), |hits| {
+            paths.extend(hits.into_iter().map(|hit| hit.entry.path));
+        })
+        .unwrap();
+        paths.sort();
+        (paths, summary)
+    }
+
+    fn sample_fs() -> (tempfile::TempDir, TemporaryFileSystem) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/nested/search_service.rs"), "").unwrap();
+        std::fs::write(root.join("target/debug/build.rs"), "").unwrap();
+        std::fs::write(root.join("README.md"), "readme").unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+
+        let fs = TemporaryFileSystem::new(root.to_path_buf());
+        (dir, fs)
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts() {
+        assert!(fuzzy_score("ss", "search_service.rs", false).is_some());
+        assert!(fuzzy_score("xyz", "search_service.rs", false).is_none());
+
+        let boundary = fuzzy_score("ss", "search_service.rs", false).unwrap();
+        let scattered = fuzzy_score("ss", "assets", false).unwrap();
+        assert!(boundary > scattered);
+    }
+
+    #[test]
+    fn test_glob_search_is_recursive() {
+        let (_dir, fs) = sample_fs();
+        let query = SearchQuery {
+            root: "/".to_string(),
+            pattern: "*.rs".to_string(),
+            match_mode: NameMatchMode::Glob,
+            include_hidden: Some(true),
+            ..Default::default()
+        };
+
+        let (paths, summary) = collect(&fs, &query);
+        assert_eq!(paths, vec!["/src/main.rs", "/src/nested/search_service.rs", "/target/debug/build.rs"]);
+        assert_eq!(summary.matched, 3);
+        assert!(!summary.cancelled);
+    }
+
+    #[test]
+    fn test_gitignore_and_predicates() {
+        let (_dir, fs) = sample_fs();
+        let query = SearchQuery {
+            root: "/".to_string(),
+            pattern: "*.rs".to_string(),
+            match_mode: NameMatchMode::Glob,
+            include_hidden: Some(true),
+            respect_gitignore: true,
+            min_size: Some(1),
+            ..Default::default()
+        };
+
+        let (paths, _) = collect(&fs, &query);
+        assert_eq!(paths, vec!["/src/main.rs"]);
+    }
+
+    #[test]
+    fn test_cancelled_search_stops() {
+        let (_dir, fs) = sample_fs();
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let summary = search_filesystem(&fs, &SearchQuery { root: "/".to_string(), ..Default::default() }, &token, |_| {})
+            .unwrap();
+        assert!(summary.cancelled);
+        assert_eq!(summary.matched, 0);
+    }
+}
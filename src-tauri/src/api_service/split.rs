@@ -0,0 +1,761 @@
+/**
+ * File Split / Join Service
+ *
+ * Splits large files into numbered parts (`name.001`, `name.002`, ...) and joins them
+ * back, compatible with Total Commander. The optional `name.crc` manifest holds
+ * `filename=`, `size=` and `crc32=` lines plus a `sha256=` line; join verifies the
+ * result against whatever the manifest contains.
+ *
+ * Source and destination may live on different backends: data is streamed through
+ * `FileSystem::open_read` / `open_write`, so a vault file can be split straight onto
+ * a USB stick on the real file system without an intermediate copy.
+ */
+
+use super::journal::{join_path, split_path};
+use super::{ApiError, ApiResult, API};
+use crate::core::{ErrorKind, FileSystem, FileSystemError};
+use crate::progress::ProgressTracker;
+use crate::queue::{OperationParams, OperationPriority, QueuedOperation, QueuedOperationType, QUEUE_MANAGER};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::sync::Mutex;
+
+const COPY_BUFFER_SIZE: usize = 256 * 1024;
+
+/// Больше частей не создаётся: защита от случайного размера части в несколько байт
+const MAX_PARTS: u64 = 9999;
+
+/// Сколько результатов заданий очереди хранится для `job_result`
+const MAX_STORED_RESULTS: usize = 32;
+
+/// Результаты завершенных заданий очереди (ID операции → результат)
+static JOB_RESULTS: Lazy<Mutex<VecDeque<(String, SplitJobResult)>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+/// Разбиение файла на части
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct SplitRequest {
+    pub source: String,
+    /// Каталог для частей
+    pub destination: String,
+    /// Размер части в байтах
+    pub part_size: Option<u64>,
+    /// Число частей; используется, если не задан `part_size`
+    pub parts: Option<u64>,
+    /// Записать манифест `<name>.crc` (CRC32 и SHA-256)
+    #[serde(default)]
+    pub manifest: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct SplitResult {
+    /// Пути созданных частей по порядку
+    pub parts: Vec<String>,
+    pub part_size: u64,
+    /// Размер исходного файла
+    pub size: u64,
+    pub manifest_path: Option<String>,
+    pub crc32: Option<String>,
+    pub sha256: Option<String>,
+    pub cancelled: bool,
+}
+
+/// Сборка файла из частей
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct JoinRequest {
+    /// Любая часть (`name.001`) или манифест `name.crc`
+    pub source: String,
+    /// Каталог для собранного файла; существующий файл с тем же именем не заменяется
+    pub destination: String,
+    /// Имя собранного файла; по умолчанию — из манифеста или из имени частей
+    pub file_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct JoinResult {
+    pub path: String,
+    /// Число объединённых частей
+    pub parts: u64,
+    pub size: u64,
+    /// Результат сверен с манифестом
+    pub verified: bool,
+    pub cancelled: bool,
+}
+
+/// Результат задания очереди
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum SplitJobResult {
+    Split(SplitResult),
+    Join(JoinResult),
+}
+
+/// Содержимое манифеста `.crc`
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SplitManifest {
+    file_name: Option<String>,
+    size: Option<u64>,
+    crc32: Option<String>,
+    sha256: Option<String>,
+}
+
+impl SplitManifest {
+    fn parse(content: &str) -> Self {
+        let mut manifest = SplitManifest::default();
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once('=') else { continue };
+            let value = value.trim();
+            match key.trim().to_lowercase().as_str() {
+                "filename" => manifest.file_name = Some(value.to_string()),
+                "size" => manifest.size = value.parse().ok(),
+                "crc32" => manifest.crc32 = Some(value.to_uppercase()),
+                "sha256" => manifest.sha256 = Some(value.to_lowercase()),
+                _ => {}
+            }
+        }
+        manifest
+    }
+
+    /// Формат Total Commander (CRLF), SHA-256 — дополнительной строкой
+    fn render(&self) -> String {
+        let mut content = String::new();
+        if let Some(name) = &self.file_name {
+            content.push_str(&format!("filename={}\r\n", name));
+        }
+        if let Some(size) = self.size {
+            content.push_str(&format!("size={}\r\n", size));
+        }
+        if let Some(crc32) = &self.crc32 {
+            content.push_str(&format!("crc32={}\r\n", crc32));
+        }
+        if let Some(sha256) = &self.sha256 {
+            content.push_str(&format!("sha256={}\r\n", sha256));
+        }
+        content
+    }
+}
+
+/// CRC32 и SHA-256 потока данных
+struct StreamDigest {
+    crc32: flate2::Crc,
+    sha256: Sha256,
+}
+
+impl StreamDigest {
+    fn new() -> Self {
+        Self { crc32: flate2::Crc::new(), sha256: Sha256::new() }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.crc32.update(data);
+        self.sha256.update(data);
+    }
+
+    fn finalize(self) -> (String, String) {
+        (format!("{:08X}", self.crc32.sum()), format!("{:x}", self.sha256.finalize()))
+    }
+}
+
+/// Service for splitting files into parts and joining them back
+pub struct SplitService;
+
+impl SplitService {
+    pub fn new() -> Self {
+        tracing::debug!("Initializing SplitService");
+        Self
+    }
+
+    /// Split `request.source` into parts in `request.destination`
+    pub fn split(
+        &self,
+        request: &SplitRequest,
+        source_fs: Option<&str>,
+        dest_fs: Option<&str>,
+        tracker: &ProgressTracker,
+    ) -> ApiResult<SplitResult> {
+        tracing::info!("Splitting {} ({:?}) into {} ({:?})", request.source, source_fs, request.destination, dest_fs);
+
+        let source = API.files.get_filesystem_by_backend(source_fs);
+        let dest = API.files.get_filesystem_by_backend(dest_fs);
+        split_file(source.as_trait(), dest.as_trait(), request, tracker)
+    }
+
+    /// Join the parts next to `request.source` into one file
+    pub fn join(
+        &self,
+        request: &JoinRequest,
+        source_fs: Option<&str>,
+        dest_fs: Option<&str>,
+        tracker: &ProgressTracker,
+    ) -> ApiResult<JoinResult> {
+        tracing::info!("Joining {} ({:?}) into {} ({:?})", request.source, source_fs, request.destination, dest_fs);
+
+        let source = API.files.get_filesystem_by_backend(source_fs);
+        let dest = API.files.get_filesystem_by_backend(dest_fs);
+        join_files(source.as_trait(), dest.as_trait(), request, tracker)
+    }
+
+    /// Queue a split; returns the queue operation ID
+    pub fn enqueue_split(&self, request: SplitRequest, source_fs: Option<String>, dest_fs: Option<String>) -> ApiResult<String> {
+        let description = format!("Split '{}'", split_path(&request.source).1);
+        let operation = QueuedOperation::new(
+            QueuedOperationType::Split,
+            OperationParams::Split { request, source_fs, dest_fs },
+            OperationPriority::Normal,
+        );
+        self.enqueue(operation, description)
+    }
+
+    /// Queue a join; returns the queue operation ID
+    pub fn enqueue_join(&self, request: JoinRequest, source_fs: Option<String>, dest_fs: Option<String>) -> ApiResult<String> {
+        let description = format!("Join '{}'", split_path(&part_base(&request.source)).1);
+        let mut operation = QueuedOperation::new(
+            QueuedOperationType::Join,
+            OperationParams::Join { request, source_fs, dest_fs },
+            OperationPriority::Normal,
+        );
+        // Повреждённые части при повторе останутся теми же
+        operation.retry_policy.enabled = false;
+        self.enqueue(operation, description)
+    }
+
+    fn enqueue(&self, mut operation: QueuedOperation, description: String) -> ApiResult<String> {
+        operation.description = Some(description);
+        QUEUE_MANAGER
+            .enqueue(operation)
+            .map_err(|message| ApiError::OperationFailed { message })
+    }
+
+    /// Run a queued split (inside the queue executor) and keep its result for `job_result`
+    pub fn run_split(
+        &self,
+        operation_id: &str,
+        request: &SplitRequest,
+        source_fs: Option<&str>,
+        dest_fs: Option<&str>,
+        tracker: &ProgressTracker,
+    ) -> ApiResult<SplitResult> {
+        let result = self.split(request, source_fs, dest_fs, tracker)?;
+        store_result(operation_id, SplitJobResult::Split(result.clone()));
+        Ok(result)
+    }
+
+    /// Run a queued join (inside the queue executor) and keep its result for `job_result`
+    pub fn run_join(
+        &self,
+        operation_id: &str,
+        request: &JoinRequest,
+        source_fs: Option<&str>,
+        dest_fs: Option<&str>,
+        tracker: &ProgressTracker,
+    ) -> ApiResult<JoinResult> {
+        let result = self.join(request, source_fs, dest_fs, tracker)?;
+        store_result(operation_id, SplitJobResult::Join(result.clone()));
+        Ok(result)
+    }
+
+    /// Result of a finished queued split or join
+    pub fn job_result(&self, operation_id: &str) -> ApiResult<SplitJobResult> {
+        JOB_RESULTS
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(id, _)| id == operation_id)
+            .map(|(_, result)| result.clone())
+            .ok_or_else(|| ApiError::NotFound {
+                resource: format!("Split result: {}", operation_id),
+            })
+    }
+}
+
+impl Default for SplitService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn store_result(operation_id: &str, result: SplitJobResult) {
+    let mut results = JOB_RESULTS.lock().unwrap();
+    results.retain(|(id, _)| id != operation_id);
+    if results.len() >= MAX_STORED_RESULTS {
+        results.pop_front();
+    }
+    results.push_back((operation_id.to_string(), result));
+}
+
+pub(crate) fn split_file(
+    source_fs: &dyn FileSystem,
+    dest_fs: &dyn FileSystem,
+    request: &SplitRequest,
+    tracker: &ProgressTracker,
+) -> ApiResult<SplitResult> {
+    let info = source_fs.get_file_info(&request.source).map_err(ApiError::from)?;
+    if info.is_dir {
+        return Err(ApiError::IsADirectory {
+            message: format!("Cannot split a directory: {}", request.source),
+        });
+    }
+    let size = info.size.unwrap_or(0);
+    let part_size = part_size(request, size)?;
+    let part_count = size.div_ceil(part_size).max(1);
+
+    // Чужие файлы не перезаписываются (и не удаляются при сбое). Лишняя старая часть после
+    // последней новой или старый манифест попали бы в сборку, поэтому тоже запрещены.
+    let (_, base) = split_names(request);
+    let taken = (1..=part_count + 1)
+        .map(|index| part_name(&base, index))
+        .chain(std::iter::once(format!("{}.crc", base)));
+    for path in taken {
+        if exists(dest_fs, &path)? {
+            return Err(ApiError::AlreadyExists { resource: path });
+        }
+    }
+
+    let mut result = SplitResult { part_size, size, ..Default::default() };
+    tracker.set_total_bytes(size);
+    tracker.set_total_items(part_count);
+
+    match write_parts(source_fs, dest_fs, request, part_count, &mut result, tracker) {
+        Ok(true) => {
+            tracing::info!("Split {} into {} parts of {} bytes", request.source, result.parts.len(), part_size);
+            Ok(result)
+        }
+        outcome => {
+            // Без остальных частей уже записанные бесполезны: удаляются и при отмене, и при ошибке
+            for path in result.parts.iter().chain(&result.manifest_path) {
+                let _ = dest_fs.delete_item(path);
+            }
+            outcome.map(|_| SplitResult { cancelled: true, part_size, size, ..Default::default() })
+        }
+    }
+}
+
+/// Запись частей и манифеста; `Ok(false)`, если операция отменена
+///
+/// Каждая начатая часть сразу попадает в `result.parts`, чтобы вызывающий мог её удалить.
+fn write_parts(
+    source_fs: &dyn FileSystem,
+    dest_fs: &dyn FileSystem,
+    request: &SplitRequest,
+    part_count: u64,
+    result: &mut SplitResult,
+    tracker: &ProgressTracker,
+) -> ApiResult<bool> {
+    let (name, base) = split_names(request);
+    let (part_size, size) = (result.part_size, result.size);
+
+    let mut reader = source_fs.open_read(&request.source).map_err(ApiError::from)?;
+    let mut digest = StreamDigest::new();
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+
+    for index in 1..=part_count {
+        let path = part_name(&base, index);
+        tracker.update_current_file(Some(path.clone()));
+        let limit = part_size.min(size - (index - 1) * part_size);
+
+        let mut writer = dest_fs.open_write(&path).map_err(ApiError::from)?;
+        result.parts.push(path);
+        let mut part = (&mut reader).take(limit);
+        let copied = copy_chunks(&mut part, &mut writer, &mut buffer, tracker, |data| digest.update(data))
+            .map_err(|e| read_error(&request.source, e))?;
+
+        match copied {
+            // Незавершённую часть бэкенд не фиксирует
+            None => return Ok(false),
+            Some(copied) if copied != limit => {
+                return Err(ApiError::OperationFailed {
+                    message: format!("{} changed while splitting", request.source),
+                });
+            }
+            Some(_) => {}
+        }
+        writer.finish().map_err(ApiError::from)?;
+        tracker.add_item();
+    }
+
+    let (crc32, sha256) = digest.finalize();
+    if request.manifest {
+        let manifest = SplitManifest {
+            file_name: Some(name),
+            size: Some(size),
+            crc32: Some(crc32.clone()),
+            sha256: Some(sha256.clone()),
+        };
+        let manifest_path = format!("{}.crc", base);
+        result.manifest_path = Some(manifest_path.clone());
+        dest_fs.write_file_bytes(&manifest_path, manifest.render().as_bytes()).map_err(ApiError::from)?;
+    }
+    result.crc32 = Some(crc32);
+    result.sha256 = Some(sha256);
+    Ok(true)
+}
+
+pub(crate) fn join_files(
+    source_fs: &dyn FileSystem,
+    dest_fs: &dyn FileSystem,
+    request: &JoinRequest,
+    tracker: &ProgressTracker,
+) -> ApiResult<JoinResult> {
+    let base = part_base(&request.source);
+    // Нечитаемый манифест — ошибка: иначе сборка прошла бы без проверки
+    let manifest = match source_fs.read_file_bytes(&format!("{}.crc", base)) {
+        Ok(content) => Some(SplitManifest::parse(&String::from_utf8_lossy(&content))),
+        Err(err) if err.kind == ErrorKind::NotFound => None,
+        Err(err) => return Err(ApiError::from(err)),
+    };
+
+    let mut parts = Vec::new();
+    let mut total = 0;
+    loop {
+        let path = part_name(&base, parts.len() as u64 + 1);
+        match source_fs.get_file_info(&path) {
+            Ok(info) if !info.is_dir => {
+                total += info.size.unwrap_or(0);
+                parts.push(path);
+            }
+            _ => break,
+        }
+    }
+    if parts.is_empty() {
+        return Err(ApiError::NotFound {
+            resource: format!("No parts found for {}", base),
+        });
+    }
+    if let Some(expected) = manifest.as_ref().and_then(|manifest| manifest.size) {
+        if expected != total {
+            return Err(ApiError::ValidationError {
+                message: format!(
+                    "Parts of {} hold {} bytes, manifest expects {}: part {} may be missing",
+                    base,
+                    total,
+                    expected,
+                    part_name(&split_path(&base).1, parts.len() as u64 + 1)
+                ),
+            });
+        }
+    }
+
+    let name = request
+        .file_name
+        .clone()
+        .or_else(|| manifest.as_ref().and_then(|manifest| manifest.file_name.clone()))
+        .map(|name| split_path(&name).1)
+        .filter(|name| !name.is_empty() && name != "." && name != "..")
+        .unwrap_or_else(|| split_path(&base).1);
+    let path = join_path(&request.destination, &name);
+    // Существующий файл не заменяется: при сбое сборки он был бы потерян
+    if exists(dest_fs, &path)? {
+        return Err(ApiError::AlreadyExists { resource: path });
+    }
+    let mut result = JoinResult { path: path.clone(), parts: parts.len() as u64, size: total, ..Default::default() };
+    tracker.set_total_bytes(total);
+    tracker.set_total_items(parts.len() as u64);
+
+    let mut writer = dest_fs.open_write(&path).map_err(ApiError::from)?;
+    let mut digest = StreamDigest::new();
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+
+    for part in &parts {
+        tracker.update_current_file(Some(part.clone()));
+        let mut reader = source_fs.open_read(part).map_err(ApiError::from)?;
+        let copied = copy_chunks(&mut reader, &mut writer, &mut buffer, tracker, |data| digest.update(data));
+        match copied {
+            Ok(Some(_)) => tracker.add_item(),
+            Ok(None) => {
+                drop(writer);
+                let _ = dest_fs.delete_item(&path);
+                result.cancelled = true;
+                return Ok(result);
+            }
+            Err(e) => {
+                drop(writer);
+                let _ = dest_fs.delete_item(&path);
+                return Err(read_error(part, e));
+            }
+        }
+    }
+
+    let (crc32, sha256) = digest.finalize();
+    if let Some(manifest) = &manifest {
+        let mismatch = [("CRC32", &manifest.crc32, &crc32), ("SHA-256", &manifest.sha256, &sha256)]
+            .into_iter()
+            .find(|(_, expected, actual)| expected.as_deref().is_some_and(|expected| expected != actual.as_str()));
+        if let Some((algorithm, expected, actual)) = mismatch {
+            drop(writer);
+            let _ = dest_fs.delete_item(&path);
+            return Err(ApiError::ValidationError {
+                message: format!(
+                    "{} mismatch for {}: expected {}, got {}",
+                    algorithm,
+                    name,
+                    expected.as_deref().unwrap_or_default(),
+                    actual
+                ),
+            });
+        }
+        result.verified = manifest.crc32.is_some() || manifest.sha256.is_some();
+    }
+    writer.finish().map_err(ApiError::from)?;
+
+    tracing::info!("Joined {} parts into {} (verified: {})", parts.len(), path, result.verified);
+    Ok(result)
+}
+
+/// Имя исходного файла и путь частей без номера: `dest/name.iso`
+fn split_names(request: &SplitRequest) -> (String, String) {
+    let name = split_path(&request.source).1;
+    let base = join_path(&request.destination, &name);
+    (name, base)
+}
+
+/// Есть ли файл; ошибки, кроме отсутствия, пробрасываются
+fn exists(fs: &dyn FileSystem, path: &str) -> ApiResult<bool> {
+    match fs.get_file_info(path) {
+        Ok(_) => Ok(true),
+        Err(err) if err.kind == ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(ApiError::from(err)),
+    }
+}
+
+/// Размер части из запроса
+fn part_size(request: &SplitRequest, size: u64) -> ApiResult<u64> {
+    let part_size = match (request.part_size, request.parts) {
+        (Some(0), _) | (None, Some(0)) => {
+            return Err(ApiError::ValidationError {
+                message: "Part size and part count must be positive".to_string(),
+            })
+        }
+        (Some(part_size), _) => part_size,
+        (None, Some(parts)) => size.div_ceil(parts).max(1),
+        (None, None) => {
+            return Err(ApiError::ValidationError {
+                message: "Either part size or part count is required".to_string(),
+            })
+        }
+    };
+    if size.div_ceil(part_size) > MAX_PARTS {
+        return Err(ApiError::ValidationError {
+            message: format!("Part size {} would create more than {} parts", part_size, MAX_PARTS),
+        });
+    }
+    Ok(part_size)
+}
+
+/// Имя или путь части: `name.001`, ..., `name.999`, `name.1000`
+fn part_name(name: &str, index: u64) -> String {
+    format!("{}.{:03}", name, index)
+}
+
+/// Путь без номера части или расширения манифеста: `dir/name.iso.002` → `dir/name.iso`
+fn part_base(path: &str) -> String {
+    match path.rsplit_once('.') {
+        Some((base, ext)) if ext.eq_ignore_ascii_case("crc") => base.to_string(),
+        Some((base, ext)) if ext.len() >= 3 && ext.chars().all(|c| c.is_ascii_digit()) => base.to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// Копирование блоками с прогрессом; `Ok(None)`, если операция отменена
+fn copy_chunks(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    buffer: &mut [u8],
+    tracker: &ProgressTracker,
+    mut inspect: impl FnMut(&[u8]),
+) -> std::io::Result<Option<u64>> {
+    let mut copied = 0;
+    loop {
+        if tracker.is_cancelled() {
+            return Ok(None);
+        }
+        let read = match reader.read(buffer) {
+            Ok(0) => return Ok(Some(copied)),
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        inspect(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        copied += read as u64;
+        tracker.add_bytes(read as u64);
+    }
+}
+
+fn read_error(path: &str, err: std::io::Error) -> ApiError {
+    ApiError::from(FileSystemError::from_io(&err, format!("Failed to copy {}: {}", path, err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::temporary_fs::TemporaryFileSystem;
+    use crate::progress::OperationType;
+
+    fn tracker() -> ProgressTracker {
+        ProgressTracker::new("split-test".to_string(), OperationType::Split, 0, 0)
+    }
+
+    #[test]
+    fn test_split_join_roundtrip_with_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("parts")).unwrap();
+        std::fs::create_dir_all(dir.path().join("out")).unwrap();
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(dir.path().join("image.iso"), &data).unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+
+        let request = SplitRequest {
+            source: "/image.iso".to_string(),
+            destination: "/parts".to_string(),
+            part_size: Some(300),
+            manifest: true,
+            ..Default::default()
+        };
+        let result = split_file(&fs, &fs, &request, &tracker()).unwrap();
+        assert_eq!(result.parts.len(), 4);
+        assert_eq!(std::fs::read(dir.path().join("parts/image.iso.004")).unwrap().len(), 100);
+        let manifest = std::fs::read_to_string(dir.path().join("parts/image.iso.crc")).unwrap();
+        assert!(manifest.starts_with("filename=image.iso\r\nsize=1000\r\ncrc32="));
+
+        let join = JoinRequest {
+            source: "/parts/image.iso.002".to_string(),
+            destination: "/out".to_string(),
+            file_name: None,
+        };
+        let joined = join_files(&fs, &fs, &join, &tracker()).unwrap();
+        assert!(joined.verified);
+        assert_eq!((joined.parts, joined.size), (4, 1000));
+        assert_eq!(std::fs::read(dir.path().join("out/image.iso")).unwrap(), data);
+
+        // Испорченная часть: файл не собирается
+        let mut part = std::fs::read(dir.path().join("parts/image.iso.002")).unwrap();
+        part[0] ^= 0xFF;
+        std::fs::write(dir.path().join("parts/image.iso.002"), part).unwrap();
+        std::fs::remove_file(dir.path().join("out/image.iso")).unwrap();
+        assert!(join_files(&fs, &fs, &join, &tracker()).is_err());
+        assert!(!dir.path().join("out/image.iso").exists());
+
+        // Пропавшая последняя часть видна по размеру из манифеста
+        std::fs::remove_file(dir.path().join("parts/image.iso.004")).unwrap();
+        assert!(join_files(&fs, &fs, &join, &tracker()).is_err());
+    }
+
+    #[test]
+    fn test_split_by_part_count_without_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("data.bin"), vec![7u8; 10]).unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+
+        let request = SplitRequest {
+            source: "/data.bin".to_string(),
+            destination: "/".to_string(),
+            parts: Some(3),
+            ..Default::default()
+        };
+        let result = split_file(&fs, &fs, &request, &tracker()).unwrap();
+        assert_eq!((result.parts.len(), result.part_size), (3, 4));
+        assert!(result.manifest_path.is_none());
+
+        let join = JoinRequest {
+            source: "/data.bin.001".to_string(),
+            destination: "/".to_string(),
+            file_name: Some("joined.bin".to_string()),
+        };
+        let joined = join_files(&fs, &fs, &join, &tracker()).unwrap();
+        assert!(!joined.verified);
+        assert_eq!(std::fs::read(dir.path().join("joined.bin")).unwrap(), vec![7u8; 10]);
+
+        let invalid = SplitRequest { part_size: Some(0), ..request };
+        assert!(split_file(&fs, &fs, &invalid, &tracker()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_split_removes_written_parts() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("data.bin"), vec![7u8; 10]).unwrap();
+        // Вторую часть записать нельзя: на её месте ссылка в несуществующий каталог
+        std::os::unix::fs::symlink(dir.path().join("missing/part"), dir.path().join("data.bin.002")).unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+
+        let request = SplitRequest {
+            source: "/data.bin".to_string(),
+            destination: "/".to_string(),
+            part_size: Some(4),
+            manifest: true,
+            ..Default::default()
+        };
+        assert!(split_file(&fs, &fs, &request, &tracker()).is_err());
+        assert!(!dir.path().join("data.bin.001").exists());
+        assert!(!dir.path().join("data.bin.crc").exists());
+        assert!(dir.path().join("data.bin.002").symlink_metadata().is_ok());
+    }
+
+    #[test]
+    fn test_split_and_join_never_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("data.bin"), vec![7u8; 10]).unwrap();
+        let fs = TemporaryFileSystem::new(dir.path().to_path_buf());
+        let request = SplitRequest {
+            source: "/data.bin".to_string(),
+            destination: "/".to_string(),
+            part_size: Some(4),
+            ..Default::default()
+        };
+
+        // Чужая первая часть остаётся нетронутой
+        std::fs::write(dir.path().join("data.bin.001"), b"mine").unwrap();
+        assert!(matches!(split_file(&fs, &fs, &request, &tracker()), Err(ApiError::AlreadyExists { .. })));
+        assert_eq!(std::fs::read(dir.path().join("data.bin.001")).unwrap(), b"mine");
+        std::fs::remove_file(dir.path().join("data.bin.001")).unwrap();
+
+        // Старая часть после последней новой попала бы в сборку
+        std::fs::write(dir.path().join("data.bin.004"), b"stale").unwrap();
+        assert!(matches!(split_file(&fs, &fs, &request, &tracker()), Err(ApiError::AlreadyExists { .. })));
+        assert!(!dir.path().join("data.bin.001").exists());
+        std::fs::remove_file(dir.path().join("data.bin.004")).unwrap();
+
+        split_file(&fs, &fs, &request, &tracker()).unwrap();
+        let join = JoinRequest {
+            source: "/data.bin.001".to_string(),
+            destination: "/".to_string(),
+            file_name: None,
+        };
+        // Исходный файл на месте результата остаётся нетронутым
+        std::fs::write(dir.path().join("data.bin"), b"keep me").unwrap();
+        assert!(matches!(join_files(&fs, &fs, &join, &tracker()), Err(ApiError::AlreadyExists { .. })));
+        assert_eq!(std::fs::read(dir.path().join("data.bin")).unwrap(), b"keep me");
+
+        // Нечитаемый манифест не значит «манифеста нет»
+        std::fs::create_dir(dir.path().join("data.bin.crc")).unwrap();
+        let join = JoinRequest { file_name: Some("joined.bin".to_string()), ..join };
+        assert!(join_files(&fs, &fs, &join, &tracker()).is_err());
+        assert!(!dir.path().join("joined.bin").exists());
+    }
+
+    #[test]
+    fn test_part_names_and_manifest_parsing() {
+        assert_eq!(part_name("a.iso", 7), "a.iso.007");
+        assert_eq!(part_name("a.iso", 1000), "a.iso.1000");
+        assert_eq!(part_base("/x/a.iso.012"), "/x/a.iso");
+        assert_eq!(part_base("/x/a.iso.CRC"), "/x/a.iso");
+        assert_eq!(part_base("/x/a.iso"), "/x/a.iso");
+
+        let manifest = SplitManifest::parse("filename=a.iso\r\nsize=42\r\ncrc32=0a1b2c3d\r\n");
+        assert_eq!(manifest.file_name.as_deref(), Some("a.iso"));
+        assert_eq!(manifest.size, Some(42));
+        assert_eq!(manifest.crc32.as_deref(), Some("0A1B2C3D"));
+        assert_eq!(SplitManifest::parse(&manifest.render()), manifest);
+    }
+}
@@ -0,0 +1,936 @@
+/**
+ * Folder Synchronization
+ *
+ * Sync jobs between two folders on any backends (real FS, vault, temporary session):
+ * one-way mirror (optionally deleting extraneous files), one-way update and two-way
+ * sync with conflict detection. A job is planned first (dry run) and then applied as
+ * a `QueuedOperation`, so it gets retry, progress and scheduling from the queue.
+ *
+ * The state of both sides after the last run (the baseline) is kept per profile;
+ * two-way sync uses it to tell which side changed, all modes use it to skip files
+ * that did not change since the last run.
+ */
+
+use super::checksum::{hash_file, ChecksumAlgorithm};
+use super::compare::{collect_tree, has_ancestor_in, within_tolerance, CompareSide, DEFAULT_MTIME_TOLERANCE_SECS};
+use super::journal::{join_path, split_path};
+use super::search::WalkOptions;
+use super::{ApiError, ApiResult, API};
+use crate::config::{SyncMode, SyncProfile};
+use crate::core::{FileSystem, FileSystemError, FileSystemResult, ListOptions};
+use crate::progress::ProgressTracker;
+use crate::queue::{OperationParams, OperationPriority, QueuedOperation, QueuedOperationType, QUEUE_MANAGER};
+use crate::state::APP_CONFIG;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+const BUFFER_SIZE: usize = 256 * 1024;
+
+type Tree = BTreeMap<String, CompareSide>;
+
+/// Действие плана синхронизации
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum SyncActionKind {
+    CopyToRight,
+    CopyToLeft,
+    /// Удаление (в корзину) справа
+    DeleteRight,
+    DeleteLeft,
+    /// Не выполняется: нужно решение пользователя
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct SyncAction {
+    /// Путь относительно корней профиля (через `/`); папка переносится целиком
+    pub relative_path: String,
+    pub kind: SyncActionKind,
+    pub is_dir: bool,
+    /// Байт к копированию (для папки — суммарный размер содержимого)
+    pub bytes: u64,
+    pub left: Option<CompareSide>,
+    pub right: Option<CompareSide>,
+    /// Причина конфликта
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Результат пробного прогона
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct SyncPlan {
+    pub actions: Vec<SyncAction>,
+    pub copies: u64,
+    pub deletions: u64,
+    pub conflicts: u64,
+    pub bytes_to_copy: u64,
+    pub cancelled: bool,
+}
+
+/// Итог выполнения синхронизации
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct SyncReport {
+    pub copied: u64,
+    pub deleted: u64,
+    pub bytes_copied: u64,
+    /// Пути, оставленные без изменений из-за конфликта
+    pub conflicts: Vec<String>,
+    pub errors: Vec<String>,
+    pub cancelled: bool,
+}
+
+/// Состояние записи одной стороны на момент последней синхронизации
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct SideState {
+    is_dir: bool,
+    size: u64,
+    modified: Option<u64>,
+}
+
+impl From<&CompareSide> for SideState {
+    fn from(side: &CompareSide) -> Self {
+        Self {
+            is_dir: side.is_dir,
+            size: side.size,
+            modified: side.modified,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct BaselineEntry {
+    left: Option<SideState>,
+    right: Option<SideState>,
+}
+
+/// Состояние после последней синхронизации
+///
+/// Ключ — хеш относительного пути: снимок лежит вне хранилища и не должен раскрывать имена.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Baseline {
+    entries: HashMap<u128, BaselineEntry>,
+}
+
+impl Baseline {
+    fn get(&self, relative: &str) -> Option<&BaselineEntry> {
+        self.entries.get(&path_key(relative))
+    }
+
+    /// Снимок профиля; пустой, если профиль еще не синхронизировался
+    fn load(profile_id: &str) -> Self {
+        baseline_path(profile_id)
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|data| bincode::deserialize(&data).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, profile_id: &str) -> ApiResult<()> {
+        let path = baseline_path(profile_id).ok_or_else(|| ApiError::Internal {
+            message: "Could not determine local data directory".to_string(),
+        })?;
+        let data = bincode::serialize(self).map_err(|e| ApiError::Internal { message: e.to_string() })?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| ApiError::from(FileSystemError::from_io(&e, format!("Failed to create {}: {}", dir.display(), e))))?;
+        }
+        crate::api::security::atomic_write(&path, &data)
+            .map_err(|e| ApiError::OperationFailed { message: format!("Failed to save sync state: {}", e) })
+    }
+}
+
+fn path_key(relative: &str) -> u128 {
+    let digest = blake3::hash(relative.as_bytes());
+    u128::from_le_bytes(digest.as_bytes()[..16].try_into().unwrap())
+}
+
+fn baseline_path(profile_id: &str) -> Option<PathBuf> {
+    let name: String = profile_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    dirs::data_local_dir().map(|dir| dir.join("vfdir").join("sync").join(format!("{}.bin", name)))
+}
+
+/// Service for folder synchronization jobs
+pub struct SyncService;
+
+impl SyncService {
+    pub fn new() -> Self {
+        tracing::debug!("Initializing SyncService");
+        Self
+    }
+
+    /// Saved sync profiles
+    pub fn list_profiles(&self) -> ApiResult<Vec<SyncProfile>> {
+        Ok(APP_CONFIG.read().unwrap().sync_profiles.clone())
+    }
+
+    /// Create or update a profile; a new profile gets an id
+    pub fn save_profile(&self, mut profile: SyncProfile) -> ApiResult<SyncProfile> {
+        if profile.left_root.is_empty() || profile.right_root.is_empty() {
+            return Err(ApiError::ValidationError {
+                message: "Both folders must be specified".to_string(),
+            });
+        }
+        if profile.id.is_empty() {
+            profile.id = format!("sync-{}", uuid::Uuid::new_v4());
+        }
+        tracing::info!("Saving sync profile {} ({})", profile.id, profile.name);
+
+        let mut config = APP_CONFIG.write().unwrap();
+        match config.sync_profiles.iter_mut().find(|p| p.id == profile.id) {
+            Some(existing) => *existing = profile.clone(),
+            None => config.sync_profiles.push(profile.clone()),
+        }
+        drop(config);
+
+        let config = APP_CONFIG.read().unwrap();
+        config.save().map_err(|e| {
+            tracing::error!("Failed to save config: {}", e);
+            ApiError::OperationFailed {
+                message: format!("Failed to save sync profile: {}", e),
+            }
+        })?;
+
+        Ok(profile)
+    }
+
+    /// Remove a profile together with its sync state
+    pub fn delete_profile(&self, id: &str) -> ApiResult<()> {
+        tracing::info!("Removing sync profile: {}", id);
+        let mut config = APP_CONFIG.write().unwrap();
+        let before = config.sync_profiles.len();
+        config.sync_profiles.retain(|p| p.id != id);
+        if config.sync_profiles.len() == before {
+            return Err(ApiError::NotFound {
+                resource: format!("Sync profile '{}'", id),
+            });
+        }
+        drop(config);
+
+        let config = APP_CONFIG.read().unwrap();
+        config.save().map_err(|e| {
+            tracing::error!("Failed to save config: {}", e);
+            ApiError::OperationFailed {
+                message: format!("Failed to remove sync profile: {}", e),
+            }
+        })?;
+
+        if let Some(path) = baseline_path(id) {
+            let _ = std::fs::remove_file(path);
+        }
+        Ok(())
+    }
+
+    pub fn profile(&self, id: &str) -> ApiResult<SyncProfile> {
+        APP_CONFIG
+            .read()
+            .unwrap()
+            .sync_profiles
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| ApiError::NotFound {
+                resource: format!("Sync profile '{}'", id),
+            })
+    }
+
+    /// Dry run: what a sync of `profile` would do now
+    pub fn plan<F>(&self, profile: &SyncProfile, tracker: &ProgressTracker, mut on_progress: F) -> ApiResult<SyncPlan>
+    where
+        F: FnMut(),
+    {
+        tracing::info!("Planning sync {} ({:?})", profile.id, profile.mode);
+        let left = API.files.get_filesystem_by_backend(profile.left_fs.as_deref());
+        let right = API.files.get_filesystem_by_backend(profile.right_fs.as_deref());
+        let baseline = if profile.id.is_empty() { Baseline::default() } else { Baseline::load(&profile.id) };
+
+        plan_sync(left.as_trait(), right.as_trait(), profile, &baseline, tracker, &mut on_progress).map(|(plan, ..)| plan)
+    }
+
+    /// Queue a sync of a saved profile; `scheduled_at` (RFC 3339) defers the run
+    pub fn enqueue(&self, profile_id: &str, scheduled_at: Option<&str>) -> ApiResult<String> {
+        let profile = self.profile(profile_id)?;
+
+        let mut operation = QueuedOperation::new(
+            QueuedOperationType::Sync,
+            OperationParams::Sync { profile_id: profile.id.clone() },
+            OperationPriority::Normal,
+        );
+        if let Some(scheduled_at) = scheduled_at {
+            let scheduled = chrono::DateTime::parse_from_rfc3339(scheduled_at).map_err(|e| ApiError::ValidationError {
+                message: format!("Invalid scheduled time: {}", e),
+            })?;
+            operation.scheduled_at = Some(scheduled.with_timezone(&chrono::Utc));
+        }
+        operation.description = Some(format!("Sync '{}'", profile.name));
+
+        QUEUE_MANAGER
+            .enqueue(operation)
+            .map_err(|message| ApiError::OperationFailed { message })
+    }
+
+    /// Plan and apply a sync of a saved profile (inside the queue executor)
+    ///
+    /// The plan is rebuilt on every run, so a retry only redoes what is still out of sync.
+    pub fn run(&self, profile_id: &str, tracker: &ProgressTracker) -> ApiResult<SyncReport> {
+        let profile = self.profile(profile_id)?;
+        tracing::info!("Running sync {} ({:?})", profile.id, profile.mode);
+
+        let left = API.files.get_filesystem_by_backend(profile.left_fs.as_deref());
+        let right = API.files.get_filesystem_by_backend(profile.right_fs.as_deref());
+        let baseline = Baseline::load(&profile.id);
+
+        let (report, baseline) = sync_trees(left.as_trait(), right.as_trait(), &profile, &baseline, tracker)?;
+        if let Some(baseline) = baseline {
+            baseline.save(&profile.id)?;
+        }
+        Ok(report)
+    }
+}
+
+impl Default for SyncService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Plan, apply and capture the new baseline (`None` if the run was cancelled)
+pub(crate) fn sync_trees(
+    left_fs: &dyn FileSystem,
+    right_fs: &dyn FileSystem,
+    profile: &SyncProfile,
+    baseline: &Baseline,
+    tracker: &ProgressTracker,
+) -> ApiResult<(SyncReport, Option<Baseline>)> {
+    let (plan, ..) = plan_sync(left_fs, right_fs, profile, baseline, tracker, &mut || {})?;
+    if plan.cancelled {
+        return Ok((SyncReport { cancelled: true, ..Default::default() }, None));
+    }
+
+    tracker.set_total_bytes(tracker.get_current_bytes() + plan.bytes_to_copy);
+    let (report, unsettled) = apply_plan(left_fs, right_fs, profile, &plan, tracker);
+    if report.cancelled {
+        return Ok((report, None));
+    }
+
+    let Some((left, right)) = collect_sides(left_fs, right_fs, profile, tracker, &mut || {})? else {
+        return Ok((report, None));
+    };
+    Ok((report, Some(capture_baseline(&left, &right, baseline, &unsettled))))
+}
+
+fn walk_options(profile: &SyncProfile) -> WalkOptions {
+    WalkOptions {
+        include_hidden: profile.include_hidden,
+        respect_gitignore: profile.respect_gitignore,
+        max_depth: None,
+    }
+}
+
+/// Оба дерева; `None`, если обход отменен
+fn collect_sides(
+    left_fs: &dyn FileSystem,
+    right_fs: &dyn FileSystem,
+    profile: &SyncProfile,
+    tracker: &ProgressTracker,
+    on_progress: &mut dyn FnMut(),
+) -> ApiResult<Option<(Tree, Tree)>> {
+    let options = walk_options(profile);
+    let Some(left) = collect_tree(left_fs, &profile.left_root, &options, tracker, on_progress)? else {
+        return Ok(None);
+    };
+    let Some(right) = collect_tree(right_fs, &profile.right_root, &options, tracker, on_progress)? else {
+        return Ok(None);
+    };
+    Ok(Some((left, right)))
+}
+
+/// Build the action list for `profile`; also returns both trees
+pub(crate) fn plan_sync(
+    left_fs: &dyn FileSystem,
+    right_fs: &dyn FileSystem,
+    profile: &SyncProfile,
+    baseline: &Baseline,
+    tracker: &ProgressTracker,
+    on_progress: &mut dyn FnMut(),
+) -> ApiResult<(SyncPlan, Tree, Tree)> {
+    for (fs, root) in [(left_fs, &profile.left_root), (right_fs, &profile.right_root)] {
+        let info = fs.get_file_info(root).map_err(ApiError::from)?;
+        if !info.is_dir {
+            return Err(ApiError::NotADirectory {
+                message: format!("Not a directory: {}", root),
+            });
+        }
+    }
+
+    let mut plan = SyncPlan::default();
+    let Some((left, right)) = collect_sides(left_fs, right_fs, profile, tracker, on_progress)? else {
+        plan.cancelled = true;
+        return Ok((plan, Tree::new(), Tree::new()));
+    };
+
+    let paths: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    // Папки, действие над которыми охватывает все их содержимое
+    // (соседи вроде `docs.d` сортируются между `docs` и `docs/…`)
+    let mut covered_dirs = HashSet::new();
+
+    for relative in paths {
+        if has_ancestor_in(&covered_dirs, relative) {
+            continue;
+        }
+        if tracker.is_cancelled() {
+            plan.cancelled = true;
+            break;
+        }
+
+        let l = left.get(relative);
+        let r = right.get(relative);
+        let base = baseline.get(relative);
+
+        // Обе стороны не менялись с прошлой синхронизации
+        if let (Some(l), Some(r), Some(b)) = (l, r, base) {
+            if !side_changed(Some(l), b.left) && !side_changed(Some(r), b.right) {
+                continue;
+            }
+        }
+
+        let decision = match profile.mode {
+            SyncMode::Mirror => decide_mirror(left_fs, right_fs, profile, l, r, tracker, on_progress),
+            SyncMode::Update => decide_update(left_fs, right_fs, profile, l, r, base, tracker, on_progress),
+            SyncMode::TwoWay => decide_two_way(left_fs, right_fs, profile, relative, &left, &right, baseline, tracker, on_progress),
+        };
+        let Some((kind, reason)) = decision else {
+            continue;
+        };
+
+        let subject = match kind {
+            SyncActionKind::CopyToRight | SyncActionKind::DeleteLeft => l,
+            SyncActionKind::CopyToLeft | SyncActionKind::DeleteRight => r,
+            SyncActionKind::Conflict => l.filter(|side| side.is_dir).or(r),
+        };
+        let is_dir = subject.is_some_and(|side| side.is_dir);
+        if l.is_some_and(|side| side.is_dir) || r.is_some_and(|side| side.is_dir) {
+            covered_dirs.insert(relative.clone());
+        }
+
+        let bytes = match kind {
+            SyncActionKind::CopyToRight => subtree_size(&left, relative),
+            SyncActionKind::CopyToLeft => subtree_size(&right, relative),
+            _ => 0,
+        };
+        match kind {
+            SyncActionKind::CopyToRight | SyncActionKind::CopyToLeft => plan.copies += 1,
+            SyncActionKind::DeleteRight | SyncActionKind::DeleteLeft => plan.deletions += 1,
+            SyncActionKind::Conflict => plan.conflicts += 1,
+        }
+        plan.bytes_to_copy += bytes;
+        plan.actions.push(SyncAction {
+            relative_path: relative.clone(),
+            kind,
+            is_dir,
+            bytes,
+            left: l.cloned(),
+            right: r.cloned(),
+            reason,
+        });
+    }
+
+    tracing::info!(
+        "Sync plan: {} copies, {} deletions, {} conflicts",
+        plan.copies,
+        plan.deletions,
+        plan.conflicts
+    );
+    Ok((plan, left, right))
+}
+
+type Decision = Option<(SyncActionKind, Option<String>)>;
+
+fn decide_mirror(
+    left_fs: &dyn FileSystem,
+    right_fs: &dyn FileSystem,
+    profile: &SyncProfile,
+    l: Option<&CompareSide>,
+    r: Option<&CompareSide>,
+    tracker: &ProgressTracker,
+    on_progress: &mut dyn FnMut(),
+) -> Decision {
+    match (l, r) {
+        (Some(_), None) => Some((SyncActionKind::CopyToRight, None)),
+        (None, Some(_)) if profile.delete_extraneous => Some((SyncActionKind::DeleteRight, None)),
+        (Some(l), Some(r)) if !same_entry(left_fs, right_fs, profile, l, r, tracker, on_progress) => {
+            Some((SyncActionKind::CopyToRight, None))
+        }
+        _ => None,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn decide_update(
+    left_fs: &dyn FileSystem,
+    right_fs: &dyn FileSystem,
+    profile: &SyncProfile,
+    l: Option<&CompareSide>,
+    r: Option<&CompareSide>,
+    base: Option<&BaselineEntry>,
+    tracker: &ProgressTracker,
+    on_progress: &mut dyn FnMut(),
+) -> Decision {
+    let (l, r) = match (l, r) {
+        (Some(_), None) => return Some((SyncActionKind::CopyToRight, None)),
+        (Some(l), Some(r)) => (l, r),
+        _ => return None,
+    };
+    if same_entry(left_fs, right_fs, profile, l, r, tracker, on_progress) {
+        return None;
+    }
+    if l.is_dir != r.is_dir {
+        return Some((SyncActionKind::Conflict, Some("File on one side, folder on the other".to_string())));
+    }
+
+    let left_newer = matches!((l.modified, r.modified), (Some(lm), Some(rm)) if lm > rm + DEFAULT_MTIME_TOLERANCE_SECS);
+    // Копия получает новое mtime, поэтому «новее» определяется и по снимку
+    let changed_left_only = base.is_some_and(|b| side_changed(Some(l), b.left) && !side_changed(Some(r), b.right));
+    (left_newer || changed_left_only).then_some((SyncActionKind::CopyToRight, None))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn decide_two_way(
+    left_fs: &dyn FileSystem,
+    right_fs: &dyn FileSystem,
+    profile: &SyncProfile,
+    relative: &str,
+    left: &Tree,
+    right: &Tree,
+    baseline: &Baseline,
+    tracker: &ProgressTracker,
+    on_progress: &mut dyn FnMut(),
+) -> Decision {
+    let base = baseline.get(relative);
+    match (left.get(relative), right.get(relative)) {
+        (Some(_), None) => match base {
+            Some(b) if b.right.is_some() => Some(if subtree_changed(left, relative, baseline, |b| b.left) {
+                (SyncActionKind::Conflict, Some("Changed on the left, deleted on the right".to_string()))
+            } else {
+                (SyncActionKind::DeleteLeft, None)
+            }),
+            _ => Some((SyncActionKind::CopyToRight, None)),
+        },
+        (None, Some(_)) => match base {
+            Some(b) if b.left.is_some() => Some(if subtree_changed(right, relative, baseline, |b| b.right) {
+                (SyncActionKind::Conflict, Some("Changed on the right, deleted on the left".to_string()))
+            } else {
+                (SyncActionKind::DeleteRight, None)
+            }),
+            _ => Some((SyncActionKind::CopyToLeft, None)),
+        },
+        (Some(l), Some(r)) => {
+            if same_entry(left_fs, right_fs, profile, l, r, tracker, on_progress) {
+                return None;
+            }
+            let Some(b) = base else {
+                return Some((SyncActionKind::Conflict, Some("Differs on both sides, never synced".to_string())));
+            };
+            match (side_changed(Some(l), b.left), side_changed(Some(r), b.right)) {
+                (true, false) => Some((SyncActionKind::CopyToRight, None)),
+                (false, true) => Some((SyncActionKind::CopyToLeft, None)),
+                (true, true) => Some((SyncActionKind::Conflict, Some("Changed on both sides".to_string()))),
+                (false, false) => None,
+            }
+        }
+        (None, None) => None,
+    }
+}
+
+/// Изменилась ли запись относительно снимка (у папок значимо только существование)
+fn side_changed(current: Option<&CompareSide>, known: Option<SideState>) -> bool {
+    match (current, known) {
+        (None, None) => false,
+        (Some(current), Some(known)) if current.is_dir && known.is_dir => false,
+        (Some(current), Some(known)) => SideState::from(current) != known,
+        _ => true,
+    }
+}
+
+/// Изменилась ли запись или что-то внутри нее относительно снимка стороны
+fn subtree_changed(tree: &Tree, relative: &str, baseline: &Baseline, side: fn(&BaselineEntry) -> Option<SideState>) -> bool {
+    let prefix = format!("{}/", relative);
+    tree.range::<String, _>(relative.to_string()..)
+        .take_while(|(path, _)| path.as_str() == relative || path.starts_with(&prefix))
+        .any(|(path, entry)| side_changed(Some(entry), baseline.get(path).and_then(side)))
+}
+
+fn subtree_size(tree: &Tree, relative: &str) -> u64 {
+    let prefix = format!("{}/", relative);
+    tree.range::<String, _>(relative.to_string()..)
+        .take_while(|(path, _)| path.as_str() == relative || path.starts_with(&prefix))
+        .map(|(_, entry)| entry.size)
+        .sum()
+}
+
+fn same_entry(
+    left_fs: &dyn FileSystem,
+    right_fs: &dyn FileSystem,
+    profile: &SyncProfile,
+    l: &CompareSide,
+    r: &CompareSide,
+    tracker: &ProgressTracker,
+    on_progress: &mut dyn FnMut(),
+) -> bool {
+    if l.is_dir || r.is_dir {
+        return l.is_dir == r.is_dir;
+    }
+    if l.size != r.size {
+        return false;
+    }
+    if !profile.compare_content {
+        return within_tolerance(l.modified, r.modified, DEFAULT_MTIME_TOLERANCE_SECS);
+    }
+
+    let left_hash = hash_file(left_fs, &l.path, ChecksumAlgorithm::Blake3, tracker, on_progress);
+    let right_hash = hash_file(right_fs, &r.path, ChecksumAlgorithm::Blake3, tracker, on_progress);
+    match (left_hash, right_hash) {
+        (Ok(Some(left_hash)), Ok(Some(right_hash))) => left_hash == right_hash,
+        _ => false,
+    }
+}
+
+/// Execute the plan; returns the report and the paths left out of sync
+fn apply_plan(
+    left_fs: &dyn FileSystem,
+    right_fs: &dyn FileSystem,
+    profile: &SyncProfile,
+    plan: &SyncPlan,
+    tracker: &ProgressTracker,
+) -> (SyncReport, HashSet<String>) {
+    let mut report = SyncReport::default();
+    let mut unsettled = HashSet::new();
+
+    for action in &plan.actions {
+        if report.cancelled || tracker.is_cancelled() {
+            report.cancelled = true;
+            unsettled.insert(action.relative_path.clone());
+            continue;
+        }
+
+        let left_path = join_path(&profile.left_root, &action.relative_path);
+        let right_path = join_path(&profile.right_root, &action.relative_path);
+        tracker.update_current_file(Some(action.relative_path.clone()));
+
+        let result = match action.kind {
+            SyncActionKind::Conflict => {
+                report.conflicts.push(action.relative_path.clone());
+                unsettled.insert(action.relative_path.clone());
+                continue;
+            }
+            SyncActionKind::CopyToRight => copy_entry(left_fs, &left_path, right_fs, &right_path, tracker),
+            SyncActionKind::CopyToLeft => copy_entry(right_fs, &right_path, left_fs, &left_path, tracker),
+            SyncActionKind::DeleteRight => right_fs.trash_item(&right_path).map(|_| 0),
+            SyncActionKind::DeleteLeft => left_fs.trash_item(&left_path).map(|_| 0),
+        };
+
+        match result {
+            Ok(bytes) => {
+                match action.kind {
+                    SyncActionKind::DeleteRight | SyncActionKind::DeleteLeft => report.deleted += 1,
+                    _ => report.copied += 1,
+                }
+                report.bytes_copied += bytes;
+            }
+            Err(_) if tracker.is_cancelled() => {
+                report.cancelled = true;
+                unsettled.insert(action.relative_path.clone());
+            }
+            Err(err) => {
+                tracing::warn!("Sync action on {} failed: {}", action.relative_path, err);
+                report.errors.push(format!("{}: {}", action.relative_path, err));
+                unsettled.insert(action.relative_path.clone());
+            }
+        }
+        tracker.add_item();
+    }
+
+    tracker.update_current_file(None);
+    (report, unsettled)
+}
+
+/// Copy a file or folder over `dst`, replacing an entry of the other kind; returns bytes copied
+fn copy_entry(
+    src_fs: &dyn FileSystem,
+    src: &str,
+    dst_fs: &dyn FileSystem,
+    dst: &str,
+    tracker: &ProgressTracker,
+) -> FileSystemResult<u64> {
+    let info = src_fs.get_file_info(src)?;
+    let existing = dst_fs.get_file_info(dst).ok();
+    if existing.as_ref().is_some_and(|e| e.is_dir != info.is_dir) {
+        dst_fs.trash_item(dst)?;
+    }
+
+    if !info.is_dir {
+        return copy_file(src_fs, src, dst_fs, dst, tracker);
+    }
+
+    if !existing.is_some_and(|e| e.is_dir) {
+        let (parent, name) = split_path(dst);
+        dst_fs.create_folder(&parent, &name)?;
+    }
+
+    let options = ListOptions {
+        show_hidden: Some(true),
+        ..Default::default()
+    };
+    let mut bytes = 0;
+    for entry in src_fs.read_directory_with_options(src, &options)?.entries {
+        bytes += copy_entry(src_fs, &entry.path, dst_fs, &join_path(dst, &entry.name), tracker)?;
+    }
+    Ok(bytes)
+}
+
+fn copy_file(
+    src_fs: &dyn FileSystem,
+    src: &str,
+    dst_fs: &dyn FileSystem,
+    dst: &str,
+    tracker: &ProgressTracker,
+) -> FileSystemResult<u64> {
+    let mut reader = src_fs.open_read(src)?;
+    let mut writer = dst_fs.open_write(dst)?;
+
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut copied = 0u64;
+    loop {
+        if tracker.is_cancelled() {
+            return Err(FileSystemError::new("Operation cancelled"));
+        }
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read {}: {}", src, e)))?;
+        if read == 0 {
+            break;
+        }
+        writer
+            .write_all(&buffer[..read])
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write {}: {}", dst, e)))?;
+        copied += read as u64;
+        tracker.add_bytes(read as u64);
+    }
+
+    writer.finish()?;
+    Ok(copied)
+}
+
+/// Новый снимок: текущее состояние, кроме несинхронизированных путей (для них — старое)
+fn capture_baseline(left: &Tree, right: &Tree, previous: &Baseline, unsettled: &HashSet<String>) -> Baseline {
+    let is_unsettled = |relative: &str| {
+        let mut path = relative;
+        loop {
+            if unsettled.contains(path) {
+                return true;
+            }
+            match path.rfind('/') {
+                Some(index) => path = &path[..index],
+                None => return false,
+            }
+        }
+    };
+
+    let mut baseline = Baseline::default();
+    for relative in left.keys().chain(right.keys()) {
+        let key = path_key(relative);
+        if baseline.entries.contains_key(&key) {
+            continue;
+        }
+        let entry = if is_unsettled(relative) {
+            match previous.entries.get(&key) {
+                Some(entry) => entry.clone(),
+                None => continue,
+            }
+        } else {
+            BaselineEntry {
+                left: left.get(relative).map(SideState::from),
+                right: right.get(relative).map(SideState::from),
+            }
+        };
+        baseline.entries.insert(key, entry);
+    }
+    baseline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::temporary_fs::TemporaryFileSystem;
+    use crate::progress::OperationType;
+    use filetime::FileTime;
+    use std::path::Path;
+
+    fn write(root: &Path, path: &str, content: &str, mtime: i64) {
+        let full = root.join(path);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::write(&full, content).unwrap();
+        filetime::set_file_mtime(&full, FileTime::from_unix_time(mtime, 0)).unwrap();
+    }
+
+    fn read(root: &Path, path: &str) -> Option<String> {
+        std::fs::read_to_string(root.join(path)).ok()
+    }
+
+    fn profile(mode: SyncMode) -> SyncProfile {
+        SyncProfile {
+            id: "test".to_string(),
+            name: "test".to_string(),
+            left_root: "/".to_string(),
+            left_fs: None,
+            right_root: "/".to_string(),
+            right_fs: None,
+            mode,
+            delete_extraneous: false,
+            compare_content: false,
+            include_hidden: Some(false),
+            respect_gitignore: false,
+        }
+    }
+
+    fn tracker() -> ProgressTracker {
+        ProgressTracker::new("sync-test".to_string(), OperationType::Sync, 0, 0)
+    }
+
+    fn actions(plan: &SyncPlan) -> Vec<(&str, SyncActionKind)> {
+        plan.actions.iter().map(|a| (a.relative_path.as_str(), a.kind)).collect()
+    }
+
+    #[test]
+    fn test_mirror_plan_and_apply() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        write(left.path(), "docs/a.txt", "alpha", 1_000);
+        write(left.path(), "changed.txt", "new text", 5_000);
+        write(right.path(), "changed.txt", "old", 1_000);
+        write(right.path(), "extra/old.txt", "stale", 1_000);
+
+        let left_fs = TemporaryFileSystem::new(left.path().to_path_buf());
+        let right_fs = TemporaryFileSystem::new(right.path().to_path_buf());
+        let mut profile = profile(SyncMode::Mirror);
+        profile.delete_extraneous = true;
+
+        let (plan, ..) = plan_sync(&left_fs, &right_fs, &profile, &Baseline::default(), &tracker(), &mut || {}).unwrap();
+        assert_eq!(
+            actions(&plan),
+            vec![
+                ("changed.txt", SyncActionKind::CopyToRight),
+                ("docs", SyncActionKind::CopyToRight),
+                ("extra", SyncActionKind::DeleteRight),
+            ]
+        );
+        assert_eq!(plan.bytes_to_copy, 13);
+        // Пробный прогон ничего не меняет
+        assert_eq!(read(right.path(), "changed.txt").as_deref(), Some("old"));
+
+        let (report, baseline) = sync_trees(&left_fs, &right_fs, &profile, &Baseline::default(), &tracker()).unwrap();
+        assert_eq!((report.copied, report.deleted), (2, 1));
+        assert!(report.errors.is_empty());
+        assert_eq!(read(right.path(), "docs/a.txt").as_deref(), Some("alpha"));
+        assert_eq!(read(right.path(), "changed.txt").as_deref(), Some("new text"));
+        assert!(!right.path().join("extra").exists());
+
+        // Копии получили новое mtime, но по снимку синхронизировать больше нечего
+        let (plan, ..) = plan_sync(&left_fs, &right_fs, &profile, &baseline.unwrap(), &tracker(), &mut || {}).unwrap();
+        assert!(plan.actions.is_empty());
+    }
+
+    #[test]
+    fn test_covered_dir_with_sibling() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        write(left.path(), "docs/a.txt", "alpha", 1_000);
+        write(left.path(), "docs.d/b.txt", "beta", 1_000);
+
+        let left_fs = TemporaryFileSystem::new(left.path().to_path_buf());
+        let right_fs = TemporaryFileSystem::new(right.path().to_path_buf());
+        let profile = profile(SyncMode::Mirror);
+
+        let (plan, ..) = plan_sync(&left_fs, &right_fs, &profile, &Baseline::default(), &tracker(), &mut || {}).unwrap();
+        assert_eq!(
+            actions(&plan),
+            vec![("docs", SyncActionKind::CopyToRight), ("docs.d", SyncActionKind::CopyToRight)]
+        );
+        assert_eq!(plan.bytes_to_copy, 9);
+    }
+
+    #[test]
+    fn test_update_keeps_newer_right() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        write(left.path(), "old-left.txt", "left", 1_000);
+        write(right.path(), "old-left.txt", "right!", 5_000);
+        write(right.path(), "right-only.txt", "keep", 1_000);
+        write(left.path(), "new.txt", "new", 1_000);
+
+        let left_fs = TemporaryFileSystem::new(left.path().to_path_buf());
+        let right_fs = TemporaryFileSystem::new(right.path().to_path_buf());
+        let profile = profile(SyncMode::Update);
+
+        let (plan, ..) = plan_sync(&left_fs, &right_fs, &profile, &Baseline::default(), &tracker(), &mut || {}).unwrap();
+        assert_eq!(actions(&plan), vec![("new.txt", SyncActionKind::CopyToRight)]);
+    }
+
+    #[test]
+    fn test_two_way_propagates_and_detects_conflicts() {
+        let left = tempfile::tempdir().unwrap();
+        let right = tempfile::tempdir().unwrap();
+        write(left.path(), "both.txt", "base", 1_000);
+        write(left.path(), "edit-left.txt", "base", 1_000);
+        write(left.path(), "edit-right.txt", "base", 1_000);
+        write(left.path(), "delete-right.txt", "base", 1_000);
+        write(left.path(), "new-left.txt", "left", 1_000);
+
+        let left_fs = TemporaryFileSystem::new(left.path().to_path_buf());
+        let right_fs = TemporaryFileSystem::new(right.path().to_path_buf());
+        let profile = profile(SyncMode::TwoWay);
+
+        // Первый прогон: правая сторона пустая
+        let (report, baseline) = sync_trees(&left_fs, &right_fs, &profile, &Baseline::default(), &tracker()).unwrap();
+        assert_eq!(report.copied, 5);
+        let baseline = baseline.unwrap();
+
+        write(left.path(), "both.txt", "left edit", 9_000);
+        write(right.path(), "both.txt", "right edit", 9_000);
+        write(left.path(), "edit-left.txt", "changed on left", 9_000);
+        write(right.path(), "edit-right.txt", "changed on right", 9_000);
+        std::fs::remove_file(right.path().join("delete-right.txt")).unwrap();
+        write(right.path(), "new-right.txt", "right", 1_000);
+
+        let (plan, ..) = plan_sync(&left_fs, &right_fs, &profile, &baseline, &tracker(), &mut || {}).unwrap();
+        assert_eq!(
+            actions(&plan),
+            vec![
+                ("both.txt", SyncActionKind::Conflict),
+                ("delete-right.txt", SyncActionKind::DeleteLeft),
+                ("edit-left.txt", SyncActionKind::CopyToRight),
+                ("edit-right.txt", SyncActionKind::CopyToLeft),
+                ("new-right.txt", SyncActionKind::CopyToLeft),
+            ]
+        );
+
+        let (report, baseline) = sync_trees(&left_fs, &right_fs, &profile, &baseline, &tracker()).unwrap();
+        assert_eq!(report.conflicts, vec!["both.txt".to_string()]);
+        assert_eq!(read(right.path(), "edit-left.txt").as_deref(), Some("changed on left"));
+        assert_eq!(read(left.path(), "edit-right.txt").as_deref(), Some("changed on right"));
+        assert!(!left.path().join("delete-right.txt").exists());
+
+        // Конфликт не попадает в снимок и остается до решения пользователя
+        let (plan, ..) = plan_sync(&left_fs, &right_fs, &profile, &baseline.unwrap(), &tracker(), &mut || {}).unwrap();
+        assert_eq!(actions(&plan), vec![("both.txt", SyncActionKind::Conflict)]);
+    }
+}
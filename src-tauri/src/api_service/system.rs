@@ -7,11 +7,19 @@
 use std::sync::Mutex;
 use super::{ApiResult, ApiError};
 use super::models::{FileSystemEntry, SystemStats, DirectorySize};
+use super::disk_usage::{self, DiskUsageReport, DiskUsageRequest};
+use super::index::{IndexHit, IndexQuery, IndexRootStatus, FILE_INDEX};
 use crate::api::{RealFileSystem, virtual_fs::VirtualFileSystem, temporary_fs::TemporaryFileSystem};
+use crate::api::sftp_fs::SftpFileSystem;
+use crate::api::webdav_fs::WebDavFileSystem;
+use crate::api::s3_fs::S3FileSystem;
+use crate::api::archive_fs::ArchiveFileSystem;
 use crate::config::FileSystemBackend;
 use crate::core::FileSystem;
+use crate::progress::ProgressTracker;
 use crate::state::APP_CONFIG;
-use crate::api_service::files::TEMP_FS_SESSIONS;
+use crate::api_service::remote::{s3_filesystem, sftp_filesystem, webdav_filesystem, S3_PREFIX, SFTP_PREFIX, WEBDAV_PREFIX};
+use crate::api_service::files::{archive_filesystem, ARCHIVE_PREFIX, TEMP_FS_SESSIONS};
 use sysinfo::{System, Pid, ProcessesToUpdate};
 
 /// Enum для хранения разных типов файловых систем
@@ -19,6 +27,10 @@ enum FileSystemInstance {
     Real(RealFileSystem),
     Virtual(VirtualFileSystem),
     Temporary(TemporaryFileSystem),
+    Sftp(SftpFileSystem),
+    WebDav(WebDavFileSystem),
+    S3(S3FileSystem),
+    Archive(ArchiveFileSystem),
 }
 
 impl FileSystemInstance {
@@ -27,6 +39,10 @@ impl FileSystemInstance {
             FileSystemInstance::Real(fs) => fs,
             FileSystemInstance::Virtual(fs) => fs,
             FileSystemInstance::Temporary(fs) => fs,
+            FileSystemInstance::Sftp(fs) => fs,
+            FileSystemInstance::WebDav(fs) => fs,
+            FileSystemInstance::S3(fs) => fs,
+            FileSystemInstance::Archive(fs) => fs,
         }
     }
 }
@@ -71,6 +87,24 @@ impl SystemService {
             }
         }
 
+        // Remote and archive sessions never fall back to a local backend, even when closed
+        if let Some(label) = backend.filter(|label| label.starts_with(SFTP_PREFIX)) {
+            tracing::debug!("Using SFTP session for label: {}", label);
+            return FileSystemInstance::Sftp(sftp_filesystem(label));
+        }
+        if let Some(label) = backend.filter(|label| label.starts_with(WEBDAV_PREFIX)) {
+            tracing::debug!("Using WebDAV session for label: {}", label);
+            return FileSystemInstance::WebDav(webdav_filesystem(label));
+        }
+        if let Some(label) = backend.filter(|label| label.starts_with(S3_PREFIX)) {
+            tracing::debug!("Using S3 session for label: {}", label);
+            return FileSystemInstance::S3(s3_filesystem(label));
+        }
+        if let Some(label) = backend.filter(|label| label.starts_with(ARCHIVE_PREFIX)) {
+            tracing::debug!("Using archive session for label: {}", label);
+            return FileSystemInstance::Archive(archive_filesystem(label));
+        }
+
         let backend_enum = match backend {
             Some("real") => FileSystemBackend::Real,
             Some("virtual") => FileSystemBackend::Virtual,
@@ -120,9 +154,7 @@ impl SystemService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().get_home_directory().map_err(|err| {
             tracing::error!("Failed to get home directory: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
+            ApiError::from(err)
         })
     }
 
@@ -132,9 +164,7 @@ impl SystemService {
 
         self.get_filesystem_by_backend(panel_fs).as_trait().get_system_folders().map_err(|err| {
             tracing::error!("Failed to get system folders: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
+            ApiError::from(err)
         })
     }
 
@@ -172,9 +202,7 @@ impl SystemService {
 
         self.get_filesystem().as_trait().open_terminal(path).map_err(|err| {
             tracing::error!("Failed to open terminal: {}", err.message);
-            ApiError::OperationFailed {
-                message: err.message,
-            }
+            ApiError::from(err)
         })
     }
 
@@ -198,6 +226,37 @@ impl SystemService {
         })
     }
 
+    /// Hierarchical disk usage of a directory: size tree, largest files, breakdown by type
+    ///
+    /// Unchanged subtrees are taken from the cache of earlier scans.
+    pub fn analyze_disk_usage<F>(
+        &self,
+        request: &DiskUsageRequest,
+        tracker: &ProgressTracker,
+        on_progress: F,
+    ) -> ApiResult<DiskUsageReport>
+    where
+        F: FnMut(),
+    {
+        disk_usage::analyze_disk_usage(request, tracker, on_progress)
+    }
+
+    /// Get state of the filename index for every indexed root
+    pub fn get_index_status(&self) -> ApiResult<Vec<IndexRootStatus>> {
+        Ok(FILE_INDEX.status())
+    }
+
+    /// Rebuild the filename index of one root (`None` — all roots) in the background
+    pub fn rebuild_index(&self, root: Option<&str>) -> ApiResult<()> {
+        tracing::info!("Rebuilding filename index: {}", root.unwrap_or("all roots"));
+        FILE_INDEX.rebuild(root)
+    }
+
+    /// Query the filename index (prefix, substring or fuzzy match)
+    pub fn query_index(&self, query: &IndexQuery) -> ApiResult<Vec<IndexHit>> {
+        FILE_INDEX.query(query)
+    }
+
     /// Execute shell command with timeout
     pub fn execute_shell_command(&self, command: &str, working_dir: &str) -> ApiResult<super::models::CommandResult> {
         use std::process::{Command, Stdio};
@@ -12,6 +12,9 @@ use crate::api::notification_channels::ChannelConfig;
 use crate::config::FileSystemBackend;
 use crate::state::APP_CONFIG;
 use crate::api_service::files::TEMP_FS_SESSIONS;
+use crate::api_service::disk_usage::DISK_USAGE_CACHE;
+use crate::api_service::remote::close_s3_sessions;
+use crate::api_service::index::{FILE_INDEX, VAULT_INDEX_FILE};
 use std::sync::{Arc, Mutex};
 use std::path::PathBuf;
 use once_cell::sync::Lazy;
@@ -123,7 +126,10 @@ impl VaultService {
                 VaultError::DecryptionFailed => ApiError::Internal { message: "Decryption failed".to_string() },
                 VaultError::Locked => ApiError::ValidationError { message: "Vault is locked".to_string() },
                 _ => ApiError::Internal { message: e.to_string() },
-            })
+            })?;
+
+        FILE_INDEX.attach_vault(vfs);
+        Ok(())
     }
 
     /// Lock vault (clear keys from memory)
@@ -131,6 +137,10 @@ impl VaultService {
         let vfs = get_or_init_vfs()
             .map_err(|e| ApiError::Internal { message: e.to_string() })?;
 
+        // Индекс дерева сохраняется, пока ключ сессии еще в памяти
+        FILE_INDEX.detach_vault(true);
+        DISK_USAGE_CACHE.forget_backend("vault");
+        close_s3_sessions();
         vfs.lock_vault()
             .map_err(|e| ApiError::Internal { message: e.to_string() })
     }
@@ -208,6 +218,10 @@ impl VaultService {
         //     });
         // }
 
+        // Stop indexing the vault tree; its index is deleted together with the vault
+        FILE_INDEX.detach_vault(false);
+        DISK_USAGE_CACHE.forget_backend("vault");
+
         // Clear VFS from memory
         {
             let mut vfs_guard = VAULT_FS.lock().unwrap();
@@ -229,6 +243,7 @@ impl VaultService {
         Self::remove_file(&vault_paths.fs_json.to_string_lossy())?;
         Self::remove_file(&vault_paths.vault_meta.to_string_lossy())?;
         Self::remove_file(&vault_paths.vault_bin.to_string_lossy())?;
+        Self::remove_file(&vault_paths.vault_bin.with_file_name(VAULT_INDEX_FILE).to_string_lossy())?;
 
         tracing::info!("Vault reset completed: files deleted from {:?}", vault_paths.dir);
         Ok(())
@@ -458,7 +473,7 @@ impl VaultService {
         } else {
             // It's raw files -> Initialize TemporaryFileSystem
             tracing::info!("Initializing TemporaryFileSystem for raw stego content at {:?}", temp_dir);
-            let tfs = TemporaryFileSystem::new(temp_dir);
+            let tfs = TemporaryFileSystem::with_label(temp_dir, &session_id);
             
             let mut sessions_guard = TEMP_FS_SESSIONS.lock().unwrap();
             sessions_guard.insert(session_id.clone(), tfs);
@@ -1,20 +1,193 @@
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs::File;
+use std::num::NonZeroU64;
 use std::path::{Path, PathBuf};
-use crate::core::FileSystemEntry;
-use std::io::{Write, Read, Seek, Cursor};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use crate::core::{FileReader, FileSystemEntry};
+use std::io::{self, Write, Read, Seek, SeekFrom};
+use serde::{Deserialize, Serialize};
 use crate::api_service::API;
+use crate::api_service::files::FileSystemInstance;
+use crate::progress::ProgressTracker;
+
+/// Формат архива
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    TarXz,
+    TarZst,
+    TarBz2,
+    SevenZ,
+}
+
+impl ArchiveFormat {
+    /// Формат по расширению имени файла
+    pub(crate) fn detect(path: &str) -> Option<Self> {
+        let ext = Path::new(path).extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "zip" => Some(ArchiveFormat::Zip),
+            "tar" => Some(ArchiveFormat::Tar),
+            "gz" | "tgz" => Some(ArchiveFormat::TarGz),
+            "xz" | "txz" => Some(ArchiveFormat::TarXz),
+            "zst" | "tzst" => Some(ArchiveFormat::TarZst),
+            "bz2" | "tbz2" | "tbz" => Some(ArchiveFormat::TarBz2),
+            "7z" => Some(ArchiveFormat::SevenZ),
+            _ => None,
+        }
+    }
+
+    /// Формат по имени из параметров операции (`zip`, `tar.gz`, `7z`, ...)
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name.trim().trim_start_matches('.').to_lowercase().as_str() {
+            "zip" => Some(ArchiveFormat::Zip),
+            "tar" => Some(ArchiveFormat::Tar),
+            "tar.gz" | "tgz" | "gz" | "gzip" => Some(ArchiveFormat::TarGz),
+            "tar.xz" | "txz" | "xz" => Some(ArchiveFormat::TarXz),
+            "tar.zst" | "tar.zstd" | "tzst" | "zst" | "zstd" => Some(ArchiveFormat::TarZst),
+            "tar.bz2" | "tbz2" | "tbz" | "bz2" | "bzip2" => Some(ArchiveFormat::TarBz2),
+            "7z" => Some(ArchiveFormat::SevenZ),
+            _ => None,
+        }
+    }
+
+    /// Формат по сигнатуре в начале файла
+    ///
+    /// Сжатый поток (gz, xz, zstd, bzip2) считается tar внутри.
+    pub(crate) fn sniff(header: &[u8]) -> Option<Self> {
+        if header.starts_with(b"PK\x03\x04") || header.starts_with(b"PK\x05\x06") || header.starts_with(b"PK\x07\x08") {
+            Some(ArchiveFormat::Zip)
+        } else if header.starts_with(b"7z\xBC\xAF\x27\x1C") {
+            Some(ArchiveFormat::SevenZ)
+        } else if header.starts_with(&[0x1F, 0x8B]) {
+            Some(ArchiveFormat::TarGz)
+        } else if header.starts_with(b"\xFD7zXZ\x00") {
+            Some(ArchiveFormat::TarXz)
+        } else if header.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+            Some(ArchiveFormat::TarZst)
+        } else if header.starts_with(b"BZh") {
+            Some(ArchiveFormat::TarBz2)
+        } else if header.get(257..262) == Some(b"ustar".as_slice()) {
+            Some(ArchiveFormat::Tar)
+        } else {
+            None
+        }
+    }
+
+    /// Формат открытого архива: по сигнатуре, а если она не распознана (старый tar без
+    /// `ustar`) — по расширению. Поток возвращается в начало.
+    pub(crate) fn identify<R: Read + Seek + ?Sized>(reader: &mut R, path: &str) -> io::Result<Option<Self>> {
+        let mut header = [0u8; 512];
+        let mut filled = 0;
+        while filled < header.len() {
+            match reader.read(&mut header[filled..])? {
+                0 => break,
+                read => filled += read,
+            }
+        }
+        reader.seek(SeekFrom::Start(0))?;
+        Ok(Self::sniff(&header[..filled]).or_else(|| Self::detect(path)))
+    }
+
+    /// tar, сжатый или нет
+    pub(crate) fn is_tar(self) -> bool {
+        !matches!(self, ArchiveFormat::Zip | ArchiveFormat::SevenZ)
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::Tar => "tar",
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::TarXz => "tar.xz",
+            ArchiveFormat::TarZst => "tar.zst",
+            ArchiveFormat::TarBz2 => "tar.bz2",
+            ArchiveFormat::SevenZ => "7z",
+        }
+    }
+}
 
 #[allow(dead_code)]
 pub fn is_archive(path: &str) -> bool {
-    let path_obj = Path::new(path);
-    if let Some(ext) = path_obj.extension() {
-        let ext_str = ext.to_string_lossy().to_lowercase();
-        return match ext_str.as_str() {
-            "zip" | "tar" | "gz" | "tgz" => true,
-            _ => false,
-        };
+    ArchiveFormat::detect(path).is_some()
+}
+
+/// Пароль архива
+///
+/// Не сериализуется: параметры операций очереди сохраняются на диск в открытом JSON.
+/// В отладочном выводе скрыт.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct ArchivePassword(String);
+
+impl ArchivePassword {
+    fn expose(&self) -> Option<&str> {
+        Some(self.0.as_str()).filter(|password| !password.is_empty())
+    }
+}
+
+impl std::fmt::Debug for ArchivePassword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ArchivePassword(***)")
+    }
+}
+
+pub(crate) const PASSWORD_REQUIRED: &str = "Archive is password-protected: password required";
+pub(crate) const WRONG_PASSWORD: &str = "Wrong password";
+
+/// Параметры создания архива
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveOptions {
+    /// Формат (`zip`, `tar.gz`, `tar.xz`, `tar.zst`, `tar.bz2`, `7z`); по умолчанию — по расширению
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Уровень сжатия: zstd — 1..=22, остальные форматы — 0..=9
+    #[serde(default)]
+    pub level: Option<u32>,
+    /// Потоки сжатия (xz и zstd); по умолчанию один
+    #[serde(default)]
+    pub threads: Option<u32>,
+    /// Шифрование AES-256 (WinZip AE-2), только для zip
+    #[serde(default, skip_serializing)]
+    pub password: Option<ArchivePassword>,
+}
+
+impl ArchiveOptions {
+    fn format_for(&self, destination_path: &str) -> Result<ArchiveFormat, String> {
+        match self.format.as_deref().filter(|name| !name.trim().is_empty()) {
+            Some(name) => ArchiveFormat::from_name(name).ok_or_else(|| format!("Unsupported archive format: {}", name)),
+            None => ArchiveFormat::detect(destination_path)
+                .ok_or_else(|| format!("Unsupported archive format: {}", destination_path)),
+        }
+    }
+
+    fn level(&self, format: ArchiveFormat) -> u32 {
+        match format {
+            ArchiveFormat::TarZst => self.level.unwrap_or(3).clamp(1, 22),
+            ArchiveFormat::TarBz2 => self.level.unwrap_or(9).clamp(1, 9),
+            _ => self.level.unwrap_or(6).min(9),
+        }
+    }
+
+    fn threads(&self) -> u32 {
+        self.threads.unwrap_or(1).max(1)
+    }
+
+    fn password(&self) -> Option<&str> {
+        self.password.as_ref().and_then(ArchivePassword::expose)
     }
-    false
+}
+
+/// Открытие архива на реальной ФС с определением формата
+fn open_real(path: &str) -> Result<(File, ArchiveFormat), String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let format = ArchiveFormat::identify(&mut file, path)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Unsupported archive format: {}", path))?;
+    Ok((file, format))
 }
 
 pub fn list_archive_contents(path: &str) -> Result<Vec<FileSystemEntry>, String> {
@@ -25,145 +198,150 @@ pub fn list_archive_contents_with_fs(path: &str, panel_fs: Option<&str>) -> Resu
     let is_real = panel_fs.is_none() || panel_fs == Some("real");
     
     if is_real {
-        let path_obj = Path::new(path);
-        if let Some(ext) = path_obj.extension() {
-            let ext_str = ext.to_string_lossy().to_lowercase();
-            return match ext_str.as_str() {
-                "zip" => list_zip(path),
-                "tar" => list_tar(path),
-                "gz" | "tgz" => list_tar_gz(path),
-                _ => Err(format!("Unsupported archive format: {}", ext_str)),
-            };
-        } else {
-            return Err("No extension found".to_string());
-        }
+        let (file, format) = open_real(path)?;
+        return match format {
+            ArchiveFormat::Zip => list_zip_generic(file, path),
+            ArchiveFormat::SevenZ => list_7z_generic(file, path),
+            _ => list_tar_generic(tar_decoder(format, file).map_err(|e| e.to_string())?, path),
+        };
     }
 
-    // Generic implementation (In-Memory)
-    let archive_bytes = API.files.read_file_bytes(path, panel_fs)
+    // Generic implementation (Streaming)
+    let mut reader = API.files.open_read(path, panel_fs)
         .map_err(|e| format!("Failed to read archive: {}", e))?;
-    
-    let cursor = Cursor::new(archive_bytes);
-    
-    let path_obj = Path::new(path);
-    if let Some(ext) = path_obj.extension() {
-        let ext_str = ext.to_string_lossy().to_lowercase();
-        match ext_str.as_str() {
-            "zip" => list_zip_generic(cursor, path),
-            "tar" => list_tar_generic(cursor, path),
-            "gz" | "tgz" => list_tar_gz_generic(cursor, path),
-            _ => Err(format!("Unsupported archive format for virtual fs: {}", ext_str)),
-        }
-    } else {
-        Err("No extension found".to_string())
+    let format = ArchiveFormat::identify(&mut reader, path)
+        .map_err(|e| format!("Failed to read archive: {}", e))?
+        .ok_or_else(|| format!("Unsupported archive format: {}", path))?;
+
+    match format {
+        ArchiveFormat::Zip => list_zip_generic(reader, path),
+        ArchiveFormat::SevenZ => list_7z_generic(reader, path),
+        _ => list_tar_generic(tar_decoder(format, reader).map_err(|e| e.to_string())?, path),
     }
 }
 
-pub fn extract_archive(archive_path: &str, destination_path: &str) -> Result<(), String> {
-    extract_archive_with_fs(archive_path, destination_path, None, None)
-}
+/// Распаковка архива с проверкой путей, выбором записей и политикой перезаписи
+///
+/// Прогресс и отмена идут через `tracker`; при отмене возвращается отчёт с `cancelled`.
+pub fn extract_archive_with_options(
+    archive_path: &str,
+    destination_path: &str,
+    options: &ExtractOptions,
+    source_fs: Option<&str>,
+    dest_fs: Option<&str>,
+    tracker: Option<&ProgressTracker>,
+) -> Result<ExtractReport, String> {
+    let mut extractor = Extractor::new(destination_path, options, dest_fs, tracker)?;
+
+    if let Some(tracker) = tracker {
+        let (total_bytes, total_items) = extraction_totals(archive_path, &extractor.selection, source_fs)?;
+        tracker.set_total_bytes(total_bytes);
+        tracker.set_total_items(total_items);
+    }
 
-pub fn extract_archive_with_fs(archive_path: &str, destination_path: &str, source_fs: Option<&str>, dest_fs: Option<&str>) -> Result<(), String> {
     let is_real_source = source_fs.is_none() || source_fs == Some("real");
-    let is_real_dest = dest_fs.is_none() || dest_fs == Some("real");
-
-    if is_real_source && is_real_dest {
-        // Use optimized real-fs implementation
-        let path_obj = Path::new(archive_path);
-        if let Some(ext) = path_obj.extension() {
-            let ext_str = ext.to_string_lossy().to_lowercase();
-            return match ext_str.as_str() {
-                "zip" => extract_zip(archive_path, destination_path),
-                "tar" => extract_tar(archive_path, destination_path),
-                "gz" | "tgz" => extract_tar_gz(archive_path, destination_path),
-                _ => Err(format!("Unsupported archive format: {}", ext_str)),
-            };
-        } else {
-            return Err("No extension found".to_string());
-        }
+    let result = if is_real_source {
+        let (file, format) = open_real(archive_path)?;
+        extract_entries(file, format, &mut extractor)
+    } else {
+        let mut reader = API.files.open_read(archive_path, source_fs)
+            .map_err(|e| format!("Failed to read archive: {}", e))?;
+        let format = ArchiveFormat::identify(&mut reader, archive_path)
+            .map_err(|e| format!("Failed to read archive: {}", e))?
+            .ok_or_else(|| format!("Unsupported archive format: {}", archive_path))?;
+        extract_entries(reader, format, &mut extractor)
+    };
+
+    match result {
+        Ok(()) => Ok(extractor.report),
+        Err(_) if extractor.report.cancelled => Ok(extractor.report),
+        Err(e) => Err(e),
     }
+}
 
-    // Generic implementation (In-Memory)
-    let archive_bytes = API.files.read_file_bytes(archive_path, source_fs)
-        .map_err(|e| format!("Failed to read archive: {}", e))?;
-    
-    let cursor = Cursor::new(archive_bytes);
-    
-    let path_obj = Path::new(archive_path);
-    if let Some(ext) = path_obj.extension() {
-        let ext_str = ext.to_string_lossy().to_lowercase();
-        match ext_str.as_str() {
-            "zip" => extract_zip_generic(cursor, destination_path, dest_fs),
-            "tar" => extract_tar_generic(cursor, destination_path, dest_fs),
-            "gz" | "tgz" => extract_tar_gz_generic(cursor, destination_path, dest_fs),
-            _ => Err(format!("Unsupported archive format for virtual fs: {}", ext_str)),
+/// Объём и число выбранных записей для прогресса распаковки
+fn extraction_totals(archive_path: &str, selection: &[String], source_fs: Option<&str>) -> Result<(u64, u64), String> {
+    let entries = list_archive_contents_with_fs(archive_path, source_fs)?;
+    let mut totals = (0, 0);
+    for entry in entries {
+        let Ok(Some(path)) = sanitize_entry_path(&entry.name) else { continue };
+        if is_selected(selection, &path) {
+            totals.0 += if entry.is_dir { 0 } else { entry.size.unwrap_or(0) };
+            totals.1 += 1;
         }
-    } else {
-        Err("No extension found".to_string())
     }
+    Ok(totals)
 }
 
-pub fn create_archive(source_paths: Vec<String>, destination_path: String) -> Result<(), String> {
-    create_archive_with_fs(source_paths, destination_path, None, None)
+fn extract_entries<R: Read + Seek + Send>(reader: R, format: ArchiveFormat, extractor: &mut Extractor) -> Result<(), String> {
+    match format {
+        ArchiveFormat::Zip => extract_zip_entries(reader, extractor),
+        ArchiveFormat::SevenZ => extract_7z_entries(reader, extractor),
+        _ => extract_tar_entries(tar_decoder(format, reader).map_err(|e| e.to_string())?, extractor),
+    }
 }
 
-pub fn create_archive_with_fs(source_paths: Vec<String>, destination_path: String, source_fs: Option<&str>, dest_fs: Option<&str>) -> Result<(), String> {
+pub fn create_archive_with_options(
+    source_paths: Vec<String>,
+    destination_path: String,
+    options: &ArchiveOptions,
+    source_fs: Option<&str>,
+    dest_fs: Option<&str>,
+) -> Result<(), String> {
     if source_paths.is_empty() {
         return Err("No source files specified".to_string());
     }
 
     let is_real_source = source_fs.is_none() || source_fs == Some("real");
     let is_real_dest = dest_fs.is_none() || dest_fs == Some("real");
-
-    let dest_path = Path::new(&destination_path);
-    let ext = dest_path.extension()
-        .and_then(|e| e.to_str())
-        .ok_or("Destination file has no extension")?;
-    let is_tar_gz = destination_path.ends_with(".tar.gz") || destination_path.ends_with(".tgz");
+    let format = options.format_for(&destination_path)?;
+    if options.password().is_some() && format != ArchiveFormat::Zip {
+        return Err(format!("Password protection is not supported for {} archives", format.name()));
+    }
 
     if is_real_source && is_real_dest {
-        if is_tar_gz {
-            create_tar_gz(&source_paths, &destination_path)
-        } else {
-            match ext.to_lowercase().as_str() {
-                "zip" => create_zip(&source_paths, &destination_path),
-                "tar" => create_tar(&source_paths, &destination_path),
-                _ => Err(format!("Unsupported archive format: {}", ext)),
-            }
-        }
-    } else {
-        // Generic implementation
-        let buffer = Cursor::new(Vec::new());
-        let result_buffer = if is_tar_gz {
-            create_tar_gz_generic(buffer, &source_paths, source_fs)?
-        } else {
-            match ext.to_lowercase().as_str() {
-                "zip" => create_zip_generic(buffer, &source_paths, source_fs)?,
-                "tar" => create_tar_generic(buffer, &source_paths, source_fs)?,
-                _ => return Err(format!("Unsupported archive format: {}", ext)),
+        return match format {
+            ArchiveFormat::Zip => create_zip(&source_paths, &destination_path, options),
+            ArchiveFormat::SevenZ => create_7z(&source_paths, &destination_path, options),
+            _ => {
+                let file = File::create(&destination_path).map_err(|e| e.to_string())?;
+                let encoder = TarEncoder::new(format, file, options).map_err(|e| e.to_string())?;
+                create_tar(encoder, &source_paths)?
+                    .finish()
+                    .map_err(|e| e.to_string())?;
+                Ok(())
             }
         };
-
-        let bytes = result_buffer.into_inner();
-        API.files.write_file_bytes(&destination_path, &bytes, dest_fs)
-            .map_err(|e| format!("Failed to write archive: {}", e))?;
-        Ok(())
     }
-}
 
-// === ZIP Implementation ===
+    // Generic implementation: stream the archive straight into the destination backend
+    let writer = API.files.open_write(&destination_path, dest_fs)
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    let writer = match format {
+        ArchiveFormat::Zip => create_zip_generic(writer, &source_paths, options, source_fs)?,
+        ArchiveFormat::SevenZ => create_7z_generic(writer, &source_paths, options, source_fs)?,
+        _ => {
+            let encoder = TarEncoder::new(format, writer, options).map_err(|e| e.to_string())?;
+            create_tar_generic(encoder, &source_paths, source_fs)?
+                .finish()
+                .map_err(|e| e.to_string())?
+        }
+    };
 
-fn list_zip(path: &str) -> Result<Vec<FileSystemEntry>, String> {
-    let file = File::open(path).map_err(|e| e.to_string())?;
-    list_zip_generic(file, path)
+    writer.finish()
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    Ok(())
 }
 
+// === ZIP Implementation ===
+
 fn list_zip_generic<R: Read + Seek>(reader: R, archive_path: &str) -> Result<Vec<FileSystemEntry>, String> {
     let mut archive = zip::ZipArchive::new(reader).map_err(|e| e.to_string())?;
     let mut entries = Vec::new();
 
     for i in 0..archive.len() {
-        let file = match archive.by_index(i) {
+        // Только метаданные: зашифрованные записи тоже попадают в список
+        let file = match archive.by_index_raw(i) {
             Ok(file) => file,
             Err(_) => continue,
         };
@@ -182,78 +360,75 @@ fn list_zip_generic<R: Read + Seek>(reader: R, archive_path: &str) -> Result<Vec
             modified,
             created: None,
             accessed: None,
+            ..Default::default()
         });
     }
 
     Ok(entries)
 }
 
-fn extract_zip(archive_path: &str, destination_path: &str) -> Result<(), String> {
-    let file = File::open(archive_path).map_err(|e| e.to_string())?;
-    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
-    
-    std::fs::create_dir_all(destination_path).map_err(|e| e.to_string())?;
-    archive.extract(destination_path).map_err(|e| e.to_string())?;
-    Ok(())
+/// Время изменения записи zip (MS-DOS, без часового пояса — считаем UTC)
+pub(crate) fn zip_timestamp(time: zip::DateTime) -> Option<u64> {
+    let date = chrono::NaiveDate::from_ymd_opt(time.year().into(), time.month().into(), time.day().into())?;
+    let date_time = date.and_hms_opt(time.hour().into(), time.minute().into(), time.second().into())?;
+    u64::try_from(date_time.and_utc().timestamp()).ok()
 }
 
-fn extract_zip_generic<R: Read + Seek>(reader: R, destination_path: &str, dest_fs: Option<&str>) -> Result<(), String> {
+fn extract_zip_entries<R: Read + Seek>(reader: R, extractor: &mut Extractor) -> Result<(), String> {
     let mut archive = zip::ZipArchive::new(reader).map_err(|e| e.to_string())?;
-    
-    // Ensure destination directory exists (if empty archive or just root)
-    if let Err(_) = API.files.create_folder(Path::new(destination_path).parent().unwrap_or(Path::new("")).to_string_lossy().as_ref(), Path::new(destination_path).file_name().unwrap_or_default().to_string_lossy().as_ref(), dest_fs) {
-         // Ignore if root already exists or is top level, but generally we try to create
-    }
 
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
-        let name = file.name().replace("\\", "/");
-        
-        // Construct full path logic is tricky with VFS string paths.
-        // Assume destination_path is a directory.
-        let dest_path = if destination_path.ends_with('/') {
-            format!("{}{}", destination_path, name)
-        } else {
-            format!("{}/{}", destination_path, name)
-        };
+    for index in 0..archive.len() {
+        extractor.checkpoint()?;
+        let mut file = match extractor.password {
+            Some(password) => archive.by_index_decrypt(index, password.as_bytes()),
+            None => archive.by_index(index),
+        }
+        .map_err(zip_read_error)?;
+        let Some(path) = sanitize_entry_path(file.name())? else { continue };
+        if !extractor.wants(&path) {
+            continue;
+        }
 
-        if file.is_dir() || name.ends_with('/') {
-            // Create directory
-            // We need to split path to parent and name for create_folder
-            let p = Path::new(&dest_path);
-            if let Some(parent) = p.parent() {
-                if let Some(fname) = p.file_name() {
-                    let _ = API.files.create_folder(&parent.to_string_lossy(), &fname.to_string_lossy(), dest_fs);
-                }
-            }
+        if file.is_dir() {
+            extractor.make_dir(&path)?;
+        } else if file.is_symlink() {
+            let mut target = String::new();
+            file.read_to_string(&mut target).map_err(|e| e.to_string())?;
+            extractor.symlink(&path, &target)?;
         } else {
-            // Read content
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
-            
-            // Ensure parent exists
-            let p = Path::new(&dest_path);
-            if let Some(parent) = p.parent() {
-                 // Try to create parent structure if missing? 
-                 // Virtual FS might handle this or fail. 
-                 // Real implementation uses create_dir_all.
-                 // Here we assume folders appear in zip before files or VFS handles missing parents (it usually doesn't).
-                 // TODO: recursively create parents if needed. 
-            }
-
-            API.files.write_file_bytes(&dest_path, &buffer, dest_fs)
-                .map_err(|e| format!("Failed to write file {}: {}", name, e))?;
+            let size = file.size();
+            let modified = file.last_modified().and_then(zip_timestamp);
+            extractor.write_file(&path, &mut file, size, modified)?;
         }
     }
     Ok(())
 }
 
-fn create_zip(source_paths: &[String], destination_path: &str) -> Result<(), String> {
+fn zip_options(options: &ArchiveOptions) -> zip::write::FileOptions<'_, ()> {
+    let file_options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .compression_level(Some(i64::from(options.level(ArchiveFormat::Zip))));
+    match options.password() {
+        Some(password) => file_options.with_aes_encryption(zip::AesMode::Aes256, password),
+        None => file_options,
+    }
+}
+
+/// Ошибка чтения zip: отсутствующий и неверный пароль различаются
+fn zip_read_error(err: zip::result::ZipError) -> String {
+    match err {
+        zip::result::ZipError::InvalidPassword => WRONG_PASSWORD.to_string(),
+        zip::result::ZipError::UnsupportedArchive(detail) if detail == zip::result::ZipError::PASSWORD_REQUIRED => {
+            PASSWORD_REQUIRED.to_string()
+        }
+        err => err.to_string(),
+    }
+}
+
+fn create_zip(source_paths: &[String], destination_path: &str, archive_options: &ArchiveOptions) -> Result<(), String> {
     let file = File::create(destination_path).map_err(|e| e.to_string())?;
     let mut zip = zip::ZipWriter::new(file);
-    let options = zip::write::SimpleFileOptions::default()
-        .compression_method(zip::CompressionMethod::Deflated)
-        .unix_permissions(0o755);
+    let options = zip_options(archive_options).unix_permissions(0o755);
 
     for src_path in source_paths {
         let path = Path::new(src_path);
@@ -295,10 +470,10 @@ fn create_zip(source_paths: &[String], destination_path: &str) -> Result<(), Str
     Ok(())
 }
 
-fn create_zip_generic<W: Write + Seek>(writer: W, source_paths: &[String], source_fs: Option<&str>) -> Result<W, String> {
-    let mut zip = zip::ZipWriter::new(writer);
-    let options = zip::write::SimpleFileOptions::default()
-        .compression_method(zip::CompressionMethod::Deflated);
+fn create_zip_generic<W: Write>(writer: W, source_paths: &[String], archive_options: &ArchiveOptions, source_fs: Option<&str>) -> Result<W, String> {
+    // Stream mode: entries are written with data descriptors, no seeking back required
+    let mut zip = zip::ZipWriter::new_stream(writer);
+    let options = zip_options(archive_options);
 
     for src_path in source_paths {
         // Recursive helper
@@ -306,14 +481,14 @@ fn create_zip_generic<W: Write + Seek>(writer: W, source_paths: &[String], sourc
     }
 
     let writer = zip.finish().map_err(|e| e.to_string())?;
-    Ok(writer)
+    Ok(writer.into_inner())
 }
 
 fn add_to_zip_recursive<W: Write + Seek>(
     zip: &mut zip::ZipWriter<W>, 
     current_path: &str, 
     root_path: &str,
-    options: zip::write::SimpleFileOptions,
+    options: zip::write::FileOptions<'_, ()>,
     source_fs: Option<&str>
 ) -> Result<(), String> {
     let info = API.files.get_file_info(current_path, source_fs)
@@ -327,27 +502,24 @@ fn add_to_zip_recursive<W: Write + Seek>(
 
     if info.is_dir {
         zip.add_directory(&relative_path, options).map_err(|e| e.to_string())?;
-        let children = API.files.list_directory(current_path, source_fs)
+        let children = API.files.list_directory(current_path, Some(true), source_fs)
             .map_err(|e| e.to_string())?;
         for child in children {
             add_to_zip_recursive(zip, &child.path, root_path, options, source_fs)?;
         }
     } else {
-        zip.start_file(&relative_path, options).map_err(|e| e.to_string())?;
-        let content = API.files.read_file_bytes(current_path, source_fs)
+        // Stream mode can't patch headers afterwards, so ZIP64 must be decided up front
+        let large_file = info.size.unwrap_or(0) >= u32::MAX as u64;
+        zip.start_file(&relative_path, options.large_file(large_file)).map_err(|e| e.to_string())?;
+        let mut reader = API.files.open_read(current_path, source_fs)
             .map_err(|e| e.to_string())?;
-        zip.write_all(&content).map_err(|e| e.to_string())?;
+        std::io::copy(&mut reader, zip).map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
 // === TAR Implementation ===
 
-fn list_tar(path: &str) -> Result<Vec<FileSystemEntry>, String> {
-    let file = File::open(path).map_err(|e| e.to_string())?;
-    list_tar_generic(file, path)
-}
-
 fn list_tar_generic<R: Read>(reader: R, archive_path: &str) -> Result<Vec<FileSystemEntry>, String> {
     let mut archive = tar::Archive::new(reader);
     let mut entries = Vec::new();
@@ -369,52 +541,53 @@ fn list_tar_generic<R: Read>(reader: R, archive_path: &str) -> Result<Vec<FileSy
             modified: Some(modified),
             created: None,
             accessed: None,
+            ..Default::default()
         });
     }
     Ok(entries)
 }
 
-fn extract_tar(archive_path: &str, destination_path: &str) -> Result<(), String> {
-    let file = File::open(archive_path).map_err(|e| e.to_string())?;
-    std::fs::create_dir_all(destination_path).map_err(|e| e.to_string())?;
-    let mut archive = tar::Archive::new(file);
-    archive.unpack(destination_path).map_err(|e| e.to_string())?;
-    Ok(())
+fn tar_link_name<R: Read>(file: &tar::Entry<R>) -> Result<String, String> {
+    Ok(file.link_name().map_err(|e| e.to_string())?
+        .map(|target| target.to_string_lossy().to_string())
+        .unwrap_or_default())
 }
 
-fn extract_tar_generic<R: Read>(reader: R, destination_path: &str, dest_fs: Option<&str>) -> Result<(), String> {
+fn extract_tar_entries<R: Read>(reader: R, extractor: &mut Extractor) -> Result<(), String> {
     let mut archive = tar::Archive::new(reader);
-    
+
     for file in archive.entries().map_err(|e| e.to_string())? {
+        extractor.checkpoint()?;
         let mut file = file.map_err(|e| e.to_string())?;
-        let path = file.path().map_err(|e| e.to_string())?.to_string_lossy().to_string();
-        
-        let dest_path = if destination_path.ends_with('/') {
-            format!("{}{}", destination_path, path)
-        } else {
-            format!("{}/{}", destination_path, path)
-        };
+        let name = file.path().map_err(|e| e.to_string())?.to_string_lossy().to_string();
+        let Some(path) = sanitize_entry_path(&name)? else { continue };
+        if !extractor.wants(&path) {
+            continue;
+        }
 
-        if file.header().entry_type().is_dir() {
-             let p = Path::new(&dest_path);
-             if let Some(parent) = p.parent() {
-                 if let Some(fname) = p.file_name() {
-                     let _ = API.files.create_folder(&parent.to_string_lossy(), &fname.to_string_lossy(), dest_fs);
-                 }
-             }
+        let kind = file.header().entry_type();
+        if kind.is_dir() {
+            extractor.make_dir(&path)?;
+        } else if kind.is_symlink() {
+            let target = tar_link_name(&file)?;
+            extractor.symlink(&path, &target)?;
+        } else if kind.is_hard_link() {
+            let target = tar_link_name(&file)?;
+            extractor.hard_link(&path, &target)?;
+        } else if kind.is_file() || kind.is_contiguous() || kind.is_gnu_sparse() {
+            let size = file.size();
+            let modified = file.header().mtime().ok();
+            extractor.write_file(&path, &mut file, size, modified)?;
         } else {
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
-            API.files.write_file_bytes(&dest_path, &buffer, dest_fs)
-                .map_err(|e| format!("Failed to write file: {}", e))?;
+            // Устройства, FIFO и прочие спецзаписи не распаковываются
+            extractor.skip(0);
         }
     }
     Ok(())
 }
 
-fn create_tar(source_paths: &[String], destination_path: &str) -> Result<(), String> {
-    let file = File::create(destination_path).map_err(|e| e.to_string())?;
-    let mut archive = tar::Builder::new(file);
+fn create_tar<W: Write>(writer: W, source_paths: &[String]) -> Result<W, String> {
+    let mut archive = tar::Builder::new(writer);
 
     for src_path in source_paths {
         let path = Path::new(src_path);
@@ -431,8 +604,7 @@ fn create_tar(source_paths: &[String], destination_path: &str) -> Result<(), Str
         }
     }
 
-    archive.finish().map_err(|e| e.to_string())?;
-    Ok(())
+    archive.into_inner().map_err(|e| e.to_string())
 }
 
 fn create_tar_generic<W: Write>(writer: W, source_paths: &[String], source_fs: Option<&str>) -> Result<W, String> {
@@ -461,68 +633,231 @@ fn add_to_tar_recursive<W: Write>(
 
     if info.is_dir {
         archive.append_dir(&relative_path, ".").map_err(|e| e.to_string())?;
-        let children = API.files.list_directory(current_path, source_fs)
+        let children = API.files.list_directory(current_path, Some(true), source_fs)
             .map_err(|e| e.to_string())?;
         for child in children {
             add_to_tar_recursive(archive, &child.path, root_path, source_fs)?;
         }
     } else {
-        let content = API.files.read_file_bytes(current_path, source_fs)
+        let reader = API.files.open_read(current_path, source_fs)
             .map_err(|e| e.to_string())?;
         let mut header = tar::Header::new_gnu();
-        header.set_size(content.len() as u64);
+        header.set_size(info.size.unwrap_or(0));
         header.set_mode(0o644);
         header.set_cksum();
         
-        archive.append_data(&mut header, &relative_path, &content[..])
+        archive.append_data(&mut header, &relative_path, reader)
             .map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
-// === TAR.GZ Implementation ===
 
-fn list_tar_gz(path: &str) -> Result<Vec<FileSystemEntry>, String> {
-    let file = File::open(path).map_err(|e| e.to_string())?;
-    list_tar_gz_generic(file, path)
+// === Compressed TAR streams (gz, xz, zst, bz2) ===
+
+/// Распакованный поток tar
+pub(crate) fn tar_decoder<'a, R: Read + Send + 'a>(format: ArchiveFormat, reader: R) -> io::Result<Box<dyn Read + Send + 'a>> {
+    Ok(match format {
+        ArchiveFormat::Tar => Box::new(reader),
+        ArchiveFormat::TarGz => Box::new(flate2::read::GzDecoder::new(reader)),
+        ArchiveFormat::TarXz => Box::new(lzma_rust2::XzReader::new(reader, true)),
+        ArchiveFormat::TarZst => Box::new(zstd::stream::read::Decoder::new(reader)?),
+        ArchiveFormat::TarBz2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+        ArchiveFormat::Zip | ArchiveFormat::SevenZ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a tar archive", format.name()),
+            ));
+        }
+    })
 }
 
-fn list_tar_gz_generic<R: Read>(reader: R, archive_path: &str) -> Result<Vec<FileSystemEntry>, String> {
-    let tar = flate2::read::GzDecoder::new(reader);
-    list_tar_generic(tar, archive_path)
+/// Поток сжатия tar; архив дописан только после `finish`
+enum TarEncoder<W: Write> {
+    Plain(W),
+    Gz(flate2::write::GzEncoder<W>),
+    Xz(lzma_rust2::XzWriter<W>),
+    XzMt(lzma_rust2::XzWriterMt<W>),
+    Zst(zstd::stream::write::Encoder<'static, W>),
+    Bz2(bzip2::write::BzEncoder<W>),
 }
 
-fn extract_tar_gz(archive_path: &str, destination_path: &str) -> Result<(), String> {
-    let file = File::open(archive_path).map_err(|e| e.to_string())?;
-    std::fs::create_dir_all(destination_path).map_err(|e| e.to_string())?;
-    let tar = flate2::read::GzDecoder::new(file);
-    let mut archive = tar::Archive::new(tar);
-    archive.unpack(destination_path).map_err(|e| e.to_string())?;
-    Ok(())
+impl<W: Write> TarEncoder<W> {
+    fn new(format: ArchiveFormat, writer: W, options: &ArchiveOptions) -> io::Result<Self> {
+        let level = options.level(format);
+        let threads = options.threads();
+
+        Ok(match format {
+            ArchiveFormat::Tar => TarEncoder::Plain(writer),
+            ArchiveFormat::TarGz => TarEncoder::Gz(flate2::write::GzEncoder::new(writer, flate2::Compression::new(level))),
+            ArchiveFormat::TarXz if threads > 1 => {
+                // Многопоточный xz режет поток на независимые блоки (как `xz -T`)
+                let mut xz_options = lzma_rust2::XzOptions::with_preset(level);
+                xz_options.block_size = NonZeroU64::new(u64::from(xz_options.lzma_options.dict_size) * 3);
+                TarEncoder::XzMt(lzma_rust2::XzWriterMt::new(writer, xz_options, threads)?)
+            }
+            ArchiveFormat::TarXz => TarEncoder::Xz(lzma_rust2::XzWriter::new(writer, lzma_rust2::XzOptions::with_preset(level))?),
+            ArchiveFormat::TarZst => {
+                let mut encoder = zstd::stream::write::Encoder::new(writer, level as i32)?;
+                if threads > 1 {
+                    encoder.multithread(threads)?;
+                }
+                TarEncoder::Zst(encoder)
+            }
+            ArchiveFormat::TarBz2 => TarEncoder::Bz2(bzip2::write::BzEncoder::new(writer, bzip2::Compression::new(level))),
+            ArchiveFormat::Zip | ArchiveFormat::SevenZ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} is not a tar archive", format.name()),
+                ));
+            }
+        })
+    }
+
+    fn finish(self) -> io::Result<W> {
+        match self {
+            TarEncoder::Plain(writer) => Ok(writer),
+            TarEncoder::Gz(encoder) => encoder.finish(),
+            TarEncoder::Xz(encoder) => encoder.finish(),
+            TarEncoder::XzMt(encoder) => encoder.finish(),
+            TarEncoder::Zst(encoder) => encoder.finish(),
+            TarEncoder::Bz2(encoder) => encoder.finish(),
+        }
+    }
+
+    fn stream(&mut self) -> &mut dyn Write {
+        match self {
+            TarEncoder::Plain(writer) => writer,
+            TarEncoder::Gz(encoder) => encoder,
+            TarEncoder::Xz(encoder) => encoder,
+            TarEncoder::XzMt(encoder) => encoder,
+            TarEncoder::Zst(encoder) => encoder,
+            TarEncoder::Bz2(encoder) => encoder,
+        }
+    }
+}
+
+impl<W: Write> Write for TarEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream().flush()
+    }
+}
+
+// === 7Z Implementation ===
+
+fn stream_len<R: Seek>(reader: &mut R) -> Result<u64, String> {
+    let len = reader.seek(SeekFrom::End(0)).map_err(|e| e.to_string())?;
+    reader.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+    Ok(len)
+}
+
+fn seven_zip_modified(entry: &sevenz_rust::SevenZArchiveEntry) -> Option<u64> {
+    if !entry.has_last_modified_date {
+        return None;
+    }
+    u64::try_from(entry.last_modified_date().to_unix_time()).ok()
+}
+
+pub(crate) fn open_7z<R: Read + Seek>(mut reader: R) -> Result<sevenz_rust::SevenZReader<R>, String> {
+    let len = stream_len(&mut reader)?;
+    sevenz_rust::SevenZReader::new(reader, len, sevenz_rust::Password::empty()).map_err(|e| e.to_string())
+}
+
+fn list_7z_generic<R: Read + Seek>(reader: R, archive_path: &str) -> Result<Vec<FileSystemEntry>, String> {
+    let archive = open_7z(reader)?;
+
+    Ok(archive.archive().files.iter()
+        .filter(|entry| !entry.is_anti_item())
+        .map(|entry| {
+            let name = entry.name().replace("\\", "/");
+            let is_dir = entry.is_directory();
+            FileSystemEntry {
+                path: format!("{}/{}", archive_path, name),
+                name,
+                is_dir,
+                is_file: !is_dir,
+                size: Some(entry.size()),
+                modified: seven_zip_modified(entry),
+                ..Default::default()
+            }
+        })
+        .collect())
+}
+
+fn extract_7z_entries<R: Read + Seek>(reader: R, extractor: &mut Extractor) -> Result<(), String> {
+    let mut archive = open_7z(reader)?;
+    let mut failure = None;
+
+    let result = archive.for_each_entries(|entry, data| {
+        let step = (|| -> Result<(), String> {
+            extractor.checkpoint()?;
+            if entry.is_anti_item() {
+                return Ok(());
+            }
+            let Some(path) = sanitize_entry_path(entry.name())? else { return Ok(()) };
+            if !extractor.wants(&path) {
+                return Ok(());
+            }
+            if entry.is_directory() {
+                extractor.make_dir(&path)
+            } else {
+                extractor.write_file(&path, data, entry.size(), seven_zip_modified(entry))
+            }
+        })();
+
+        match step {
+            Ok(()) => {
+                // Невыбранные записи всё равно нужно прочитать: в solid-блоке за ними идут следующие
+                io::copy(data, &mut io::sink()).map_err(sevenz_rust::Error::io)?;
+                Ok(true)
+            }
+            Err(e) => {
+                failure = Some(e);
+                Err(sevenz_rust::Error::other("extraction aborted"))
+            }
+        }
+    });
+
+    match (failure, result) {
+        (Some(e), _) => Err(e),
+        (None, result) => result.map_err(|e| e.to_string()),
+    }
 }
 
-fn extract_tar_gz_generic<R: Read>(reader: R, destination_path: &str, dest_fs: Option<&str>) -> Result<(), String> {
-    let tar = flate2::read::GzDecoder::new(reader);
-    extract_tar_generic(tar, destination_path, dest_fs)
+fn seven_zip_writer<W: Write + Seek>(writer: W, options: &ArchiveOptions) -> Result<sevenz_rust::SevenZWriter<W>, String> {
+    let mut archive = sevenz_rust::SevenZWriter::new(writer).map_err(|e| e.to_string())?;
+    let level = options.level(ArchiveFormat::SevenZ);
+    archive.set_content_methods(vec![sevenz_rust::lzma::LZMA2Options::with_preset(level).into()]);
+    Ok(archive)
 }
 
-fn create_tar_gz(source_paths: &[String], destination_path: &str) -> Result<(), String> {
+fn create_7z(source_paths: &[String], destination_path: &str, options: &ArchiveOptions) -> Result<(), String> {
     let file = File::create(destination_path).map_err(|e| e.to_string())?;
-    let enc = flate2::write::GzEncoder::new(file, flate2::Compression::default());
-    let mut archive = tar::Builder::new(enc);
+    let mut archive = seven_zip_writer(file, options)?;
 
     for src_path in source_paths {
         let path = Path::new(src_path);
         if !path.exists() { continue; }
 
-        let file_name = path.file_name() 
-            .ok_or("Invalid file name")?;
-
-        if path.is_dir() {
-            archive.append_dir_all(&file_name, path).map_err(|e| e.to_string())?;
-        } else {
-            let mut f = File::open(path).map_err(|e| e.to_string())?;
-            archive.append_file(&file_name, &mut f).map_err(|e| e.to_string())?;
+        let root = path.parent().unwrap_or(path);
+        for entry in walkdir::WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+            let entry_path = entry.path();
+            let name = entry_path.strip_prefix(root)
+                .map_err(|e| e.to_string())?
+                .to_string_lossy()
+                .replace("\\", "/");
+
+            let archive_entry = sevenz_rust::SevenZArchiveEntry::from_path(entry_path, name);
+            let reader = if entry_path.is_file() {
+                Some(File::open(entry_path).map_err(|e| e.to_string())?)
+            } else {
+                None
+            };
+            archive.push_archive_entry(archive_entry, reader).map_err(|e| e.to_string())?;
         }
     }
 
@@ -530,9 +865,1580 @@ fn create_tar_gz(source_paths: &[String], destination_path: &str) -> Result<(),
     Ok(())
 }
 
-fn create_tar_gz_generic<W: Write>(writer: W, source_paths: &[String], source_fs: Option<&str>) -> Result<W, String> {
-    let enc = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
-    let enc_finished = create_tar_generic(enc, source_paths, source_fs)?;
-    let writer = enc_finished.finish().map_err(|e| e.to_string())?;
+fn create_7z_generic<W: Write>(writer: W, source_paths: &[String], options: &ArchiveOptions, source_fs: Option<&str>) -> Result<W, String> {
+    // Заголовок 7z пишется в конце со ссылкой в начале архива: архив собирается в буфере.
+    // Содержимое хранилища не должно попадать на диск открытым, поэтому для него буфер в памяти.
+    if matches!(API.files.get_filesystem_by_backend(source_fs), FileSystemInstance::Virtual(_)) {
+        build_7z(io::Cursor::new(Vec::new()), writer, source_paths, options, source_fs)
+    } else {
+        let buffer = tempfile::tempfile().map_err(|e| e.to_string())?;
+        build_7z(buffer, writer, source_paths, options, source_fs)
+    }
+}
+
+fn build_7z<B: Read + Write + Seek, W: Write>(
+    buffer: B,
+    mut writer: W,
+    source_paths: &[String],
+    options: &ArchiveOptions,
+    source_fs: Option<&str>,
+) -> Result<W, String> {
+    let mut archive = seven_zip_writer(buffer, options)?;
+
+    for src_path in source_paths {
+        add_to_7z_recursive(&mut archive, src_path, src_path, source_fs)?;
+    }
+
+    let mut buffer = archive.finish().map_err(|e| e.to_string())?;
+    buffer.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+    io::copy(&mut buffer, &mut writer).map_err(|e| e.to_string())?;
     Ok(writer)
 }
+
+fn add_to_7z_recursive<W: Write + Seek>(
+    archive: &mut sevenz_rust::SevenZWriter<W>,
+    current_path: &str,
+    root_path: &str,
+    source_fs: Option<&str>
+) -> Result<(), String> {
+    let info = API.files.get_file_info(current_path, source_fs)
+        .map_err(|e| e.to_string())?;
+
+    let root_parent = Path::new(root_path).parent().unwrap_or(Path::new(""));
+    let relative_path = Path::new(current_path).strip_prefix(root_parent)
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .replace("\\", "/");
+
+    let mut entry = sevenz_rust::SevenZArchiveEntry::new();
+    entry.name = relative_path;
+    entry.is_directory = info.is_dir;
+    entry.has_stream = !info.is_dir;
+    let modified = info.modified
+        .and_then(|secs| i64::try_from(secs).ok())
+        .and_then(|secs| sevenz_rust::nt_time::FileTime::from_unix_time(secs).ok());
+    if let Some(modified) = modified {
+        entry.last_modified_date = modified;
+        entry.has_last_modified_date = true;
+    }
+
+    if info.is_dir {
+        archive.push_archive_entry::<io::Empty>(entry, None).map_err(|e| e.to_string())?;
+        let children = API.files.list_directory(current_path, Some(true), source_fs)
+            .map_err(|e| e.to_string())?;
+        for child in children {
+            add_to_7z_recursive(archive, &child.path, root_path, source_fs)?;
+        }
+    } else {
+        let reader = API.files.open_read(current_path, source_fs)
+            .map_err(|e| e.to_string())?;
+        archive.push_archive_entry(entry, Some(reader)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// === Safe extraction ===
+
+/// Что делать с файлом, который уже есть в месте распаковки
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverwritePolicy {
+    /// Оставить существующий файл
+    Skip,
+    #[default]
+    Overwrite,
+    /// Распаковать рядом под именем `name (N).ext`
+    Rename,
+    /// Заменить, только если запись в архиве новее
+    Newer,
+}
+
+/// Параметры распаковки
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractOptions {
+    /// Пути внутри архива; каталог выбирает всё своё содержимое. Пусто — весь архив
+    #[serde(default)]
+    pub entries: Vec<String>,
+    #[serde(default)]
+    pub overwrite: OverwritePolicy,
+    /// Пароль для zip (AES или устаревший ZipCrypto)
+    #[serde(default, skip_serializing)]
+    pub password: Option<ArchivePassword>,
+}
+
+/// Итог распаковки
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractReport {
+    pub extracted: u64,
+    pub skipped: u64,
+    /// Файлы, сохранённые под новым именем из-за совпадения
+    pub renamed: u64,
+    pub bytes: u64,
+    pub cancelled: bool,
+}
+
+/// Относительный путь записи внутри места распаковки
+///
+/// Абсолютные пути, буквы дисков и `..` отклоняются (zip-slip). `None` — сам корень архива.
+pub(crate) fn sanitize_entry_path(name: &str) -> Result<Option<String>, String> {
+    let unsafe_path = || format!("Unsafe path in archive: {}", name);
+    let normalized = name.replace('\\', "/");
+    if normalized.starts_with('/') || normalized.contains('\0') {
+        return Err(unsafe_path());
+    }
+
+    let mut parts = Vec::new();
+    for part in normalized.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => return Err(unsafe_path()),
+            _ if parts.is_empty() && has_drive_prefix(part) => return Err(unsafe_path()),
+            _ => parts.push(part),
+        }
+    }
+    Ok((!parts.is_empty()).then(|| parts.join("/")))
+}
+
+fn has_drive_prefix(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// `path` совпадает с `prefix` или лежит внутри него
+fn is_under(path: &str, prefix: &str) -> bool {
+    path == prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn is_selected(selection: &[String], path: &str) -> bool {
+    selection.is_empty() || selection.iter().any(|selected| is_under(path, selected))
+}
+
+/// Ожидание на паузе; `true` — операция отменена
+fn is_cancelled(tracker: Option<&ProgressTracker>) -> bool {
+    let Some(tracker) = tracker else { return false };
+    while tracker.is_paused() && !tracker.is_cancelled() {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    tracker.is_cancelled()
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &str, link: &str) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &str, _link: &str) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "Symlinks are not supported"))
+}
+
+/// Запись распакованных данных в целевую ФС: проверки, общие для всех форматов
+struct Extractor<'a> {
+    destination: String,
+    dest_fs: Option<&'a str>,
+    /// Канонический корень на реальной ФС: по нему ловится выход наружу через симлинки
+    real_root: Option<PathBuf>,
+    selection: Vec<String>,
+    overwrite: OverwritePolicy,
+    password: Option<&'a str>,
+    tracker: Option<&'a ProgressTracker>,
+    created_dirs: HashSet<String>,
+    report: ExtractReport,
+}
+
+impl<'a> Extractor<'a> {
+    fn new(
+        destination: &str,
+        options: &'a ExtractOptions,
+        dest_fs: Option<&'a str>,
+        tracker: Option<&'a ProgressTracker>,
+    ) -> Result<Self, String> {
+        let mut selection = Vec::new();
+        for entry in &options.entries {
+            match sanitize_entry_path(entry)? {
+                Some(path) => selection.push(path),
+                // Выбран корень архива — распаковывается всё
+                None => {
+                    selection.clear();
+                    break;
+                }
+            }
+        }
+
+        let is_real = dest_fs.is_none() || dest_fs == Some("real");
+        let real_root = if is_real {
+            std::fs::create_dir_all(destination).map_err(|e| e.to_string())?;
+            Some(std::fs::canonicalize(destination).map_err(|e| e.to_string())?)
+        } else {
+            let path = Path::new(destination);
+            if let (Some(parent), Some(name)) = (path.parent(), path.file_name()) {
+                // Каталог может уже существовать
+                let _ = API.files.create_folder(&parent.to_string_lossy(), &name.to_string_lossy(), dest_fs);
+            }
+            None
+        };
+
+        Ok(Self {
+            destination: destination.trim_end_matches(['/', '\\']).to_string(),
+            dest_fs,
+            real_root,
+            selection,
+            overwrite: options.overwrite,
+            password: options.password.as_ref().and_then(ArchivePassword::expose),
+            tracker,
+            created_dirs: HashSet::new(),
+            report: ExtractReport::default(),
+        })
+    }
+
+    fn dest_path(&self, path: &str) -> String {
+        match (path.is_empty(), self.destination.is_empty()) {
+            (true, true) => "/".to_string(),
+            (true, false) => self.destination.clone(),
+            (false, _) => format!("{}/{}", self.destination, path),
+        }
+    }
+
+    fn wants(&self, path: &str) -> bool {
+        is_selected(&self.selection, path)
+    }
+
+    /// Ожидание на паузе и проверка отмены
+    fn checkpoint(&mut self) -> Result<(), String> {
+        if is_cancelled(self.tracker) {
+            self.report.cancelled = true;
+            return Err("Operation cancelled".to_string());
+        }
+        Ok(())
+    }
+
+    fn skip(&mut self, size: u64) {
+        self.report.skipped += 1;
+        if let Some(tracker) = self.tracker {
+            tracker.add_bytes(size);
+            tracker.add_item();
+        }
+    }
+
+    fn done(&mut self) {
+        if let Some(tracker) = self.tracker {
+            tracker.add_item();
+        }
+    }
+
+    /// Ни один уже существующий компонент пути не должен быть симлинком за пределы корня
+    fn ensure_contained(&self, path: &str) -> Result<(), String> {
+        let Some(root) = &self.real_root else { return Ok(()) };
+        let mut current = root.clone();
+        for part in path.split('/') {
+            current.push(part);
+            match std::fs::symlink_metadata(&current) {
+                Err(_) => break,
+                Ok(meta) if meta.file_type().is_symlink() => {
+                    let inside = current.canonicalize().is_ok_and(|resolved| resolved.starts_with(root));
+                    if !inside {
+                        return Err(format!("Entry escapes destination through a symlink: {}", path));
+                    }
+                }
+                Ok(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Время изменения существующего файла; внешний `None` — файла нет
+    fn existing(&self, path: &str) -> Option<Option<u64>> {
+        if self.real_root.is_some() {
+            // symlink_metadata: висящая ссылка тоже считается занятым именем
+            let meta = std::fs::symlink_metadata(path).ok()?;
+            Some(meta.modified().ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_secs()))
+        } else {
+            API.files.get_file_info(path, self.dest_fs).ok().map(|info| info.modified)
+        }
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        if self.real_root.is_some() {
+            Path::new(path).is_dir()
+        } else {
+            API.files.get_file_info(path, self.dest_fs).is_ok_and(|info| info.is_dir)
+        }
+    }
+
+    /// Создаёт каталог и всех его родителей внутри места распаковки
+    fn ensure_dirs(&mut self, path: &str) -> Result<(), String> {
+        let mut current = String::new();
+        for part in path.split('/') {
+            let parent = self.dest_path(&current);
+            if !current.is_empty() {
+                current.push('/');
+            }
+            current.push_str(part);
+            if self.created_dirs.contains(&current) {
+                continue;
+            }
+
+            self.ensure_contained(&current)?;
+            if !self.is_dir(&self.dest_path(&current)) {
+                API.files.create_folder(&parent, part, self.dest_fs)
+                    .map_err(|e| format!("Failed to create folder {}: {}", current, e))?;
+            }
+            self.created_dirs.insert(current.clone());
+        }
+        Ok(())
+    }
+
+    /// Итоговый путь с учётом политики перезаписи; `None` — запись пропускается
+    fn resolve_target(&mut self, path: &str, modified: Option<u64>) -> Result<Option<String>, String> {
+        let target = self.dest_path(path);
+        let Some(existing_modified) = self.existing(&target) else { return Ok(Some(target)) };
+
+        match self.overwrite {
+            OverwritePolicy::Skip => return Ok(None),
+            OverwritePolicy::Newer => {
+                if !matches!((modified, existing_modified), (Some(new), Some(old)) if new > old) {
+                    return Ok(None);
+                }
+            }
+            OverwritePolicy::Rename => {
+                self.report.renamed += 1;
+                return Ok(Some(self.free_name(&target)));
+            }
+            OverwritePolicy::Overwrite => {}
+        }
+
+        // Существующий симлинк заменяется, а не перезаписывается то, на что он указывает
+        if self.real_root.is_some() && std::fs::symlink_metadata(&target).is_ok_and(|meta| meta.file_type().is_symlink()) {
+            std::fs::remove_file(&target).map_err(|e| e.to_string())?;
+        }
+        Ok(Some(target))
+    }
+
+    fn free_name(&self, target: &str) -> String {
+        let (parent, name) = target.rsplit_once('/').unwrap_or(("", target));
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+            _ => (name, String::new()),
+        };
+
+        let mut number = 1;
+        loop {
+            let candidate = format!("{}/{} ({}){}", parent, stem, number, extension);
+            if self.existing(&candidate).is_none() {
+                return candidate;
+            }
+            number += 1;
+        }
+    }
+
+    fn make_dir(&mut self, path: &str) -> Result<(), String> {
+        self.ensure_dirs(path)?;
+        self.done();
+        Ok(())
+    }
+
+    fn write_file(&mut self, path: &str, reader: &mut dyn Read, size: u64, modified: Option<u64>) -> Result<(), String> {
+        if let Some(tracker) = self.tracker {
+            tracker.update_current_file(Some(path.to_string()));
+        }
+        if let Some((parent, _)) = path.rsplit_once('/') {
+            self.ensure_dirs(parent)?;
+        }
+        let Some(target) = self.resolve_target(path, modified)? else {
+            self.skip(size);
+            return Ok(());
+        };
+
+        let mut writer = API.files.open_write(&target, self.dest_fs)
+            .map_err(|e| format!("Failed to write file {}: {}", path, e))?;
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            self.checkpoint()?;
+            let read = reader.read(&mut buffer)
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buffer[..read])
+                .map_err(|e| format!("Failed to write file {}: {}", path, e))?;
+            self.report.bytes += read as u64;
+            if let Some(tracker) = self.tracker {
+                tracker.add_bytes(read as u64);
+            }
+        }
+        writer.finish()
+            .map_err(|e| format!("Failed to write file {}: {}", path, e))?;
+
+        if let (Some(modified), Some(_)) = (modified, &self.real_root) {
+            // Время из архива нужно политике `Newer` при повторной распаковке
+            let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(modified);
+            let _ = File::options().write(true).open(&target).and_then(|file| file.set_modified(time));
+        }
+
+        self.report.extracted += 1;
+        self.done();
+        Ok(())
+    }
+
+    fn symlink(&mut self, path: &str, target: &str) -> Result<(), String> {
+        let escapes = || format!("Symlink escapes destination: {} -> {}", path, target);
+        let normalized = target.replace('\\', "/");
+        if normalized.starts_with('/') || has_drive_prefix(&normalized) {
+            return Err(escapes());
+        }
+        // Цель отсчитывается от каталога ссылки и не должна подниматься выше корня
+        let mut depth = path.matches('/').count();
+        for part in normalized.split('/') {
+            match part {
+                "" | "." => {}
+                ".." => depth = depth.checked_sub(1).ok_or_else(escapes)?,
+                _ => depth += 1,
+            }
+        }
+
+        // Остальные бэкенды симлинков не поддерживают
+        if self.real_root.is_none() || !cfg!(unix) {
+            self.skip(0);
+            return Ok(());
+        }
+        if let Some((parent, _)) = path.rsplit_once('/') {
+            self.ensure_dirs(parent)?;
+        }
+        let Some(link) = self.resolve_target(path, None)? else {
+            self.skip(0);
+            return Ok(());
+        };
+        create_symlink(target, &link).map_err(|e| format!("Failed to create symlink {}: {}", path, e))?;
+
+        self.report.extracted += 1;
+        self.done();
+        Ok(())
+    }
+
+    /// Жёсткая ссылка tar распаковывается копией уже извлечённого файла
+    fn hard_link(&mut self, path: &str, target: &str) -> Result<(), String> {
+        let Some(target) = sanitize_entry_path(target)? else {
+            self.skip(0);
+            return Ok(());
+        };
+        self.ensure_contained(&target)?;
+        let source = self.dest_path(&target);
+        let Ok(info) = API.files.get_file_info(&source, self.dest_fs) else {
+            self.skip(0);
+            return Ok(());
+        };
+
+        let mut reader = API.files.open_read(&source, self.dest_fs)
+            .map_err(|e| format!("Failed to read {}: {}", target, e))?;
+        self.write_file(path, &mut reader, info.size.unwrap_or(0), None)
+    }
+}
+
+// === Integrity test ===
+
+/// Сколько результатов проверок из очереди хранится для `archive_test_result`
+const MAX_STORED_TEST_RESULTS: usize = 32;
+
+/// Результаты завершённых проверок очереди (ID операции → отчёт)
+static TEST_RESULTS: Lazy<Mutex<VecDeque<(String, ArchiveTestReport)>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+/// Повреждённая запись архива
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveTestFailure {
+    /// Имя записи; пустое — ошибка самого архива (заголовки, сжатый поток)
+    pub entry: String,
+    pub error: String,
+}
+
+/// Итог проверки целостности архива
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveTestReport {
+    pub archive_path: String,
+    pub format: String,
+    /// Проверено записей
+    pub tested: u64,
+    /// Распаковано байт
+    pub bytes: u64,
+    pub failures: Vec<ArchiveTestFailure>,
+    pub cancelled: bool,
+}
+
+impl ArchiveTestReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Проверка целостности: каждая запись распаковывается целиком без записи на диск
+///
+/// Сверяются CRC записей zip и 7z, размеры записей tar и контрольные суммы сжатых
+/// потоков (gzip, xz, zstd, bzip2). Повреждённые записи попадают в `failures`, проверка
+/// продолжается со следующей; `Err` — только если архив не удалось открыть.
+pub fn test_archive(
+    archive_path: &str,
+    source_fs: Option<&str>,
+    password: Option<&ArchivePassword>,
+    tracker: Option<&ProgressTracker>,
+) -> Result<ArchiveTestReport, String> {
+    let is_real = source_fs.is_none() || source_fs == Some("real");
+    let (reader, format): (Box<dyn FileReader>, ArchiveFormat) = if is_real {
+        let (file, format) = open_real(archive_path)?;
+        (Box::new(file), format)
+    } else {
+        let mut reader = API.files.open_read(archive_path, source_fs)
+            .map_err(|e| format!("Failed to read archive: {}", e))?;
+        let format = ArchiveFormat::identify(&mut reader, archive_path)
+            .map_err(|e| format!("Failed to read archive: {}", e))?
+            .ok_or_else(|| format!("Unsupported archive format: {}", archive_path))?;
+        (reader, format)
+    };
+
+    let mut tester = ArchiveTester {
+        tracker,
+        password: password.and_then(ArchivePassword::expose),
+        report: ArchiveTestReport {
+            archive_path: archive_path.to_string(),
+            format: format.name().to_string(),
+            ..Default::default()
+        },
+    };
+
+    let result = match format {
+        ArchiveFormat::Zip => test_zip(reader, &mut tester),
+        ArchiveFormat::SevenZ => test_7z(reader, &mut tester),
+        _ => test_tar(reader, format, &mut tester),
+    };
+    match result {
+        Ok(()) => {}
+        Err(_) if tester.report.cancelled => {}
+        Err(e) => tester.fail("", e),
+    }
+    Ok(tester.report)
+}
+
+/// Проверка из очереди: отчёт сохраняется для `archive_test_result`
+pub fn run_archive_test(
+    operation_id: &str,
+    archive_path: &str,
+    source_fs: Option<&str>,
+    password: Option<&ArchivePassword>,
+    tracker: Option<&ProgressTracker>,
+) -> Result<ArchiveTestReport, String> {
+    let report = test_archive(archive_path, source_fs, password, tracker)?;
+    let mut results = TEST_RESULTS.lock().unwrap();
+    results.retain(|(id, _)| id != operation_id);
+    if results.len() >= MAX_STORED_TEST_RESULTS {
+        results.pop_front();
+    }
+    results.push_back((operation_id.to_string(), report.clone()));
+    Ok(report)
+}
+
+/// Отчёт завершённой проверки из очереди
+pub fn archive_test_result(operation_id: &str) -> Option<ArchiveTestReport> {
+    TEST_RESULTS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(id, _)| id == operation_id)
+        .map(|(_, report)| report.clone())
+}
+
+fn test_zip<R: Read + Seek>(reader: R, tester: &mut ArchiveTester) -> Result<(), String> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(zip_read_error)?;
+
+    if let Some(tracker) = tester.tracker {
+        let total_bytes = (0..archive.len())
+            .filter_map(|index| archive.by_index_raw(index).ok().map(|file| file.size()))
+            .sum();
+        tracker.set_total_bytes(total_bytes);
+        tracker.set_total_items(archive.len() as u64);
+    }
+
+    for index in 0..archive.len() {
+        tester.checkpoint()?;
+        let name = archive.name_for_index(index).unwrap_or_default().to_string();
+        let file = match tester.password {
+            Some(password) => archive.by_index_decrypt(index, password.as_bytes()),
+            None => archive.by_index(index),
+        };
+        match file {
+            Ok(mut file) => {
+                let expected = (!file.is_dir()).then(|| file.size());
+                tester.test_entry(&name, &mut file, expected, true)?;
+            }
+            Err(e) => {
+                tester.fail(&name, zip_read_error(e));
+                tester.report.tested += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// tar читается потоком: прогресс считается по прочитанным байтам самого архива
+fn test_tar<R: Read + Seek + Send>(mut reader: R, format: ArchiveFormat, tester: &mut ArchiveTester) -> Result<(), String> {
+    if let Some(tracker) = tester.tracker {
+        tracker.set_total_bytes(stream_len(&mut reader)?);
+    }
+    let counted = TrackedReader { inner: reader, tracker: tester.tracker };
+    let mut archive = tar::Archive::new(tar_decoder(format, counted).map_err(|e| e.to_string())?);
+
+    for entry in archive.entries().map_err(|e| e.to_string())? {
+        tester.checkpoint()?;
+        let mut entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.path()
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // Размер в заголовке sparse-записи не совпадает с распакованным
+        let expected = entry.header().entry_type().is_file().then(|| entry.size());
+        if !tester.test_entry(&name, &mut entry, expected, false)? {
+            // После сбоя границы следующих записей в потоке неизвестны
+            return Ok(());
+        }
+    }
+
+    // За концом tar остаётся хвост сжатого потока с его контрольной суммой
+    io::copy(&mut archive.into_inner(), &mut io::sink()).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn test_7z<R: Read + Seek>(mut reader: R, tester: &mut ArchiveTester) -> Result<(), String> {
+    let len = stream_len(&mut reader)?;
+    let archive = sevenz_rust::Archive::read(&mut reader, len, &[]).map_err(|e| e.to_string())?;
+
+    if let Some(tracker) = tester.tracker {
+        let files = &archive.files;
+        tracker.set_total_bytes(files.iter().filter(|entry| entry.has_stream()).map(|entry| entry.size()).sum());
+        tracker.set_total_items(files.len() as u64);
+    }
+
+    // Блоки проверяются по одному: сбой одного не прерывает проверку остальных
+    for block in 0..archive.folders.len() {
+        let decoder = sevenz_rust::BlockDecoder::new(block, &archive, &[], &mut reader);
+        let names: Vec<String> = decoder.entries().iter().map(|entry| entry.name().to_string()).collect();
+        let mut reached = 0;
+        let mut error = None;
+        let outcome = decoder.for_each_entries(&mut |entry: &sevenz_rust::SevenZArchiveEntry, data: &mut dyn Read| {
+            reached += 1;
+            let expected = (!entry.is_directory()).then(|| entry.size());
+            tester.test_entry(entry.name(), data, expected, true).or_else(|e| {
+                error = Some(e);
+                Ok::<_, sevenz_rust::Error>(false)
+            })
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+
+        // После сбоя остаток solid-блока не читается: эти записи не проверены и тоже в отчёте
+        let reason = match outcome {
+            Ok(_) => "Not tested: preceding entry in solid block is damaged".to_string(),
+            Err(e) => e.to_string(),
+        };
+        for name in &names[reached..] {
+            tester.skip(name, &reason);
+        }
+    }
+
+    // Пустые файлы и каталоги не входят ни в один блок
+    for (index, entry) in archive.files.iter().enumerate() {
+        if archive.stream_map.file_folder_index[index].is_none() {
+            tester.checkpoint()?;
+            let expected = (!entry.is_directory()).then(|| entry.size());
+            tester.test_entry(entry.name(), &mut io::empty(), expected, true)?;
+        }
+    }
+    Ok(())
+}
+
+/// Состояние проверки целостности
+struct ArchiveTester<'a> {
+    tracker: Option<&'a ProgressTracker>,
+    password: Option<&'a str>,
+    report: ArchiveTestReport,
+}
+
+impl ArchiveTester<'_> {
+    fn checkpoint(&mut self) -> Result<(), String> {
+        if is_cancelled(self.tracker) {
+            self.report.cancelled = true;
+            return Err("Operation cancelled".to_string());
+        }
+        Ok(())
+    }
+
+    fn fail(&mut self, entry: &str, error: impl ToString) {
+        self.report.failures.push(ArchiveTestFailure {
+            entry: entry.to_string(),
+            error: error.to_string(),
+        });
+    }
+
+    /// Запись, которую не удалось прочитать, считается повреждённой
+    fn skip(&mut self, entry: &str, reason: &str) {
+        self.fail(entry, reason);
+        if let Some(tracker) = self.tracker {
+            tracker.add_item();
+        }
+    }
+
+    /// Полное чтение записи; `Ok(false)` — запись повреждена
+    fn test_entry(&mut self, name: &str, data: &mut dyn Read, expected: Option<u64>, count_bytes: bool) -> Result<bool, String> {
+        if let Some(tracker) = self.tracker {
+            tracker.update_current_file(Some(name.to_string()));
+        }
+
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut read = 0u64;
+        let outcome = loop {
+            self.checkpoint()?;
+            match data.read(&mut buffer) {
+                Ok(0) => break Ok(()),
+                Ok(count) => {
+                    read += count as u64;
+                    if count_bytes {
+                        if let Some(tracker) = self.tracker {
+                            tracker.add_bytes(count as u64);
+                        }
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => break Err(e.to_string()),
+            }
+        };
+        // Отмена внутри счётчика байт tar приходит ошибкой чтения
+        self.checkpoint()?;
+
+        self.report.tested += 1;
+        self.report.bytes += read;
+        if let Some(tracker) = self.tracker {
+            tracker.add_item();
+        }
+
+        let outcome = outcome.and_then(|()| match expected {
+            Some(size) if size != read => Err(format!("Unexpected end of data: {} of {} bytes", read, size)),
+            _ => Ok(()),
+        });
+        match outcome {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                self.fail(name, e);
+                Ok(false)
+            }
+        }
+    }
+}
+
+// === Archive editing ===
+
+/// Предел размера архива и нового содержимого при изменении не на реальной ФС
+const STORED_EDIT_LIMIT: u64 = 1 << 30;
+
+/// Изменение существующего архива
+#[derive(Debug, Clone)]
+pub enum ArchiveEdit {
+    /// Добавить файлы и каталоги с любого бэкенда в каталог архива (`""` — корень)
+    Add { sources: Vec<String>, target_dir: String, source_fs: Option<String> },
+    /// Удалить записи; каталог удаляется вместе с содержимым
+    Delete { entries: Vec<String> },
+    /// Переименовать или переместить запись; каталог — вместе с содержимым
+    Rename { from: String, to: String },
+    /// Заменить содержимое записи файлом с любого бэкенда
+    Replace { entry: String, source: String, source_fs: Option<String> },
+}
+
+/// Итог изменения архива
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEditReport {
+    /// Записей в новом архиве
+    pub entries: u64,
+    pub added: u64,
+    pub removed: u64,
+    pub cancelled: bool,
+}
+
+/// Изменение zip или tar (в том числе сжатого)
+///
+/// Архив пересобирается целиком: на реальной ФС — во временный файл рядом, на остальных
+/// бэкендах (включая хранилище) исходный архив читается потоком, а новый собирается в памяти
+/// (не больше `STORED_EDIT_LIMIT`) и записывается через `write_file_bytes`.
+/// Исходный архив подменяется только после успешной сборки, так что ошибка или отмена
+/// его не портят.
+///
+/// `options` задают сжатие пересобранного tar и новых записей zip (`format` не используется).
+/// Новые записи зашифрованного zip шифруются паролем из `options`, и он должен подходить
+/// к существующим записям.
+pub fn edit_archive(
+    archive_path: &str,
+    archive_fs: Option<&str>,
+    edits: &[ArchiveEdit],
+    options: &ArchiveOptions,
+    tracker: Option<&ProgressTracker>,
+) -> Result<ArchiveEditReport, String> {
+    let mut editor = ArchiveEditor::new(edits, tracker)?;
+
+    if let Some(tracker) = tracker {
+        let existing = list_archive_contents_with_fs(archive_path, archive_fs)?.len() as u64;
+        tracker.set_total_items(existing + editor.additions.len() as u64);
+        tracker.set_total_bytes(editor.content_bytes());
+    }
+
+    let is_real = archive_fs.is_none() || archive_fs == Some("real");
+    let result = if is_real {
+        edit_real_archive(archive_path, options, &mut editor)
+    } else {
+        edit_stored_archive(archive_path, archive_fs, options, &mut editor)
+    };
+
+    match result {
+        Ok(()) => Ok(editor.report),
+        Err(_) if editor.report.cancelled || tracker.is_some_and(|tracker| tracker.is_cancelled()) => {
+            editor.report.cancelled = true;
+            Ok(editor.report)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn edit_real_archive(archive_path: &str, options: &ArchiveOptions, editor: &mut ArchiveEditor) -> Result<(), String> {
+    let (file, format) = open_real(archive_path)?;
+    let permissions = file.metadata().map_err(|e| e.to_string())?.permissions();
+    let path = Path::new(archive_path);
+    let directory = path.parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+
+    let temp = tempfile::NamedTempFile::new_in(directory).map_err(|e| e.to_string())?;
+    let writer = temp.as_file().try_clone().map_err(|e| e.to_string())?;
+    rebuild_archive(file, format, writer, options, editor)?
+        .sync_all()
+        .map_err(|e| e.to_string())?;
+    editor.check_matched()?;
+
+    std::fs::set_permissions(temp.path(), permissions).map_err(|e| e.to_string())?;
+    temp.persist(path).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn edit_stored_archive(
+    archive_path: &str,
+    archive_fs: Option<&str>,
+    options: &ArchiveOptions,
+    editor: &mut ArchiveEditor,
+) -> Result<(), String> {
+    let size = API.files.get_file_info(archive_path, archive_fs)
+        .map_err(|e| format!("Failed to read archive: {}", e))?
+        .size
+        .unwrap_or(0);
+    // Новый архив не может лечь на диск открытым (бэкенд может быть хранилищем) — только память
+    if size.saturating_add(editor.content_bytes()) > STORED_EDIT_LIMIT {
+        return Err(format!(
+            "Archive is too large to edit on this backend (limit {} MiB)",
+            STORED_EDIT_LIMIT >> 20
+        ));
+    }
+
+    let mut reader = API.files.open_read(archive_path, archive_fs)
+        .map_err(|e| format!("Failed to read archive: {}", e))?;
+    let format = ArchiveFormat::identify(&mut reader, archive_path)
+        .map_err(|e| format!("Failed to read archive: {}", e))?
+        .ok_or_else(|| format!("Unsupported archive format: {}", archive_path))?;
+
+    let output = rebuild_archive(reader, format, io::Cursor::new(Vec::new()), options, editor)?;
+    editor.check_matched()?;
+    API.files.write_file_bytes(archive_path, &output.into_inner(), archive_fs)
+        .map_err(|e| format!("Failed to write archive: {}", e))
+}
+
+fn rebuild_archive<R: Read + Seek + Send, W: Write + Seek>(
+    reader: R,
+    format: ArchiveFormat,
+    writer: W,
+    options: &ArchiveOptions,
+    editor: &mut ArchiveEditor,
+) -> Result<W, String> {
+    match format {
+        ArchiveFormat::Zip => edit_zip(reader, writer, options, editor),
+        ArchiveFormat::SevenZ => Err("Editing 7z archives is not supported".to_string()),
+        _ if options.password().is_some() => {
+            Err(format!("Password protection is not supported for {} archives", format.name()))
+        }
+        _ => {
+            let decoder = tar_decoder(format, reader).map_err(|e| e.to_string())?;
+            let encoder = TarEncoder::new(format, writer, options).map_err(|e| e.to_string())?;
+            edit_tar(decoder, encoder, editor)?
+                .finish()
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+fn edit_zip<R: Read + Seek, W: Write + Seek>(
+    reader: R,
+    writer: W,
+    archive_options: &ArchiveOptions,
+    editor: &mut ArchiveEditor,
+) -> Result<W, String> {
+    let mut source = zip::ZipArchive::new(reader).map_err(zip_read_error)?;
+    if editor.writes_content() {
+        check_zip_password(&mut source, archive_options.password())?;
+    }
+    let mut zip = zip::ZipWriter::new(writer);
+    let options = zip_options(archive_options);
+
+    for index in 0..source.len() {
+        editor.checkpoint()?;
+        // Оставшиеся записи копируются как есть, без пересжатия (и без пароля для зашифрованных)
+        let file = source.by_index_raw(index).map_err(zip_read_error)?;
+        let path = normalize_entry_name(file.name());
+        let is_dir = file.is_dir();
+        let Some(name) = editor.final_name(&path) else { continue };
+        editor.claim(&name)?;
+        let stored_name = if is_dir { format!("{}/", name) } else { name };
+
+        match editor.take_replacement(&path) {
+            Some(_) if is_dir => return Err(format!("Only files can be replaced: {}", path)),
+            Some(content) => {
+                drop(file);
+                zip.start_file(stored_name, options).map_err(|e| e.to_string())?;
+                io::copy(&mut editor.open_content(&content)?, &mut zip).map_err(|e| e.to_string())?;
+            }
+            None => zip.raw_copy_file_rename(file, stored_name).map_err(|e| e.to_string())?,
+        }
+    }
+
+    for (name, content) in editor.take_additions() {
+        editor.checkpoint()?;
+        editor.claim(&name)?;
+        if content.is_dir {
+            zip.add_directory(name, options).map_err(|e| e.to_string())?;
+        } else {
+            zip.start_file(name, options).map_err(|e| e.to_string())?;
+            io::copy(&mut editor.open_content(&content)?, &mut zip).map_err(|e| e.to_string())?;
+        }
+        editor.report.added += 1;
+    }
+
+    zip.finish().map_err(|e| e.to_string())
+}
+
+/// Новые записи зашифрованного zip нельзя писать открытыми: нужен пароль, подходящий
+/// к существующим записям. Проверяется самая маленькая зашифрованная запись целиком,
+/// чтобы сверить и контрольную сумму, а не только короткий проверочный код пароля.
+fn check_zip_password<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, password: Option<&str>) -> Result<(), String> {
+    let mut smallest: Option<(usize, u64)> = None;
+    for index in 0..archive.len() {
+        let file = archive.by_index_raw(index).map_err(zip_read_error)?;
+        if file.encrypted() && !file.is_dir() && smallest.is_none_or(|(_, size)| file.size() < size) {
+            smallest = Some((index, file.size()));
+        }
+    }
+    let Some((index, _)) = smallest else { return Ok(()) };
+    let password = password.ok_or_else(|| PASSWORD_REQUIRED.to_string())?;
+
+    let mut file = archive.by_index_decrypt(index, password.as_bytes()).map_err(zip_read_error)?;
+    io::copy(&mut file, &mut io::sink()).map_err(|_| WRONG_PASSWORD.to_string())?;
+    Ok(())
+}
+
+fn edit_tar<R: Read, W: Write>(reader: R, writer: W, editor: &mut ArchiveEditor) -> Result<W, String> {
+    let mut source = tar::Archive::new(reader);
+    let mut builder = tar::Builder::new(writer);
+
+    for entry in source.entries().map_err(|e| e.to_string())? {
+        editor.checkpoint()?;
+        let mut entry = entry.map_err(|e| e.to_string())?;
+        let path = normalize_entry_name(&entry.path().map_err(|e| e.to_string())?.to_string_lossy());
+        if path.is_empty() {
+            continue;
+        }
+        let Some(name) = editor.final_name(&path) else { continue };
+        editor.claim(&name)?;
+
+        let mut header = entry.header().clone();
+        let kind = header.entry_type();
+        let stored_name = if kind.is_dir() { format!("{}/", name) } else { name };
+
+        if let Some(content) = editor.take_replacement(&path) {
+            if !kind.is_file() {
+                return Err(format!("Only files can be replaced: {}", path));
+            }
+            header.set_size(content.size);
+            if let Some(modified) = content.modified {
+                header.set_mtime(modified);
+            }
+            builder.append_data(&mut header, &stored_name, editor.open_content(&content)?)
+                .map_err(|e| e.to_string())?;
+        } else if kind.is_symlink() || kind.is_hard_link() {
+            let mut target = tar_link_name(&entry)?;
+            // Жёсткая ссылка указывает на запись архива и переименовывается вместе с ней
+            if kind.is_hard_link() {
+                target = editor.renamed(&normalize_entry_name(&target));
+            }
+            builder.append_link(&mut header, &stored_name, target).map_err(|e| e.to_string())?;
+        } else {
+            builder.append_data(&mut header, &stored_name, &mut entry).map_err(|e| e.to_string())?;
+        }
+    }
+
+    for (name, content) in editor.take_additions() {
+        editor.checkpoint()?;
+        editor.claim(&name)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_mtime(content.modified.unwrap_or(0));
+        let appended = if content.is_dir {
+            header.set_entry_type(tar::EntryType::Directory);
+            header.set_mode(0o755);
+            header.set_size(0);
+            builder.append_data(&mut header, format!("{}/", name), io::empty())
+        } else {
+            header.set_mode(0o644);
+            header.set_size(content.size);
+            builder.append_data(&mut header, &name, editor.open_content(&content)?)
+        };
+        appended.map_err(|e| e.to_string())?;
+        editor.report.added += 1;
+    }
+
+    builder.into_inner().map_err(|e| e.to_string())
+}
+
+/// Имя записи для сопоставления с запросом: без `./`, лишних и концевых `/`
+fn normalize_entry_name(name: &str) -> String {
+    name.replace('\\', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn required_entry_name(name: &str) -> Result<String, String> {
+    sanitize_entry_path(name)?.ok_or_else(|| "Archive entry name is empty".to_string())
+}
+
+/// Новое содержимое записи: файл или каталог на одном из бэкендов
+struct NewContent {
+    source: String,
+    source_fs: Option<String>,
+    is_dir: bool,
+    size: u64,
+    modified: Option<u64>,
+}
+
+/// Чтение нового содержимого с прогрессом и отменой
+struct TrackedReader<'a, R = Box<dyn FileReader>> {
+    inner: R,
+    tracker: Option<&'a ProgressTracker>,
+}
+
+impl<R: Read> Read for TrackedReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if is_cancelled(self.tracker) {
+            return Err(io::Error::other("Operation cancelled"));
+        }
+        let read = self.inner.read(buf)?;
+        if let Some(tracker) = self.tracker {
+            tracker.add_bytes(read as u64);
+        }
+        Ok(read)
+    }
+}
+
+/// План пересборки архива и её состояние
+struct ArchiveEditor<'a> {
+    deleted: Vec<String>,
+    renames: Vec<(String, String)>,
+    /// Замены по исходному имени записи
+    replacements: HashMap<String, NewContent>,
+    additions: Vec<(String, NewContent)>,
+    /// Имена из запроса, для которых нашлась запись
+    matched: HashSet<String>,
+    written: HashSet<String>,
+    tracker: Option<&'a ProgressTracker>,
+    report: ArchiveEditReport,
+}
+
+impl<'a> ArchiveEditor<'a> {
+    fn new(edits: &[ArchiveEdit], tracker: Option<&'a ProgressTracker>) -> Result<Self, String> {
+        let mut editor = Self {
+            deleted: Vec::new(),
+            renames: Vec::new(),
+            replacements: HashMap::new(),
+            additions: Vec::new(),
+            matched: HashSet::new(),
+            written: HashSet::new(),
+            tracker,
+            report: ArchiveEditReport::default(),
+        };
+
+        for edit in edits {
+            match edit {
+                ArchiveEdit::Add { sources, target_dir, source_fs } => {
+                    let target_dir = sanitize_entry_path(target_dir)?.unwrap_or_default();
+                    for source in sources {
+                        editor.collect_addition(source, &target_dir, source_fs.as_deref())?;
+                    }
+                }
+                ArchiveEdit::Delete { entries } => {
+                    for entry in entries {
+                        editor.deleted.push(required_entry_name(entry)?);
+                    }
+                }
+                ArchiveEdit::Rename { from, to } => {
+                    editor.renames.push((required_entry_name(from)?, required_entry_name(to)?));
+                }
+                ArchiveEdit::Replace { entry, source, source_fs } => {
+                    let info = API.files.get_file_info(source, source_fs.as_deref())
+                        .map_err(|e| format!("Failed to read {}: {}", source, e))?;
+                    if info.is_dir {
+                        return Err(format!("Cannot replace an archive entry with a folder: {}", source));
+                    }
+                    editor.replacements.insert(required_entry_name(entry)?, NewContent {
+                        source: source.clone(),
+                        source_fs: source_fs.clone(),
+                        is_dir: false,
+                        size: info.size.unwrap_or(0),
+                        modified: info.modified,
+                    });
+                }
+            }
+        }
+        Ok(editor)
+    }
+
+    fn collect_addition(&mut self, source: &str, target_dir: &str, source_fs: Option<&str>) -> Result<(), String> {
+        let info = API.files.get_file_info(source, source_fs)
+            .map_err(|e| format!("Failed to read {}: {}", source, e))?;
+        let name = required_entry_name(&info.name)?;
+        let name = if target_dir.is_empty() { name } else { format!("{}/{}", target_dir, name) };
+
+        self.additions.push((name.clone(), NewContent {
+            source: source.to_string(),
+            source_fs: source_fs.map(str::to_string),
+            is_dir: info.is_dir,
+            size: info.size.unwrap_or(0),
+            modified: info.modified,
+        }));
+
+        if info.is_dir {
+            let children = API.files.list_directory(source, Some(true), source_fs)
+                .map_err(|e| format!("Failed to list {}: {}", source, e))?;
+            for child in children {
+                self.collect_addition(&child.path, &name, source_fs)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn content_bytes(&self) -> u64 {
+        let added: u64 = self.additions.iter()
+            .filter(|(_, content)| !content.is_dir)
+            .map(|(_, content)| content.size)
+            .sum();
+        added + self.replacements.values().map(|content| content.size).sum::<u64>()
+    }
+
+    fn checkpoint(&mut self) -> Result<(), String> {
+        if is_cancelled(self.tracker) {
+            self.report.cancelled = true;
+            return Err("Operation cancelled".to_string());
+        }
+        Ok(())
+    }
+
+    fn renamed(&self, path: &str) -> String {
+        let mut name = path.to_string();
+        for (from, to) in &self.renames {
+            if is_under(&name, from) {
+                name = format!("{}{}", to, &name[from.len()..]);
+            }
+        }
+        name
+    }
+
+    /// Имя записи в новом архиве; `None` — запись не переносится
+    fn final_name(&mut self, path: &str) -> Option<String> {
+        if let Some(deleted) = self.deleted.iter().find(|deleted| is_under(path, deleted)) {
+            self.matched.insert(deleted.clone());
+            self.report.removed += 1;
+            return None;
+        }
+
+        let mut name = path.to_string();
+        for (from, to) in &self.renames {
+            if is_under(&name, from) {
+                self.matched.insert(from.clone());
+                name = format!("{}{}", to, &name[from.len()..]);
+            }
+        }
+        // Добавляемые файлы вытесняют одноимённые записи
+        if self.additions.iter().any(|(added, _)| *added == name) {
+            return None;
+        }
+        Some(name)
+    }
+
+    fn take_replacement(&mut self, path: &str) -> Option<NewContent> {
+        self.replacements.remove(path)
+    }
+
+    /// Пишутся ли в архив новые данные (а не только переносятся старые записи)
+    fn writes_content(&self) -> bool {
+        !self.additions.is_empty() || !self.replacements.is_empty()
+    }
+
+    fn take_additions(&mut self) -> Vec<(String, NewContent)> {
+        std::mem::take(&mut self.additions)
+    }
+
+    /// Учёт записи нового архива; имена не должны повторяться
+    fn claim(&mut self, name: &str) -> Result<(), String> {
+        if !self.written.insert(name.to_string()) {
+            return Err(format!("Entry already exists in archive: {}", name));
+        }
+        self.report.entries += 1;
+        if let Some(tracker) = self.tracker {
+            tracker.update_current_file(Some(name.to_string()));
+            tracker.add_item();
+        }
+        Ok(())
+    }
+
+    fn open_content(&self, content: &NewContent) -> Result<TrackedReader<'a>, String> {
+        let inner = API.files.open_read(&content.source, content.source_fs.as_deref())
+            .map_err(|e| format!("Failed to read {}: {}", content.source, e))?;
+        Ok(TrackedReader { inner, tracker: self.tracker })
+    }
+
+    /// Все записи из запроса должны найтись в архиве
+    fn check_matched(&self) -> Result<(), String> {
+        let missing = self.deleted.iter()
+            .chain(self.renames.iter().map(|(from, _)| from))
+            .find(|name| !self.matched.contains(*name))
+            .or_else(|| self.replacements.keys().next());
+        match missing {
+            Some(name) => Err(format!("Entry not found in archive: {}", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniff_recognizes_signatures() {
+        assert_eq!(ArchiveFormat::sniff(b"PK\x03\x04rest"), Some(ArchiveFormat::Zip));
+        assert_eq!(ArchiveFormat::sniff(b"7z\xBC\xAF\x27\x1C\x00\x04"), Some(ArchiveFormat::SevenZ));
+        assert_eq!(ArchiveFormat::sniff(&[0x1F, 0x8B, 0x08]), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::sniff(b"\xFD7zXZ\x00\x00"), Some(ArchiveFormat::TarXz));
+        assert_eq!(ArchiveFormat::sniff(&[0x28, 0xB5, 0x2F, 0xFD]), Some(ArchiveFormat::TarZst));
+        assert_eq!(ArchiveFormat::sniff(b"BZh91AY"), Some(ArchiveFormat::TarBz2));
+        assert_eq!(ArchiveFormat::sniff(b"plain text"), None);
+
+        let mut header = vec![0u8; 512];
+        header[257..262].copy_from_slice(b"ustar");
+        assert_eq!(ArchiveFormat::sniff(&header), Some(ArchiveFormat::Tar));
+    }
+
+    #[test]
+    fn roundtrip_every_format_and_detect_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("data");
+        std::fs::create_dir_all(source.join("nested")).unwrap();
+        std::fs::write(source.join("nested").join("file.txt"), b"payload").unwrap();
+        let source = source.to_string_lossy().to_string();
+
+        for format in ["zip", "tar", "tar.gz", "tar.xz", "tar.zst", "tar.bz2", "7z"] {
+            // Расширение намеренно не совпадает с форматом: распознавание идёт по сигнатуре
+            let archive = dir.path().join(format!("archive-{}.bin", format)).to_string_lossy().to_string();
+            let options = ArchiveOptions { format: Some(format.to_string()), level: Some(1), ..Default::default() };
+            create_archive_with_options(vec![source.clone()], archive.clone(), &options, None, None).unwrap();
+
+            let entries = list_archive_contents(&archive).unwrap();
+            assert!(entries.iter().any(|e| e.name.ends_with("nested/file.txt")), "{}: {:?}", format, entries);
+
+            let out = dir.path().join(format!("out-{}", format));
+            extract_archive_with_options(&archive, &out.to_string_lossy(), &ExtractOptions::default(), None, None, None).unwrap();
+            let restored = std::fs::read(out.join("data").join("nested").join("file.txt")).unwrap();
+            assert_eq!(restored, b"payload", "{}", format);
+        }
+    }
+
+    #[test]
+    fn options_clamp_level_and_reject_unknown_format() {
+        let options = ArchiveOptions { format: Some("rar".to_string()), level: Some(99), ..Default::default() };
+        assert!(options.format_for("a.rar").is_err());
+        assert_eq!(options.level(ArchiveFormat::TarZst), 22);
+        assert_eq!(options.level(ArchiveFormat::TarBz2), 9);
+        assert_eq!(options.level(ArchiveFormat::TarGz), 9);
+    }
+
+    #[test]
+    fn sanitize_rejects_escaping_paths() {
+        assert_eq!(sanitize_entry_path("./dir//file.txt").unwrap(), Some("dir/file.txt".to_string()));
+        assert_eq!(sanitize_entry_path("dir\\file.txt").unwrap(), Some("dir/file.txt".to_string()));
+        assert_eq!(sanitize_entry_path("./").unwrap(), None);
+        assert!(sanitize_entry_path("../evil").is_err());
+        assert!(sanitize_entry_path("dir/../../evil").is_err());
+        assert!(sanitize_entry_path("/etc/passwd").is_err());
+        assert!(sanitize_entry_path("C:/Windows/evil.dll").is_err());
+    }
+
+    #[test]
+    fn extraction_rejects_zip_slip_and_symlink_escapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out").to_string_lossy().to_string();
+
+        let zip_path = dir.path().join("slip.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        zip.start_file("../evil.txt", zip::write::SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"evil").unwrap();
+        zip.finish().unwrap();
+        let result = extract_archive_with_options(&zip_path.to_string_lossy(), &out, &ExtractOptions::default(), None, None, None);
+        assert!(result.is_err());
+        assert!(!dir.path().join("evil.txt").exists());
+
+        let tar_path = dir.path().join("link.tar");
+        let mut builder = tar::Builder::new(File::create(&tar_path).unwrap());
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, "link", "../..").unwrap();
+        builder.into_inner().unwrap();
+        let result = extract_archive_with_options(&tar_path.to_string_lossy(), &out, &ExtractOptions::default(), None, None, None);
+        assert!(result.is_err());
+        assert!(std::fs::symlink_metadata(Path::new(&out).join("link")).is_err());
+    }
+
+    #[test]
+    fn extraction_honors_selection_and_overwrite_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("data.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        for (name, content) in [("keep/a.txt", "new a"), ("keep/b.txt", "new b"), ("other/c.txt", "c")] {
+            zip.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let out = dir.path().join("out");
+        std::fs::create_dir_all(out.join("keep")).unwrap();
+        std::fs::write(out.join("keep").join("a.txt"), "old a").unwrap();
+        let archive = zip_path.to_string_lossy().to_string();
+        let destination = out.to_string_lossy().to_string();
+
+        let skip = ExtractOptions { entries: vec!["keep/".to_string()], overwrite: OverwritePolicy::Skip, ..Default::default() };
+        let report = extract_archive_with_options(&archive, &destination, &skip, None, None, None).unwrap();
+        assert_eq!((report.extracted, report.skipped), (1, 1));
+        assert_eq!(std::fs::read_to_string(out.join("keep").join("a.txt")).unwrap(), "old a");
+        assert!(!out.join("other").exists());
+
+        let rename = ExtractOptions { entries: vec!["keep/a.txt".to_string()], overwrite: OverwritePolicy::Rename, ..Default::default() };
+        let report = extract_archive_with_options(&archive, &destination, &rename, None, None, None).unwrap();
+        assert_eq!(report.renamed, 1);
+        assert_eq!(std::fs::read_to_string(out.join("keep").join("a (1).txt")).unwrap(), "new a");
+
+        let overwrite = ExtractOptions { entries: Vec::new(), overwrite: OverwritePolicy::Overwrite, ..Default::default() };
+        let report = extract_archive_with_options(&archive, &destination, &overwrite, None, None, None).unwrap();
+        assert_eq!(report.extracted, 3);
+        assert_eq!(std::fs::read_to_string(out.join("keep").join("a.txt")).unwrap(), "new a");
+    }
+
+    #[test]
+    fn aes_zip_roundtrip_and_password_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("secret.txt");
+        std::fs::write(&source, b"top secret").unwrap();
+        let archive = dir.path().join("secret.zip").to_string_lossy().to_string();
+
+        let options = ArchiveOptions { password: Some(ArchivePassword("s3cret".to_string())), ..Default::default() };
+        create_archive_with_options(vec![source.to_string_lossy().to_string()], archive.clone(), &options, None, None).unwrap();
+        // Листинг не требует пароля
+        assert_eq!(list_archive_contents(&archive).unwrap().len(), 1);
+
+        let extract = |password: Option<&str>, out: &str| {
+            let options = ExtractOptions {
+                password: password.map(|password| ArchivePassword(password.to_string())),
+                ..Default::default()
+            };
+            extract_archive_with_options(&archive, &dir.path().join(out).to_string_lossy(), &options, None, None, None)
+        };
+        assert_eq!(extract(None, "none").unwrap_err(), PASSWORD_REQUIRED);
+        assert_eq!(extract(Some("wrong"), "wrong").unwrap_err(), WRONG_PASSWORD);
+        extract(Some("s3cret"), "ok").unwrap();
+        assert_eq!(std::fs::read(dir.path().join("ok").join("secret.txt")).unwrap(), b"top secret");
+
+        let tar = ArchiveOptions { format: Some("tar.gz".to_string()), ..options };
+        assert!(create_archive_with_options(vec![source.to_string_lossy().to_string()], archive, &tar, None, None).is_err());
+    }
+
+    #[test]
+    fn edit_zip_and_tar_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("docs");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(source.join("a.txt"), "a").unwrap();
+        std::fs::write(source.join("b.txt"), "b").unwrap();
+        let extra = dir.path().join("extra.txt");
+        std::fs::write(&extra, "extra").unwrap();
+        let replacement = dir.path().join("new-a.txt");
+        std::fs::write(&replacement, "replaced").unwrap();
+
+        for format in ["zip", "tar.gz"] {
+            let archive = dir.path().join(format!("docs.{}", format)).to_string_lossy().to_string();
+            let options = ArchiveOptions { format: Some(format.to_string()), ..Default::default() };
+            create_archive_with_options(vec![source.to_string_lossy().to_string()], archive.clone(), &options, None, None).unwrap();
+
+            let edits = [
+                ArchiveEdit::Add { sources: vec![extra.to_string_lossy().to_string()], target_dir: "docs/more".to_string(), source_fs: None },
+                ArchiveEdit::Delete { entries: vec!["docs/b.txt".to_string()] },
+                ArchiveEdit::Replace { entry: "docs/a.txt".to_string(), source: replacement.to_string_lossy().to_string(), source_fs: None },
+                ArchiveEdit::Rename { from: "docs".to_string(), to: "papers".to_string() },
+            ];
+            let report = edit_archive(&archive, None, &edits, &ArchiveOptions::default(), None).unwrap();
+            assert_eq!((report.added, report.removed), (1, 1), "{}", format);
+
+            let out = dir.path().join(format!("out-{}", format));
+            extract_archive_with_options(&archive, &out.to_string_lossy(), &ExtractOptions::default(), None, None, None).unwrap();
+            assert_eq!(std::fs::read_to_string(out.join("papers").join("a.txt")).unwrap(), "replaced", "{}", format);
+            assert_eq!(std::fs::read_to_string(out.join("docs").join("more").join("extra.txt")).unwrap(), "extra", "{}", format);
+            assert!(!out.join("papers").join("b.txt").exists(), "{}", format);
+        }
+    }
+
+    #[test]
+    fn edit_encrypted_zip_needs_its_password() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("secret.txt");
+        std::fs::write(&source, b"top secret").unwrap();
+        let extra = dir.path().join("extra.txt");
+        std::fs::write(&extra, b"more secrets").unwrap();
+        let archive = dir.path().join("secret.zip").to_string_lossy().to_string();
+        let password = |password: &str| ArchiveOptions { password: Some(ArchivePassword(password.to_string())), ..Default::default() };
+        create_archive_with_options(vec![source.to_string_lossy().to_string()], archive.clone(), &password("s3cret"), None, None).unwrap();
+
+        let add = [ArchiveEdit::Add { sources: vec![extra.to_string_lossy().to_string()], target_dir: String::new(), source_fs: None }];
+        assert_eq!(edit_archive(&archive, None, &add, &ArchiveOptions::default(), None).unwrap_err(), PASSWORD_REQUIRED);
+        assert_eq!(edit_archive(&archive, None, &add, &password("wrong"), None).unwrap_err(), WRONG_PASSWORD);
+        edit_archive(&archive, None, &add, &password("s3cret"), None).unwrap();
+
+        // Новая запись зашифрована тем же паролем
+        let mut zip = zip::ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+        assert!(zip.by_name("extra.txt").is_err());
+        let mut content = String::new();
+        zip.by_name_decrypt("extra.txt", b"s3cret").unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!(content, "more secrets");
+
+        // Перенос старых записей пароля не требует
+        let delete = [ArchiveEdit::Delete { entries: vec!["extra.txt".to_string()] }];
+        assert_eq!(edit_archive(&archive, None, &delete, &ArchiveOptions::default(), None).unwrap().removed, 1);
+    }
+
+    #[test]
+    fn edit_with_missing_entry_leaves_archive_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("file.txt");
+        std::fs::write(&source, "data").unwrap();
+        let archive = dir.path().join("file.zip").to_string_lossy().to_string();
+        create_archive_with_options(vec![source.to_string_lossy().to_string()], archive.clone(), &ArchiveOptions::default(), None, None).unwrap();
+        let before = std::fs::read(&archive).unwrap();
+
+        let edits = [
+            ArchiveEdit::Delete { entries: vec!["file.txt".to_string()] },
+            ArchiveEdit::Rename { from: "missing.txt".to_string(), to: "other.txt".to_string() },
+        ];
+        let error = edit_archive(&archive, None, &edits, &ArchiveOptions::default(), None).unwrap_err();
+        assert!(error.contains("missing.txt"), "{}", error);
+        assert_eq!(std::fs::read(&archive).unwrap(), before);
+    }
+    #[test]
+    fn archive_test_reports_damaged_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("data");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(source.join("file.txt"), b"payload").unwrap();
+        let source = source.to_string_lossy().to_string();
+
+        for format in ["zip", "tar", "tar.gz", "tar.xz", "tar.zst", "tar.bz2", "7z"] {
+            let archive = dir.path().join(format!("ok.{}", format)).to_string_lossy().to_string();
+            let options = ArchiveOptions { format: Some(format.to_string()), ..Default::default() };
+            create_archive_with_options(vec![source.clone()], archive.clone(), &options, None, None).unwrap();
+            let report = test_archive(&archive, None, None, None).unwrap();
+            assert!(report.is_ok() && report.bytes == 7, "{}: {:?}", format, report);
+        }
+
+        // Испорченные данные одной записи: CRC не сходится только у неё
+        let zip_path = dir.path().join("damaged.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        let stored = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("bad.txt", stored).unwrap();
+        zip.write_all(b"original").unwrap();
+        zip.start_file("good.txt", stored).unwrap();
+        zip.write_all(b"intact").unwrap();
+        zip.finish().unwrap();
+        let mut bytes = std::fs::read(&zip_path).unwrap();
+        let offset = bytes.windows(8).position(|window| window == b"original").unwrap();
+        bytes[offset] = b'O';
+        std::fs::write(&zip_path, &bytes).unwrap();
+
+        let report = test_archive(&zip_path.to_string_lossy(), None, None, None).unwrap();
+        assert_eq!(report.tested, 2);
+        assert_eq!(report.failures.len(), 1, "{:?}", report);
+        assert_eq!(report.failures[0].entry, "bad.txt");
+
+        // Solid-блок 7z: после повреждённой записи следующие не читаются, но попадают в отчёт.
+        // Шум не сжимается, и LZMA2 хранит его как есть — байт можно испортить напрямую.
+        let mut seed = 0x2545_f491_u32;
+        let noise: Vec<u8> = (0..4096)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                seed as u8
+            })
+            .collect();
+        let sevenz_path = dir.path().join("solid.7z");
+        let mut writer = sevenz_rust::SevenZWriter::new(File::create(&sevenz_path).unwrap()).unwrap();
+        let entries = ["noise.bin", "good.txt"]
+            .map(|name| {
+                let mut entry = sevenz_rust::SevenZArchiveEntry::new();
+                entry.name = name.to_string();
+                entry.has_stream = true;
+                entry
+            })
+            .to_vec();
+        let sources = sevenz_rust::SeqReader::new(vec![
+            sevenz_rust::SourceReader::new(&noise[..]),
+            sevenz_rust::SourceReader::new(&b"intact"[..]),
+        ]);
+        writer.push_archive_entries(entries, sources).unwrap();
+        writer.finish().unwrap();
+        let mut bytes = std::fs::read(&sevenz_path).unwrap();
+        let offset = bytes.windows(64).position(|window| window == &noise[1000..1064]).unwrap();
+        bytes[offset] ^= 0xff;
+        std::fs::write(&sevenz_path, &bytes).unwrap();
+
+        let report = test_archive(&sevenz_path.to_string_lossy(), None, None, None).unwrap();
+        let failed: Vec<&str> = report.failures.iter().map(|failure| failure.entry.as_str()).collect();
+        assert_eq!(failed, ["noise.bin", "good.txt"], "{:?}", report);
+        assert!(report.failures[1].error.starts_with("Not tested"), "{:?}", report);
+
+        // Обрезанный хвост gzip (CRC и длина) — ошибка сжатого потока
+        let gz_path = dir.path().join("ok.tar.gz");
+        let bytes = std::fs::read(&gz_path).unwrap();
+        let truncated = dir.path().join("truncated.tar.gz");
+        std::fs::write(&truncated, &bytes[..bytes.len() - 8]).unwrap();
+        let report = test_archive(&truncated.to_string_lossy(), None, None, None).unwrap();
+        assert!(!report.is_ok(), "{:?}", report);
+    }
+}
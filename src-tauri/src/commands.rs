@@ -1,6 +1,20 @@
+use crate::api_service::journal::{JournalEntry, JournalHistory};
+use crate::api_service::checksum::{ChecksumJob, ChecksumJobResult, ChecksumRequest, ChecksumResult, VerifyReport, VerifyRequest};
+use crate::api_service::compare::{CompareRequest, DirectoryComparison};
+use crate::api_service::files::ArchiveSession;
+use crate::archives::{ArchiveOptions, ArchivePassword, ArchiveTestReport, ExtractOptions, ExtractReport};
+use crate::api_service::disk_usage::{DiskUsageReport, DiskUsageRequest};
+use crate::api_service::duplicates::{DuplicateActionRequest, DuplicateReport, DuplicateScanRequest};
+use crate::api_service::grep::{ContentMatch, ContentSearchQuery, ContentSearchSummary};
+use crate::api_service::index::{IndexHit, IndexQuery, IndexRootStatus};
+use crate::api_service::search::{SearchHit, SearchQuery, SearchSummary};
+use crate::api::s3_fs::S3Credentials;
+use crate::api_service::remote::RemoteSession;
+use crate::api_service::split::{JoinRequest, SplitJobResult, SplitRequest};
+use crate::api_service::sync::SyncPlan;
 use crate::api_service::API;
-use crate::config::{AppConfig, Bookmark, UIState};
-use crate::core::FileSystemEntry;
+use crate::config::{AppConfig, Bookmark, S3Profile, SftpProfile, SyncProfile, UIState, WebDavProfile};
+use crate::core::{DirectoryPage, FileSystemEntry, ListOptions, TrashEntry};
 use crate::progress::{emit_progress, OperationType, OPERATIONS_MANAGER};
 use crate::file_operations::{
     calculate_total_size, copy_items_with_progress, delete_items_with_progress,
@@ -27,13 +41,20 @@ pub fn set_filesystem_backend(backend: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn read_directory(path: String, panel_fs: Option<String>) -> Result<Vec<FileSystemEntry>, String> {
-    API.files.list_directory(&path, panel_fs.as_deref()).map_err(|e| e.to_string())
+pub fn read_directory(path: String, show_hidden: Option<bool>, panel_fs: Option<String>) -> Result<Vec<FileSystemEntry>, String> {
+    API.files.list_directory(&path, show_hidden, panel_fs.as_deref()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn delete_item(path: String, panel_fs: Option<String>) -> Result<(), String> {
-    API.files.delete_item(&path, panel_fs.as_deref()).map_err(|e| e.to_string())
+pub fn read_directory_page(path: String, options: Option<ListOptions>, panel_fs: Option<String>) -> Result<DirectoryPage, String> {
+    API.files
+        .list_directory_page(&path, options.unwrap_or_default(), panel_fs.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_item(path: String, permanent: Option<bool>, panel_fs: Option<String>) -> Result<(), String> {
+    API.files.delete_item(&path, permanent.unwrap_or(false), panel_fs.as_deref()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -51,6 +72,55 @@ pub fn create_file(path: String, name: String, content: Option<String>, panel_fs
     API.files.create_file(&path, &name, content.as_deref(), panel_fs.as_deref()).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn watch_directory(path: String, recursive: Option<bool>, panel_fs: Option<String>) -> Result<String, String> {
+    API.files.watch_directory(&path, recursive.unwrap_or(false), panel_fs.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn unwatch_directory(subscription_id: String) {
+    API.files.unwatch_directory(&subscription_id)
+}
+
+// ====== Корзина ======
+
+#[tauri::command]
+pub fn list_trash(panel_fs: Option<String>) -> Result<Vec<TrashEntry>, String> {
+    API.files.list_trash(panel_fs.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn restore_from_trash(ids: Vec<String>, panel_fs: Option<String>) -> Result<Vec<TrashEntry>, String> {
+    API.files.restore_from_trash(&ids, panel_fs.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn empty_trash(panel_fs: Option<String>) -> Result<usize, String> {
+    API.files.empty_trash(panel_fs.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn purge_trash_older_than(max_age_secs: u64, panel_fs: Option<String>) -> Result<usize, String> {
+    API.files.purge_trash_older_than(max_age_secs, panel_fs.as_deref()).map_err(|e| e.to_string())
+}
+
+// ====== Отмена и повтор операций ======
+
+#[tauri::command]
+pub fn undo_last_operation(panel_fs: Option<String>) -> Result<JournalEntry, String> {
+    API.files.undo_last(panel_fs.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn redo_operation(panel_fs: Option<String>) -> Result<JournalEntry, String> {
+    API.files.redo(panel_fs.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_operation_history(panel_fs: Option<String>) -> JournalHistory {
+    API.files.operation_history(panel_fs.as_deref())
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileSpec {
@@ -77,7 +147,7 @@ pub fn get_file_templates() -> Result<Vec<crate::templates::FileTemplate>, Strin
 #[tauri::command]
 pub fn suggest_file_extension(path: String, panel_fs: Option<String>) -> Result<Option<String>, String> {
     // Получаем список файлов в директории
-    let entries = API.files.list_directory(&path, panel_fs.as_deref()).map_err(|e| e.to_string())?;
+    let entries = API.files.list_directory(&path, None, panel_fs.as_deref()).map_err(|e| e.to_string())?;
 
     let file_names: Vec<String> = entries
         .into_iter()
@@ -196,8 +266,8 @@ pub fn rename_bookmark(id: String, new_name: String) -> Result<(), String> {
 // ====== Archive Commands ======
 
 #[tauri::command]
-pub fn extract_archive(archive_path: String, destination_path: String) -> Result<(), String> {
-    crate::archives::extract_archive(&archive_path, &destination_path)
+pub fn extract_archive(archive_path: String, destination_path: String, options: Option<ExtractOptions>) -> Result<ExtractReport, String> {
+    crate::archives::extract_archive_with_options(&archive_path, &destination_path, &options.unwrap_or_default(), None, None, None)
 }
 
 #[tauri::command]
@@ -206,8 +276,44 @@ pub fn list_archive_contents(archive_path: String, panel_fs: Option<String>) ->
 }
 
 #[tauri::command]
-pub fn create_archive(source_paths: Vec<String>, destination_path: String) -> Result<(), String> {
-    crate::archives::create_archive(source_paths, destination_path)
+pub fn create_archive(source_paths: Vec<String>, destination_path: String, options: Option<ArchiveOptions>) -> Result<(), String> {
+    crate::archives::create_archive_with_options(source_paths, destination_path, &options.unwrap_or_default(), None, None)
+}
+
+/// Открытие архива как панели только для чтения; в ответе — `panel_fs` сессии
+#[tauri::command]
+pub async fn open_archive(archive_path: String, panel_fs: Option<String>) -> Result<ArchiveSession, String> {
+    API.files.open_archive(&archive_path, panel_fs.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn close_archive(panel_fs: String) {
+    API.files.close_archive(&panel_fs)
+}
+
+/// Поставить проверку целостности архива в очередь; возвращает ID операции очереди
+#[tauri::command]
+pub fn queue_archive_test(archive_path: String, panel_fs: Option<String>, password: Option<ArchivePassword>) -> Result<String, String> {
+    let name = std::path::Path::new(&archive_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| archive_path.clone());
+    let mut operation = QueuedOperation::new(
+        QueuedOperationType::ArchiveTest,
+        OperationParams::ArchiveTest { archive_path, password, source_fs: panel_fs },
+        OperationPriority::Normal,
+    );
+    // Повтор проверки повреждённого архива даст тот же результат
+    operation.retry_policy.enabled = false;
+    operation.description = Some(format!("Test '{}'", name));
+    QUEUE_MANAGER.enqueue(operation)
+}
+
+/// Отчёт завершённой проверки архива из очереди
+#[tauri::command]
+pub fn get_archive_test_result(operation_id: String) -> Result<ArchiveTestReport, String> {
+    crate::archives::archive_test_result(&operation_id)
+        .ok_or_else(|| format!("Archive test result not found: {}", operation_id))
 }
 
 // ====== Команды для работы с UI состоянием ======
@@ -327,6 +433,7 @@ pub async fn delete_items_with_progress_command<R: Runtime>(
     operation_id: String,
     paths: Vec<String>,
     panel_fs: Option<String>,
+    permanent: Option<bool>,
 ) -> Result<(), String> {
     // Вычисляем общий размер
     let (total_bytes, total_items) = calculate_total_size(&paths)
@@ -344,7 +451,7 @@ pub async fn delete_items_with_progress_command<R: Runtime>(
     emit_progress(&app, &tracker);
 
     // Выполняем операцию
-    match delete_items_with_progress(&paths, &tracker, &app, panel_fs) {
+    match delete_items_with_progress(&paths, &tracker, &app, panel_fs, permanent.unwrap_or(false)) {
         Ok(_) => {
             // Убеждаемся, что прогресс на 100%
             tracker.set_total_bytes(tracker.get_current_bytes());
@@ -362,6 +469,467 @@ pub async fn delete_items_with_progress_command<R: Runtime>(
     }
 }
 
+/// Пачка результатов поиска для события "search-results"
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResultsEvent {
+    operation_id: String,
+    hits: Vec<SearchHit>,
+}
+
+/// Рекурсивный поиск по имени; результаты приходят событиями "search-results",
+/// отмена — через cancel_operation
+#[tauri::command]
+pub async fn search_files_command<R: Runtime>(
+    app: AppHandle<R>,
+    operation_id: String,
+    query: SearchQuery,
+    panel_fs: Option<String>,
+) -> Result<SearchSummary, String> {
+    let tracker = OPERATIONS_MANAGER.create_operation(
+        operation_id.clone(),
+        OperationType::Search,
+        0,
+        0,
+    );
+
+    emit_progress(&app, &tracker);
+
+    let result = API.search.search(&query, panel_fs.as_deref(), tracker.cancellation_token(), |hits| {
+        tracker.add_items(hits.len() as u64);
+        let _ = app.emit("search-results", SearchResultsEvent {
+            operation_id: operation_id.clone(),
+            hits,
+        });
+        emit_progress(&app, &tracker);
+    });
+
+    let outcome = match result {
+        Ok(summary) => {
+            if summary.cancelled {
+                tracker.mark_cancelled();
+            } else {
+                tracker.mark_completed();
+            }
+            Ok(summary)
+        }
+        Err(e) => {
+            tracker.mark_failed(e.to_string());
+            Err(e.to_string())
+        }
+    };
+
+    // Принудительно отправляем финальное событие
+    let _ = app.emit("file-operation-progress", tracker.get_progress_event());
+    OPERATIONS_MANAGER.remove_operation(&operation_id);
+    outcome
+}
+
+/// Пачка совпадений для события "grep-results"
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GrepResultsEvent {
+    operation_id: String,
+    matches: Vec<ContentMatch>,
+}
+
+/// Поиск по содержимому файлов; совпадения приходят событиями "grep-results",
+/// отмена — через cancel_operation
+#[tauri::command]
+pub async fn grep_files_command<R: Runtime>(
+    app: AppHandle<R>,
+    operation_id: String,
+    query: ContentSearchQuery,
+    panel_fs: Option<String>,
+) -> Result<ContentSearchSummary, String> {
+    let tracker = OPERATIONS_MANAGER.create_operation(
+        operation_id.clone(),
+        OperationType::Search,
+        0,
+        0,
+    );
+
+    emit_progress(&app, &tracker);
+
+    let result = API.search.grep(&query, panel_fs.as_deref(), tracker.cancellation_token(), |matches| {
+        tracker.add_items(matches.len() as u64);
+        let _ = app.emit("grep-results", GrepResultsEvent {
+            operation_id: operation_id.clone(),
+            matches,
+        });
+        emit_progress(&app, &tracker);
+    });
+
+    let outcome = match result {
+        Ok(summary) => {
+            if summary.cancelled {
+                tracker.mark_cancelled();
+            } else {
+                tracker.mark_completed();
+            }
+            Ok(summary)
+        }
+        Err(e) => {
+            tracker.mark_failed(e.to_string());
+            Err(e.to_string())
+        }
+    };
+
+    // Принудительно отправляем финальное событие
+    let _ = app.emit("file-operation-progress", tracker.get_progress_event());
+    OPERATIONS_MANAGER.remove_operation(&operation_id);
+    outcome
+}
+
+/// Поиск дубликатов по содержимому; прогресс приходит событиями "file-operation-progress",
+/// отмена — через cancel_operation
+#[tauri::command]
+pub async fn find_duplicates_command<R: Runtime>(
+    app: AppHandle<R>,
+    operation_id: String,
+    request: DuplicateScanRequest,
+) -> Result<DuplicateReport, String> {
+    let tracker = OPERATIONS_MANAGER.create_operation(
+        operation_id.clone(),
+        OperationType::Duplicates,
+        0,
+        0,
+    );
+
+    emit_progress(&app, &tracker);
+
+    let result = API.duplicates.find(&request, &tracker, || emit_progress(&app, &tracker));
+
+    let outcome = match result {
+        Ok(report) => {
+            if report.cancelled {
+                tracker.mark_cancelled();
+            } else {
+                tracker.mark_completed();
+            }
+            Ok(report)
+        }
+        Err(e) => {
+            tracker.mark_failed(e.to_string());
+            Err(e.to_string())
+        }
+    };
+
+    // Принудительно отправляем финальное событие
+    let _ = app.emit("file-operation-progress", tracker.get_progress_event());
+    OPERATIONS_MANAGER.remove_operation(&operation_id);
+    outcome
+}
+
+/// Поставить в очередь действие над группами дубликатов; возвращает ID операции очереди
+#[tauri::command]
+pub fn queue_duplicate_action(request: DuplicateActionRequest) -> Result<String, String> {
+    API.duplicates.enqueue_action(request).map_err(|e| e.to_string())
+}
+
+/// Контрольные суммы файлов и папок (с записью манифеста, если указан manifest_path)
+#[tauri::command]
+pub async fn calculate_checksums_command<R: Runtime>(
+    app: AppHandle<R>,
+    operation_id: String,
+    request: ChecksumRequest,
+    panel_fs: Option<String>,
+) -> Result<ChecksumResult, String> {
+    let tracker = OPERATIONS_MANAGER.create_operation(
+        operation_id.clone(),
+        OperationType::Checksum,
+        0,
+        0,
+    );
+
+    emit_progress(&app, &tracker);
+
+    let outcome = match API.checksums.compute(&request, panel_fs.as_deref(), &tracker, || emit_progress(&app, &tracker)) {
+        Ok(result) => {
+            if result.cancelled {
+                tracker.mark_cancelled();
+            } else {
+                tracker.mark_completed();
+            }
+            Ok(result)
+        }
+        Err(e) => {
+            tracker.mark_failed(e.to_string());
+            Err(e.to_string())
+        }
+    };
+
+    let _ = app.emit("file-operation-progress", tracker.get_progress_event());
+    OPERATIONS_MANAGER.remove_operation(&operation_id);
+    outcome
+}
+
+/// Проверка манифеста контрольных сумм с отчетом OK/FAILED/MISSING по каждому файлу
+#[tauri::command]
+pub async fn verify_checksums_command<R: Runtime>(
+    app: AppHandle<R>,
+    operation_id: String,
+    request: VerifyRequest,
+    panel_fs: Option<String>,
+) -> Result<VerifyReport, String> {
+    let tracker = OPERATIONS_MANAGER.create_operation(
+        operation_id.clone(),
+        OperationType::Checksum,
+        0,
+        0,
+    );
+
+    emit_progress(&app, &tracker);
+
+    let outcome = match API.checksums.verify(&request, panel_fs.as_deref(), &tracker, || emit_progress(&app, &tracker)) {
+        Ok(report) => {
+            if report.cancelled {
+                tracker.mark_cancelled();
+            } else {
+                tracker.mark_completed();
+            }
+            Ok(report)
+        }
+        Err(e) => {
+            tracker.mark_failed(e.to_string());
+            Err(e.to_string())
+        }
+    };
+
+    let _ = app.emit("file-operation-progress", tracker.get_progress_event());
+    OPERATIONS_MANAGER.remove_operation(&operation_id);
+    outcome
+}
+
+/// Поставить расчет или проверку контрольных сумм в очередь; возвращает ID операции очереди
+#[tauri::command]
+pub fn queue_checksum_job(job: ChecksumJob, panel_fs: Option<String>) -> Result<String, String> {
+    API.checksums.enqueue(job, panel_fs).map_err(|e| e.to_string())
+}
+
+/// Результат завершенного задания контрольных сумм из очереди
+#[tauri::command]
+pub fn get_checksum_job_result(operation_id: String) -> Result<ChecksumJobResult, String> {
+    API.checksums.job_result(&operation_id).map_err(|e| e.to_string())
+}
+
+/// Поставить разбиение файла на части в очередь; возвращает ID операции очереди
+#[tauri::command]
+pub fn queue_split_file(request: SplitRequest, source_fs: Option<String>, dest_fs: Option<String>) -> Result<String, String> {
+    API.split.enqueue_split(request, source_fs, dest_fs).map_err(|e| e.to_string())
+}
+
+/// Поставить сборку файла из частей в очередь; возвращает ID операции очереди
+#[tauri::command]
+pub fn queue_join_files(request: JoinRequest, source_fs: Option<String>, dest_fs: Option<String>) -> Result<String, String> {
+    API.split.enqueue_join(request, source_fs, dest_fs).map_err(|e| e.to_string())
+}
+
+/// Результат завершенного разбиения или сборки из очереди
+#[tauri::command]
+pub fn get_split_job_result(operation_id: String) -> Result<SplitJobResult, String> {
+    API.split.job_result(&operation_id).map_err(|e| e.to_string())
+}
+
+/// Сравнение двух деревьев (в том числе на разных backend'ах); прогресс приходит
+/// событиями "file-operation-progress", отмена — через cancel_operation
+#[tauri::command]
+pub async fn compare_directories_command<R: Runtime>(
+    app: AppHandle<R>,
+    operation_id: String,
+    request: CompareRequest,
+) -> Result<DirectoryComparison, String> {
+    let tracker = OPERATIONS_MANAGER.create_operation(
+        operation_id.clone(),
+        OperationType::Compare,
+        0,
+        0,
+    );
+
+    emit_progress(&app, &tracker);
+
+    let result = API.compare.compare(&request, &tracker, || emit_progress(&app, &tracker));
+
+    let outcome = match result {
+        Ok(comparison) => {
+            if comparison.summary.cancelled {
+                tracker.mark_cancelled();
+            } else {
+                tracker.mark_completed();
+            }
+            Ok(comparison)
+        }
+        Err(e) => {
+            tracker.mark_failed(e.to_string());
+            Err(e.to_string())
+        }
+    };
+
+    // Принудительно отправляем финальное событие
+    let _ = app.emit("file-operation-progress", tracker.get_progress_event());
+    OPERATIONS_MANAGER.remove_operation(&operation_id);
+    outcome
+}
+
+/// Сохраненные профили синхронизации
+#[tauri::command]
+pub fn get_sync_profiles() -> Result<Vec<SyncProfile>, String> {
+    API.sync.list_profiles().map_err(|e| e.to_string())
+}
+
+/// Создать или обновить профиль синхронизации (новый профиль получает id)
+#[tauri::command]
+pub fn save_sync_profile(profile: SyncProfile) -> Result<SyncProfile, String> {
+    API.sync.save_profile(profile).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_sync_profile(id: String) -> Result<(), String> {
+    API.sync.delete_profile(&id).map_err(|e| e.to_string())
+}
+
+/// Пробный прогон синхронизации: план действий без изменений на диске
+#[tauri::command]
+pub async fn plan_sync_command<R: Runtime>(
+    app: AppHandle<R>,
+    operation_id: String,
+    profile: SyncProfile,
+) -> Result<SyncPlan, String> {
+    let tracker = OPERATIONS_MANAGER.create_operation(
+        operation_id.clone(),
+        OperationType::Sync,
+        0,
+        0,
+    );
+
+    emit_progress(&app, &tracker);
+
+    let result = API.sync.plan(&profile, &tracker, || emit_progress(&app, &tracker));
+
+    let outcome = match result {
+        Ok(plan) => {
+            if plan.cancelled {
+                tracker.mark_cancelled();
+            } else {
+                tracker.mark_completed();
+            }
+            Ok(plan)
+        }
+        Err(e) => {
+            tracker.mark_failed(e.to_string());
+            Err(e.to_string())
+        }
+    };
+
+    // Принудительно отправляем финальное событие
+    let _ = app.emit("file-operation-progress", tracker.get_progress_event());
+    OPERATIONS_MANAGER.remove_operation(&operation_id);
+    outcome
+}
+
+/// Поставить синхронизацию сохраненного профиля в очередь (scheduled_at — RFC 3339);
+/// возвращает ID операции очереди
+#[tauri::command]
+pub fn queue_sync_job(profile_id: String, scheduled_at: Option<String>) -> Result<String, String> {
+    API.sync.enqueue(&profile_id, scheduled_at.as_deref()).map_err(|e| e.to_string())
+}
+
+// ====== Удаленные подключения (SFTP, WebDAV, S3) ======
+
+/// Сохраненные SFTP-подключения
+#[tauri::command]
+pub fn get_sftp_profiles() -> Result<Vec<SftpProfile>, String> {
+    API.remote.list_sftp_profiles().map_err(|e| e.to_string())
+}
+
+/// Создать или обновить SFTP-подключение (пустой id — новое)
+#[tauri::command]
+pub fn save_sftp_profile(profile: SftpProfile) -> Result<SftpProfile, String> {
+    API.remote.save_sftp_profile(profile).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_sftp_profile(id: String) -> Result<(), String> {
+    API.remote.delete_sftp_profile(&id).map_err(|e| e.to_string())
+}
+
+/// Подключиться; `secret` заменяет сохраненный пароль или парольную фразу ключа,
+/// `host_key` — подтвержденный пользователем отпечаток неизвестного сервера.
+/// `panel_fs` из результата выбирает сервер в панели
+#[tauri::command]
+pub async fn connect_sftp(id: String, secret: Option<String>, host_key: Option<String>) -> Result<RemoteSession, String> {
+    API.remote
+        .connect_sftp(&id, secret.as_deref(), host_key.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn disconnect_sftp(id: String) {
+    API.remote.disconnect_sftp(&id)
+}
+
+/// Сохраненные WebDAV-подключения
+#[tauri::command]
+pub fn get_webdav_profiles() -> Result<Vec<WebDavProfile>, String> {
+    API.remote.list_webdav_profiles().map_err(|e| e.to_string())
+}
+
+/// Создать или обновить WebDAV-подключение (пустой id — новое)
+#[tauri::command]
+pub fn save_webdav_profile(profile: WebDavProfile) -> Result<WebDavProfile, String> {
+    API.remote.save_webdav_profile(profile).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_webdav_profile(id: String) -> Result<(), String> {
+    API.remote.delete_webdav_profile(&id).map_err(|e| e.to_string())
+}
+
+/// Подключиться; `secret` заменяет сохраненный пароль
+#[tauri::command]
+pub async fn connect_webdav(id: String, secret: Option<String>) -> Result<RemoteSession, String> {
+    API.remote.connect_webdav(&id, secret.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn disconnect_webdav(id: String) {
+    API.remote.disconnect_webdav(&id)
+}
+
+/// Сохраненные бакеты S3 (без ключей)
+#[tauri::command]
+pub fn get_s3_profiles() -> Result<Vec<S3Profile>, String> {
+    API.remote.list_s3_profiles().map_err(|e| e.to_string())
+}
+
+/// Создать или обновить бакет; ключи шифруются в хранилище, `None` оставляет сохраненные
+#[tauri::command]
+pub fn save_s3_profile(profile: S3Profile, credentials: Option<S3Credentials>) -> Result<S3Profile, String> {
+    API.remote.save_s3_profile(profile, credentials).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_s3_profile(id: String) -> Result<(), String> {
+    API.remote.delete_s3_profile(&id).map_err(|e| e.to_string())
+}
+
+/// Подключиться с ключами из хранилища (оно должно быть разблокировано)
+#[tauri::command]
+pub async fn connect_s3(id: String) -> Result<RemoteSession, String> {
+    API.remote.connect_s3(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn disconnect_s3(id: String) {
+    API.remote.disconnect_s3(&id)
+}
+
+/// Открытые удаленные подключения
+#[tauri::command]
+pub fn get_remote_sessions() -> Vec<RemoteSession> {
+    API.remote.sessions()
+}
+
 /// Отмена операции
 #[tauri::command]
 pub fn cancel_operation(operation_id: String) -> Result<(), String> {
@@ -413,6 +981,46 @@ pub fn calculate_directory_size(path: String) -> Result<DirectorySize, String> {
     })
 }
 
+/// Анализ занятого места: дерево размеров, самые большие файлы, разбивка по типам;
+/// прогресс приходит событиями "file-operation-progress", отмена — через cancel_operation
+#[tauri::command]
+pub async fn analyze_disk_usage_command<R: Runtime>(
+    app: AppHandle<R>,
+    operation_id: String,
+    request: DiskUsageRequest,
+) -> Result<DiskUsageReport, String> {
+    let tracker = OPERATIONS_MANAGER.create_operation(
+        operation_id.clone(),
+        OperationType::DiskUsage,
+        0,
+        0,
+    );
+
+    emit_progress(&app, &tracker);
+
+    let result = API.system.analyze_disk_usage(&request, &tracker, || emit_progress(&app, &tracker));
+
+    let outcome = match result {
+        Ok(report) => {
+            if report.cancelled {
+                tracker.mark_cancelled();
+            } else {
+                tracker.mark_completed();
+            }
+            Ok(report)
+        }
+        Err(e) => {
+            tracker.mark_failed(e.to_string());
+            Err(e.to_string())
+        }
+    };
+
+    // Принудительно отправляем финальное событие
+    let _ = app.emit("file-operation-progress", tracker.get_progress_event());
+    OPERATIONS_MANAGER.remove_operation(&operation_id);
+    outcome
+}
+
 // ====== Команды для мониторинга системы ======
 
 use crate::api_service::models::SystemStats;
@@ -423,6 +1031,26 @@ pub async fn get_system_stats() -> Result<SystemStats, String> {
     API.system.get_stats().map_err(|e| e.to_string())
 }
 
+// ====== Команды индекса имен файлов ======
+
+/// Состояние индекса по каждому корню
+#[tauri::command]
+pub fn get_index_status() -> Result<Vec<IndexRootStatus>, String> {
+    API.system.get_index_status().map_err(|e| e.to_string())
+}
+
+/// Перестроить индекс корня (или всех корней, если root не указан)
+#[tauri::command]
+pub fn rebuild_index(root: Option<String>) -> Result<(), String> {
+    API.system.rebuild_index(root.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Мгновенный поиск по индексу
+#[tauri::command]
+pub fn query_index(query: IndexQuery) -> Result<Vec<IndexHit>, String> {
+    API.system.query_index(&query).map_err(|e| e.to_string())
+}
+
 // ====== Conflict Resolution Commands ======
 
 #[derive(Serialize)]
@@ -874,6 +1502,15 @@ use crate::queue::{
     QueuedOperationType,
 };
 
+/// Параметры операции для логов: пароль архива не выводится
+fn redact_params(params: &serde_json::Value) -> serde_json::Value {
+    let mut params = params.clone();
+    if let Some(password) = params.get_mut("password") {
+        *password = serde_json::Value::String("***".to_string());
+    }
+    params
+}
+
 #[tauri::command]
 pub fn queue_add_operation(
     operation_type: String,
@@ -887,7 +1524,7 @@ pub fn queue_add_operation(
     use tracing::info;
 
     info!("queue_add_operation called with type: {}", operation_type);
-    info!("params: {}", serde_json::to_string_pretty(&params).unwrap_or_default());
+    info!("params: {}", serde_json::to_string_pretty(&redact_params(&params)).unwrap_or_default());
 
     // Parse operation type
     let op_type: QueuedOperationType = serde_json::from_value(serde_json::json!(operation_type))
@@ -900,7 +1537,7 @@ pub fn queue_add_operation(
     // Parse parameters
     let op_params: OperationParams = serde_json::from_value(params.clone())
         .map_err(|e| {
-            let err_msg = format!("Invalid operation params: {}. Params were: {}", e, serde_json::to_string(&params).unwrap_or_default());
+            let err_msg = format!("Invalid operation params: {}. Params were: {}", e, serde_json::to_string(&redact_params(&params)).unwrap_or_default());
             tracing::error!("{}", err_msg);
             err_msg
         })?;
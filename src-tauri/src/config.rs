@@ -52,6 +52,177 @@ pub struct Bookmark {
     pub created_at: u64, // Unix timestamp
 }
 
+/// Режим синхронизации папок
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum SyncMode {
+    /// Правая сторона становится копией левой
+    Mirror,
+    /// Новые и обновленные файлы копируются слева направо, ничего не удаляется
+    Update,
+    /// Изменения переносятся в обе стороны, одновременные изменения — конфликт
+    TwoWay,
+}
+
+/// Сохраненный профиль синхронизации двух папок (возможно, на разных backend'ах)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct SyncProfile {
+    /// Пустой id у нового профиля — назначается при сохранении
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub left_root: String,
+    #[serde(default)]
+    pub left_fs: Option<String>,
+    pub right_root: String,
+    #[serde(default)]
+    pub right_fs: Option<String>,
+    pub mode: SyncMode,
+    /// Mirror: удалять справа то, чего нет слева
+    #[serde(default)]
+    pub delete_extraneous: bool,
+    /// Сравнивать содержимое файлов одинакового размера
+    #[serde(default)]
+    pub compare_content: bool,
+    #[serde(default)]
+    pub include_hidden: Option<bool>,
+    #[serde(default)]
+    pub respect_gitignore: bool,
+}
+
+/// Способ аутентификации SFTP
+///
+/// Пароль и парольная фраза принимаются только при сохранении профиля: они запечатываются
+/// в хранилище (vault) и не попадают ни в конфиг, ни в ответы API.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum SftpAuth {
+    /// `None` — сохраненный пароль не меняется, пустая строка — удаляется
+    Password {
+        #[serde(default, skip_serializing)]
+        password: Option<String>,
+    },
+    /// Приватный ключ OpenSSH; `~/` раскрывается в домашнюю папку
+    Key {
+        private_key_path: String,
+        #[serde(default, skip_serializing)]
+        passphrase: Option<String>,
+    },
+}
+
+impl SftpAuth {
+    /// Забрать пароль или парольную фразу из профиля
+    pub fn take_secret(&mut self) -> Option<String> {
+        match self {
+            SftpAuth::Password { password } => password.take(),
+            SftpAuth::Key { passphrase, .. } => passphrase.take(),
+        }
+    }
+}
+
+/// Сохраненное SFTP-подключение; панель выбирает его через `panel_fs = "sftp:<id>"`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct SftpProfile {
+    /// Пустой id у нового профиля — назначается при сохранении
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub host: String,
+    #[serde(default = "default_sftp_port")]
+    pub port: u16,
+    pub username: String,
+    pub auth: SftpAuth,
+    /// Пароль или парольная фраза запечатаны в хранилище; иначе они запрашиваются при подключении
+    #[serde(default)]
+    pub secret_saved: bool,
+    /// Отпечаток ключа сервера (`SHA256:…`), закрепленный при первом подключении;
+    /// сервер с другим ключом отклоняется
+    #[serde(default)]
+    pub host_key: Option<String>,
+}
+
+fn default_sftp_port() -> u16 {
+    22
+}
+
+/// Аутентификация WebDAV
+///
+/// Пароль, как и у SFTP, принимается только при сохранении и запечатывается в хранилище.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum WebDavAuth {
+    None,
+    /// `password: None` — сохраненный пароль не меняется, пустая строка — удаляется
+    Basic {
+        username: String,
+        #[serde(default, skip_serializing)]
+        password: Option<String>,
+    },
+    Digest {
+        username: String,
+        #[serde(default, skip_serializing)]
+        password: Option<String>,
+    },
+}
+
+impl WebDavAuth {
+    /// Забрать пароль из профиля
+    pub fn take_secret(&mut self) -> Option<String> {
+        match self {
+            WebDavAuth::None => None,
+            WebDavAuth::Basic { password, .. } | WebDavAuth::Digest { password, .. } => password.take(),
+        }
+    }
+}
+
+/// Сохраненное WebDAV-подключение; панель выбирает его через `panel_fs = "webdav:<id>"`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct WebDavProfile {
+    /// Пустой id у нового профиля — назначается при сохранении
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// URL корня, например `https://cloud.example.com/remote.php/dav/files/alice`
+    pub url: String,
+    pub auth: WebDavAuth,
+    /// Пароль запечатан в хранилище; иначе он запрашивается при подключении
+    #[serde(default)]
+    pub secret_saved: bool,
+    /// Разрешить Basic-аутентификацию по http:// — пароль уходит по сети открытым текстом
+    #[serde(default)]
+    pub allow_insecure_basic: bool,
+}
+
+/// Сохраненный бакет S3-совместимого хранилища; панель выбирает его через `panel_fs = "s3:<id>"`
+///
+/// Ключи доступа в конфиг не попадают — они хранятся зашифрованными в хранилище (vault).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct S3Profile {
+    /// Пустой id у нового профиля — назначается при сохранении
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Адрес сервиса, например `https://s3.eu-central-1.amazonaws.com` или `http://127.0.0.1:9000`
+    pub endpoint: String,
+    #[serde(default = "default_s3_region")]
+    pub region: String,
+    pub bucket: String,
+    /// Адресация `endpoint/bucket/key` вместо `bucket.endpoint/key` (MinIO и большинство self-hosted серверов)
+    #[serde(default)]
+    pub path_style: bool,
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TabState {
     pub id: u64,
@@ -282,6 +453,30 @@ pub struct AppConfig {
 
     #[serde(default)]
     pub vault: VaultConfig,
+
+    /// Папки реальной ФС, для которых ведется индекс имен
+    #[serde(default)]
+    pub index_roots: Vec<String>,
+
+    /// Вести зашифрованный индекс дерева хранилища, пока оно разблокировано
+    #[serde(default)]
+    pub index_vault: bool,
+
+    /// Сохраненные профили синхронизации папок
+    #[serde(default)]
+    pub sync_profiles: Vec<SyncProfile>,
+
+    /// Сохраненные SFTP-подключения
+    #[serde(default)]
+    pub sftp_profiles: Vec<SftpProfile>,
+
+    /// Сохраненные WebDAV-подключения
+    #[serde(default)]
+    pub webdav_profiles: Vec<WebDavProfile>,
+
+    /// Сохраненные бакеты S3
+    #[serde(default)]
+    pub s3_profiles: Vec<S3Profile>,
 }
 
 fn default_show_hidden() -> bool {
@@ -303,6 +498,12 @@ impl Default for AppConfig {
             ui_state: UIState::default(),
             smtp_config: None,
             vault: VaultConfig::default(),
+            index_roots: Vec::new(),
+            index_vault: false,
+            sync_profiles: Vec::new(),
+            sftp_profiles: Vec::new(),
+            webdav_profiles: Vec::new(),
+            s3_profiles: Vec::new(),
         }
     }
 }
@@ -446,6 +647,53 @@ mod tests {
             ui_state: Default::default(),
             smtp_config: None,
             vault: Default::default(),
+            index_roots: vec!["/home/user/projects".to_string()],
+            index_vault: true,
+            sync_profiles: vec![SyncProfile {
+                id: "sync-1".to_string(),
+                name: "Photos backup".to_string(),
+                left_root: "/home/user/photos".to_string(),
+                left_fs: None,
+                right_root: "/photos".to_string(),
+                right_fs: Some("virtual".to_string()),
+                mode: SyncMode::TwoWay,
+                delete_extraneous: false,
+                compare_content: true,
+                include_hidden: None,
+                respect_gitignore: false,
+            }],
+            sftp_profiles: vec![SftpProfile {
+                id: "sftp-1".to_string(),
+                name: "Build server".to_string(),
+                host: "build.example.com".to_string(),
+                port: 2222,
+                username: "deploy".to_string(),
+                auth: SftpAuth::Key {
+                    private_key_path: "~/.ssh/id_ed25519".to_string(),
+                    passphrase: None,
+                },
+                secret_saved: true,
+                host_key: Some("SHA256:47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU".to_string()),
+            }],
+            webdav_profiles: vec![WebDavProfile {
+                id: "webdav-1".to_string(),
+                name: "Nextcloud".to_string(),
+                url: "https://cloud.example.com/remote.php/dav/files/alice".to_string(),
+                auth: WebDavAuth::Digest {
+                    username: "alice".to_string(),
+                    password: None,
+                },
+                secret_saved: false,
+                allow_insecure_basic: false,
+            }],
+            s3_profiles: vec![S3Profile {
+                id: "s3-1".to_string(),
+                name: "Artifacts".to_string(),
+                endpoint: "http://127.0.0.1:9000".to_string(),
+                region: "us-east-1".to_string(),
+                bucket: "artifacts".to_string(),
+                path_style: true,
+            }],
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -453,5 +701,26 @@ mod tests {
 
         assert_eq!(config.filesystem_backend, deserialized.filesystem_backend);
         assert_eq!(config.show_hidden_files, deserialized.show_hidden_files);
+        assert_eq!(config.index_roots, deserialized.index_roots);
+        assert_eq!(config.sync_profiles, deserialized.sync_profiles);
+        assert!(json.contains("\"mode\":\"two_way\""));
+        assert_eq!(config.sftp_profiles, deserialized.sftp_profiles);
+        assert!(json.contains("\"type\":\"key\""));
+        assert_eq!(config.webdav_profiles, deserialized.webdav_profiles);
+        assert!(json.contains("\"type\":\"digest\""));
+        assert_eq!(config.s3_profiles, deserialized.s3_profiles);
+    }
+
+    #[test]
+    fn test_remote_secrets_are_not_serialized() {
+        let mut auth: SftpAuth = serde_json::from_str(r#"{"type":"password","password":"hunter2"}"#).unwrap();
+        assert!(!serde_json::to_string(&auth).unwrap().contains("hunter2"));
+        assert_eq!(auth.take_secret().as_deref(), Some("hunter2"));
+        assert_eq!(auth.take_secret(), None);
+
+        let mut auth: WebDavAuth =
+            serde_json::from_str(r#"{"type":"basic","username":"alice","password":"hunter2"}"#).unwrap();
+        assert!(!serde_json::to_string(&auth).unwrap().contains("hunter2"));
+        assert_eq!(auth.take_secret().as_deref(), Some("hunter2"));
     }
 }
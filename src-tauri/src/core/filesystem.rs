@@ -1,21 +1,111 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::io::{Read, Seek, Write};
+
+use super::listing::{DirectoryPage, ListOptions};
 
 /// Результат операции файловой системы
 pub type FileSystemResult<T> = Result<T, FileSystemError>;
 
+/// Категория ошибки файловой системы
+///
+/// Позволяет вызывающему коду (ApiError, фронтенд) различать причины ошибки,
+/// не разбирая текст сообщения.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum ErrorKind {
+    /// Путь не существует
+    NotFound,
+    /// Недостаточно прав доступа
+    PermissionDenied,
+    /// Файл или папка уже существует
+    AlreadyExists,
+    /// Ожидалась директория
+    NotADirectory,
+    /// Ожидался файл, а не директория
+    IsADirectory,
+    /// Ресурс заблокирован (например, хранилище не разблокировано)
+    Locked,
+    /// Превышен лимит размера или места
+    QuotaExceeded,
+    /// Некорректные параметры запроса (шаблон, курсор и т.п.)
+    InvalidInput,
+    /// Прочие ошибки ввода-вывода
+    #[default]
+    Io,
+}
+
+impl From<std::io::ErrorKind> for ErrorKind {
+    fn from(kind: std::io::ErrorKind) -> Self {
+        use std::io::ErrorKind as IoKind;
+
+        match kind {
+            IoKind::NotFound => ErrorKind::NotFound,
+            IoKind::PermissionDenied | IoKind::ReadOnlyFilesystem => ErrorKind::PermissionDenied,
+            IoKind::AlreadyExists => ErrorKind::AlreadyExists,
+            IoKind::NotADirectory => ErrorKind::NotADirectory,
+            IoKind::IsADirectory => ErrorKind::IsADirectory,
+            IoKind::ResourceBusy | IoKind::ExecutableFileBusy => ErrorKind::Locked,
+            IoKind::StorageFull | IoKind::QuotaExceeded | IoKind::FileTooLarge => ErrorKind::QuotaExceeded,
+            _ => ErrorKind::Io,
+        }
+    }
+}
+
 /// Ошибки файловой системы
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileSystemError {
+    #[serde(default)]
+    pub kind: ErrorKind,
     pub message: String,
 }
 
 impl FileSystemError {
+    /// Ошибка без явной категории (ErrorKind::Io)
     pub fn new(message: impl Into<String>) -> Self {
+        Self::with_kind(ErrorKind::Io, message)
+    }
+
+    pub fn with_kind(kind: ErrorKind, message: impl Into<String>) -> Self {
         Self {
+            kind,
             message: message.into(),
         }
     }
+
+    /// Ошибка ввода-вывода; категория определяется по `std::io::ErrorKind`
+    pub fn from_io(err: &std::io::Error, message: impl Into<String>) -> Self {
+        Self::with_kind(err.kind().into(), message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::with_kind(ErrorKind::NotFound, message)
+    }
+
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        Self::with_kind(ErrorKind::PermissionDenied, message)
+    }
+
+    pub fn already_exists(message: impl Into<String>) -> Self {
+        Self::with_kind(ErrorKind::AlreadyExists, message)
+    }
+
+    pub fn not_a_directory(message: impl Into<String>) -> Self {
+        Self::with_kind(ErrorKind::NotADirectory, message)
+    }
+
+    pub fn is_a_directory(message: impl Into<String>) -> Self {
+        Self::with_kind(ErrorKind::IsADirectory, message)
+    }
+
+    pub fn locked(message: impl Into<String>) -> Self {
+        Self::with_kind(ErrorKind::Locked, message)
+    }
+
+    pub fn quota_exceeded(message: impl Into<String>) -> Self {
+        Self::with_kind(ErrorKind::QuotaExceeded, message)
+    }
 }
 
 impl fmt::Display for FileSystemError {
@@ -36,8 +126,17 @@ impl From<&str> for FileSystemError {
     }
 }
 
+impl From<std::io::Error> for FileSystemError {
+    fn from(err: std::io::Error) -> Self {
+        FileSystemError::from_io(&err, err.to_string())
+    }
+}
+
 /// Запись файловой системы (файл или директория)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Расширенные поля заполняются бэкендом, если он их поддерживает;
+/// иначе остаются `None` / `false`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
 pub struct FileSystemEntry {
@@ -49,6 +148,79 @@ pub struct FileSystemEntry {
     pub modified: Option<u64>,
     pub created: Option<u64>,
     pub accessed: Option<u64>,
+    /// Права доступа (Unix mode, включая тип файла)
+    #[serde(default)]
+    pub mode: Option<u32>,
+    #[serde(default)]
+    pub uid: Option<u32>,
+    #[serde(default)]
+    pub gid: Option<u32>,
+    /// Имя владельца (по uid)
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub is_symlink: bool,
+    /// Куда указывает символическая ссылка (как записано в ссылке)
+    #[serde(default)]
+    pub symlink_target: Option<String>,
+    /// Скрытый файл (dot-файл или атрибут hidden в Windows)
+    #[serde(default)]
+    pub is_hidden: bool,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    /// Количество жестких ссылок
+    #[serde(default)]
+    pub nlink: Option<u64>,
+    #[serde(default)]
+    pub inode: Option<u64>,
+    /// Устройство файла; вместе с `inode` однозначно определяет файл на диске
+    #[serde(default)]
+    pub dev: Option<u64>,
+}
+
+/// MIME-тип по имени файла; для директорий — `inode/directory`
+pub fn guess_mime_type(name: &str, is_dir: bool) -> Option<String> {
+    if is_dir {
+        return Some("inode/directory".to_string());
+    }
+    mime_guess::from_path(name).first().map(|mime| mime.essence_str().to_string())
+}
+
+/// Элемент корзины
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct TrashEntry {
+    /// Идентификатор элемента внутри корзины (используется для восстановления)
+    pub id: String,
+    pub name: String,
+    /// Путь, по которому элемент находился до удаления
+    pub original_path: String,
+    /// Время удаления (секунды UNIX)
+    pub deleted_at: u64,
+    pub is_dir: bool,
+    pub size: Option<u64>,
+}
+
+/// Поток чтения файла с произвольным доступом
+pub trait FileReader: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> FileReader for T {}
+
+/// Поток записи файла
+///
+/// Бэкенды, которые не пишут напрямую на диск (например, зашифрованное хранилище),
+/// фиксируют файл только в `finish`. Без вызова `finish` запись считается прерванной.
+pub trait FileWriter: Write + Send {
+    /// Завершение записи: сброс буферов и фиксация файла
+    fn finish(self: Box<Self>) -> FileSystemResult<()>;
+}
+
+impl FileWriter for std::fs::File {
+    fn finish(mut self: Box<Self>) -> FileSystemResult<()> {
+        self.flush()
+            .map_err(|e| FileSystemError::new(format!("Failed to flush file: {}", e)))
+    }
 }
 
 /// Trait для различных реализаций файловой системы
@@ -57,8 +229,21 @@ pub struct FileSystemEntry {
 /// - Virtual filesystem (in-memory)
 /// - Database-backed filesystem
 pub trait FileSystem: Send + Sync {
-    /// Чтение содержимого директории
-    fn read_directory(&self, path: &str) -> FileSystemResult<Vec<FileSystemEntry>>;
+    /// Чтение содержимого директории (без скрытых файлов)
+    fn read_directory(&self, path: &str) -> FileSystemResult<Vec<FileSystemEntry>> {
+        self.read_directory_with_options(path, &ListOptions::default())
+            .map(|page| page.entries)
+    }
+
+    /// Чтение содержимого директории с параметрами листинга
+    ///
+    /// Реализация отбрасывает записи по `options.name_filter()` и скрытые файлы
+    /// при обходе, остальное делегирует `options.apply`.
+    fn read_directory_with_options(
+        &self,
+        path: &str,
+        options: &ListOptions,
+    ) -> FileSystemResult<DirectoryPage>;
 
     /// Получение информации о файле/директории
     fn get_file_info(&self, path: &str) -> FileSystemResult<FileSystemEntry>;
@@ -66,6 +251,24 @@ pub trait FileSystem: Send + Sync {
     /// Удаление файла или директории
     fn delete_item(&self, path: &str) -> FileSystemResult<()>;
 
+    /// Перемещение файла или директории в корзину
+    fn trash_item(&self, path: &str) -> FileSystemResult<TrashEntry>;
+
+    /// Содержимое корзины
+    fn list_trash(&self) -> FileSystemResult<Vec<TrashEntry>>;
+
+    /// Восстановление элемента из корзины по исходному пути
+    /// Возвращает восстановленный элемент
+    fn restore_from_trash(&self, id: &str) -> FileSystemResult<TrashEntry>;
+
+    /// Окончательное удаление всех элементов корзины
+    /// Возвращает количество удаленных элементов
+    fn empty_trash(&self) -> FileSystemResult<usize>;
+
+    /// Окончательное удаление элементов, помещенных в корзину раньше `cutoff` (секунды UNIX)
+    /// Возвращает количество удаленных элементов
+    fn purge_trash_older_than(&self, cutoff: u64) -> FileSystemResult<usize>;
+
     /// Переименование файла или директории
     fn rename_item(&self, old_path: &str, new_name: &str) -> FileSystemResult<()>;
 
@@ -117,6 +320,13 @@ pub trait FileSystem: Send + Sync {
     /// Запись байтов в файл (для копирования)
     fn write_file_bytes(&self, path: &str, content: &[u8]) -> FileSystemResult<()>;
 
+    /// Открытие файла для потокового чтения (без загрузки целиком в память)
+    fn open_read(&self, path: &str) -> FileSystemResult<Box<dyn FileReader>>;
+
+    /// Открытие файла для потоковой записи
+    /// Создает или перезаписывает файл; данные сохранены только после `FileWriter::finish`
+    fn open_write(&self, path: &str) -> FileSystemResult<Box<dyn FileWriter>>;
+
     /// Открытие файла в системном приложении
     fn open_file(&self, path: &str) -> FileSystemResult<()>;
 
@@ -0,0 +1,488 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use globset::{GlobBuilder, GlobMatcher};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use super::filesystem::{ErrorKind, FileSystemEntry, FileSystemError, FileSystemResult};
+
+/// Максимальный размер одной страницы листинга
+pub const MAX_PAGE_SIZE: usize = 10_000;
+
+/// Ключ сортировки листинга
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum SortKey {
+    Name,
+    Size,
+    Modified,
+    Created,
+    Extension,
+}
+
+/// Направление сортировки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Фильтр по типу записи
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub enum EntryTypeFilter {
+    Files,
+    Directories,
+    Symlinks,
+}
+
+impl EntryTypeFilter {
+    fn matches(self, entry: &FileSystemEntry) -> bool {
+        match self {
+            EntryTypeFilter::Files => entry.is_file,
+            EntryTypeFilter::Directories => entry.is_dir,
+            EntryTypeFilter::Symlinks => entry.is_symlink,
+        }
+    }
+}
+
+/// Параметры чтения директории
+///
+/// Фильтр по имени применяется бэкендом при обходе директории (до чтения
+/// метаданных), остальное — в [`ListOptions::apply`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct ListOptions {
+    /// Включать скрытые файлы; `None` — не включать
+    #[serde(default)]
+    pub show_hidden: Option<bool>,
+    /// Ключ сортировки; без него сохраняется порядок бэкенда
+    #[serde(default)]
+    pub sort_by: Option<SortKey>,
+    #[serde(default)]
+    pub sort_order: SortOrder,
+    /// Естественная сортировка имен ("file2" < "file10")
+    #[serde(default)]
+    pub natural_sort: bool,
+    /// Директории перед файлами (независимо от направления сортировки)
+    #[serde(default)]
+    pub directories_first: bool,
+    /// Glob-шаблон для имени (`*.rs`, `IMG_????.jpg`)
+    #[serde(default)]
+    pub glob: Option<String>,
+    /// Регулярное выражение для имени
+    #[serde(default)]
+    pub regex: Option<String>,
+    /// Учитывать регистр в glob/regex
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub entry_type: Option<EntryTypeFilter>,
+    /// Курсор из `DirectoryPage::next_cursor` предыдущей страницы
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Размер страницы (не больше `MAX_PAGE_SIZE`)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Страница листинга директории
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "api-server", derive(utoipa::ToSchema))]
+pub struct DirectoryPage {
+    pub entries: Vec<FileSystemEntry>,
+    /// Количество записей после фильтрации (по всем страницам)
+    pub total: usize,
+    /// Курсор следующей страницы; `None` — это последняя страница
+    pub next_cursor: Option<String>,
+}
+
+/// Скомпилированный фильтр по имени
+pub struct NameFilter {
+    glob: Option<GlobMatcher>,
+    regex: Option<Regex>,
+}
+
+impl NameFilter {
+    pub fn matches(&self, name: &str) -> bool {
+        self.glob.as_ref().is_none_or(|glob| glob.is_match(name))
+            && self.regex.as_ref().is_none_or(|regex| regex.is_match(name))
+    }
+}
+
+/// Позиция в отсортированном листинге, закодированная в курсоре
+#[derive(Serialize, Deserialize)]
+struct CursorKey {
+    name: String,
+    path: String,
+    is_dir: bool,
+    size: Option<u64>,
+    modified: Option<u64>,
+    created: Option<u64>,
+}
+
+impl CursorKey {
+    fn from_entry(entry: &FileSystemEntry) -> Self {
+        Self {
+            name: entry.name.clone(),
+            path: entry.path.clone(),
+            is_dir: entry.is_dir,
+            size: entry.size,
+            modified: entry.modified,
+            created: entry.created,
+        }
+    }
+
+    fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    fn decode(cursor: &str) -> FileSystemResult<Self> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| FileSystemError::with_kind(ErrorKind::InvalidInput, "Invalid cursor"))
+    }
+
+    fn into_entry(self) -> FileSystemEntry {
+        FileSystemEntry {
+            name: self.name,
+            path: self.path,
+            is_dir: self.is_dir,
+            is_file: !self.is_dir,
+            size: self.size,
+            modified: self.modified,
+            created: self.created,
+            ..Default::default()
+        }
+    }
+}
+
+/// Запись вместе с ключом сравнения имени (чтобы не пересчитывать его при сортировке)
+struct Keyed {
+    folded: String,
+    entry: FileSystemEntry,
+}
+
+impl Keyed {
+    fn new(entry: FileSystemEntry) -> Self {
+        Self {
+            folded: entry.name.to_lowercase(),
+            entry,
+        }
+    }
+}
+
+impl ListOptions {
+    pub fn include_hidden(&self) -> bool {
+        self.show_hidden.unwrap_or(false)
+    }
+
+    /// Компиляция glob/regex; ошибка шаблона — `ErrorKind::InvalidInput`
+    pub fn name_filter(&self) -> FileSystemResult<NameFilter> {
+        let glob = match &self.glob {
+            Some(pattern) if !pattern.is_empty() => Some(
+                GlobBuilder::new(pattern)
+                    .case_insensitive(!self.case_sensitive)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|e| {
+                        FileSystemError::with_kind(ErrorKind::InvalidInput, format!("Invalid glob pattern: {}", e))
+                    })?
+                    .compile_matcher(),
+            ),
+            _ => None,
+        };
+
+        let regex = match &self.regex {
+            Some(pattern) if !pattern.is_empty() => Some(
+                RegexBuilder::new(pattern)
+                    .case_insensitive(!self.case_sensitive)
+                    .build()
+                    .map_err(|e| {
+                        FileSystemError::with_kind(ErrorKind::InvalidInput, format!("Invalid regex: {}", e))
+                    })?,
+            ),
+            _ => None,
+        };
+
+        Ok(NameFilter { glob, regex })
+    }
+
+    /// Нужна ли сортировка: явно запрошена или нужна для стабильной пагинации
+    fn needs_sort(&self) -> bool {
+        self.sort_by.is_some() || self.directories_first || self.cursor.is_some() || self.limit.is_some()
+    }
+
+    fn compare(&self, a: &Keyed, b: &Keyed) -> Ordering {
+        if self.directories_first {
+            match (a.entry.is_dir, b.entry.is_dir) {
+                (true, false) => return Ordering::Less,
+                (false, true) => return Ordering::Greater,
+                _ => {}
+            }
+        }
+
+        let by_name = || {
+            let folded = if self.natural_sort {
+                natural_cmp(&a.folded, &b.folded)
+            } else {
+                a.folded.cmp(&b.folded)
+            };
+            folded
+                .then_with(|| a.entry.name.cmp(&b.entry.name))
+                .then_with(|| a.entry.path.cmp(&b.entry.path))
+        };
+
+        let ordering = match self.sort_by.unwrap_or(SortKey::Name) {
+            SortKey::Name => by_name(),
+            SortKey::Size => a.entry.size.cmp(&b.entry.size).then_with(by_name),
+            SortKey::Modified => a.entry.modified.cmp(&b.entry.modified).then_with(by_name),
+            SortKey::Created => a.entry.created.cmp(&b.entry.created).then_with(by_name),
+            SortKey::Extension => extension(&a.folded, a.entry.is_dir)
+                .cmp(extension(&b.folded, b.entry.is_dir))
+                .then_with(by_name),
+        };
+
+        match self.sort_order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    }
+
+    /// Фильтр по типу, сортировка и выдача страницы
+    pub fn apply(&self, entries: Vec<FileSystemEntry>) -> FileSystemResult<DirectoryPage> {
+        let limit = match self.limit {
+            Some(0) => {
+                return Err(FileSystemError::with_kind(
+                    ErrorKind::InvalidInput,
+                    "Page limit must be greater than zero",
+                ))
+            }
+            Some(limit) => Some(limit.min(MAX_PAGE_SIZE)),
+            None => None,
+        };
+
+        let entries = entries
+            .into_iter()
+            .filter(|entry| self.entry_type.is_none_or(|filter| filter.matches(entry)));
+
+        if !self.needs_sort() {
+            let entries: Vec<_> = entries.collect();
+            return Ok(DirectoryPage {
+                total: entries.len(),
+                entries,
+                next_cursor: None,
+            });
+        }
+
+        let mut keyed: Vec<Keyed> = entries.map(Keyed::new).collect();
+        keyed.sort_by(|a, b| self.compare(a, b));
+        let total = keyed.len();
+
+        let start = match &self.cursor {
+            Some(cursor) => {
+                let after = Keyed::new(CursorKey::decode(cursor)?.into_entry());
+                keyed.partition_point(|item| self.compare(item, &after) != Ordering::Greater)
+            }
+            None => 0,
+        };
+
+        let end = limit.map_or(total, |limit| start.saturating_add(limit).min(total));
+        let next_cursor = (end < total && end > start)
+            .then(|| CursorKey::from_entry(&keyed[end - 1].entry).encode());
+
+        let entries = keyed
+            .into_iter()
+            .skip(start)
+            .take(end.saturating_sub(start))
+            .map(|item| item.entry)
+            .collect();
+
+        Ok(DirectoryPage {
+            entries,
+            total,
+            next_cursor,
+        })
+    }
+}
+
+fn extension(folded_name: &str, is_dir: bool) -> &str {
+    if is_dir {
+        return "";
+    }
+    match folded_name.rfind('.') {
+        Some(0) | None => "",
+        Some(dot) => &folded_name[dot + 1..],
+    }
+}
+
+/// Естественное сравнение строк: последовательности цифр сравниваются как числа
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+
+    loop {
+        match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let a_len = a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len());
+                let b_len = b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len());
+                let (a_digits, a_rest) = a.split_at(a_len);
+                let (b_digits, b_rest) = b.split_at(b_len);
+
+                let a_num = a_digits.trim_start_matches('0');
+                let b_num = b_digits.trim_start_matches('0');
+                let ordering = a_num
+                    .len()
+                    .cmp(&b_num.len())
+                    .then_with(|| a_num.cmp(b_num))
+                    // "01" и "1" равны как числа; короткая запись идет первой
+                    .then_with(|| a_digits.len().cmp(&b_digits.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+
+                a = a_rest;
+                b = b_rest;
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a = &a[x.len_utf8()..];
+                b = &b[y.len_utf8()..];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, is_dir: bool, size: u64) -> FileSystemEntry {
+        FileSystemEntry {
+            path: format!("/dir/{}", name),
+            name: name.to_string(),
+            is_dir,
+            is_file: !is_dir,
+            size: (!is_dir).then_some(size),
+            ..Default::default()
+        }
+    }
+
+    fn names(page: &DirectoryPage) -> Vec<&str> {
+        page.entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut names = vec!["file10", "file2", "file1", "file02", "a"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, vec!["a", "file1", "file2", "file02", "file10"]);
+    }
+
+    #[test]
+    fn test_sort_directories_first_desc() {
+        let options = ListOptions {
+            sort_by: Some(SortKey::Size),
+            sort_order: SortOrder::Desc,
+            directories_first: true,
+            ..Default::default()
+        };
+        let page = options
+            .apply(vec![entry("small", false, 1), entry("docs", true, 0), entry("big", false, 100)])
+            .unwrap();
+        assert_eq!(names(&page), vec!["docs", "big", "small"]);
+    }
+
+    #[test]
+    fn test_name_and_type_filters() {
+        let options = ListOptions {
+            glob: Some("*.RS".to_string()),
+            entry_type: Some(EntryTypeFilter::Files),
+            ..Default::default()
+        };
+        let filter = options.name_filter().unwrap();
+        assert!(filter.matches("main.rs"));
+        assert!(!filter.matches("main.toml"));
+
+        let page = options.apply(vec![entry("src.rs", true, 0), entry("lib.rs", false, 1)]).unwrap();
+        assert_eq!(names(&page), vec!["lib.rs"]);
+
+        let regex = ListOptions {
+            regex: Some("^img_\\d+".to_string()),
+            ..Default::default()
+        };
+        assert!(regex.name_filter().unwrap().matches("IMG_001.jpg"));
+        assert!(!regex.name_filter().unwrap().matches("photo.jpg"));
+
+        let invalid = ListOptions {
+            regex: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(invalid.name_filter().err().unwrap().kind, ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_cursor_pagination_visits_every_entry_once() {
+        let entries: Vec<_> = (0..25).map(|i| entry(&format!("f{}", i), false, i)).collect();
+        let mut options = ListOptions {
+            natural_sort: true,
+            limit: Some(10),
+            ..Default::default()
+        };
+
+        let mut seen = Vec::new();
+        loop {
+            let page = options.apply(entries.clone()).unwrap();
+            assert_eq!(page.total, 25);
+            seen.extend(page.entries.into_iter().map(|e| e.name));
+            match page.next_cursor {
+                Some(cursor) => options.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        let expected: Vec<_> = (0..25).map(|i| format!("f{}", i)).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_cursor_survives_removed_entry() {
+        let options = ListOptions {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let page = options
+            .apply(vec![entry("a", false, 0), entry("b", false, 0), entry("c", false, 0)])
+            .unwrap();
+
+        // "b" удален между запросами страниц
+        let next = ListOptions {
+            cursor: page.next_cursor,
+            ..options
+        }
+        .apply(vec![entry("a", false, 0), entry("c", false, 0)])
+        .unwrap();
+        assert_eq!(names(&next), vec!["c"]);
+        assert!(next.next_cursor.is_none());
+
+        let bad = ListOptions {
+            cursor: Some("???".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(bad.apply(Vec::new()).err().unwrap().kind, ErrorKind::InvalidInput);
+    }
+}
@@ -3,5 +3,10 @@ mod file;
 mod directory;
 mod node;
 pub mod filesystem;
+pub mod listing;
 
-pub use filesystem::{FileSystem, FileSystemEntry, FileSystemError, FileSystemResult};
\ No newline at end of file
+pub use filesystem::{
+    guess_mime_type, ErrorKind, FileReader, FileSystem, FileSystemEntry, FileSystemError,
+    FileSystemResult, FileWriter, TrashEntry,
+};
+pub use listing::{DirectoryPage, EntryTypeFilter, ListOptions, SortKey, SortOrder};
\ No newline at end of file
@@ -94,10 +94,10 @@ pub fn copy_file_real_fs<R: tauri::Runtime>(
     ));
 
     let mut source = fs::File::open(src)
-        .map_err(|e| FileSystemError::new(format!("Failed to open source: {}", e)))?;
+        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to open source: {}", e)))?;
 
     let mut destination = fs::File::create(dest)
-        .map_err(|e| FileSystemError::new(format!("Failed to create destination: {}", e)))?;
+        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create destination: {}", e)))?;
 
     let mut buffer = vec![0; BUFFER_SIZE];
 
@@ -106,7 +106,7 @@ pub fn copy_file_real_fs<R: tauri::Runtime>(
 
         let bytes_read = source
             .read(&mut buffer)
-            .map_err(|e| FileSystemError::new(format!("Failed to read: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read: {}", e)))?;
 
         if bytes_read == 0 {
             break;
@@ -114,7 +114,7 @@ pub fn copy_file_real_fs<R: tauri::Runtime>(
 
         destination
             .write_all(&buffer[..bytes_read])
-            .map_err(|e| FileSystemError::new(format!("Failed to write: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write: {}", e)))?;
 
         tracker.add_bytes(bytes_read as u64);
         emit_progress(app, tracker);
@@ -138,13 +138,13 @@ pub fn copy_dir_real_fs<R: tauri::Runtime>(
     check_pause_and_cancel(tracker, app)?;
 
     fs::create_dir_all(dest)
-        .map_err(|e| FileSystemError::new(format!("Failed to create directory: {}", e)))?;
+        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create directory: {}", e)))?;
 
     for entry in fs::read_dir(src)
-        .map_err(|e| FileSystemError::new(format!("Failed to read directory: {}", e)))?
+        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read directory: {}", e)))?
     {
         let entry = entry
-            .map_err(|e| FileSystemError::new(format!("Failed to read entry: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read entry: {}", e)))?;
 
         let path = entry.path();
         let file_name = path
@@ -175,7 +175,7 @@ fn copy_recursive_cross_fs<R: tauri::Runtime>(
 
     // Get info about source
     let info = API.files.get_file_info(source_path, source_fs)
-        .map_err(|e| FileSystemError::new(e.to_string()))?;
+        .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), e.to_string()))?;
 
     let name = &info.name;
     let dest_path_buf = Path::new(dest_parent).join(name);
@@ -186,27 +186,47 @@ fn copy_recursive_cross_fs<R: tauri::Runtime>(
     if info.is_dir {
         // Create directory in destination
         API.files.create_folder(dest_parent, name, dest_fs)
-            .map_err(|e| FileSystemError::new(format!("Failed to create folder: {}", e)))?;
+            .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to create folder: {}", e)))?;
 
         // List contents
-        let entries = API.files.list_directory(source_path, source_fs)
-            .map_err(|e| FileSystemError::new(format!("Failed to list directory: {}", e)))?;
+        let entries = API.files.list_directory(source_path, Some(true), source_fs)
+            .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to list directory: {}", e)))?;
 
         for entry in entries {
             copy_recursive_cross_fs(&entry.path, &dest_path, tracker, app, source_fs, dest_fs)?;
         }
     } else {
-        // Read content (binary safe)
-        let content = API.files.read_file_bytes(source_path, source_fs)
-            .map_err(|e| FileSystemError::new(format!("Failed to read file content: {}", e)))?;
+        // Stream content (binary safe) without loading the whole file into memory
+        let mut source = API.files.open_read(source_path, source_fs)
+            .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to open source file: {}", e)))?;
 
-        // Write content (binary safe) using write_file_bytes which expects full path
         // We already constructed dest_path above as full path to destination file
-        API.files.write_file_bytes(&dest_path, &content, dest_fs)
-            .map_err(|e| FileSystemError::new(format!("Failed to write file: {}", e)))?;
+        let mut destination = API.files.open_write(&dest_path, dest_fs)
+            .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to create destination file: {}", e)))?;
+
+        let mut buffer = vec![0u8; BUFFER_SIZE];
+        loop {
+            check_pause_and_cancel(tracker, app)?;
+
+            let bytes_read = source
+                .read(&mut buffer)
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read: {}", e)))?;
+            if bytes_read == 0 {
+                break;
+            }
+
+            destination
+                .write_all(&buffer[..bytes_read])
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write: {}", e)))?;
+
+            tracker.add_bytes(bytes_read as u64);
+            emit_progress(app, tracker);
+        }
+
+        destination.finish()
+            .map_err(|e| FileSystemError::with_kind(e.kind, format!("Failed to write file: {}", e)))?;
 
         // Update progress
-        tracker.add_bytes(content.len() as u64);
         tracker.add_item();
         emit_progress(app, tracker);
     }
@@ -237,7 +257,7 @@ pub fn copy_items_with_progress<R: tauri::Runtime>(
             check_pause_and_cancel(tracker, app)?;
             let source_path = PathBuf::from(source);
             if !source_path.exists() {
-                return Err(FileSystemError::new(format!("Source does not exist: {}", source)));
+                return Err(FileSystemError::not_found(format!("Source does not exist: {}", source)));
             }
             let file_name = source_path.file_name().ok_or_else(|| FileSystemError::new("Could not get file name"))?;
             let dest_file_path = dest_path.join(file_name);
@@ -281,7 +301,7 @@ pub fn move_items_with_progress<R: tauri::Runtime>(
             check_pause_and_cancel(tracker, app)?;
             let source_path = PathBuf::from(source);
             if !source_path.exists() {
-                return Err(FileSystemError::new(format!("Source does not exist: {}", source)));
+                return Err(FileSystemError::not_found(format!("Source does not exist: {}", source)));
             }
             let file_name = source_path.file_name().ok_or_else(|| FileSystemError::new("Could not get file name"))?;
             let dest_file_path = dest_path.join(file_name);
@@ -299,10 +319,10 @@ pub fn move_items_with_progress<R: tauri::Runtime>(
             } else {
                 if source_path.is_dir() {
                     copy_dir_real_fs(&source_path, &dest_file_path, tracker, app)?;
-                    fs::remove_dir_all(&source_path).map_err(|e| FileSystemError::new(format!("Failed to remove source directory: {}", e)))?;
+                    fs::remove_dir_all(&source_path).map_err(|e| FileSystemError::from_io(&e, format!("Failed to remove source directory: {}", e)))?;
                 } else {
                     copy_file_real_fs(&source_path, &dest_file_path, tracker, app)?;
-                    fs::remove_file(&source_path).map_err(|e| FileSystemError::new(format!("Failed to remove source file: {}", e)))?;
+                    fs::remove_file(&source_path).map_err(|e| FileSystemError::from_io(&e, format!("Failed to remove source file: {}", e)))?;
                 }
             }
         }
@@ -315,8 +335,8 @@ pub fn move_items_with_progress<R: tauri::Runtime>(
             copy_recursive_cross_fs(source, destination, tracker, app, source_fs.as_deref(), dest_fs.as_deref())?;
             
             // 2. Delete source
-            API.files.delete_item(source, source_fs.as_deref())
-                .map_err(|e| FileSystemError::new(format!("Failed to delete source after move: {}", e)))?;
+            API.files.delete_item(source, true, source_fs.as_deref())
+                .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to delete source after move: {}", e)))?;
         }
     }
 
@@ -324,20 +344,35 @@ pub fn move_items_with_progress<R: tauri::Runtime>(
 }
 
 /// Удаляет элементы с прогрессом
+///
+/// Без `permanent` элементы перемещаются в корзину бэкенда
 pub fn delete_items_with_progress<R: tauri::Runtime>(
     paths: &[String],
     tracker: &Arc<ProgressTracker>,
     app: &AppHandle<R>,
     panel_fs: Option<String>,
+    permanent: bool,
 ) -> Result<(), FileSystemError> {
     let is_real_fs = panel_fs.is_none() || panel_fs.as_deref() == Some("real");
 
-    if is_real_fs {
+    if !permanent {
+        // Перемещение в корзину — один шаг на элемент верхнего уровня
+        for path in paths {
+            check_pause_and_cancel(tracker, app)?;
+            tracker.update_current_file(Some(path.clone()));
+
+            API.files.delete_item(path, false, panel_fs.as_deref())
+                .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to move item to trash: {}", e)))?;
+
+            tracker.add_item();
+            emit_progress(app, tracker);
+        }
+    } else if is_real_fs {
         for path_str in paths {
             check_pause_and_cancel(tracker, app)?;
             let path = PathBuf::from(path_str);
             if !path.exists() {
-                return Err(FileSystemError::new(format!("Path does not exist: {}", path_str)));
+                return Err(FileSystemError::not_found(format!("Path does not exist: {}", path_str)));
             }
             if path.is_dir() {
                 delete_dir_real_fs(&path, tracker, app)?;
@@ -354,8 +389,8 @@ pub fn delete_items_with_progress<R: tauri::Runtime>(
             // Note: API.files.delete_item is usually recursive.
             // We might not get granular progress here unless we recurse manually.
             // For now, we trust delete_item.
-            API.files.delete_item(path, panel_fs.as_deref())
-                .map_err(|e| FileSystemError::new(format!("Failed to delete item: {}", e)))?;
+            API.files.delete_item(path, true, panel_fs.as_deref())
+                .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to delete item: {}", e)))?;
             
             tracker.add_item();
             emit_progress(app, tracker);
@@ -374,7 +409,7 @@ fn delete_file_real_fs<R: tauri::Runtime>(
     tracker.update_current_file(Some(path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string()));
     
     let len = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
-    fs::remove_file(path).map_err(|e| FileSystemError::new(format!("Failed to delete file: {}", e)))?;
+    fs::remove_file(path).map_err(|e| FileSystemError::from_io(&e, format!("Failed to delete file: {}", e)))?;
     
     tracker.add_bytes(len);
     tracker.add_item();
@@ -389,8 +424,8 @@ fn delete_dir_real_fs<R: tauri::Runtime>(
 ) -> Result<(), FileSystemError> {
     check_pause_and_cancel(tracker, app)?;
     
-    for entry in fs::read_dir(path).map_err(|e| FileSystemError::new(format!("Failed to read dir: {}", e)))? {
-        let entry = entry.map_err(|e| FileSystemError::new(format!("Failed to read entry: {}", e)))?;
+    for entry in fs::read_dir(path).map_err(|e| FileSystemError::from_io(&e, format!("Failed to read dir: {}", e)))? {
+        let entry = entry.map_err(|e| FileSystemError::from_io(&e, format!("Failed to read entry: {}", e)))?;
         let entry_path = entry.path();
         if entry_path.is_dir() {
             delete_dir_real_fs(&entry_path, tracker, app)?;
@@ -399,7 +434,7 @@ fn delete_dir_real_fs<R: tauri::Runtime>(
         }
     }
     
-    fs::remove_dir(path).map_err(|e| FileSystemError::new(format!("Failed to delete dir: {}", e)))?;
+    fs::remove_dir(path).map_err(|e| FileSystemError::from_io(&e, format!("Failed to delete dir: {}", e)))?;
     Ok(())
 }
 
@@ -432,9 +467,9 @@ fn copy_file_real_fs_simple(
     check_pause_and_cancel_simple(tracker)?;
 
     let mut source = fs::File::open(src)
-        .map_err(|e| FileSystemError::new(format!("Failed to open source file: {}", e)))?;
+        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to open source file: {}", e)))?;
     let mut destination = fs::File::create(dest)
-        .map_err(|e| FileSystemError::new(format!("Failed to create destination file: {}", e)))?;
+        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create destination file: {}", e)))?;
 
     let mut buffer = vec![0u8; BUFFER_SIZE];
     loop {
@@ -442,14 +477,14 @@ fn copy_file_real_fs_simple(
 
         let bytes_read = source
             .read(&mut buffer)
-            .map_err(|e| FileSystemError::new(format!("Failed to read from source: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read from source: {}", e)))?;
         if bytes_read == 0 {
             break;
         }
 
         destination
             .write_all(&buffer[..bytes_read])
-            .map_err(|e| FileSystemError::new(format!("Failed to write to destination: {}", e)))?;
+            .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write to destination: {}", e)))?;
 
         tracker.add_bytes(bytes_read as u64);
     }
@@ -467,12 +502,12 @@ fn copy_dir_real_fs_simple(
     check_pause_and_cancel_simple(tracker)?;
 
     fs::create_dir_all(dest)
-        .map_err(|e| FileSystemError::new(format!("Failed to create directory: {}", e)))?;
+        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to create directory: {}", e)))?;
 
     for entry in fs::read_dir(src)
-        .map_err(|e| FileSystemError::new(format!("Failed to read directory: {}", e)))?
+        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read directory: {}", e)))?
     {
-        let entry = entry.map_err(|e| FileSystemError::new(format!("Failed to read entry: {}", e)))?;
+        let entry = entry.map_err(|e| FileSystemError::from_io(&e, format!("Failed to read entry: {}", e)))?;
         let entry_path = entry.path();
         let file_name = entry.file_name();
         let dest_path = dest.join(&file_name);
@@ -501,7 +536,7 @@ fn copy_recursive_generic(
 
     // Get info about source
     let info = API.files.get_file_info(source_path, source_fs)
-        .map_err(|e| FileSystemError::new(e.to_string()))?;
+        .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), e.to_string()))?;
 
     let name = &info.name;
     // Simple path join - assumes forward slash for now as VirtualFS standard
@@ -517,11 +552,11 @@ fn copy_recursive_generic(
     if info.is_dir {
         // Create directory in destination
         API.files.create_folder(dest_parent, name, dest_fs)
-            .map_err(|e| FileSystemError::new(format!("Failed to create folder: {}", e)))?;
+            .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to create folder: {}", e)))?;
 
         // List contents
-        let entries = API.files.list_directory(source_path, source_fs)
-            .map_err(|e| FileSystemError::new(format!("Failed to list directory: {}", e)))?;
+        let entries = API.files.list_directory(source_path, Some(true), source_fs)
+            .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to list directory: {}", e)))?;
 
         for entry in entries {
             copy_recursive_generic(&entry.path, &dest_path, tracker, source_fs, dest_fs)?;
@@ -530,16 +565,34 @@ fn copy_recursive_generic(
         // Add directory itself to item count
         tracker.add_item();
     } else {
-        // Read content
-        let content = API.files.read_file_bytes(source_path, source_fs)
-            .map_err(|e| FileSystemError::new(format!("Failed to read file: {}", e)))?;
+        // Stream content chunk by chunk
+        let mut source = API.files.open_read(source_path, source_fs)
+            .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to open source file: {}", e)))?;
+        let mut destination = API.files.open_write(&dest_path, dest_fs)
+            .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to create destination file: {}", e)))?;
+
+        let mut buffer = vec![0u8; BUFFER_SIZE];
+        loop {
+            check_pause_and_cancel_simple(tracker)?;
 
-        // Write content
-        API.files.write_file_bytes(&dest_path, &content, dest_fs)
-            .map_err(|e| FileSystemError::new(format!("Failed to write file: {}", e)))?;
+            let bytes_read = source
+                .read(&mut buffer)
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to read from source: {}", e)))?;
+            if bytes_read == 0 {
+                break;
+            }
+
+            destination
+                .write_all(&buffer[..bytes_read])
+                .map_err(|e| FileSystemError::from_io(&e, format!("Failed to write to destination: {}", e)))?;
+
+            tracker.add_bytes(bytes_read as u64);
+        }
+
+        destination.finish()
+            .map_err(|e| FileSystemError::with_kind(e.kind, format!("Failed to write file: {}", e)))?;
 
         // Update progress
-        tracker.add_bytes(content.len() as u64);
         tracker.add_item();
     }
 
@@ -571,7 +624,7 @@ pub fn copy_items_simple(
             check_pause_and_cancel_simple(tracker)?;
             let source_path = PathBuf::from(source);
             if !source_path.exists() {
-                return Err(FileSystemError::new(format!("Source does not exist: {}", source)));
+                return Err(FileSystemError::not_found(format!("Source does not exist: {}", source)));
             }
             let file_name = source_path
                 .file_name()
@@ -618,7 +671,7 @@ pub fn move_items_simple(
             check_pause_and_cancel_simple(tracker)?;
             let source_path = PathBuf::from(source);
             if !source_path.exists() {
-                return Err(FileSystemError::new(format!("Source does not exist: {}", source)));
+                return Err(FileSystemError::not_found(format!("Source does not exist: {}", source)));
             }
 
             let file_name = source_path
@@ -638,10 +691,10 @@ pub fn move_items_simple(
                 // Delete source
                 if source_path.is_dir() {
                     fs::remove_dir_all(&source_path)
-                        .map_err(|e| FileSystemError::new(format!("Failed to delete source: {}", e)))?;
+                        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to delete source: {}", e)))?;
                 } else {
                     fs::remove_file(&source_path)
-                        .map_err(|e| FileSystemError::new(format!("Failed to delete source: {}", e)))?;
+                        .map_err(|e| FileSystemError::from_io(&e, format!("Failed to delete source: {}", e)))?;
                 }
             }
 
@@ -655,8 +708,8 @@ pub fn move_items_simple(
             
             // 2. Delete source
             check_pause_and_cancel_simple(tracker)?;
-            API.files.delete_item(source, source_fs)
-                .map_err(|e| FileSystemError::new(format!("Failed to delete source after move: {}", e)))?;
+            API.files.delete_item(source, true, source_fs)
+                .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to delete source after move: {}", e)))?;
         }
     }
 
@@ -664,39 +717,25 @@ pub fn move_items_simple(
 }
 
 /// Delete items without AppHandle (for queue operations)
+///
+/// Unless `permanent` is set, items go to the backend's trash and can be restored.
 pub fn delete_items_simple(
     paths: &[String],
     tracker: &Arc<ProgressTracker>,
     panel_fs: Option<&str>,
+    permanent: bool,
 ) -> Result<(), FileSystemError> {
-    let is_real = panel_fs.is_none() || panel_fs == Some("real");
+    // Без panel_fs очередь всегда работала с реальной ФС, а не с бэкендом из конфигурации
+    let fs = panel_fs.or(Some("real"));
 
-    if is_real {
-        for path_str in paths {
-            check_pause_and_cancel_simple(tracker)?;
-            let path = PathBuf::from(path_str);
+    for path in paths {
+        check_pause_and_cancel_simple(tracker)?;
+        tracker.update_current_file(Some(path.clone()));
 
-            if path.is_dir() {
-                fs::remove_dir_all(&path)
-                    .map_err(|e| FileSystemError::new(format!("Failed to delete directory: {}", e)))?;
-            } else {
-                fs::remove_file(&path)
-                    .map_err(|e| FileSystemError::new(format!("Failed to delete file: {}", e)))?;
-            }
+        API.files.delete_item(path, permanent, fs)
+            .map_err(|e| FileSystemError::with_kind(e.kind().unwrap_or_default(), format!("Failed to delete {}: {}", path, e)))?;
 
-            tracker.add_item();
-        }
-    } else {
-        // Virtual/Generic Delete
-        for path in paths {
-            check_pause_and_cancel_simple(tracker)?;
-            tracker.update_current_file(Some(path.clone()));
-            
-            API.files.delete_item(path, panel_fs)
-                .map_err(|e| FileSystemError::new(format!("Failed to delete item: {}", e)))?;
-            
-            tracker.add_item();
-        }
+        tracker.add_item();
     }
 
     Ok(())
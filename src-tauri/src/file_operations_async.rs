@@ -118,6 +118,7 @@ pub async fn move_items_with_progress_async(
 #[cfg(feature = "api-server")]
 pub async fn delete_items_with_progress_async(
     paths: Vec<String>,
+    permanent: bool,
     tracker: Arc<ProgressTracker>,
     state: Arc<AppState>,
 ) -> Result<(), FileSystemError> {
@@ -143,7 +144,13 @@ pub async fn delete_items_with_progress_async(
             tracker.update_current_file(Some(file_name));
             emit_progress_websocket(&state, &tracker);
 
-            if let Err(e) = fs.delete_item(path) {
+            let result = if permanent {
+                fs.delete_item(path)
+            } else {
+                fs.trash_item(path).map(|_| ())
+            };
+
+            if let Err(e) = result {
                 tracker.mark_failed(e.message.clone());
                 emit_progress_websocket(&state, &tracker);
                 return Err(FileSystemError::new(e.message));
@@ -54,6 +54,25 @@ pub fn run() {
         .setup(|app| {
             let handle = app.handle().clone();
             app.manage(api::monitor::MonitorState::new());
+            // Forward debounced directory changes to the desktop panels
+            let watch_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                use tauri::Emitter;
+                let mut changes = api::watcher::WATCHER.subscribe_events();
+                loop {
+                    match changes.recv().await {
+                        Ok(change) => {
+                            let _ = watch_handle.emit("fs-change", change);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!("Directory watcher lagged, {} changes skipped", skipped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+            // Load persisted filename indexes and start keeping them up to date
+            api_service::index::FILE_INDEX.configure();
             tauri::async_runtime::spawn(async move {
                 let state = api::torrent::init_torrent_state().await;
                 handle.manage(state);
@@ -64,6 +83,7 @@ pub fn run() {
             greet,
             // Filesystem commands
             read_directory,
+            read_directory_page,
             delete_item,
             rename_item,
             create_folder,
@@ -81,6 +101,18 @@ pub fn run() {
             normalize_path,
             get_path_suggestions,
             open_terminal,
+            // Directory watching
+            watch_directory,
+            unwatch_directory,
+            // Trash
+            list_trash,
+            restore_from_trash,
+            empty_trash,
+            purge_trash_older_than,
+            // Undo/redo journal
+            undo_last_operation,
+            redo_operation,
+            get_operation_history,
             execute_command,
             // Config commands
             get_config,
@@ -98,13 +130,50 @@ pub fn run() {
             copy_items_with_progress_command,
             move_items_with_progress_command,
             delete_items_with_progress_command,
+            search_files_command,
+            grep_files_command,
+            find_duplicates_command,
+            queue_duplicate_action,
+            calculate_checksums_command,
+            verify_checksums_command,
+            queue_checksum_job,
+            get_checksum_job_result,
+            queue_split_file,
+            queue_join_files,
+            get_split_job_result,
+            compare_directories_command,
+            get_sync_profiles,
+            save_sync_profile,
+            delete_sync_profile,
+            plan_sync_command,
+            queue_sync_job,
+            get_sftp_profiles,
+            save_sftp_profile,
+            delete_sftp_profile,
+            connect_sftp,
+            disconnect_sftp,
+            get_webdav_profiles,
+            save_webdav_profile,
+            delete_webdav_profile,
+            connect_webdav,
+            disconnect_webdav,
+            get_s3_profiles,
+            save_s3_profile,
+            delete_s3_profile,
+            connect_s3,
+            disconnect_s3,
+            get_remote_sessions,
             cancel_operation,
             pause_operation,
             resume_operation,
             // Directory size calculation
             calculate_directory_size,
+            analyze_disk_usage_command,
             // System monitoring commands
             get_system_stats,
+            get_index_status,
+            rebuild_index,
+            query_index,
             api::monitor::start_monitoring,
             api::monitor::stop_monitoring,
             api::monitor::kill_process,
@@ -124,6 +193,10 @@ pub fn run() {
             extract_archive,
             list_archive_contents,
             create_archive,
+            open_archive,
+            close_archive,
+            queue_archive_test,
+            get_archive_test_result,
             // Vault security commands
             vault_is_enabled,
             vault_get_status,
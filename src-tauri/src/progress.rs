@@ -13,6 +13,17 @@ pub enum OperationType {
     Copy,
     Move,
     Delete,
+    Search,
+    Duplicates,
+    Checksum,
+    Compare,
+    Sync,
+    DiskUsage,
+    Archive,
+    Extract,
+    ArchiveTest,
+    Split,
+    Join,
 }
 
 /// Состояние операции
@@ -66,6 +77,11 @@ impl CancellationToken {
         self.cancelled.load(Ordering::Relaxed)
     }
 
+    /// Это тот же токен (или его клон)
+    pub fn same_as(&self, other: &CancellationToken) -> bool {
+        Arc::ptr_eq(&self.cancelled, &other.cancelled)
+    }
+
     pub fn pause(&self) {
         self.paused.store(true, Ordering::Relaxed);
     }
@@ -133,10 +149,18 @@ impl ProgressTracker {
         self.current_items.fetch_add(1, Ordering::Relaxed);
     }
 
+    pub fn add_items(&self, count: u64) {
+        self.current_items.fetch_add(count, Ordering::Relaxed);
+    }
+
     pub fn set_total_bytes(&self, bytes: u64) {
         self.total_bytes.store(bytes, Ordering::Relaxed);
     }
 
+    pub fn set_total_items(&self, items: u64) {
+        self.total_items.store(items, Ordering::Relaxed);
+    }
+
     pub fn get_current_bytes(&self) -> u64 {
         self.current_bytes.load(Ordering::Relaxed)
     }
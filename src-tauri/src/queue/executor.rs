@@ -1,11 +1,21 @@
 use super::types::*;
 use crate::progress::{OPERATIONS_MANAGER, OperationType as CoreOperationType};
 use crate::{file_operations, archives};
+use crate::archives::{ArchiveEdit, ArchiveOptions, ArchivePassword, ExtractOptions};
+use crate::api_service::API;
+use crate::api_service::checksum::{ChecksumJob, ChecksumJobResult};
+use crate::api_service::split::{JoinRequest, SplitRequest};
+use crate::api_service::duplicates::{DuplicateAction, DuplicateActionRequest, DuplicateGroup};
 use tracing::{info, error, debug};
 
 /// Operation executor that integrates with existing file operations
 pub struct OperationExecutor;
 
+/// Options for rebuilding an edited archive; the format comes from the archive itself
+fn edit_options(level: Option<u32>, threads: Option<u32>, password: Option<ArchivePassword>) -> ArchiveOptions {
+    ArchiveOptions { format: None, level, threads, password }
+}
+
 impl OperationExecutor {
     /// Create a new executor
     pub fn new() -> Self {
@@ -20,6 +30,12 @@ impl OperationExecutor {
         info!("Executing operation: {} ({:?})", operation.id, operation.operation_type);
         debug!("Operation params: {:?}", operation.params);
 
+        // Пароль не сохраняется на диск: после перезапуска операция без него не выполняется
+        if operation.password_missing() {
+            error!("Operation {} has no archive password", operation.id);
+            return Err(archives::PASSWORD_REQUIRED.to_string());
+        }
+
         let result = match &operation.params {
             OperationParams::Copy { sources, destination, source_fs, dest_fs } => {
                 self.execute_copy(operation, sources, destination, source_fs, dest_fs)
@@ -27,16 +43,58 @@ impl OperationExecutor {
             OperationParams::Move { sources, destination, source_fs, dest_fs } => {
                 self.execute_move(operation, sources, destination, source_fs, dest_fs)
             }
-            OperationParams::Delete { paths, panel_fs, source_fs } => {
+            OperationParams::Delete { paths, permanent, panel_fs, source_fs } => {
                 // Prefer source_fs, fall back to panel_fs
                 let fs = source_fs.as_ref().or(panel_fs.as_ref()).map(|s| s.clone());
-                self.execute_delete(operation, paths, fs)
+                self.execute_delete(operation, paths, fs, *permanent)
+            }
+            OperationParams::Archive { sources, archive_path, format, level, threads, password, source_fs, dest_fs } => {
+                let options = ArchiveOptions {
+                    format: format.clone(),
+                    level: *level,
+                    threads: *threads,
+                    password: password.clone(),
+                };
+                self.execute_archive(operation, sources, archive_path, &options, source_fs, dest_fs)
+            }
+            OperationParams::Extract { archive_path, destination, entries, overwrite, password, source_fs, dest_fs } => {
+                let options = ExtractOptions {
+                    entries: entries.clone(),
+                    overwrite: *overwrite,
+                    password: password.clone(),
+                };
+                self.execute_extract(operation, archive_path, destination, &options, source_fs, dest_fs)
             }
-            OperationParams::Archive { sources, archive_path, format, source_fs, dest_fs } => {
-                self.execute_archive(operation, sources, archive_path, format, source_fs, dest_fs)
+            OperationParams::ArchiveAdd { archive_path, sources, target_dir, level, threads, password, archive_fs, source_fs } => {
+                let edit = ArchiveEdit::Add {
+                    sources: sources.clone(),
+                    target_dir: target_dir.clone(),
+                    source_fs: source_fs.clone(),
+                };
+                let options = edit_options(*level, *threads, password.clone());
+                self.execute_archive_edit(operation, archive_path, archive_fs, edit, &options)
             }
-            OperationParams::Extract { archive_path, destination, source_fs, dest_fs } => {
-                self.execute_extract(operation, archive_path, destination, source_fs, dest_fs)
+            OperationParams::ArchiveDelete { archive_path, entries, level, threads, archive_fs } => {
+                let edit = ArchiveEdit::Delete { entries: entries.clone() };
+                let options = edit_options(*level, *threads, None);
+                self.execute_archive_edit(operation, archive_path, archive_fs, edit, &options)
+            }
+            OperationParams::ArchiveRename { archive_path, from, to, level, threads, archive_fs } => {
+                let edit = ArchiveEdit::Rename { from: from.clone(), to: to.clone() };
+                let options = edit_options(*level, *threads, None);
+                self.execute_archive_edit(operation, archive_path, archive_fs, edit, &options)
+            }
+            OperationParams::ArchiveUpdate { archive_path, entry, source, level, threads, password, archive_fs, source_fs } => {
+                let edit = ArchiveEdit::Replace {
+                    entry: entry.clone(),
+                    source: source.clone(),
+                    source_fs: source_fs.clone(),
+                };
+                let options = edit_options(*level, *threads, password.clone());
+                self.execute_archive_edit(operation, archive_path, archive_fs, edit, &options)
+            }
+            OperationParams::ArchiveTest { archive_path, password, source_fs } => {
+                self.execute_archive_test(operation, archive_path, password.as_ref(), source_fs)
             }
             OperationParams::BatchRename { items, config, source_fs } => {
                 self.execute_batch_rename(operation, items, config, source_fs)
@@ -44,6 +102,21 @@ impl OperationExecutor {
             OperationParams::BatchAttribute { items, config, source_fs } => {
                 self.execute_batch_attribute(operation, items, config, source_fs)
             }
+            OperationParams::Deduplicate { action, groups } => {
+                self.execute_deduplicate(operation, *action, groups)
+            }
+            OperationParams::Checksum { job, source_fs } => {
+                self.execute_checksum(operation, job, source_fs)
+            }
+            OperationParams::Split { request, source_fs, dest_fs } => {
+                self.execute_split(operation, request, source_fs, dest_fs)
+            }
+            OperationParams::Join { request, source_fs, dest_fs } => {
+                self.execute_join(operation, request, source_fs, dest_fs)
+            }
+            OperationParams::Sync { profile_id } => {
+                self.execute_sync(operation, profile_id)
+            }
             OperationParams::Custom { command, args } => {
                 self.execute_custom(operation, command, args)
             }
@@ -170,11 +243,13 @@ impl OperationExecutor {
         operation: &QueuedOperation,
         paths: &[String],
         fs: Option<String>,
+        permanent: bool,
     ) -> Result<(), String> {
         info!("=== DELETE OPERATION ===");
         info!("Operation ID: {}", operation.id);
         info!("Paths: {:?}", paths);
         info!("FS: {:?}", fs);
+        info!("Permanent: {}", permanent);
 
         let (total_bytes, total_items) = file_operations::calculate_total_size(paths)
             .map_err(|e| format!("Failed to calculate size: {}", e))?;
@@ -190,6 +265,7 @@ impl OperationExecutor {
             paths,
             &tracker,
             fs.as_deref(),
+            permanent,
         );
 
         match result {
@@ -212,7 +288,7 @@ impl OperationExecutor {
         operation: &QueuedOperation,
         sources: &[String],
         archive_path: &str,
-        _format: &Option<String>,
+        options: &ArchiveOptions,
         source_fs: &Option<String>,
         dest_fs: &Option<String>,
     ) -> Result<(), String> {
@@ -220,12 +296,14 @@ impl OperationExecutor {
         info!("Operation ID: {}", operation.id);
         info!("Sources: {:?}", sources);
         info!("Archive path: {}", archive_path);
+        info!("Options: {:?}", options);
         info!("Source FS: {:?}", source_fs);
         info!("Dest FS: {:?}", dest_fs);
 
-        let result = archives::create_archive_with_fs(
+        let result = archives::create_archive_with_options(
             sources.to_vec(),
             archive_path.to_string(),
+            options,
             source_fs.as_deref(),
             dest_fs.as_deref()
         );
@@ -249,6 +327,7 @@ impl OperationExecutor {
         operation: &QueuedOperation,
         archive_path: &str,
         destination: &str,
+        options: &ExtractOptions,
         source_fs: &Option<String>,
         dest_fs: &Option<String>,
     ) -> Result<(), String> {
@@ -256,24 +335,132 @@ impl OperationExecutor {
         info!("Operation ID: {}", operation.id);
         info!("Archive path: {}", archive_path);
         info!("Destination: {}", destination);
+        info!("Options: {:?}", options);
         info!("Source FS: {:?}", source_fs);
         info!("Dest FS: {:?}", dest_fs);
 
-        let result = archives::extract_archive_with_fs(
+        let tracker = OPERATIONS_MANAGER.create_operation(
+            operation.id.clone(),
+            CoreOperationType::Extract,
+            0,
+            0,
+        );
+
+        let result = archives::extract_archive_with_options(
             archive_path,
             destination,
+            options,
             source_fs.as_deref(),
-            dest_fs.as_deref()
+            dest_fs.as_deref(),
+            Some(&tracker),
         );
 
         match result {
-            Ok(_) => {
-                info!("✓ Archive extracted successfully");
+            Ok(report) if report.cancelled => {
+                tracker.mark_cancelled();
+                Ok(())
+            }
+            Ok(report) => {
+                tracker.mark_completed();
+                info!("✓ Archive extracted: {} files, {} skipped, {} renamed", report.extracted, report.skipped, report.renamed);
                 Ok(())
             }
             Err(e) => {
                 let err_msg = format!("Extraction failed: {}", e);
                 error!("✗ {}", err_msg);
+                tracker.mark_failed(err_msg.clone());
+                Err(err_msg)
+            }
+        }
+    }
+
+    /// Execute an in-place change of an existing archive
+    fn execute_archive_edit(
+        &self,
+        operation: &QueuedOperation,
+        archive_path: &str,
+        archive_fs: &Option<String>,
+        edit: ArchiveEdit,
+        options: &ArchiveOptions,
+    ) -> Result<(), String> {
+        info!("=== ARCHIVE EDIT OPERATION ===");
+        info!("Operation ID: {}", operation.id);
+        info!("Archive path: {}", archive_path);
+        info!("Edit: {:?}", edit);
+        info!("Archive FS: {:?}", archive_fs);
+
+        let tracker = OPERATIONS_MANAGER.create_operation(
+            operation.id.clone(),
+            CoreOperationType::Archive,
+            0,
+            0,
+        );
+
+        match archives::edit_archive(archive_path, archive_fs.as_deref(), &[edit], options, Some(&tracker)) {
+            Ok(report) if report.cancelled => {
+                tracker.mark_cancelled();
+                Ok(())
+            }
+            Ok(report) => {
+                tracker.mark_completed();
+                info!("✓ Archive updated: {} entries, {} added, {} removed", report.entries, report.added, report.removed);
+                Ok(())
+            }
+            Err(e) => {
+                let err_msg = format!("Archive update failed: {}", e);
+                error!("✗ {}", err_msg);
+                tracker.mark_failed(err_msg.clone());
+                Err(err_msg)
+            }
+        }
+    }
+
+    /// Execute an archive integrity test; the report is kept for `archive_test_result`
+    fn execute_archive_test(
+        &self,
+        operation: &QueuedOperation,
+        archive_path: &str,
+        password: Option<&ArchivePassword>,
+        source_fs: &Option<String>,
+    ) -> Result<(), String> {
+        info!("=== ARCHIVE TEST OPERATION ===");
+        info!("Operation ID: {}", operation.id);
+        info!("Archive path: {}", archive_path);
+        info!("Source FS: {:?}", source_fs);
+
+        let tracker = OPERATIONS_MANAGER.create_operation(
+            operation.id.clone(),
+            CoreOperationType::ArchiveTest,
+            0,
+            0,
+        );
+
+        match archives::run_archive_test(&operation.id, archive_path, source_fs.as_deref(), password, Some(&tracker)) {
+            Ok(report) if report.cancelled => {
+                tracker.mark_cancelled();
+                Ok(())
+            }
+            Ok(report) if report.is_ok() => {
+                tracker.mark_completed();
+                info!("✓ Archive OK: {} entries, {} bytes", report.tested, report.bytes);
+                Ok(())
+            }
+            Ok(report) => {
+                let damaged: Vec<String> = report.failures.iter()
+                    .map(|failure| match failure.entry.as_str() {
+                        "" => failure.error.clone(),
+                        entry => format!("{}: {}", entry, failure.error),
+                    })
+                    .collect();
+                let err_msg = format!("Archive is damaged ({} errors): {}", report.failures.len(), damaged.join("; "));
+                error!("✗ {}", err_msg);
+                tracker.mark_failed(err_msg.clone());
+                Err(err_msg)
+            }
+            Err(e) => {
+                let err_msg = format!("Archive test failed: {}", e);
+                error!("✗ {}", err_msg);
+                tracker.mark_failed(err_msg.clone());
                 Err(err_msg)
             }
         }
@@ -322,6 +509,218 @@ impl OperationExecutor {
         Err("Batch attribute change not implemented yet".to_string())
     }
 
+    /// Execute an action on duplicate file groups
+    fn execute_deduplicate(
+        &self,
+        operation: &QueuedOperation,
+        action: DuplicateAction,
+        groups: &[DuplicateGroup],
+    ) -> Result<(), String> {
+        info!("=== DEDUPLICATE OPERATION ===");
+        info!("Operation ID: {}", operation.id);
+        info!("Action: {:?}, groups: {}", action, groups.len());
+
+        let total_bytes = groups.iter().map(|g| g.size * g.files.len() as u64).sum();
+        let total_items = groups.iter().map(|g| g.files.len().saturating_sub(1) as u64).sum();
+        let tracker = OPERATIONS_MANAGER.create_operation(
+            operation.id.clone(),
+            CoreOperationType::Duplicates,
+            total_bytes,
+            total_items,
+        );
+
+        let request = DuplicateActionRequest {
+            action,
+            groups: groups.to_vec(),
+        };
+        let summary = API.duplicates.apply(&request, &tracker);
+
+        if summary.cancelled {
+            tracker.mark_cancelled();
+            return Ok(());
+        }
+        if summary.errors.is_empty() {
+            tracker.mark_completed();
+            info!("✓ Deduplicate operation completed: {} files, {} bytes freed", summary.processed, summary.bytes_freed);
+            Ok(())
+        } else {
+            let err_msg = format!("Deduplicate finished with {} errors: {}", summary.errors.len(), summary.errors.join("; "));
+            error!("✗ {}", err_msg);
+            tracker.mark_failed(err_msg.clone());
+            Err(err_msg)
+        }
+    }
+
+    /// Execute checksum calculation or manifest verification
+    fn execute_checksum(
+        &self,
+        operation: &QueuedOperation,
+        job: &ChecksumJob,
+        source_fs: &Option<String>,
+    ) -> Result<(), String> {
+        info!("=== CHECKSUM OPERATION ===");
+        info!("Operation ID: {}", operation.id);
+        info!("Job: {:?}", job);
+        info!("Source FS: {:?}", source_fs);
+
+        let tracker = OPERATIONS_MANAGER.create_operation(
+            operation.id.clone(),
+            CoreOperationType::Checksum,
+            0,
+            0,
+        );
+
+        let result = API.checksums.run_job(&operation.id, job, source_fs.as_deref(), &tracker);
+
+        let err_msg = match result {
+            Ok(ChecksumJobResult::Compute(result)) if result.cancelled => None,
+            Ok(ChecksumJobResult::Verify(report)) if report.cancelled => None,
+            Ok(ChecksumJobResult::Compute(result)) if !result.errors.is_empty() => {
+                Some(format!("{} files could not be read: {}", result.errors.len(), result.errors.join("; ")))
+            }
+            Ok(ChecksumJobResult::Verify(report)) if report.failed > 0 || report.missing > 0 => {
+                Some(format!("Verification failed: {} FAILED, {} MISSING", report.failed, report.missing))
+            }
+            Ok(_) => {
+                tracker.mark_completed();
+                info!("✓ Checksum operation completed: {}", operation.id);
+                return Ok(());
+            }
+            Err(e) => Some(format!("Checksum failed: {}", e)),
+        };
+
+        match err_msg {
+            None => {
+                tracker.mark_cancelled();
+                Ok(())
+            }
+            Some(err_msg) => {
+                error!("✗ {}", err_msg);
+                tracker.mark_failed(err_msg.clone());
+                Err(err_msg)
+            }
+        }
+    }
+
+    /// Execute a file split; the result is kept for `SplitService::job_result`
+    fn execute_split(
+        &self,
+        operation: &QueuedOperation,
+        request: &SplitRequest,
+        source_fs: &Option<String>,
+        dest_fs: &Option<String>,
+    ) -> Result<(), String> {
+        info!("=== SPLIT OPERATION ===");
+        info!("Operation ID: {}", operation.id);
+        info!("Request: {:?}", request);
+        info!("Source FS: {:?}", source_fs);
+        info!("Dest FS: {:?}", dest_fs);
+
+        let tracker = OPERATIONS_MANAGER.create_operation(
+            operation.id.clone(),
+            CoreOperationType::Split,
+            0,
+            0,
+        );
+
+        match API.split.run_split(&operation.id, request, source_fs.as_deref(), dest_fs.as_deref(), &tracker) {
+            Ok(result) if result.cancelled => {
+                tracker.mark_cancelled();
+                Ok(())
+            }
+            Ok(result) => {
+                tracker.mark_completed();
+                info!("✓ File split into {} parts", result.parts.len());
+                Ok(())
+            }
+            Err(e) => {
+                let err_msg = format!("Split failed: {}", e);
+                error!("✗ {}", err_msg);
+                tracker.mark_failed(err_msg.clone());
+                Err(err_msg)
+            }
+        }
+    }
+
+    /// Execute a join of split parts; the result is kept for `SplitService::job_result`
+    fn execute_join(
+        &self,
+        operation: &QueuedOperation,
+        request: &JoinRequest,
+        source_fs: &Option<String>,
+        dest_fs: &Option<String>,
+    ) -> Result<(), String> {
+        info!("=== JOIN OPERATION ===");
+        info!("Operation ID: {}", operation.id);
+        info!("Request: {:?}", request);
+        info!("Source FS: {:?}", source_fs);
+        info!("Dest FS: {:?}", dest_fs);
+
+        let tracker = OPERATIONS_MANAGER.create_operation(
+            operation.id.clone(),
+            CoreOperationType::Join,
+            0,
+            0,
+        );
+
+        match API.split.run_join(&operation.id, request, source_fs.as_deref(), dest_fs.as_deref(), &tracker) {
+            Ok(result) if result.cancelled => {
+                tracker.mark_cancelled();
+                Ok(())
+            }
+            Ok(result) => {
+                tracker.mark_completed();
+                info!("✓ {} parts joined into {} (verified: {})", result.parts, result.path, result.verified);
+                Ok(())
+            }
+            Err(e) => {
+                let err_msg = format!("Join failed: {}", e);
+                error!("✗ {}", err_msg);
+                tracker.mark_failed(err_msg.clone());
+                Err(err_msg)
+            }
+        }
+    }
+
+    /// Execute a folder sync job (re-planned on every attempt)
+    fn execute_sync(
+        &self,
+        operation: &QueuedOperation,
+        profile_id: &str,
+    ) -> Result<(), String> {
+        info!("=== SYNC OPERATION ===");
+        info!("Operation ID: {}", operation.id);
+        info!("Profile: {}", profile_id);
+
+        let tracker = OPERATIONS_MANAGER.create_operation(
+            operation.id.clone(),
+            CoreOperationType::Sync,
+            0,
+            0,
+        );
+
+        let err_msg = match API.sync.run(profile_id, &tracker) {
+            Ok(report) if report.cancelled => {
+                tracker.mark_cancelled();
+                return Ok(());
+            }
+            Ok(report) if report.errors.is_empty() => {
+                if !report.conflicts.is_empty() {
+                    info!("Sync left {} conflicts unresolved: {:?}", report.conflicts.len(), report.conflicts);
+                }
+                tracker.mark_completed();
+                info!("✓ Sync completed: {} copied, {} deleted", report.copied, report.deleted);
+                return Ok(());
+            }
+            Ok(report) => format!("Sync finished with {} errors: {}", report.errors.len(), report.errors.join("; ")),
+            Err(e) => format!("Sync failed: {}", e),
+        };
+
+        error!("✗ {}", err_msg);
+        tracker.mark_failed(err_msg.clone());
+        Err(err_msg)
+    }
+
     /// Execute custom operation
     fn execute_custom(
         &self,
@@ -9,6 +9,7 @@ use super::types::*;
 use super::persistence::QueuePersistence;
 use super::executor::OperationExecutor;
 use super::scheduler::TaskScheduler;
+use crate::archives::PASSWORD_REQUIRED;
 
 /// Wrapper for prioritizing operations in the queue
 #[derive(Debug, Clone)]
@@ -92,6 +93,11 @@ impl QueueManager {
                             warn!("Operation {} was interrupted, marking as failed", op.id);
                             op.mark_failed("Operation interrupted by application shutdown".to_string());
                         }
+                        QueueOperationStatus::Queued | QueueOperationStatus::Scheduled if op.password_missing() => {
+                            // Без пароля архив получился бы незашифрованным: не запускаем
+                            warn!("Operation {} lost its archive password on restart, marking as failed", op.id);
+                            op.mark_failed_permanently(PASSWORD_REQUIRED.to_string());
+                        }
                         QueueOperationStatus::Queued => {
                             pq.push(PrioritizedOperation { operation: op.clone() });
                         }
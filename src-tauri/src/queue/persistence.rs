@@ -147,6 +147,37 @@ mod tests {
         assert_eq!(loaded[1].operation_type, QueuedOperationType::Move);
     }
 
+    #[test]
+    fn test_archive_password_is_not_persisted() {
+        let temp_dir = tempdir().unwrap();
+        let test_path = temp_dir.path().join("test_queue.json");
+
+        let mut persistence = QueuePersistence::new();
+        persistence.file_path = test_path.clone();
+
+        let params = OperationParams::Archive {
+            sources: vec!["/test/file1".to_string()],
+            archive_path: "/test/secret.zip".to_string(),
+            format: None,
+            level: None,
+            threads: None,
+            password: Some(serde_json::from_str("\"s3cret\"").unwrap()),
+            source_fs: None,
+            dest_fs: None,
+        };
+        let op = QueuedOperation::new(QueuedOperationType::Archive, params, OperationPriority::Normal);
+        assert!(op.password_required && !op.password_missing());
+
+        persistence.save(&[op]).unwrap();
+        assert!(!std::fs::read_to_string(&test_path).unwrap().contains("s3cret"));
+
+        // После загрузки пароля нет, но признак остаётся: операция не выполнится без пароля
+        let loaded = persistence.load().unwrap();
+        assert!(loaded[0].params.password().is_none());
+        assert!(loaded[0].password_required);
+        assert!(loaded[0].password_missing());
+    }
+
     #[test]
     fn test_load_nonexistent() {
         let temp_dir = tempdir().unwrap();
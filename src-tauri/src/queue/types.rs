@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
+use crate::api_service::checksum::ChecksumJob;
+use crate::api_service::split::{JoinRequest, SplitRequest};
+use crate::api_service::duplicates::{DuplicateAction, DuplicateGroup};
+use crate::archives::{ArchivePassword, OverwritePolicy};
 
 /// Extended operation types for the queue system
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -11,8 +15,18 @@ pub enum QueuedOperationType {
     Delete,
     Archive,
     Extract,
+    ArchiveAdd,
+    ArchiveDelete,
+    ArchiveRename,
+    ArchiveUpdate,
+    ArchiveTest,
     BatchRename,
     BatchAttribute,
+    Deduplicate,
+    Checksum,
+    Split,
+    Join,
+    Sync,
     Custom(String),
 }
 
@@ -109,6 +123,9 @@ pub enum OperationParams {
     #[serde(rename_all = "camelCase")]
     Delete {
         paths: Vec<String>,
+        /// Удалить без корзины; по умолчанию элементы перемещаются в корзину
+        #[serde(default)]
+        permanent: bool,
         #[serde(skip_serializing_if = "Option::is_none")]
         panel_fs: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -118,8 +135,18 @@ pub enum OperationParams {
     Archive {
         sources: Vec<String>,
         archive_path: String,
+        /// `zip`, `tar`, `tar.gz`, `tar.xz`, `tar.zst`, `tar.bz2` или `7z`; по умолчанию — по расширению
         #[serde(skip_serializing_if = "Option::is_none")]
         format: Option<String>,
+        /// Уровень сжатия (zstd — 1..=22, остальные — 0..=9)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        level: Option<u32>,
+        /// Потоки сжатия для xz и zstd
+        #[serde(skip_serializing_if = "Option::is_none")]
+        threads: Option<u32>,
+        /// Пароль zip (AES-256); в сохраняемый на диск JSON очереди не пишется
+        #[serde(default, skip_serializing)]
+        password: Option<ArchivePassword>,
         #[serde(skip_serializing_if = "Option::is_none")]
         source_fs: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -129,11 +156,94 @@ pub enum OperationParams {
     Extract {
         archive_path: String,
         destination: String,
+        /// Пути внутри архива для выборочной распаковки; пусто — весь архив
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        entries: Vec<String>,
+        #[serde(default)]
+        overwrite: OverwritePolicy,
+        /// Пароль zip; как и у `Archive`, только в памяти
+        #[serde(default, skip_serializing)]
+        password: Option<ArchivePassword>,
         #[serde(skip_serializing_if = "Option::is_none")]
         source_fs: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         dest_fs: Option<String>,
     },
+    /// Добавление файлов с любого бэкенда в существующий zip/tar
+    #[serde(rename_all = "camelCase")]
+    ArchiveAdd {
+        archive_path: String,
+        sources: Vec<String>,
+        /// Каталог внутри архива; пусто — корень
+        #[serde(default)]
+        target_dir: String,
+        /// Сжатие пересобранного tar (как у `Archive`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        level: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        threads: Option<u32>,
+        /// Пароль зашифрованного zip для новых записей; только в памяти
+        #[serde(default, skip_serializing)]
+        password: Option<ArchivePassword>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        archive_fs: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source_fs: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ArchiveDelete {
+        archive_path: String,
+        entries: Vec<String>,
+        /// Сжатие пересобранного tar (как у `Archive`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        level: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        threads: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        archive_fs: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ArchiveRename {
+        archive_path: String,
+        from: String,
+        to: String,
+        /// Сжатие пересобранного tar (как у `Archive`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        level: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        threads: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        archive_fs: Option<String>,
+    },
+    /// Замена содержимого записи файлом с любого бэкенда
+    #[serde(rename_all = "camelCase")]
+    ArchiveUpdate {
+        archive_path: String,
+        entry: String,
+        source: String,
+        /// Сжатие пересобранного tar (как у `Archive`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        level: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        threads: Option<u32>,
+        /// Пароль зашифрованного zip для новых записей; только в памяти
+        #[serde(default, skip_serializing)]
+        password: Option<ArchivePassword>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        archive_fs: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source_fs: Option<String>,
+    },
+    /// Проверка целостности архива без распаковки на диск
+    #[serde(rename_all = "camelCase")]
+    ArchiveTest {
+        archive_path: String,
+        /// Пароль zip; только в памяти
+        #[serde(default, skip_serializing)]
+        password: Option<ArchivePassword>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source_fs: Option<String>,
+    },
     #[serde(rename_all = "camelCase")]
     BatchRename {
         items: Vec<String>,
@@ -149,12 +259,59 @@ pub enum OperationParams {
         source_fs: Option<String>,
     },
     #[serde(rename_all = "camelCase")]
+    Deduplicate {
+        action: DuplicateAction,
+        groups: Vec<DuplicateGroup>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Checksum {
+        job: ChecksumJob,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source_fs: Option<String>,
+    },
+    /// Разбиение файла на части `.001`, `.002`, ...
+    #[serde(rename_all = "camelCase")]
+    Split {
+        request: SplitRequest,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source_fs: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dest_fs: Option<String>,
+    },
+    /// Сборка файла из частей с проверкой по манифесту `.crc`
+    #[serde(rename_all = "camelCase")]
+    Join {
+        request: JoinRequest,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source_fs: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dest_fs: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Sync {
+        profile_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
     Custom {
         command: String,
         args: HashMap<String, serde_json::Value>,
     },
 }
 
+impl OperationParams {
+    /// Пароль архива, если операция его принимает и он задан
+    pub fn password(&self) -> Option<&ArchivePassword> {
+        match self {
+            OperationParams::Archive { password, .. }
+            | OperationParams::Extract { password, .. }
+            | OperationParams::ArchiveAdd { password, .. }
+            | OperationParams::ArchiveUpdate { password, .. }
+            | OperationParams::ArchiveTest { password, .. } => password.as_ref(),
+            _ => None,
+        }
+    }
+}
+
 /// A queued operation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -213,6 +370,11 @@ pub struct QueuedOperation {
     /// Optional description
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+
+    /// Операция создана с паролем архива. Сам пароль на диск не пишется, поэтому после
+    /// перезапуска по этому признаку видно, что выполнять операцию без него нельзя
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub password_required: bool,
 }
 
 impl QueuedOperation {
@@ -222,6 +384,7 @@ impl QueuedOperation {
         params: OperationParams,
         priority: OperationPriority,
     ) -> Self {
+        let password_required = params.password().is_some();
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             operation_type,
@@ -240,9 +403,15 @@ impl QueuedOperation {
             error_logs: Vec::new(),
             tags: Vec::new(),
             description: None,
+            password_required,
         }
     }
 
+    /// Пароль архива потерян (операция загружена с диска): выполнять её нельзя
+    pub fn password_missing(&self) -> bool {
+        self.password_required && self.params.password().is_none()
+    }
+
     /// Check if the operation should be retried
     pub fn should_retry(&self) -> bool {
         self.retry_policy.enabled
@@ -294,6 +463,14 @@ impl QueuedOperation {
         }
     }
 
+    /// Mark operation as failed without scheduling a retry
+    pub fn mark_failed_permanently(&mut self, error_msg: String) {
+        self.error_message = Some(error_msg.clone());
+        self.error_logs.push(error_msg);
+        self.status = QueueOperationStatus::Failed;
+        self.completed_at = Some(Utc::now());
+    }
+
     /// Mark operation as cancelled
     pub fn mark_cancelled(&mut self) {
         self.status = QueueOperationStatus::Cancelled;
@@ -397,4 +574,20 @@ mod tests {
         assert_eq!(op.id, deserialized.id);
         assert_eq!(op.priority, deserialized.priority);
     }
+
+    #[test]
+    fn test_archive_password_is_not_serialized() {
+        let params: OperationParams = serde_json::from_value(serde_json::json!({
+            "type": "Extract",
+            "archivePath": "/in/secret.zip",
+            "destination": "/out",
+            "password": "hunter2",
+        }))
+        .unwrap();
+        assert!(matches!(&params, OperationParams::Extract { password: Some(_), .. }));
+
+        let json = serde_json::to_string(&params).unwrap();
+        assert!(!json.contains("hunter2"));
+        assert!(!format!("{:?}", params).contains("hunter2"));
+    }
 }